@@ -0,0 +1,136 @@
+//! Builds a block on top of another, from extrinsics or from the transaction pool.
+
+use crate::{
+    runtime_api::{BlockOf, HeaderOf, RuntimeApi},
+    transaction_pool::{TransactionPool, TxHash},
+};
+use frame_support::{
+    dispatch::ApplyExtrinsicResult,
+    generic::{Block, Digest, Header},
+    pallet_prelude::{One, Saturating},
+    storage::{Storage, TestExternalities},
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+
+/// The transactions skipped because they don't fit before [`BlockBuilder::apply_from_pool`]
+/// considers the block full.
+const MAX_SKIPPED_TRANSACTIONS: usize = 8;
+
+/// A block being built on its own copy of the state, see [`BlockBuilder::build`].
+pub struct BlockBuilder<R: RuntimeApi> {
+    ext: TestExternalities,
+    extrinsics: Vec<R::Extrinsic>,
+}
+
+/// A block which was built, with the state it ends with.
+pub struct BuiltBlock<R: RuntimeApi> {
+    pub block: BlockOf<R>,
+    pub state: Storage,
+}
+
+impl<R: RuntimeApi> BlockBuilder<R> {
+    /// Start the block following `parent`, whose state is `state`, with the pre-runtime logs
+    /// of `digest`, e.g. the slot the block is authored in.
+    pub fn new(parent: &HeaderOf<R>, state: Storage, digest: Digest) -> Self {
+        let mut ext = TestExternalities::new(state);
+        let number = parent.number.saturating_add(One::one());
+        let header = Header { digest, ..Header::new_unfinalized(number, R::hash_header(parent)) };
+        ext.execute_with(|| R::initialize_block(&header));
+        BlockBuilder { ext, extrinsics: vec![] }
+    }
+
+    /// Apply `xt` to the block. An extrinsic which is refused is not part of the block.
+    pub fn push(&mut self, xt: R::Extrinsic) -> ApplyExtrinsicResult {
+        let res = self.ext.execute_with(|| R::apply_extrinsic(xt.clone()));
+        if res.is_ok() {
+            self.extrinsics.push(xt);
+        }
+        res
+    }
+
+    /// Apply the ready transactions of `pool` by priority, until the block is full by weight or
+    /// by length. Gives the transactions which are invalid, to remove from the pool.
+    ///
+    /// A transaction which doesn't fit is skipped, as smaller ones may still fit, until
+    /// `MAX_SKIPPED_TRANSACTIONS` were skipped.
+    pub fn apply_from_pool(&mut self, pool: &TransactionPool<R::Extrinsic>) -> Vec<TxHash> {
+        let mut skipped = 0;
+        let mut invalid = vec![];
+        for tx in pool.ready() {
+            match self.push(tx.data.clone()) {
+                Ok(_) => {}
+                Err(TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources)) => {
+                    skipped += 1;
+                    if skipped >= MAX_SKIPPED_TRANSACTIONS {
+                        break
+                    }
+                }
+                // A transaction it requires was skipped.
+                Err(TransactionValidityError::Invalid(InvalidTransaction::Future)) => {}
+                Err(_) => invalid.push(tx.hash),
+            }
+        }
+        invalid
+    }
+
+    /// The extrinsics applied so far.
+    pub fn extrinsics(&self) -> &[R::Extrinsic] {
+        &self.extrinsics
+    }
+
+    /// Finalize the block.
+    pub fn build(mut self) -> BuiltBlock<R> {
+        let header = self.ext.execute_with(R::finalize_block);
+        let block = Block { header, extrinsics: self.extrinsics };
+        BuiltBlock { block, state: self.ext.into_storage() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{
+        codec::Encode,
+        generic::{Era, SignedPayload},
+        testing::TestSignature,
+    };
+    use template::{Executive, RuntimeCall, UncheckedExtrinsic};
+
+    fn remark(who: u32, genesis_hash: [u8; 32], len: usize) -> UncheckedExtrinsic {
+        let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![0; len] });
+        let extra = (
+            frame_system::CheckSpecVersion::new(),
+            frame_system::CheckGenesis::new(),
+            frame_system::CheckMortality::from(Era::immortal()),
+            frame_system::CheckNonce::from(0),
+            frame_system::CheckWeight::new(),
+        );
+        let additional = (0, genesis_hash, genesis_hash, (), ());
+        let payload = SignedPayload::from_raw(call.clone(), extra.clone(), additional);
+        UncheckedExtrinsic::new_signed(call, who, TestSignature(who, payload.encode()), extra)
+    }
+
+    #[test]
+    fn built_blocks_are_filled_up_to_their_length() {
+        let genesis = TestExternalities::default().execute_with(Executive::genesis_header);
+        let genesis_hash = Executive::hash_header(&genesis);
+        let mut builder =
+            BlockBuilder::<Executive>::new(&genesis, Storage::default(), Default::default());
+
+        // Normal extrinsics may use 3/4 of the 5 MiB of a block.
+        for who in 0..3 {
+            assert_eq!(builder.push(remark(who, genesis_hash, 1024 * 1024)), Ok(Ok(())));
+        }
+        assert_eq!(
+            builder.push(remark(3, genesis_hash, 1024 * 1024)),
+            Err(InvalidTransaction::ExhaustsResources.into())
+        );
+        assert_eq!(builder.push(remark(3, genesis_hash, 1024)), Ok(Ok(())));
+        assert_eq!(builder.extrinsics().len(), 4);
+
+        let block = builder.build().block;
+        assert_eq!(block.header.parent_hash, genesis_hash);
+        // The block is valid for any node starting from the same genesis.
+        TestExternalities::default().execute_with(|| Executive::execute_block(block));
+    }
+}
@@ -0,0 +1,436 @@
+//! The blocks a node knows of, forks included, and the best chain among them.
+//!
+//! Every block is executed on the state of its parent when it is imported, but only the states
+//! after the blocks of the best chain are kept. The best chain is the longest one, the first
+//! imported winning ties: once a fork gets longer, the tree reorganizes by re-importing the
+//! blocks of the fork on the state of its common ancestor with the best chain.
+
+use crate::runtime_api::{BlockOf, RuntimeApi};
+use frame_support::{
+    generic::Block,
+    pallet_prelude::{One, Saturating},
+    storage::{Storage, TestExternalities},
+    transaction_validity::TransactionValidityError,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Why a block was not imported, or not finalized.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// The block is already in the tree.
+    AlreadyImported,
+    /// The parent of the block is not in the tree.
+    UnknownParent,
+    /// The block is not in the tree.
+    UnknownBlock,
+    /// The block is not a descendant of the last finalized block.
+    NotInFinalizedChain,
+    /// The block is not in the best chain, so it can't be finalized.
+    NotInBestChain,
+    /// A block after this one is finalized already.
+    AlreadyFinalized,
+    /// An extrinsic of the block was refused.
+    InvalidExtrinsic(TransactionValidityError),
+    /// The header of the block is not the one its execution gives, e.g. another state root.
+    HeaderMismatch,
+}
+
+/// How importing a block changed the best chain.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportOutcome<Hash> {
+    pub is_new_best: bool,
+    /// The blocks which left the best chain, from the former best block down.
+    pub retracted: Vec<Hash>,
+    /// The blocks which joined the best chain, up to the new best block.
+    pub enacted: Vec<Hash>,
+}
+
+/// The blocks of a chain and its forks, from genesis.
+pub struct BlockTree<R: RuntimeApi> {
+    blocks: BTreeMap<R::Hash, BlockOf<R>>,
+    /// The blocks without children, at the end of each fork.
+    leaves: BTreeSet<R::Hash>,
+    genesis: R::Hash,
+    best: R::Hash,
+    finalized: R::Hash,
+    /// The state after each block of the best chain.
+    states: BTreeMap<R::Hash, Storage>,
+}
+
+/// Execute `block` on `state`, giving the state after it.
+///
+/// This is what `Executive::execute_block` does, refusing an invalid block instead of panicking.
+pub fn execute_block<R: RuntimeApi>(block: &BlockOf<R>, state: Storage) -> Result<Storage, Error> {
+    let mut ext = TestExternalities::new(state);
+    let header = ext.execute_with(|| {
+        R::initialize_block(&block.header);
+        for xt in &block.extrinsics {
+            // A call failing to dispatch doesn't make the block invalid, unlike a refused one.
+            let _dispatched = R::apply_extrinsic(xt.clone()).map_err(Error::InvalidExtrinsic)?;
+        }
+        Ok(R::finalize_block())
+    })?;
+    if header != block.header {
+        return Err(Error::HeaderMismatch)
+    }
+    Ok(ext.into_storage())
+}
+
+impl<R: RuntimeApi> BlockTree<R> {
+    /// A tree of the genesis block only, whose state is `genesis`.
+    pub fn new(genesis: Storage) -> Self {
+        let mut ext = TestExternalities::new(genesis);
+        let header = ext.execute_with(R::genesis_header);
+        let hash = R::hash_header(&header);
+        BlockTree {
+            blocks: BTreeMap::from([(hash, Block { header, extrinsics: vec![] })]),
+            leaves: BTreeSet::from([hash]),
+            genesis: hash,
+            best: hash,
+            finalized: hash,
+            states: BTreeMap::from([(hash, ext.into_storage())]),
+        }
+    }
+
+    pub fn genesis_hash(&self) -> R::Hash {
+        self.genesis
+    }
+
+    pub fn best_hash(&self) -> R::Hash {
+        self.best
+    }
+
+    pub fn finalized_hash(&self) -> R::Hash {
+        self.finalized
+    }
+
+    /// The block of `hash`, on any fork.
+    pub fn block(&self, hash: &R::Hash) -> Option<&BlockOf<R>> {
+        self.blocks.get(hash)
+    }
+
+    pub fn best_block(&self) -> &BlockOf<R> {
+        &self.blocks[&self.best]
+    }
+
+    /// The blocks without children.
+    pub fn leaves(&self) -> Vec<R::Hash> {
+        self.leaves.iter().copied().collect()
+    }
+
+    /// The hash of the block of the best chain at `number`.
+    pub fn hash(&self, number: R::BlockNumber) -> Option<R::Hash> {
+        let mut hash = self.best;
+        loop {
+            let header = &self.blocks[&hash].header;
+            if header.number <= number {
+                return (header.number == number).then_some(hash)
+            }
+            hash = header.parent_hash;
+        }
+    }
+
+    /// The state after the best block.
+    pub fn best_state(&self) -> &Storage {
+        &self.states[&self.best]
+    }
+
+    /// The state after the block of `hash`, re-executing the blocks of its fork if it is not in
+    /// the best chain.
+    pub fn state_at(&self, hash: &R::Hash) -> Option<Storage> {
+        let mut fork = vec![];
+        let mut ancestor = *hash;
+        while !self.states.contains_key(&ancestor) {
+            let block = self.blocks.get(&ancestor)?;
+            fork.push(block);
+            ancestor = block.header.parent_hash;
+        }
+        let state = self.states[&ancestor].clone();
+        let state = fork.into_iter().rev().fold(state, |state, block| {
+            execute_block::<R>(block, state).expect("imported blocks are valid")
+        });
+        Some(state)
+    }
+
+    /// Execute `block` on the state of its parent and add it to the tree.
+    pub fn import(&mut self, block: BlockOf<R>) -> Result<ImportOutcome<R::Hash>, Error> {
+        self.check_parent(&block)?;
+        let parent = block.header.parent_hash;
+        let state = self.state_at(&parent).ok_or(Error::UnknownParent)?;
+        let state = execute_block::<R>(&block, state)?;
+        Ok(self.insert(block, state))
+    }
+
+    /// Add `block`, ending with `state`, to the tree without executing it, e.g. a block the node
+    /// built itself.
+    pub fn import_executed(
+        &mut self,
+        block: BlockOf<R>,
+        state: Storage,
+    ) -> Result<ImportOutcome<R::Hash>, Error> {
+        self.check_parent(&block)?;
+        Ok(self.insert(block, state))
+    }
+
+    /// Finalize the block of `hash`, of the best chain, and prune the forks which don't descend
+    /// from it.
+    pub fn finalize(&mut self, hash: &R::Hash) -> Result<(), Error> {
+        let number = self.blocks.get(hash).ok_or(Error::UnknownBlock)?.header.number;
+        if self.hash(number) != Some(*hash) {
+            return Err(Error::NotInBestChain)
+        }
+        if number < self.blocks[&self.finalized].header.number {
+            return Err(Error::AlreadyFinalized)
+        }
+        self.finalized = *hash;
+
+        for leaf in self.leaves() {
+            if self.is_descendant_of(&leaf, hash) {
+                continue
+            }
+            self.leaves.remove(&leaf);
+            // Remove the fork down to the best chain, or to a block another fork goes on from.
+            let mut stale = leaf;
+            while self.hash(self.blocks[&stale].header.number) != Some(stale) &&
+                !self.blocks.values().any(|block| block.header.parent_hash == stale)
+            {
+                let block = self.blocks.remove(&stale).expect("forks are in the tree");
+                stale = block.header.parent_hash;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the block of `hash` is `ancestor` or comes after it.
+    fn is_descendant_of(&self, hash: &R::Hash, ancestor: &R::Hash) -> bool {
+        let number = self.blocks[ancestor].header.number;
+        let mut hash = *hash;
+        loop {
+            let header = &self.blocks[&hash].header;
+            if header.number <= number {
+                return hash == *ancestor
+            }
+            hash = header.parent_hash;
+        }
+    }
+
+    fn check_parent(&self, block: &BlockOf<R>) -> Result<(), Error> {
+        let hash = R::hash_header(&block.header);
+        if self.blocks.contains_key(&hash) {
+            return Err(Error::AlreadyImported)
+        }
+        let parent = self.blocks.get(&block.header.parent_hash).ok_or(Error::UnknownParent)?;
+        if block.header.number != parent.header.number.saturating_add(One::one()) {
+            return Err(Error::HeaderMismatch)
+        }
+        if !self.is_descendant_of(&block.header.parent_hash, &self.finalized) {
+            return Err(Error::NotInFinalizedChain)
+        }
+        Ok(())
+    }
+
+    /// Add `block` to the tree, and make it the best block if it ends the longest chain.
+    fn insert(&mut self, block: BlockOf<R>, state: Storage) -> ImportOutcome<R::Hash> {
+        let hash = R::hash_header(&block.header);
+        let number = block.header.number;
+        self.leaves.remove(&block.header.parent_hash);
+        self.leaves.insert(hash);
+        self.blocks.insert(hash, block);
+
+        if number <= self.best_block().header.number {
+            return ImportOutcome { is_new_best: false, retracted: vec![], enacted: vec![] }
+        }
+        let (retracted, enacted) = self.route(&self.best, &hash);
+        for retracted in &retracted {
+            self.states.remove(retracted);
+        }
+        // Re-import the fork on the state of the common ancestor.
+        let (last, fork) = enacted.split_last().expect("the new best block is enacted");
+        for hash in fork {
+            let parent = self.blocks[hash].header.parent_hash;
+            let state = execute_block::<R>(&self.blocks[hash], self.states[&parent].clone())
+                .expect("imported blocks are valid");
+            self.states.insert(*hash, state);
+        }
+        self.states.insert(*last, state);
+        self.best = hash;
+        ImportOutcome { is_new_best: true, retracted, enacted }
+    }
+
+    /// The blocks to go down from `from` to the common ancestor of `from` and `to`, and the
+    /// blocks to go up from there to `to`.
+    fn route(&self, from: &R::Hash, to: &R::Hash) -> (Vec<R::Hash>, Vec<R::Hash>) {
+        let (mut from, mut to) = (*from, *to);
+        let (mut retracted, mut enacted) = (vec![], vec![]);
+        let header = |hash: &R::Hash| &self.blocks[hash].header;
+        while from != to {
+            if header(&from).number >= header(&to).number {
+                retracted.push(from);
+                from = header(&from).parent_hash;
+            } else {
+                enacted.push(to);
+                to = header(&to).parent_hash;
+            }
+        }
+        enacted.reverse();
+        (retracted, enacted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_builder::BlockBuilder;
+    use frame_support::{
+        codec::Encode,
+        generic::{Digest, DigestItem, Era, SignedPayload},
+        testing::TestSignature,
+        transaction_validity::InvalidTransaction,
+    };
+    use template::{Executive, RuntimeCall, System, UncheckedExtrinsic};
+
+    type Tree = BlockTree<Executive>;
+
+    fn remark(genesis_hash: [u8; 32], who: u32, nonce: u32) -> UncheckedExtrinsic {
+        let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+        let extra = (
+            frame_system::CheckSpecVersion::new(),
+            frame_system::CheckGenesis::new(),
+            frame_system::CheckMortality::from(Era::immortal()),
+            frame_system::CheckNonce::from(nonce),
+            frame_system::CheckWeight::new(),
+        );
+        let additional = (0, genesis_hash, genesis_hash, (), ());
+        let payload = SignedPayload::from_raw(call.clone(), extra.clone(), additional);
+        UncheckedExtrinsic::new_signed(call, who, TestSignature(who, payload.encode()), extra)
+    }
+
+    /// A block of `extrinsics` on top of `parent`, on the fork `fork` so that siblings differ.
+    fn block(
+        tree: &Tree,
+        parent: [u8; 32],
+        fork: u8,
+        extrinsics: Vec<UncheckedExtrinsic>,
+    ) -> BlockOf<Executive> {
+        let digest = Digest { logs: vec![DigestItem::PreRuntime(*b"test", vec![fork])] };
+        let header = &tree.block(&parent).unwrap().header;
+        let mut builder =
+            BlockBuilder::<Executive>::new(header, tree.state_at(&parent).unwrap(), digest);
+        for xt in extrinsics {
+            builder.push(xt).unwrap().unwrap();
+        }
+        builder.build().block
+    }
+
+    /// Import a block built like [`block`] does, giving its hash.
+    fn import(
+        tree: &mut Tree,
+        parent: [u8; 32],
+        fork: u8,
+        extrinsics: Vec<UncheckedExtrinsic>,
+    ) -> [u8; 32] {
+        let block = block(tree, parent, fork, extrinsics);
+        let hash = Executive::hash_header(&block.header);
+        tree.import(block).unwrap();
+        hash
+    }
+
+    fn nonce(tree: &Tree, who: u32) -> u32 {
+        TestExternalities::new(tree.best_state().clone())
+            .execute_with(|| System::account_nonce(&who))
+    }
+
+    #[test]
+    fn the_longest_fork_is_the_best_chain() {
+        let mut tree = Tree::new(Storage::default());
+        let genesis = tree.genesis_hash();
+
+        let a1 = import(&mut tree, genesis, 0, vec![remark(genesis, 1, 0)]);
+        let b1 = block(&tree, genesis, 1, vec![remark(genesis, 2, 0)]);
+        let b1_hash = Executive::hash_header(&b1.header);
+        // The first block seen wins a tie.
+        assert_eq!(
+            tree.import(b1),
+            Ok(ImportOutcome { is_new_best: false, retracted: vec![], enacted: vec![] })
+        );
+        assert_eq!(tree.best_hash(), a1);
+        assert_eq!(tree.leaves(), BTreeSet::from([a1, b1_hash]).into_iter().collect::<Vec<_>>());
+        assert_eq!((nonce(&tree, 1), nonce(&tree, 2)), (1, 0));
+
+        let b2 = block(&tree, b1_hash, 1, vec![remark(genesis, 2, 1)]);
+        let b2_hash = Executive::hash_header(&b2.header);
+        assert_eq!(
+            tree.import(b2),
+            Ok(ImportOutcome {
+                is_new_best: true,
+                retracted: vec![a1],
+                enacted: vec![b1_hash, b2_hash]
+            })
+        );
+        assert_eq!(tree.best_hash(), b2_hash);
+        assert_eq!(tree.hash(1), Some(b1_hash));
+        assert_eq!(tree.hash(3), None);
+        // The state is the one of the new best chain.
+        assert_eq!((nonce(&tree, 1), nonce(&tree, 2)), (0, 2));
+
+        // And back, once the first fork gets longer.
+        let a2 = import(&mut tree, a1, 0, vec![]);
+        let a3 = import(&mut tree, a2, 0, vec![remark(genesis, 1, 1)]);
+        assert_eq!(tree.best_hash(), a3);
+        assert_eq!((nonce(&tree, 1), nonce(&tree, 2)), (2, 0));
+    }
+
+    #[test]
+    fn invalid_blocks_are_not_imported() {
+        let mut tree = Tree::new(Storage::default());
+        let genesis = tree.genesis_hash();
+        let valid = block(&tree, genesis, 0, vec![remark(genesis, 1, 0)]);
+
+        let mut unknown_parent = valid.clone();
+        unknown_parent.header.parent_hash = [7; 32];
+        assert_eq!(tree.import(unknown_parent), Err(Error::UnknownParent));
+
+        let mut wrong_number = valid.clone();
+        wrong_number.header.number = 2;
+        assert_eq!(tree.import(wrong_number), Err(Error::HeaderMismatch));
+
+        let mut wrong_root = valid.clone();
+        wrong_root.header.state_root = [7; 32];
+        assert_eq!(tree.import(wrong_root), Err(Error::HeaderMismatch));
+
+        let mut replayed = valid.clone();
+        replayed.extrinsics.push(remark(genesis, 1, 0));
+        assert_eq!(
+            tree.import(replayed),
+            Err(Error::InvalidExtrinsic(InvalidTransaction::Stale.into()))
+        );
+
+        assert_eq!(tree.leaves(), vec![genesis]);
+        let hash = Executive::hash_header(&valid.header);
+        tree.import(valid.clone()).unwrap();
+        assert_eq!(tree.import(valid), Err(Error::AlreadyImported));
+        assert_eq!(tree.best_hash(), hash);
+    }
+
+    #[test]
+    fn finalizing_prunes_the_other_forks() {
+        let mut tree = Tree::new(Storage::default());
+        let genesis = tree.genesis_hash();
+        let a1 = import(&mut tree, genesis, 0, vec![]);
+        let a2 = import(&mut tree, a1, 0, vec![]);
+        let b1 = import(&mut tree, genesis, 1, vec![]);
+        let b2 = import(&mut tree, b1, 1, vec![]);
+        let c2 = import(&mut tree, a1, 2, vec![]);
+        assert_eq!(tree.leaves().len(), 3);
+
+        assert_eq!(tree.finalize(&b2), Err(Error::NotInBestChain));
+        assert_eq!(tree.finalize(&[7; 32]), Err(Error::UnknownBlock));
+        tree.finalize(&a1).unwrap();
+        assert_eq!(tree.finalized_hash(), a1);
+        assert!(tree.block(&b1).is_none() && tree.block(&b2).is_none());
+        assert_eq!(tree.leaves(), BTreeSet::from([a2, c2]).into_iter().collect::<Vec<_>>());
+
+        assert_eq!(tree.finalize(&genesis), Err(Error::AlreadyFinalized));
+        assert_eq!(tree.import(block(&tree, genesis, 3, vec![])), Err(Error::NotInFinalizedChain));
+    }
+}
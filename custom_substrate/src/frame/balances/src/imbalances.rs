@@ -0,0 +1,100 @@
+//! The imbalances of the pallet, which settle the total issuance when they are dropped.
+
+use crate::pallet::{Config, TotalIssuance};
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Imbalance, SameOrOther, TryDrop},
+};
+use std::mem;
+
+/// Funds created but not deposited anywhere yet: the total issuance goes up by their amount
+/// when this is dropped.
+#[must_use]
+pub struct PositiveImbalance<T: Config>(T::Balances);
+
+/// Funds destroyed but not withdrawn from anywhere yet, e.g. what a slash took: the total
+/// issuance goes down by their amount when this is dropped.
+#[must_use]
+pub struct NegativeImbalance<T: Config>(T::Balances);
+
+macro_rules! impl_imbalance {
+    ($name:ident, $opposite:ident, $settle:ident) => {
+        impl<T: Config> $name<T> {
+            /// An imbalance of `amount`.
+            pub fn new(amount: T::Balances) -> Self {
+                $name(amount)
+            }
+        }
+
+        impl<T: Config> Default for $name<T> {
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        impl<T: Config> TryDrop for $name<T> {
+            fn try_drop(self) -> Result<(), Self> {
+                self.drop_zero()
+            }
+        }
+
+        impl<T: Config> Imbalance<T::Balances> for $name<T> {
+            type Opposite = $opposite<T>;
+
+            fn zero() -> Self {
+                $name(Zero::zero())
+            }
+
+            fn drop_zero(self) -> Result<(), Self> {
+                if self.0.is_zero() {
+                    mem::forget(self);
+                    Ok(())
+                } else {
+                    Err(self)
+                }
+            }
+
+            fn split(self, amount: T::Balances) -> (Self, Self) {
+                let first = self.0.min(amount);
+                let second = self.0 - first;
+                mem::forget(self);
+                ($name(first), $name(second))
+            }
+
+            fn merge(mut self, other: Self) -> Self {
+                self.subsume(other);
+                self
+            }
+
+            fn subsume(&mut self, other: Self) {
+                self.0 = self.0.saturating_add(other.0);
+                mem::forget(other);
+            }
+
+            fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
+                let (this, that) = (self.0, other.0);
+                mem::forget((self, other));
+                if this > that {
+                    SameOrOther::Same($name(this - that))
+                } else if that > this {
+                    SameOrOther::Other($opposite(that - this))
+                } else {
+                    SameOrOther::None
+                }
+            }
+
+            fn peek(&self) -> T::Balances {
+                self.0
+            }
+        }
+
+        impl<T: Config> Drop for $name<T> {
+            fn drop(&mut self) {
+                TotalIssuance::<T>::mutate(|issuance| *issuance = issuance.$settle(self.0));
+            }
+        }
+    };
+}
+
+impl_imbalance!(PositiveImbalance, NegativeImbalance, saturating_add);
+impl_imbalance!(NegativeImbalance, PositiveImbalance, saturating_sub);
@@ -0,0 +1,951 @@
+//! The currency traits, implemented over the balances of the pallet.
+
+use crate::{
+    pallet::{
+        Account, BalanceLock, Config, Error, Event, Locks, Pallet, ReserveData, Reserves,
+        TotalIssuance,
+    },
+    NegativeImbalance, PositiveImbalance,
+};
+use frame_support::{
+    pallet_prelude::*,
+    traits::{
+        BalanceStatus, Currency, ExistenceRequirement, Imbalance, LockIdentifier,
+        LockableCurrency, NamedReservableCurrency, ReservableCurrency, SignedImbalance,
+        WithdrawReasons,
+    },
+};
+
+impl<T: Config> Pallet<T> {
+    /// Cache the largest lock on `who` in its frozen balance. An account which does not exist
+    /// caches its locks once created.
+    fn update_frozen(who: &T::AccountId) {
+        let frozen = Self::frozen_balance(who, WithdrawReasons::all());
+        let _ = Self::try_mutate_account(who, |account, _| -> DispatchResult {
+            account.frozen = frozen;
+            Ok(())
+        });
+    }
+
+    /// Change the named reserves of `who` with `f`. They are removed along with the account
+    /// if `f` reaps it.
+    fn try_mutate_reserves<R>(
+        who: &T::AccountId,
+        f: impl FnOnce(
+            &mut BoundedVec<ReserveData<T::ReserveIdentifier, T::Balances>, T::MaxReserves>,
+        ) -> Result<R, DispatchError>,
+    ) -> Result<R, DispatchError> {
+        let mut reserves = Reserves::<T>::get(who);
+        let result = f(&mut reserves)?;
+        if reserves.is_empty() || Self::total_balance(who).is_zero() {
+            Reserves::<T>::remove(who);
+        } else {
+            Reserves::<T>::insert(who, reserves);
+        }
+        Ok(result)
+    }
+}
+
+impl<T: Config> Currency<T::AccountId> for Pallet<T> {
+    type Balance = T::Balances;
+    type PositiveImbalance = PositiveImbalance<T>;
+    type NegativeImbalance = NegativeImbalance<T>;
+
+    fn total_balance(who: &T::AccountId) -> T::Balances {
+        Account::<T>::get(who).total()
+    }
+
+    fn can_slash(who: &T::AccountId, value: T::Balances) -> bool {
+        value.is_zero() || Self::free_balance(who) >= value
+    }
+
+    fn total_issuance() -> T::Balances {
+        TotalIssuance::<T>::get()
+    }
+
+    fn minimum_balance() -> T::Balances {
+        T::ExistentialDeposit::get()
+    }
+
+    fn burn(mut amount: T::Balances) -> PositiveImbalance<T> {
+        if amount.is_zero() {
+            return PositiveImbalance::zero()
+        }
+        TotalIssuance::<T>::mutate(|issued| {
+            amount = amount.min(*issued);
+            *issued -= amount;
+        });
+        PositiveImbalance::new(amount)
+    }
+
+    fn issue(mut amount: T::Balances) -> NegativeImbalance<T> {
+        if amount.is_zero() {
+            return NegativeImbalance::zero()
+        }
+        TotalIssuance::<T>::mutate(|issued| {
+            let new_issued = issued.saturating_add(amount);
+            amount = new_issued - *issued;
+            *issued = new_issued;
+        });
+        NegativeImbalance::new(amount)
+    }
+
+    fn free_balance(who: &T::AccountId) -> T::Balances {
+        Account::<T>::get(who).free
+    }
+
+    fn ensure_can_withdraw(
+        who: &T::AccountId,
+        amount: T::Balances,
+        reasons: WithdrawReasons,
+        new_balance: T::Balances,
+    ) -> DispatchResult {
+        if amount.is_zero() || new_balance >= Self::frozen_balance(who, reasons) {
+            Ok(())
+        } else {
+            Err(Error::<T>::LiquidityRestrictions.into())
+        }
+    }
+
+    fn transfer(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        value: T::Balances,
+        existence_requirement: ExistenceRequirement,
+    ) -> DispatchResult {
+        if value.is_zero() || source == dest {
+            return Ok(())
+        }
+        let ed = T::ExistentialDeposit::get();
+        Self::try_mutate_account(dest, |to, _| -> DispatchResult {
+            Self::try_mutate_account(source, |from, _| -> DispatchResult {
+                from.free = from.free.checked_sub(&value).ok_or(Error::<T>::InsufficientBalance)?;
+                to.free = to.free.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+                if to.total() < ed {
+                    return Err(Error::<T>::ExistentialDeposit.into())
+                }
+                Self::ensure_can_withdraw(source, value, WithdrawReasons::TRANSFER, from.free)?;
+                let would_die = from.total() < ed;
+                if would_die && existence_requirement == ExistenceRequirement::KeepAlive {
+                    return Err(Error::<T>::KeepAlive.into())
+                }
+                Ok(())
+            })
+        })?;
+        let (from, to) = (source.clone(), dest.clone());
+        Self::deposit_event(Event::Transfer { from, to, amount: value });
+        Ok(())
+    }
+
+    fn slash(who: &T::AccountId, value: T::Balances) -> (NegativeImbalance<T>, T::Balances) {
+        if value.is_zero() {
+            return (NegativeImbalance::zero(), value)
+        }
+        // An account which other modules depend on can't be reaped, so the second attempt leaves
+        // it the existential deposit.
+        for attempt in 0..2 {
+            let slashed: Result<_, DispatchError> = Self::try_mutate_account(who, |account, _| {
+                let best_value = match attempt {
+                    0 => value,
+                    _ => value.min(account.total().saturating_sub(T::ExistentialDeposit::get())),
+                };
+                let from_free = best_value.min(account.free);
+                let from_reserved = (best_value - from_free).min(account.reserved);
+                account.free -= from_free;
+                account.reserved -= from_reserved;
+                Ok(from_free + from_reserved)
+            });
+            if let Ok(slashed) = slashed {
+                return (NegativeImbalance::new(slashed), value - slashed)
+            }
+        }
+        (NegativeImbalance::zero(), value)
+    }
+
+    fn deposit_into_existing(
+        who: &T::AccountId,
+        value: T::Balances,
+    ) -> Result<PositiveImbalance<T>, DispatchError> {
+        if value.is_zero() {
+            return Ok(PositiveImbalance::zero())
+        }
+        Self::try_mutate_account(who, |account, is_new| {
+            if is_new {
+                return Err(Error::<T>::DeadAccount.into())
+            }
+            account.free = account.free.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+            Ok(PositiveImbalance::new(value))
+        })
+    }
+
+    fn deposit_creating(who: &T::AccountId, value: T::Balances) -> PositiveImbalance<T> {
+        if value.is_zero() {
+            return PositiveImbalance::zero()
+        }
+        let deposited: Result<_, DispatchError> = Self::try_mutate_account(who, |account, is_new| {
+            if is_new && value < T::ExistentialDeposit::get() {
+                return Err(Error::<T>::ExistentialDeposit.into())
+            }
+            account.free = account.free.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+            Ok(PositiveImbalance::new(value))
+        });
+        deposited.unwrap_or_else(|_| PositiveImbalance::zero())
+    }
+
+    fn withdraw(
+        who: &T::AccountId,
+        value: T::Balances,
+        reasons: WithdrawReasons,
+        liveness: ExistenceRequirement,
+    ) -> Result<NegativeImbalance<T>, DispatchError> {
+        if value.is_zero() {
+            return Ok(NegativeImbalance::zero())
+        }
+        Self::try_mutate_account(who, |account, _| {
+            let new_free = account.free.checked_sub(&value).ok_or(Error::<T>::InsufficientBalance)?;
+            let new_total = new_free.saturating_add(account.reserved);
+            if new_total < T::ExistentialDeposit::get() &&
+                liveness == ExistenceRequirement::KeepAlive
+            {
+                return Err(Error::<T>::KeepAlive.into())
+            }
+            Self::ensure_can_withdraw(who, value, reasons, new_free)?;
+            account.free = new_free;
+            Ok(NegativeImbalance::new(value))
+        })
+    }
+
+    fn make_free_balance_be(
+        who: &T::AccountId,
+        balance: T::Balances,
+    ) -> SignedImbalance<T::Balances, PositiveImbalance<T>> {
+        let imbalance = Self::try_mutate_account(who, |account, is_new| {
+            // A new account would be reaped straight away, its dust settled before the funds
+            // which created it.
+            if is_new && balance < T::ExistentialDeposit::get() {
+                return Err(DispatchError::from(Error::<T>::ExistentialDeposit))
+            }
+            let old = std::mem::replace(&mut account.free, balance);
+            Ok(if balance >= old {
+                SignedImbalance::Positive(PositiveImbalance::new(balance - old))
+            } else {
+                SignedImbalance::Negative(NegativeImbalance::new(old - balance))
+            })
+        });
+        imbalance.unwrap_or_else(|_| SignedImbalance::Positive(PositiveImbalance::zero()))
+    }
+}
+
+impl<T: Config> ReservableCurrency<T::AccountId> for Pallet<T> {
+    fn can_reserve(who: &T::AccountId, value: T::Balances) -> bool {
+        match Self::free_balance(who).checked_sub(&value) {
+            Some(free) => {
+                Self::ensure_can_withdraw(who, value, WithdrawReasons::RESERVE, free).is_ok()
+            },
+            None => false,
+        }
+    }
+
+    fn slash_reserved(
+        who: &T::AccountId,
+        value: T::Balances,
+    ) -> (NegativeImbalance<T>, T::Balances) {
+        if value.is_zero() {
+            return (NegativeImbalance::zero(), value)
+        }
+        // Like `slash`, an account which can't be reaped keeps the existential deposit.
+        for attempt in 0..2 {
+            let slashed: Result<_, DispatchError> = Self::try_mutate_account(who, |account, _| {
+                let best_value = match attempt {
+                    0 => value,
+                    _ => value.min(account.total().saturating_sub(T::ExistentialDeposit::get())),
+                };
+                let slashed = best_value.min(account.reserved);
+                account.reserved -= slashed;
+                Ok(slashed)
+            });
+            if let Ok(slashed) = slashed {
+                return (NegativeImbalance::new(slashed), value - slashed)
+            }
+        }
+        (NegativeImbalance::zero(), value)
+    }
+
+    fn reserved_balance(who: &T::AccountId) -> T::Balances {
+        Account::<T>::get(who).reserved
+    }
+
+    fn reserve(who: &T::AccountId, value: T::Balances) -> DispatchResult {
+        if value.is_zero() {
+            return Ok(())
+        }
+        Self::try_mutate_account(who, |account, _| -> DispatchResult {
+            let new_free = account.free.checked_sub(&value).ok_or(Error::<T>::InsufficientBalance)?;
+            account.reserved = account.reserved.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+            Self::ensure_can_withdraw(who, value, WithdrawReasons::RESERVE, new_free)?;
+            account.free = new_free;
+            Ok(())
+        })?;
+        Self::deposit_event(Event::Reserved { who: who.clone(), amount: value });
+        Ok(())
+    }
+
+    fn unreserve(who: &T::AccountId, value: T::Balances) -> T::Balances {
+        if value.is_zero() {
+            return value
+        }
+        let unreserved: Result<_, DispatchError> = Self::try_mutate_account(who, |account, _| {
+            let unreserved = value.min(account.reserved);
+            account.reserved -= unreserved;
+            // The free balance can't overflow, as it stays below the total issuance.
+            account.free = account.free.saturating_add(unreserved);
+            Ok(unreserved)
+        });
+        let unreserved = unreserved.unwrap_or_else(|_| Zero::zero());
+        if !unreserved.is_zero() {
+            Self::deposit_event(Event::Unreserved { who: who.clone(), amount: unreserved });
+        }
+        value - unreserved
+    }
+
+    fn repatriate_reserved(
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: T::Balances,
+        status: BalanceStatus,
+    ) -> Result<T::Balances, DispatchError> {
+        if value.is_zero() {
+            return Ok(Zero::zero())
+        }
+        if slashed == beneficiary {
+            return match status {
+                BalanceStatus::Free => Ok(Self::unreserve(slashed, value)),
+                BalanceStatus::Reserved => {
+                    Ok(value.saturating_sub(Self::reserved_balance(slashed)))
+                },
+            }
+        }
+        let moved = Self::try_mutate_account(beneficiary, |to, is_new| -> Result<_, DispatchError> {
+            if is_new {
+                return Err(Error::<T>::DeadAccount.into())
+            }
+            Self::try_mutate_account(slashed, |from, _| {
+                let moved = value.min(from.reserved);
+                let to = match status {
+                    BalanceStatus::Free => &mut to.free,
+                    BalanceStatus::Reserved => &mut to.reserved,
+                };
+                *to = to.checked_add(&moved).ok_or(Error::<T>::Overflow)?;
+                from.reserved -= moved;
+                Ok(moved)
+            })
+        })?;
+        Self::deposit_event(Event::ReserveRepatriated {
+            from: slashed.clone(),
+            to: beneficiary.clone(),
+            amount: moved,
+            destination_status: status,
+        });
+        Ok(value - moved)
+    }
+}
+
+impl<T: Config> LockableCurrency<T::AccountId> for Pallet<T> {
+    type Moment = T::BlockNumber;
+    type MaxLocks = T::MaxLocks;
+
+    fn set_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        amount: T::Balances,
+        reasons: WithdrawReasons,
+    ) {
+        if amount.is_zero() || reasons.is_empty() {
+            return Self::remove_lock(id, who)
+        }
+        let lock = BalanceLock { id, amount, reasons };
+        Locks::<T>::mutate(who, |locks| {
+            match locks.iter().position(|lock| lock.id == id) {
+                Some(index) => locks.as_mut_slice()[index] = lock,
+                // Dropping the lock would leave the funds free, so the bound gives way.
+                None => {
+                    let mut new_locks = std::mem::take(locks).into_inner();
+                    new_locks.push(lock);
+                    *locks = WeakBoundedVec::force_from(new_locks, Some("Balances Update Locks"));
+                },
+            }
+        });
+        Self::update_frozen(who);
+    }
+
+    fn extend_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        amount: T::Balances,
+        reasons: WithdrawReasons,
+    ) {
+        if amount.is_zero() || reasons.is_empty() {
+            return
+        }
+        let lock = Locks::<T>::get(who).iter().find(|lock| lock.id == id).cloned();
+        match lock {
+            Some(lock) => {
+                Self::set_lock(id, who, lock.amount.max(amount), lock.reasons | reasons)
+            },
+            None => Self::set_lock(id, who, amount, reasons),
+        }
+    }
+
+    fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+        Locks::<T>::mutate(who, |locks| locks.retain(|lock| lock.id != id));
+        Self::update_frozen(who);
+    }
+}
+
+impl<T: Config> NamedReservableCurrency<T::AccountId> for Pallet<T> {
+    type ReserveIdentifier = T::ReserveIdentifier;
+
+    fn reserved_balance_named(id: &T::ReserveIdentifier, who: &T::AccountId) -> T::Balances {
+        let reserves = Reserves::<T>::get(who);
+        match reserves.binary_search_by_key(id, |data| data.id) {
+            Ok(index) => reserves[index].amount,
+            Err(_) => Zero::zero(),
+        }
+    }
+
+    fn reserve_named(
+        id: &T::ReserveIdentifier,
+        who: &T::AccountId,
+        value: T::Balances,
+    ) -> DispatchResult {
+        if value.is_zero() {
+            return Ok(())
+        }
+        Self::try_mutate_reserves(who, |reserves| {
+            match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => {
+                    let amount = &mut reserves.as_mut_slice()[index].amount;
+                    *amount = amount.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+                },
+                Err(index) => reserves
+                    .try_insert(index, ReserveData { id: *id, amount: value })
+                    .map_err(|_| Error::<T>::TooManyReserves)?,
+            }
+            Self::reserve(who, value)
+        })
+    }
+
+    fn unreserve_named(
+        id: &T::ReserveIdentifier,
+        who: &T::AccountId,
+        value: T::Balances,
+    ) -> T::Balances {
+        if value.is_zero() {
+            return value
+        }
+        let left = Self::try_mutate_reserves(who, |reserves| {
+            let index = match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => index,
+                Err(_) => return Ok(value),
+            };
+            let to_change = value.min(reserves[index].amount);
+            let actual = to_change - Self::unreserve(who, to_change);
+            reserves.as_mut_slice()[index].amount -= actual;
+            if reserves[index].amount.is_zero() {
+                reserves.remove(index);
+            }
+            Ok(value - actual)
+        });
+        left.unwrap_or(value)
+    }
+
+    fn slash_reserved_named(
+        id: &T::ReserveIdentifier,
+        who: &T::AccountId,
+        value: T::Balances,
+    ) -> (NegativeImbalance<T>, T::Balances) {
+        if value.is_zero() {
+            return (NegativeImbalance::zero(), value)
+        }
+        let slashed = Self::try_mutate_reserves(who, |reserves| {
+            let index = match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => index,
+                Err(_) => return Ok((NegativeImbalance::zero(), value)),
+            };
+            let to_change = value.min(reserves[index].amount);
+            let (slashed, _) = Self::slash_reserved(who, to_change);
+            reserves.as_mut_slice()[index].amount -= slashed.peek();
+            if reserves[index].amount.is_zero() {
+                reserves.remove(index);
+            }
+            let left = value - slashed.peek();
+            Ok((slashed, left))
+        });
+        slashed.unwrap_or_else(|_| (NegativeImbalance::zero(), value))
+    }
+
+    fn repatriate_reserved_named(
+        id: &T::ReserveIdentifier,
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: T::Balances,
+        status: BalanceStatus,
+    ) -> Result<T::Balances, DispatchError> {
+        if value.is_zero() {
+            return Ok(Zero::zero())
+        }
+        if slashed == beneficiary {
+            return match status {
+                BalanceStatus::Free => Ok(Self::unreserve_named(id, slashed, value)),
+                BalanceStatus::Reserved => {
+                    Ok(value.saturating_sub(Self::reserved_balance_named(id, slashed)))
+                },
+            }
+        }
+        Self::try_mutate_reserves(slashed, |reserves| {
+            let index = match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => index,
+                Err(_) => return Ok(value),
+            };
+            let to_change = value.min(reserves[index].amount);
+            let actual = match status {
+                BalanceStatus::Free => {
+                    to_change - Self::repatriate_reserved(slashed, beneficiary, to_change, status)?
+                },
+                // The funds are reserved under the same name by the beneficiary.
+                BalanceStatus::Reserved => Self::try_mutate_reserves(beneficiary, |to_reserves| {
+                    let to_index = match to_reserves.binary_search_by_key(id, |data| data.id) {
+                        Ok(to_index) => to_index,
+                        Err(to_index) => {
+                            to_reserves
+                                .try_insert(to_index, ReserveData { id: *id, amount: Zero::zero() })
+                                .map_err(|_| Error::<T>::TooManyReserves)?;
+                            to_index
+                        },
+                    };
+                    let left = Self::repatriate_reserved(slashed, beneficiary, to_change, status)?;
+                    let actual = to_change - left;
+                    let amount = &mut to_reserves.as_mut_slice()[to_index].amount;
+                    *amount = amount.saturating_add(actual);
+                    Ok(actual)
+                })?,
+            };
+            reserves.as_mut_slice()[index].amount -= actual;
+            if reserves[index].amount.is_zero() {
+                reserves.remove(index);
+            }
+            Ok(value - actual)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock::*, pallet, Error};
+    use frame_support::{
+        dispatch::DispatchError,
+        traits::{
+            BalanceStatus, Currency, ExistenceRequirement, Imbalance, LockableCurrency,
+            NamedReservableCurrency, ReservableCurrency, SignedImbalance, WithdrawReasons,
+        },
+    };
+
+    const ID_1: [u8; 8] = *b"locker_1";
+    const ID_2: [u8; 8] = *b"locker_2";
+    const NAME_1: [u8; 8] = *b"reserve1";
+    const NAME_2: [u8; 8] = *b"reserve2";
+
+    #[test]
+    fn issue_and_burn_are_balanced_by_their_imbalances() {
+        new_test_ext().execute_with(|| {
+            let issued = Balances::issue(100);
+            assert_eq!(Balances::total_issuance(), 100);
+            Balances::resolve_creating(&1, issued);
+            assert_eq!(Balances::free_balance(&1), 100);
+            assert_eq!(Balances::total_issuance(), 100);
+
+            let burnt = Balances::burn(40);
+            assert_eq!(Balances::total_issuance(), 60);
+            assert!(Balances::settle(
+                &1,
+                burnt,
+                WithdrawReasons::TRANSFER,
+                ExistenceRequirement::KeepAlive,
+            )
+            .is_ok());
+            assert_eq!(Balances::free_balance(&1), 60);
+            assert_eq!(Balances::total_issuance(), 60);
+
+            // Burning more than was issued only burns what was.
+            assert_eq!(Balances::burn(100).peek(), 60);
+            assert_eq!(Balances::total_issuance(), 60);
+        });
+    }
+
+    #[test]
+    fn transfer_moves_free_funds() {
+        new_test_ext().execute_with(|| {
+            let _ = Balances::deposit_creating(&1, 100);
+            let allow_death = ExistenceRequirement::AllowDeath;
+            assert_eq!(<Balances as Currency<_>>::transfer(&1, &2, 30, allow_death), Ok(()));
+            assert_eq!((Balances::free_balance(&1), Balances::free_balance(&2)), (70, 30));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::Transfer {
+                from: 1,
+                to: 2,
+                amount: 30,
+            }));
+            assert_eq!(
+                <Balances as Currency<_>>::transfer(&1, &2, 71, allow_death),
+                Err(Error::<Test>::InsufficientBalance.into())
+            );
+            let _ = Balances::deposit_creating(&3, u64::MAX - 50);
+            assert_eq!(
+                <Balances as Currency<_>>::transfer(&1, &3, 70, allow_death),
+                Err(Error::<Test>::Overflow.into())
+            );
+            assert_eq!(Balances::total_issuance(), u64::MAX);
+        });
+    }
+
+    #[test]
+    fn deposits_and_withdrawals_follow_the_issuance() {
+        new_test_ext().execute_with(|| {
+            let dead = Balances::deposit_into_existing(&1, 10);
+            assert_eq!(dead.err(), Some(Error::<Test>::DeadAccount.into()));
+            drop(Balances::deposit_creating(&1, 10));
+            drop(Balances::deposit_into_existing(&1, 5));
+            assert_eq!(Balances::total_issuance(), 15);
+
+            let reasons = WithdrawReasons::TRANSFER;
+            let keep_alive = ExistenceRequirement::KeepAlive;
+            assert_eq!(
+                Balances::withdraw(&1, 16, reasons, keep_alive).err(),
+                Some(Error::<Test>::InsufficientBalance.into())
+            );
+            drop(Balances::withdraw(&1, 6, reasons, keep_alive));
+            assert_eq!(Balances::free_balance(&1), 9);
+            assert_eq!(Balances::total_issuance(), 9);
+
+            match Balances::make_free_balance_be(&1, 4) {
+                SignedImbalance::Negative(destroyed) => assert_eq!(destroyed.peek(), 5),
+                SignedImbalance::Positive(_) => panic!("the balance went down"),
+            }
+            assert_eq!(Balances::total_issuance(), 4);
+        });
+    }
+
+    #[test]
+    fn slash_takes_free_funds_first() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(Balances::reserve(&1, 60), Ok(()));
+            let (slashed, left) = Balances::slash(&1, 50);
+            assert_eq!((slashed.peek(), left), (50, 0));
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (0, 50));
+            drop(slashed);
+            let (slashed, left) = Balances::slash(&1, 80);
+            assert_eq!((slashed.peek(), left), (50, 30));
+            drop(slashed);
+            assert_eq!(Balances::total_balance(&1), 0);
+            assert_eq!(Balances::total_issuance(), 0);
+        });
+    }
+
+    #[test]
+    fn reserves_move_funds_aside() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert!(Balances::can_reserve(&1, 100));
+            assert!(!Balances::can_reserve(&1, 101));
+            assert_eq!(Balances::reserve(&1, 101), Err(Error::<Test>::InsufficientBalance.into()));
+            assert_eq!(Balances::reserve(&1, 40), Ok(()));
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (60, 40));
+            assert_eq!(Balances::total_balance(&1), 100);
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::Reserved {
+                who: 1,
+                amount: 40,
+            }));
+
+            assert_eq!(Balances::unreserve(&1, 50), 10);
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (100, 0));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::Unreserved {
+                who: 1,
+                amount: 40,
+            }));
+
+            assert_eq!(Balances::reserve(&1, 40), Ok(()));
+            let (slashed, left) = Balances::slash_reserved(&1, 30);
+            assert_eq!((slashed.peek(), left), (30, 0));
+            drop(slashed);
+            assert_eq!(Balances::reserved_balance(&1), 10);
+            assert_eq!(Balances::total_issuance(), 70);
+        });
+    }
+
+    #[test]
+    fn repatriation_moves_reserved_funds_to_another_account() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(Balances::reserve(&1, 50), Ok(()));
+            assert_eq!(
+                Balances::repatriate_reserved(&1, &2, 10, BalanceStatus::Free),
+                Err(Error::<Test>::DeadAccount.into())
+            );
+            drop(Balances::deposit_creating(&2, 1));
+            assert_eq!(Balances::repatriate_reserved(&1, &2, 10, BalanceStatus::Free), Ok(0));
+            assert_eq!(Balances::repatriate_reserved(&1, &2, 50, BalanceStatus::Reserved), Ok(10));
+            assert_eq!((Balances::free_balance(&2), Balances::reserved_balance(&2)), (11, 40));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::ReserveRepatriated {
+                from: 1,
+                to: 2,
+                amount: 40,
+                destination_status: BalanceStatus::Reserved,
+            }));
+            assert_eq!(Balances::reserved_balance(&1), 0);
+            assert_eq!(Balances::total_issuance(), 101);
+        });
+    }
+
+    #[test]
+    fn locks_hold_free_funds_for_their_reasons() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            Balances::set_lock(ID_1, &1, 60, WithdrawReasons::TRANSFER);
+            Balances::set_lock(ID_2, &1, 30, WithdrawReasons::all());
+            // The largest lock is cached whatever its reasons.
+            assert_eq!(pallet::Account::<Test>::get(1).frozen, 60);
+            let allow_death = ExistenceRequirement::AllowDeath;
+            assert_eq!(
+                <Balances as Currency<_>>::transfer(&1, &2, 41, allow_death),
+                Err(Error::<Test>::LiquidityRestrictions.into())
+            );
+            assert_eq!(<Balances as Currency<_>>::transfer(&1, &2, 40, allow_death), Ok(()));
+            // Only the lock of everything holds funds against a reserve.
+            assert_eq!(Balances::reserve(&1, 31), Err(Error::<Test>::LiquidityRestrictions.into()));
+            assert_eq!(Balances::reserve(&1, 30), Ok(()));
+
+            Balances::remove_lock(ID_1, &1);
+            Balances::extend_lock(ID_2, &1, 10, WithdrawReasons::TRANSFER);
+            assert_eq!(Balances::frozen_balance(&1, WithdrawReasons::TRANSFER), 30);
+            assert_eq!(pallet::Account::<Test>::get(1).frozen, 30);
+            Balances::set_lock(ID_2, &1, 0, WithdrawReasons::all());
+            assert!(pallet::Locks::<Test>::get(1).is_empty());
+            assert_eq!(pallet::Account::<Test>::get(1).frozen, 0);
+        });
+    }
+
+    #[test]
+    fn locks_past_the_bound_are_still_set() {
+        new_test_ext().execute_with(|| {
+            let _ = Balances::deposit_creating(&1, 100);
+            for id in 0..MAX_LOCKS as u8 {
+                Balances::set_lock([id; 8], &1, 10, WithdrawReasons::all());
+            }
+            Balances::set_lock([u8::MAX; 8], &1, 60, WithdrawReasons::all());
+            assert_eq!(pallet::Locks::<Test>::get(1).len(), MAX_LOCKS as usize + 1);
+            assert_eq!(Balances::frozen_balance(&1, WithdrawReasons::FEE), 60);
+            assert_eq!(
+                Balances::ensure_can_withdraw(&1, 50, WithdrawReasons::TRANSFER, 40),
+                Err(Error::<Test>::LiquidityRestrictions.into())
+            );
+            // The locks already set can still change.
+            Balances::set_lock([0; 8], &1, 70, WithdrawReasons::all());
+            assert_eq!(Balances::frozen_balance(&1, WithdrawReasons::FEE), 70);
+        });
+    }
+
+    #[test]
+    fn accounts_live_while_they_hold_the_existential_deposit() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            assert_eq!(Balances::deposit_creating(&1, 9).peek(), 0);
+            assert_eq!(System::providers(&1), 0);
+            drop(Balances::deposit_creating(&1, 100));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::Endowed {
+                account: 1,
+                free_balance: 100,
+            }));
+            assert_eq!(System::providers(&1), 1);
+
+            let (allow_death, keep_alive) =
+                (ExistenceRequirement::AllowDeath, ExistenceRequirement::KeepAlive);
+            let below_minimum = Err(Error::<Test>::ExistentialDeposit.into());
+            assert_eq!(<Balances as Currency<_>>::transfer(&1, &2, 9, allow_death), below_minimum);
+            assert_eq!(
+                <Balances as Currency<_>>::transfer(&1, &2, 95, keep_alive),
+                Err(Error::<Test>::KeepAlive.into())
+            );
+            assert_eq!(<Balances as Currency<_>>::transfer(&1, &2, 95, allow_death), Ok(()));
+            System::assert_has_event(RuntimeEvent::Balances(pallet::Event::DustLost {
+                account: 1,
+                amount: 5,
+            }));
+            System::assert_has_event(RuntimeEvent::Balances(pallet::Event::Endowed {
+                account: 2,
+                free_balance: 95,
+            }));
+            assert_eq!((System::providers(&1), System::providers(&2)), (0, 1));
+            assert_eq!(Balances::free_balance(&1), 0);
+            assert_eq!(DustTrap::collected(), 5);
+            assert_eq!(Balances::total_issuance(), 95);
+        });
+    }
+
+    #[test]
+    fn withdrawals_and_slashes_reap_below_the_existential_deposit() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(Balances::reserve(&1, 5), Ok(()));
+            let reasons = WithdrawReasons::TRANSFER;
+            assert_eq!(
+                Balances::withdraw(&1, 91, reasons, ExistenceRequirement::KeepAlive).err(),
+                Some(Error::<Test>::KeepAlive.into())
+            );
+            drop(Balances::withdraw(&1, 91, reasons, ExistenceRequirement::AllowDeath));
+            // The reserved balance goes with the rest of the dust.
+            assert_eq!(Balances::total_balance(&1), 0);
+            assert_eq!(DustTrap::collected(), 9);
+
+            drop(Balances::deposit_creating(&2, 50));
+            drop(Balances::slash(&2, 45));
+            assert_eq!(Balances::total_balance(&2), 0);
+            assert_eq!(DustTrap::collected(), 14);
+            assert_eq!(Balances::total_issuance(), 0);
+        });
+    }
+
+    #[test]
+    fn accounts_depended_on_are_not_reaped() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(System::inc_consumers(&1), Ok(()));
+            assert_eq!(
+                <Balances as Currency<_>>::transfer(&1, &2, 100, ExistenceRequirement::AllowDeath),
+                Err(DispatchError::ConsumerRemaining)
+            );
+            // Slashes take all they can, leaving the existential deposit.
+            let (slashed, left) = Balances::slash(&1, 100);
+            assert_eq!((slashed.peek(), left), (90, 10));
+            drop(slashed);
+            assert_eq!(Balances::free_balance(&1), 10);
+            drop(Balances::deposit_creating(&1, 90));
+            assert_eq!(Balances::reserve(&1, 95), Ok(()));
+            let (slashed, left) = Balances::slash_reserved(&1, 100);
+            assert_eq!((slashed.peek(), left), (90, 10));
+            drop(slashed);
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (5, 5));
+            assert_eq!(Balances::total_issuance(), 10);
+
+            System::dec_consumers(&1);
+            assert_eq!(Balances::unreserve(&1, 5), 0);
+            let allow_death = ExistenceRequirement::AllowDeath;
+            assert_eq!(<Balances as Currency<_>>::transfer(&1, &2, 10, allow_death), Ok(()));
+            assert_eq!(System::providers(&1), 0);
+        });
+    }
+
+    #[test]
+    fn named_reserves_are_kept_apart() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(Balances::reserve_named(&NAME_1, &1, 30), Ok(()));
+            assert_eq!(Balances::reserve_named(&NAME_2, &1, 20), Ok(()));
+            assert_eq!(Balances::reserve_named(&NAME_1, &1, 10), Ok(()));
+            assert_eq!(Balances::reserved_balance_named(&NAME_1, &1), 40);
+            assert_eq!(Balances::reserved_balance_named(&NAME_2, &1), 20);
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (40, 60));
+            assert_eq!(
+                Balances::reserve_named(&NAME_2, &1, 41),
+                Err(Error::<Test>::InsufficientBalance.into())
+            );
+            assert_eq!(Balances::reserved_balance_named(&NAME_2, &1), 20);
+
+            // Only what was reserved under the name comes back.
+            assert_eq!(Balances::unreserve_named(&NAME_2, &1, 30), 10);
+            assert_eq!(Balances::reserved_balance_named(&NAME_2, &1), 0);
+            assert_eq!(pallet::Reserves::<Test>::get(1).len(), 1);
+            let (slashed, left) = Balances::slash_reserved_named(&NAME_1, &1, 50);
+            assert_eq!((slashed.peek(), left), (40, 10));
+            drop(slashed);
+            assert!(pallet::Reserves::<Test>::get(1).is_empty());
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (60, 0));
+        });
+    }
+
+    #[test]
+    fn named_slashes_keep_the_reserves_in_step() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(Balances::reserve_named(&NAME_1, &1, 40), Ok(()));
+            assert_eq!(Balances::reserve(&1, 10), Ok(()));
+            // Slashes whose imbalances the caller throws away.
+            let _ = Balances::slash_reserved_named(&NAME_1, &1, 30);
+            let _ = Balances::slash_reserved(&1, 10);
+            let reserve = pallet::ReserveData { id: NAME_1, amount: 10 };
+            assert_eq!(pallet::Reserves::<Test>::get(1).into_inner(), vec![reserve]);
+            assert_eq!(Balances::reserved_balance(&1), 10);
+            assert_eq!(Balances::total_issuance(), 60);
+
+            let _ = Balances::slash_reserved_named(&NAME_1, &1, 50);
+            assert!(!pallet::Reserves::<Test>::contains_key(1));
+            assert_eq!(Balances::reserved_balance(&1), 0);
+            assert_eq!(Balances::total_issuance(), 50);
+        });
+    }
+
+    #[test]
+    fn named_reserves_are_bounded() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            for name in 0..MAX_RESERVES as u8 {
+                assert_eq!(Balances::reserve_named(&[name; 8], &1, 10), Ok(()));
+            }
+            assert_eq!(
+                Balances::reserve_named(&[MAX_RESERVES as u8; 8], &1, 10),
+                Err(Error::<Test>::TooManyReserves.into())
+            );
+            assert_eq!(Balances::reserved_balance(&1), 10 * MAX_RESERVES as u64);
+            // A reserve which already exists can still grow.
+            assert_eq!(Balances::reserve_named(&[0; 8], &1, 10), Ok(()));
+            assert_eq!(Balances::reserved_balance_named(&[0; 8], &1), 20);
+        });
+    }
+
+    #[test]
+    fn named_repatriation_keeps_the_name() {
+        new_test_ext().execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            drop(Balances::deposit_creating(&2, 10));
+            assert_eq!(Balances::reserve_named(&NAME_1, &1, 50), Ok(()));
+            assert_eq!(
+                Balances::repatriate_reserved_named(&NAME_1, &1, &2, 20, BalanceStatus::Free),
+                Ok(0)
+            );
+            assert_eq!(
+                Balances::repatriate_reserved_named(&NAME_1, &1, &2, 40, BalanceStatus::Reserved),
+                Ok(10)
+            );
+            assert_eq!(Balances::reserved_balance_named(&NAME_1, &1), 0);
+            assert_eq!(Balances::reserved_balance_named(&NAME_1, &2), 30);
+            assert_eq!((Balances::free_balance(&2), Balances::reserved_balance(&2)), (30, 30));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::ReserveRepatriated {
+                from: 1,
+                to: 2,
+                amount: 30,
+                destination_status: BalanceStatus::Reserved,
+            }));
+            assert_eq!(Balances::total_issuance(), 110);
+        });
+    }
+
+    #[test]
+    fn named_reserves_go_with_the_account() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            drop(Balances::deposit_creating(&1, 100));
+            assert_eq!(Balances::reserve_named(&NAME_1, &1, 95), Ok(()));
+            let (slashed, _) = Balances::slash(&1, 100);
+            assert_eq!(slashed.peek(), 100);
+            assert!(!pallet::Account::<Test>::contains_key(1));
+            assert!(!pallet::Reserves::<Test>::contains_key(1));
+        });
+    }
+}
@@ -0,0 +1,342 @@
+//! The fungible traits, implemented over the balances of the pallet. Holds are reserves.
+
+use crate::{
+    pallet::{Account, Config, Error, Pallet, TotalIssuance},
+    NegativeImbalance, PositiveImbalance,
+};
+use frame_support::{
+    dispatch::ArithmeticError,
+    pallet_prelude::*,
+    traits::{
+        fungible, BalanceStatus, Currency, DepositConsequence, ExistenceRequirement,
+        ReservableCurrency, WithdrawConsequence,
+    },
+};
+
+impl<T: Config> Pallet<T> {
+    /// What would become of depositing `amount` into `who`, minting it if `mint`. The pallet
+    /// provides for the accounts it creates, so it can always create them.
+    fn deposit_consequence(
+        who: &T::AccountId,
+        amount: T::Balances,
+        mint: bool,
+    ) -> DepositConsequence {
+        if amount.is_zero() {
+            return DepositConsequence::Success
+        }
+        if mint && TotalIssuance::<T>::get().checked_add(&amount).is_none() {
+            return DepositConsequence::Overflow
+        }
+        match Self::total_balance(who).checked_add(&amount) {
+            None => DepositConsequence::Overflow,
+            Some(total) if total < Self::minimum_balance() => DepositConsequence::BelowMinimum,
+            Some(_) => DepositConsequence::Success,
+        }
+    }
+
+    /// What would become of withdrawing `amount` from the free balance of `who`.
+    fn withdraw_consequence(
+        who: &T::AccountId,
+        amount: T::Balances,
+    ) -> WithdrawConsequence<T::Balances> {
+        if amount.is_zero() {
+            return WithdrawConsequence::Success
+        }
+        if TotalIssuance::<T>::get().checked_sub(&amount).is_none() {
+            return WithdrawConsequence::Underflow
+        }
+        let account = Account::<T>::get(who);
+        let new_free = match account.free.checked_sub(&amount) {
+            Some(new_free) => new_free,
+            None => return WithdrawConsequence::NoFunds,
+        };
+        if new_free < account.frozen {
+            return WithdrawConsequence::Frozen
+        }
+        let new_total = new_free.saturating_add(account.reserved);
+        if new_total >= Self::minimum_balance() {
+            WithdrawConsequence::Success
+        } else if frame_system::Pallet::<T>::can_dec_provider(who) {
+            WithdrawConsequence::ReducedToZero(new_total)
+        } else {
+            WithdrawConsequence::WouldDie
+        }
+    }
+}
+
+impl<T: Config> fungible::Inspect<T::AccountId> for Pallet<T> {
+    type Balance = T::Balances;
+
+    fn total_issuance() -> T::Balances {
+        TotalIssuance::<T>::get()
+    }
+
+    fn minimum_balance() -> T::Balances {
+        <Self as Currency<_>>::minimum_balance()
+    }
+
+    fn balance(who: &T::AccountId) -> T::Balances {
+        Self::total_balance(who)
+    }
+
+    fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> T::Balances {
+        let account = Account::<T>::get(who);
+        let liquid = account.free.saturating_sub(account.frozen);
+        // An account which other modules depend on can't be reaped, whatever the caller wants.
+        if !keep_alive && frame_system::Pallet::<T>::can_dec_provider(who) {
+            return liquid
+        }
+        // What stays in the account, locked or reserved, counts towards keeping it alive.
+        let staying = account.total() - liquid;
+        liquid.saturating_sub(<Self as Currency<_>>::minimum_balance().saturating_sub(staying))
+    }
+
+    fn can_deposit(who: &T::AccountId, amount: T::Balances, mint: bool) -> DepositConsequence {
+        Self::deposit_consequence(who, amount, mint)
+    }
+
+    fn can_withdraw(who: &T::AccountId, amount: T::Balances) -> WithdrawConsequence<T::Balances> {
+        Self::withdraw_consequence(who, amount)
+    }
+}
+
+impl<T: Config> fungible::Mutate<T::AccountId> for Pallet<T> {
+    fn mint_into(who: &T::AccountId, amount: T::Balances) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(())
+        }
+        Self::deposit_consequence(who, amount, true).into_result()?;
+        Self::try_mutate_account(who, |account, _| -> DispatchResult {
+            account.free += amount;
+            Ok(())
+        })?;
+        drop(PositiveImbalance::<T>::new(amount));
+        Ok(())
+    }
+
+    fn burn_from(who: &T::AccountId, amount: T::Balances) -> Result<T::Balances, DispatchError> {
+        if amount.is_zero() {
+            return Ok(amount)
+        }
+        let rest = Self::withdraw_consequence(who, amount).into_result()?;
+        // The dust of a reaped account is burnt along with the amount.
+        let ((), dust) = Self::try_mutate_account_with_dust(who, |account, _| -> DispatchResult {
+            account.free -= amount;
+            Ok(())
+        })?;
+        drop(NegativeImbalance::<T>::new(amount));
+        drop(dust);
+        Ok(amount + rest)
+    }
+}
+
+impl<T: Config> fungible::Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: T::Balances,
+        keep_alive: bool,
+    ) -> Result<T::Balances, DispatchError> {
+        let existence_requirement = if keep_alive {
+            ExistenceRequirement::KeepAlive
+        } else {
+            ExistenceRequirement::AllowDeath
+        };
+        <Self as Currency<_>>::transfer(source, dest, amount, existence_requirement)?;
+        Ok(amount)
+    }
+}
+
+impl<T: Config> fungible::Unbalanced<T::AccountId> for Pallet<T> {
+    fn set_balance(who: &T::AccountId, amount: T::Balances) -> DispatchResult {
+        Self::try_mutate_account(who, |account, _| -> DispatchResult {
+            let free = amount.checked_sub(&account.reserved).ok_or(ArithmeticError::Underflow)?;
+            account.free = free;
+            Ok(())
+        })
+    }
+
+    fn set_total_issuance(amount: T::Balances) {
+        TotalIssuance::<T>::put(amount);
+    }
+}
+
+impl<T: Config> fungible::InspectHold<T::AccountId> for Pallet<T> {
+    fn balance_on_hold(who: &T::AccountId) -> T::Balances {
+        Self::reserved_balance(who)
+    }
+
+    fn can_hold(who: &T::AccountId, amount: T::Balances) -> bool {
+        let account = Account::<T>::get(who);
+        if account.reserved.checked_add(&amount).is_none() {
+            return false
+        }
+        // What is held can then all be slashed without touching the locked funds, nor reaping
+        // the account.
+        match <Self as Currency<_>>::minimum_balance().max(account.frozen).checked_add(&amount) {
+            Some(required) => account.free >= required,
+            None => false,
+        }
+    }
+}
+
+impl<T: Config> fungible::MutateHold<T::AccountId> for Pallet<T> {
+    fn hold(who: &T::AccountId, amount: T::Balances) -> DispatchResult {
+        if !<Self as fungible::InspectHold<_>>::can_hold(who, amount) {
+            return Err(Error::<T>::InsufficientBalance.into())
+        }
+        Self::reserve(who, amount)
+    }
+
+    fn release(
+        who: &T::AccountId,
+        amount: T::Balances,
+        best_effort: bool,
+    ) -> Result<T::Balances, DispatchError> {
+        let actual = amount.min(Self::reserved_balance(who));
+        if !best_effort && actual < amount {
+            return Err(Error::<T>::InsufficientBalance.into())
+        }
+        Self::unreserve(who, actual);
+        Ok(actual)
+    }
+
+    fn transfer_held(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: T::Balances,
+        best_effort: bool,
+        on_hold: bool,
+    ) -> Result<T::Balances, DispatchError> {
+        if !best_effort && Self::reserved_balance(source) < amount {
+            return Err(Error::<T>::InsufficientBalance.into())
+        }
+        let status = if on_hold { BalanceStatus::Reserved } else { BalanceStatus::Free };
+        let left = Self::repatriate_reserved(source, dest, amount, status)?;
+        Ok(amount - left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock::*, pallet, Error};
+    use frame_support::{
+        dispatch::{ArithmeticError, TokenError},
+        traits::{
+            fungible::{Inspect, InspectHold, Mutate, MutateHold, Transfer, Unbalanced},
+            DepositConsequence, LockableCurrency, WithdrawConsequence, WithdrawReasons,
+        },
+    };
+
+    #[test]
+    fn consequences_tell_what_would_happen() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::mint_into(&1, 100), Ok(()));
+            let success = DepositConsequence::Success;
+            assert_eq!(Balances::can_deposit(&1, u64::MAX - 100, true), success);
+            let overflow = DepositConsequence::Overflow;
+            assert_eq!(Balances::can_deposit(&1, u64::MAX - 99, false), overflow);
+            assert_eq!(Balances::can_withdraw(&1, 100), WithdrawConsequence::ReducedToZero(0));
+            assert_eq!(Balances::can_withdraw(&1, 101), WithdrawConsequence::Underflow);
+
+            assert_eq!(Balances::mint_into(&2, 10), Ok(()));
+            assert_eq!(Balances::can_withdraw(&1, 101), WithdrawConsequence::NoFunds);
+            Balances::set_lock(*b"locked!!", &1, 30, WithdrawReasons::TRANSFER);
+            assert_eq!(Balances::can_withdraw(&1, 71), WithdrawConsequence::Frozen);
+            assert_eq!(Balances::reducible_balance(&1, false), 70);
+            assert_eq!(Balances::can_withdraw(&1, 70), WithdrawConsequence::Success);
+        });
+    }
+
+    #[test]
+    fn mint_and_burn_keep_the_total_issuance() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::mint_into(&1, 100), Ok(()));
+            assert_eq!(Balances::mint_into(&2, 10), Ok(()));
+            assert_eq!(Balances::mint_into(&2, u64::MAX), Err(ArithmeticError::Overflow.into()));
+            assert_eq!(Balances::burn_from(&1, 30), Ok(30));
+            assert_eq!(Balances::burn_from(&1, 71), Err(TokenError::NoFunds.into()));
+            assert_eq!(Balances::slash(&1, 100), Ok(70));
+            assert_eq!(Balances::balance(&1), 0);
+            assert_eq!(Balances::total_issuance(), 10);
+        });
+    }
+
+    #[test]
+    fn burning_below_the_minimum_burns_the_dust() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            assert_eq!(Balances::can_deposit(&1, 9, true), DepositConsequence::BelowMinimum);
+            assert_eq!(Balances::mint_into(&1, 9), Err(TokenError::BelowMinimum.into()));
+            assert_eq!(Balances::mint_into(&1, 100), Ok(()));
+            assert_eq!(Balances::reducible_balance(&1, true), 90);
+            assert_eq!(Balances::can_withdraw(&1, 95), WithdrawConsequence::ReducedToZero(5));
+            assert_eq!(Balances::burn_from(&1, 95), Ok(100));
+            assert_eq!(Balances::balance(&1), 0);
+            assert_eq!(Balances::total_issuance(), 0);
+            assert_eq!(DustTrap::collected(), 0);
+        });
+    }
+
+    #[test]
+    fn transfer_moves_the_amount() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::mint_into(&1, 100), Ok(()));
+            assert_eq!(<Balances as Transfer<_>>::transfer(&1, &2, 40, true), Ok(40));
+            assert_eq!((Balances::balance(&1), Balances::balance(&2)), (60, 40));
+            assert_eq!(
+                <Balances as Transfer<_>>::transfer(&1, &2, 61, false),
+                Err(Error::<Test>::InsufficientBalance.into())
+            );
+        });
+    }
+
+    #[test]
+    fn unbalanced_changes_leave_the_issuance_to_the_caller() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::mint_into(&1, 100), Ok(()));
+            assert_eq!(Balances::hold(&1, 40), Ok(()));
+            let underflow = Err(ArithmeticError::Underflow.into());
+            assert_eq!(<Balances as Unbalanced<_>>::set_balance(&1, 30), underflow);
+            assert_eq!(<Balances as Unbalanced<_>>::set_balance(&1, 50), Ok(()));
+            assert_eq!(Balances::reducible_balance(&1, false), 10);
+            assert_eq!(Balances::decrease_balance(&1, 11), Err(TokenError::NoFunds.into()));
+            assert_eq!(Balances::decrease_balance_at_most(&1, 11), 10);
+            assert_eq!(Balances::increase_balance(&1, 5), Ok(5));
+            assert_eq!(Balances::increase_balance_at_most(&1, u64::MAX), u64::MAX - 45);
+            assert_eq!(Balances::balance(&1), u64::MAX);
+            assert_eq!(Balances::total_issuance(), 100);
+            Balances::set_total_issuance(u64::MAX);
+            assert_eq!(Balances::total_issuance(), u64::MAX);
+        });
+    }
+
+    #[test]
+    fn holds_are_reserves() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::mint_into(&1, 100), Ok(()));
+            assert_eq!(Balances::mint_into(&2, 1), Ok(()));
+            Balances::set_lock(*b"locked!!", &1, 30, WithdrawReasons::all());
+            assert!(Balances::can_hold(&1, 70));
+            assert!(!Balances::can_hold(&1, 71));
+            assert_eq!(Balances::hold(&1, 71), Err(Error::<Test>::InsufficientBalance.into()));
+            assert_eq!(Balances::hold(&1, 50), Ok(()));
+            assert_eq!(Balances::balance_on_hold(&1), 50);
+
+            assert_eq!(
+                Balances::release(&1, 60, false),
+                Err(Error::<Test>::InsufficientBalance.into())
+            );
+            assert_eq!(Balances::release(&1, 10, false), Ok(10));
+            assert_eq!(
+                Balances::transfer_held(&1, &2, 50, false, true),
+                Err(Error::<Test>::InsufficientBalance.into())
+            );
+            assert_eq!(Balances::transfer_held(&1, &2, 15, false, true), Ok(15));
+            assert_eq!(Balances::transfer_held(&1, &2, 50, true, false), Ok(25));
+            assert_eq!(Balances::balance_on_hold(&1), 0);
+            let account = pallet::Account::<Test>::get(2);
+            assert_eq!((account.free, account.reserved), (26, 15));
+            assert_eq!(Balances::total_issuance(), 101);
+        });
+    }
+}
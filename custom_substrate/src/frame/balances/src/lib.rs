@@ -1,39 +1,500 @@
 
+//! The balances of accounts, in a currency which other pallets use through the
+//! [`Currency`](frame_support::traits::Currency) traits.
+//!
+//! The free balance of an account can be spent, unless locks hold part of it, and its reserved
+//! balance is set aside, e.g. as the deposit of something it holds, possibly under the name of
+//! the pallet reserving it. Funds are only created and destroyed through imbalances, so the total
+//! issuance stays the sum of all balances.
+//!
+//! An account exists while its balance is at least the existential deposit, providing for it in
+//! the system pallet. An account left below is reaped, and what remains of its balance is dust
+//! handed to `DustRemoval`.
+
+pub use imbalances::{NegativeImbalance, PositiveImbalance};
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod imbalances;
+mod impl_currency;
+mod impl_fungible;
+#[cfg(test)]
+mod mock;
+pub mod weights;
 
 pub mod pallet {
 
-    use frame_support::pallet_prelude::*;
-    
-    pub trait Config: Sized {
-        type Event: From<Event<Self>>;
-        type AccountId: Eq + Hash;
-        type Balances: Eq + Hash + Default + Zero + Copy + CheckedSub + CheckedAdd;
+    use frame_support::{
+        codec::HasCompact,
+        dispatch::{annotated_dispatch_info, ensure_root, ensure_signed, ModuleError},
+        pallet_prelude::*,
+        traits::{
+            fungible, BalanceStatus, Currency, ExistenceRequirement, LockIdentifier, OnUnbalanced,
+            StaticLookup, WithdrawReasons,
+        },
+    };
+    use crate::{weights::WeightInfo, NegativeImbalance, PositiveImbalance};
+    use std::convert::Infallible;
+
+    /// The way calls name an account.
+    pub type AccountIdLookupOf<T> =
+        <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
+
+    pub trait Config: frame_system::Config {
+        /// The event type of the runtime.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// The balance of an account.
+        type Balances: Parameter + Member + AtLeast32BitUnsigned + Default + MaxEncodedLen
+            + HasCompact + FixedPointOperand;
+        /// The maximum number of locks on an account. Locks beyond it are still set, with a
+        /// warning, so it should exceed the number of pallets locking funds.
+        type MaxLocks: Get<u32>;
+        /// The minimum balance of an account, below which it is reaped. It must not be zero.
+        type ExistentialDeposit: Get<Self::Balances>;
+        /// What to do with the dust of the reaped accounts.
+        type DustRemoval: OnUnbalanced<NegativeImbalance<Self>>;
+        /// The name of a reserve, e.g. of the pallet reserving it.
+        type ReserveIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+        /// The maximum number of named reserves on an account.
+        type MaxReserves: Get<u32>;
+        type WeightInfo: WeightInfo;
+    }
+
+    /// The balances of an account.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Default, Debug)]
+    pub struct AccountData<Balance> {
+        /// The balance which can be spent, unless frozen.
+        pub free: Balance,
+        /// The balance set aside, which can only be slashed or unreserved.
+        pub reserved: Balance,
+        /// The part of the free balance held by the largest lock, whatever its reasons.
+        pub frozen: Balance,
     }
 
+    impl<Balance: AtLeast32BitUnsigned + Copy> AccountData<Balance> {
+        /// The free and reserved balances together.
+        pub fn total(&self) -> Balance {
+            self.free.saturating_add(self.reserved)
+        }
+    }
+
+    /// An amount reserved under a name.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Debug)]
+    pub struct ReserveData<ReserveIdentifier, Balance> {
+        /// The name of the reserve.
+        pub id: ReserveIdentifier,
+        /// The amount reserved under it.
+        pub amount: Balance,
+    }
+
+    /// A lock on the free balance of an account.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Debug)]
+    pub struct BalanceLock<Balance> {
+        /// The name of the lock.
+        pub id: LockIdentifier,
+        /// The amount of the free balance which can't go.
+        pub amount: Balance,
+        /// What the amount can't go for.
+        pub reasons: WithdrawReasons,
+    }
+
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
     pub enum Event<T: Config> {
-        Dummy(PhantomData<T>)
+        /// The balances of an account were set.
+        BalanceSet { who: T::AccountId, free: T::Balances, reserved: T::Balances },
+        /// Funds were transferred from an account to another.
+        Transfer { from: T::AccountId, to: T::AccountId, amount: T::Balances },
+        /// An account was created with a free balance.
+        Endowed { account: T::AccountId, free_balance: T::Balances },
+        /// An account was reaped, losing what remained of its balance.
+        DustLost { account: T::AccountId, amount: T::Balances },
+        /// Funds were moved from the free to the reserved balance of an account.
+        Reserved { who: T::AccountId, amount: T::Balances },
+        /// Funds were moved from the reserved to the free balance of an account.
+        Unreserved { who: T::AccountId, amount: T::Balances },
+        /// Reserved funds were moved to another account, where they are free or reserved.
+        ReserveRepatriated {
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::Balances,
+            destination_status: BalanceStatus,
+        },
     }
 
-    #[derive(PartialEq, Debug)]
-    pub struct Pallet<T: Config> {
-        pub balance: HashMap<T::AccountId, T::Balances>
+    /// Why a call of the pallet failed.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    pub enum Error<T> {
+        /// The balance is too low.
+        InsufficientBalance,
+        /// A lock holds the funds.
+        LiquidityRestrictions,
+        /// The account does not exist.
+        DeadAccount,
+        /// The balance would overflow.
+        Overflow,
+        /// The account would be created below the existential deposit.
+        ExistentialDeposit,
+        /// The account would be reaped, while it must be kept alive.
+        KeepAlive,
+        /// The account has as many named reserves as it can.
+        TooManyReserves,
+        #[doc(hidden)]
+        #[codec(skip)]
+        __Ignore(PhantomData<T>, Infallible),
+    }
+
+    impl<T> Error<T> {
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Error::InsufficientBalance => "InsufficientBalance",
+                Error::LiquidityRestrictions => "LiquidityRestrictions",
+                Error::DeadAccount => "DeadAccount",
+                Error::Overflow => "Overflow",
+                Error::ExistentialDeposit => "ExistentialDeposit",
+                Error::KeepAlive => "KeepAlive",
+                Error::TooManyReserves => "TooManyReserves",
+                Error::__Ignore(_, never) => match *never {},
+            }
+        }
+    }
+
+    impl<T: Config> From<Error<T>> for DispatchError {
+        fn from(error: Error<T>) -> Self {
+            // Out of a runtime which knows the pallet, only the message is left.
+            ModuleError::new::<T::PalletInfo, Pallet<T>>(&error, error.as_str())
+                .map_or(DispatchError::Other(error.as_str()), DispatchError::Module)
+        }
+    }
+
+    /// The dispatchable functions of the pallet, with their arguments.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    #[allow(non_camel_case_types)]
+    pub enum Call<T: Config> {
+        /// Transfer `value` from the sender to `dest`, which may reap the sender.
+        #[codec(index = 0)]
+        transfer {
+            dest: AccountIdLookupOf<T>,
+            #[codec(compact)]
+            value: T::Balances,
+        },
+        /// Set the free and reserved balances of `who`. Root only.
+        #[codec(index = 1)]
+        set_balance {
+            who: AccountIdLookupOf<T>,
+            #[codec(compact)]
+            new_free: T::Balances,
+            #[codec(compact)]
+            new_reserved: T::Balances,
+        },
+        /// Transfer `value` from `source` to `dest`, which may reap `source`. Root only.
+        #[codec(index = 2)]
+        force_transfer {
+            source: AccountIdLookupOf<T>,
+            dest: AccountIdLookupOf<T>,
+            #[codec(compact)]
+            value: T::Balances,
+        },
+        /// Transfer `value` from the sender to `dest`, keeping the sender alive.
+        #[codec(index = 3)]
+        transfer_keep_alive {
+            dest: AccountIdLookupOf<T>,
+            #[codec(compact)]
+            value: T::Balances,
+        },
+        /// Transfer all the funds the sender can spend to `dest`, keeping the sender alive if
+        /// `keep_alive`.
+        #[codec(index = 4)]
+        transfer_all { dest: AccountIdLookupOf<T>, keep_alive: bool },
+    }
+
+    impl<T: Config> GetDispatchInfo for Call<T> {
+        fn get_dispatch_info(&self) -> DispatchInfo {
+            match self {
+                Call::transfer { dest, value } => {
+                    annotated_dispatch_info(&T::WeightInfo::transfer(), (dest, value))
+                }
+                Call::set_balance { who, new_free, new_reserved } => annotated_dispatch_info(
+                    &(T::WeightInfo::set_balance(), DispatchClass::Operational),
+                    (who, new_free, new_reserved),
+                ),
+                Call::force_transfer { source, dest, value } => annotated_dispatch_info(
+                    &(T::WeightInfo::force_transfer(), DispatchClass::Operational),
+                    (source, dest, value),
+                ),
+                Call::transfer_keep_alive { dest, value } => {
+                    annotated_dispatch_info(&T::WeightInfo::transfer_keep_alive(), (dest, value))
+                }
+                Call::transfer_all { dest, keep_alive } => {
+                    annotated_dispatch_info(&T::WeightInfo::transfer_all(), (dest, keep_alive))
+                }
+            }
+        }
+    }
+
+    impl<T: Config> UnfilteredDispatchable for Call<T> {
+        type RuntimeOrigin = T::RuntimeOrigin;
+
+        fn dispatch_bypass_filter(self, origin: Self::RuntimeOrigin) -> DispatchResultWithPostInfo {
+            match self {
+                Call::transfer { dest, value } => Pallet::<T>::transfer(origin, dest, value)?,
+                Call::set_balance { who, new_free, new_reserved } => {
+                    Pallet::<T>::set_balance(origin, who, new_free, new_reserved)?
+                }
+                Call::force_transfer { source, dest, value } => {
+                    Pallet::<T>::force_transfer(origin, source, dest, value)?
+                }
+                Call::transfer_keep_alive { dest, value } => {
+                    Pallet::<T>::transfer_keep_alive(origin, dest, value)?
+                }
+                Call::transfer_all { dest, keep_alive } => {
+                    Pallet::<T>::transfer_all(origin, dest, keep_alive)?
+                }
+            }
+            Ok(().into())
+        }
     }
 
+    #[derive(PartialEq, Debug)]
+    pub struct Pallet<T: Config>(PhantomData<T>);
+
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+    frame_support::storage_prefix!(pub AccountPrefix => "Balances", "Account");
+
+    /// The balances of every account.
+    pub type Account<T> = StorageMap<
+        AccountPrefix,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        AccountData<<T as Config>::Balances>,
+        ValueQuery,
+    >;
+
+    frame_support::storage_prefix!(pub LocksPrefix => "Balances", "Locks");
+
+    /// The locks on the free balance of every account.
+    pub type Locks<T> = StorageMap<
+        LocksPrefix,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        WeakBoundedVec<BalanceLock<<T as Config>::Balances>, <T as Config>::MaxLocks>,
+        ValueQuery,
+    >;
+
+    frame_support::storage_prefix!(pub ReservesPrefix => "Balances", "Reserves");
+
+    /// The named reserves of every account, sorted by name.
+    pub type Reserves<T> = StorageMap<
+        ReservesPrefix,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<
+            ReserveData<<T as Config>::ReserveIdentifier, <T as Config>::Balances>,
+            <T as Config>::MaxReserves,
+        >,
+        ValueQuery,
+    >;
+
+    frame_support::storage_prefix!(pub TotalIssuancePrefix => "Balances", "TotalIssuance");
+
+    /// The sum of the balances of all accounts.
+    pub type TotalIssuance<T> =
+        StorageValue<TotalIssuancePrefix, <T as Config>::Balances, ValueQuery>;
+
+    frame_support::impl_storage_info!(Pallet<T: Config> {
+        Account<T>,
+        Locks<T>,
+        Reserves<T>,
+        TotalIssuance<T>,
+    });
+
+    frame_support::impl_constant_metadata!(Pallet<T: Config> {
+        /// The maximum number of locks on an account.
+        MaxLocks: u32 = T::MaxLocks::get(),
+        /// The maximum number of named reserves on an account.
+        MaxReserves: u32 = T::MaxReserves::get(),
+        /// The minimum balance of an account.
+        ExistentialDeposit: T::Balances = T::ExistentialDeposit::get(),
+    });
+
+    frame_support::impl_storage_metadata!(Pallet<T: Config> => "Balances" {
+        /// The balances of every account.
+        Account<T>,
+        /// The locks on the free balance of every account.
+        Locks<T>,
+        /// The named reserves of every account, sorted by name.
+        Reserves<T>,
+        /// The sum of the balances of all accounts.
+        TotalIssuance<T>,
+    });
+
     impl<T: Config> Pallet<T> {
-        
-        pub fn new() -> Self {
-            Self {
-                balance: HashMap::new()
+        /// Transfer `value` from the sender to `dest`, which may reap the sender.
+        pub fn transfer(
+            origin: T::RuntimeOrigin,
+            dest: AccountIdLookupOf<T>,
+            value: T::Balances,
+        ) -> DispatchResult {
+            let transactor = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            let allow_death = ExistenceRequirement::AllowDeath;
+            <Self as Currency<_>>::transfer(&transactor, &dest, value, allow_death)
+        }
+
+        /// Set the free and reserved balances of `who`, which only root can do. Both are set to
+        /// zero, reaping the account, if they add up to less than the existential deposit.
+        pub fn set_balance(
+            origin: T::RuntimeOrigin,
+            who: AccountIdLookupOf<T>,
+            new_free: T::Balances,
+            new_reserved: T::Balances,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            let new_total = new_free.checked_add(&new_reserved).ok_or(Error::<T>::Overflow)?;
+            let (new_free, new_reserved, new_total) = if new_total < T::ExistentialDeposit::get() {
+                (Zero::zero(), Zero::zero(), Zero::zero())
+            } else {
+                (new_free, new_reserved, new_total)
+            };
+            let old_total = Self::total_balance(&who);
+            // The total issuance covers the old balances, so only the new ones can overflow it.
+            TotalIssuance::<T>::get()
+                .saturating_sub(old_total)
+                .checked_add(&new_total)
+                .ok_or(Error::<T>::Overflow)?;
+
+            Self::try_mutate_account(&who, |account, _| -> DispatchResult {
+                account.free = new_free;
+                account.reserved = new_reserved;
+                Ok(())
+            })?;
+            if new_total > old_total {
+                drop(PositiveImbalance::<T>::new(new_total - old_total));
+            } else if new_total < old_total {
+                drop(NegativeImbalance::<T>::new(old_total - new_total));
             }
+            Self::deposit_event(Event::BalanceSet { who, free: new_free, reserved: new_reserved });
+            Ok(())
+        }
+
+        /// Transfer `value` from `source` to `dest`, which may reap `source` and only root can
+        /// do.
+        pub fn force_transfer(
+            origin: T::RuntimeOrigin,
+            source: AccountIdLookupOf<T>,
+            dest: AccountIdLookupOf<T>,
+            value: T::Balances,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let source = T::Lookup::lookup(source)?;
+            let dest = T::Lookup::lookup(dest)?;
+            let allow_death = ExistenceRequirement::AllowDeath;
+            <Self as Currency<_>>::transfer(&source, &dest, value, allow_death)
         }
 
-        pub fn set_balances(&mut self, account: T::AccountId, balance: T::Balances) {
-            self.balance.insert(account, balance);
+        /// Transfer `value` from the sender to `dest`, failing rather than reaping the sender.
+        pub fn transfer_keep_alive(
+            origin: T::RuntimeOrigin,
+            dest: AccountIdLookupOf<T>,
+            value: T::Balances,
+        ) -> DispatchResult {
+            let transactor = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            let keep_alive = ExistenceRequirement::KeepAlive;
+            <Self as Currency<_>>::transfer(&transactor, &dest, value, keep_alive)
         }
 
-        pub fn get_balances(&self, account: T::AccountId) -> T::Balances {
-            *self.balance.get(&account).unwrap_or(&T::Balances::zero())
+        /// Transfer all the funds the sender can spend to `dest`. Unless `keep_alive`, this
+        /// reaps the sender if nothing is locked or reserved.
+        pub fn transfer_all(
+            origin: T::RuntimeOrigin,
+            dest: AccountIdLookupOf<T>,
+            keep_alive: bool,
+        ) -> DispatchResult {
+            let transactor = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            let value = <Self as fungible::Inspect<_>>::reducible_balance(&transactor, keep_alive);
+            let existence_requirement = if keep_alive {
+                ExistenceRequirement::KeepAlive
+            } else {
+                ExistenceRequirement::AllowDeath
+            };
+            <Self as Currency<_>>::transfer(&transactor, &dest, value, existence_requirement)
+        }
+
+        pub fn get_balances(account: T::AccountId) -> T::Balances {
+            Account::<T>::get(account).free
+        }
+
+        /// The largest amount locked against a withdrawal for any of `reasons`.
+        pub fn frozen_balance(who: &T::AccountId, reasons: WithdrawReasons) -> T::Balances {
+            Locks::<T>::get(who)
+                .iter()
+                .filter(|lock| lock.reasons.intersects(reasons))
+                .map(|lock| lock.amount)
+                .max()
+                .unwrap_or_else(Zero::zero)
+        }
+
+        /// Change the balances of `who` with `f`, which is told whether the account is new. The
+        /// account is created if it then holds the existential deposit, and reaped with its dust
+        /// going to `DustRemoval` if it drops below. Nothing changes if `f` fails, or if the
+        /// account can't be reaped while other modules depend on it.
+        pub(crate) fn try_mutate_account<R, E: From<DispatchError>>(
+            who: &T::AccountId,
+            f: impl FnOnce(&mut AccountData<T::Balances>, bool) -> Result<R, E>,
+        ) -> Result<R, E> {
+            let (result, dust) = Self::try_mutate_account_with_dust(who, f)?;
+            if let Some(dust) = dust {
+                T::DustRemoval::on_unbalanced(dust);
+            }
+            Ok(result)
+        }
+
+        /// Like [`Self::try_mutate_account`], but gives the dust back to the caller.
+        pub(crate) fn try_mutate_account_with_dust<R, E: From<DispatchError>>(
+            who: &T::AccountId,
+            f: impl FnOnce(&mut AccountData<T::Balances>, bool) -> Result<R, E>,
+        ) -> Result<(R, Option<NegativeImbalance<T>>), E> {
+            let mut account = Account::<T>::get(who);
+            let is_new = account.total().is_zero();
+            if is_new {
+                // Locks outlive the account they were set on.
+                account.frozen = Self::frozen_balance(who, WithdrawReasons::all());
+            }
+            let result = f(&mut account, is_new)?;
+
+            let total = account.total();
+            if total >= T::ExistentialDeposit::get() {
+                let free_balance = account.free;
+                Account::<T>::insert(who, account);
+                if is_new {
+                    frame_system::Pallet::<T>::inc_providers(who);
+                    Self::deposit_event(Event::Endowed { account: who.clone(), free_balance });
+                }
+                return Ok((result, None))
+            }
+
+            if !is_new {
+                frame_system::Pallet::<T>::dec_providers(who)?;
+            }
+            // The named reserves go with the reserved balance.
+            Account::<T>::remove(who);
+            Reserves::<T>::remove(who);
+            if total.is_zero() {
+                return Ok((result, None))
+            }
+            Self::deposit_event(Event::DustLost { account: who.clone(), amount: total });
+            Ok((result, Some(NegativeImbalance::new(total))))
+        }
+
+        pub(crate) fn deposit_event(event: Event<T>) {
+            let event = <T as Config>::RuntimeEvent::from(event);
+            frame_system::Pallet::<T>::deposit_event(event.into());
         }
     }
 }
@@ -41,38 +502,259 @@ pub mod pallet {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use crate::mock::*;
+    use frame_support::{
+        metadata::{StorageEntryModifier, StorageEntryType, StorageHasher},
+        pallet_prelude::*,
+        scale_info::meta_type,
+        traits::{Currency, Imbalance, OnUnbalanced, ReservableCurrency, SameOrOther},
+    };
 
-    #[derive(PartialEq, Debug)]
-    struct Test {}
-    struct Event {}
+    #[test]
+    fn balance_pallet_new_works() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(pallet::Account::<Test>::iter().count(), 0);
+        });
+    }
 
-    impl<T: pallet::Config> From<pallet::Event<T>> for Event {
-        fn from(_: pallet::Event<T>) -> Self {
-            Self {}
-        }
+    #[test]
+    fn set_balance_should_work() {
+        new_test_ext().execute_with(|| {
+            let user1 = 1;
+            let user2 = 2;
+            assert_eq!(pallet::Account::<Test>::iter().count(), 0);
+            let root = RuntimeOrigin::root();
+            assert_eq!(pallet::Pallet::<Test>::set_balance(root, user1, 100, 0), Ok(()));
+            assert_eq!(pallet::Pallet::<Test>::get_balances(user1), 100);
+            assert_eq!(pallet::Pallet::<Test>::get_balances(user2), 0);
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::BalanceSet {
+                who: user1,
+                free: 100,
+                reserved: 0,
+            }));
+        });
     }
-    
-    impl pallet::Config for Test {
-        type Event = Event;
-        type AccountId = i32;
-        type Balances = i32;
+
+    #[test]
+    fn set_balance_requires_root() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                pallet::Pallet::<Test>::set_balance(RuntimeOrigin::signed(1), 1, 100, 0),
+                Err(DispatchError::BadOrigin)
+            );
+            assert_eq!(pallet::Pallet::<Test>::get_balances(1), 0);
+        });
     }
 
     #[test]
-    fn balance_pallet_new_works() {
-        let balance = pallet::Pallet::<Test>::new();
-        assert_eq!(balance.balance, HashMap::new());
+    fn set_balance_keeps_the_total_issuance() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 100, 0), Ok(()));
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 2, 50, 0), Ok(()));
+            assert_eq!(Balances::total_issuance(), 150);
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 30, 0), Ok(()));
+            assert_eq!(Balances::total_issuance(), 80);
+        });
     }
 
     #[test]
-    fn set_balance_should_work() {
-        let mut pallet_balance = pallet::Pallet::<Test>::new();
-        let user1 = 1;
-        let user2 = 2;
-        assert_eq!(pallet_balance.balance, HashMap::new());
-        pallet_balance.set_balances(user1, 100);
-        assert_eq!(pallet_balance.get_balances(user1), 100);
-        assert_eq!(pallet_balance.get_balances(user2), 0);
-    }
-}
\ No newline at end of file
+    fn set_balance_sets_the_reserved_balance_too() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 60, 40), Ok(()));
+            assert_eq!((Balances::free_balance(&1), Balances::reserved_balance(&1)), (60, 40));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::BalanceSet {
+                who: 1,
+                free: 60,
+                reserved: 40,
+            }));
+            assert_eq!(Balances::total_issuance(), 100);
+
+            let overflow = Err(Error::<Test>::Overflow.into());
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, u64::MAX, 1), overflow);
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 2, u64::MAX, 0), overflow);
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, u64::MAX, 0), Ok(()));
+            assert_eq!(Balances::total_issuance(), u64::MAX);
+
+            // Below the existential deposit, the account is reaped.
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 5, 4), Ok(()));
+            assert_eq!(Balances::total_balance(&1), 0);
+            assert_eq!(System::providers(&1), 0);
+            assert_eq!(Balances::total_issuance(), 0);
+        });
+    }
+
+    #[test]
+    fn transfers_move_funds_between_accounts() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 100, 0), Ok(()));
+            let bad_origin = Err(DispatchError::BadOrigin);
+            assert_eq!(Balances::transfer(RuntimeOrigin::root(), 2, 30), bad_origin);
+            assert_eq!(Balances::transfer(RuntimeOrigin::signed(1), 2, 30), Ok(()));
+            System::assert_last_event(RuntimeEvent::Balances(pallet::Event::Transfer {
+                from: 1,
+                to: 2,
+                amount: 30,
+            }));
+            assert_eq!(
+                Balances::transfer(RuntimeOrigin::signed(1), 2, 71),
+                Err(Error::<Test>::InsufficientBalance.into())
+            );
+            assert_eq!(
+                Balances::transfer_keep_alive(RuntimeOrigin::signed(1), 2, 65),
+                Err(Error::<Test>::KeepAlive.into())
+            );
+            assert_eq!(Balances::transfer_keep_alive(RuntimeOrigin::signed(1), 2, 60), Ok(()));
+            assert_eq!((Balances::free_balance(&1), Balances::free_balance(&2)), (10, 90));
+
+            assert_eq!(Balances::force_transfer(RuntimeOrigin::signed(2), 2, 3, 10), bad_origin);
+            assert_eq!(Balances::force_transfer(RuntimeOrigin::root(), 2, 3, 10), Ok(()));
+            assert_eq!(Balances::transfer_all(RuntimeOrigin::signed(2), 3, true), Ok(()));
+            assert_eq!((Balances::free_balance(&2), Balances::free_balance(&3)), (10, 80));
+            assert_eq!(Balances::transfer_all(RuntimeOrigin::signed(2), 3, false), Ok(()));
+            assert_eq!(Balances::transfer_all(RuntimeOrigin::signed(1), 3, false), Ok(()));
+            assert_eq!((System::providers(&1), System::providers(&2)), (0, 0));
+            assert_eq!(Balances::free_balance(&3), 100);
+            assert_eq!(Balances::total_issuance(), 100);
+        });
+    }
+
+    #[test]
+    fn transfer_all_keeps_accounts_depended_on_alive() {
+        new_test_ext_with_existential_deposit(10).execute_with(|| {
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 100, 0), Ok(()));
+            assert_eq!(System::inc_consumers(&1), Ok(()));
+            assert_eq!(Balances::transfer_all(RuntimeOrigin::signed(1), 2, false), Ok(()));
+            assert_eq!((Balances::free_balance(&1), Balances::free_balance(&2)), (10, 90));
+            assert_eq!(System::providers(&1), 1);
+        });
+    }
+
+    #[test]
+    fn issuance_follows_imbalances_when_dropped() {
+        new_test_ext().execute_with(|| {
+            let created = PositiveImbalance::<Test>::new(100);
+            assert_eq!(Balances::total_issuance(), 0);
+            let (deposited, rest) = created.split(60);
+            assert_eq!((deposited.peek(), rest.peek()), (60, 40));
+            drop(deposited);
+            assert_eq!(Balances::total_issuance(), 60);
+
+            let slashed = NegativeImbalance::<Test>::new(15).merge(NegativeImbalance::new(10));
+            let rest = match rest.offset(slashed) {
+                SameOrOther::Same(rest) => rest,
+                _ => panic!("the positive side is larger"),
+            };
+            assert_eq!(Balances::total_issuance(), 60);
+            assert_eq!(rest.peek(), 15);
+            <() as OnUnbalanced<_>>::on_unbalanced(rest);
+            assert_eq!(Balances::total_issuance(), 75);
+        });
+    }
+
+    #[test]
+    fn discarded_slashes_still_leave_the_issuance() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 100, 0), Ok(()));
+            // A slash of 30, whose imbalance the caller throws away.
+            pallet::Account::<Test>::mutate(1, |balance| balance.free -= 30);
+            let _ = NegativeImbalance::<Test>::new(30);
+            assert_eq!(Balances::total_issuance(), 70);
+            assert_eq!(Balances::total_issuance(), Balances::get_balances(1));
+
+            let cancelled = NegativeImbalance::<Test>::new(1).offset(PositiveImbalance::new(1));
+            assert!(cancelled.try_none().is_ok());
+            assert!(PositiveImbalance::<Test>::zero().drop_zero().is_ok());
+            assert_eq!(Balances::total_issuance(), 70);
+        });
+    }
+
+    #[test]
+    fn calls_dispatch_to_the_pallet() {
+        new_test_ext().execute_with(|| {
+            let call = pallet::Call::<Test>::set_balance { who: 1, new_free: 100, new_reserved: 0 };
+            assert_eq!(
+                call.clone().dispatch_bypass_filter(RuntimeOrigin::none()),
+                Err(DispatchError::BadOrigin.into())
+            );
+            assert_eq!(call.dispatch_bypass_filter(RuntimeOrigin::root()), Ok(().into()));
+            let call = pallet::Call::<Test>::transfer_all { dest: 2, keep_alive: false };
+            assert_eq!(call.dispatch_bypass_filter(RuntimeOrigin::signed(1)), Ok(().into()));
+            assert_eq!(pallet::Pallet::<Test>::get_balances(2), 100);
+        });
+    }
+
+    #[test]
+    fn calls_encode_balances_compactly() {
+        let call = pallet::Call::<Test>::transfer { dest: 2, value: 100 };
+        assert_eq!(call.encode(), vec![0, 2, 0, 0, 0, 145, 1]);
+        let call = pallet::Call::<Test>::set_balance { who: 2, new_free: 1, new_reserved: 0 };
+        assert_eq!(call.encode(), vec![1, 2, 0, 0, 0, 4, 0]);
+        assert_eq!(pallet::Call::<Test>::decode(&mut &call.encode()[..]), Ok(call));
+    }
+
+    #[test]
+    fn root_calls_are_operational() {
+        let call = pallet::Call::<Test>::set_balance { who: 1, new_free: 100, new_reserved: 0 };
+        assert_eq!(
+            call.get_dispatch_info(),
+            DispatchInfo {
+                weight: <() as WeightInfo>::set_balance(),
+                class: DispatchClass::Operational,
+                pays_fee: Pays::Yes,
+            }
+        );
+        let call = pallet::Call::<Test>::force_transfer { source: 1, dest: 2, value: 100 };
+        assert_eq!(call.get_dispatch_info().class, DispatchClass::Operational);
+        let call = pallet::Call::<Test>::transfer { dest: 2, value: 100 };
+        assert_eq!(call.get_dispatch_info().class, DispatchClass::Normal);
+    }
+
+    #[test]
+    fn storage_info_is_bounded() {
+        let info = pallet::Pallet::<Test>::storage_info();
+        assert_eq!(info.len(), 4);
+        assert_eq!(info[0].storage_name, b"Account".to_vec());
+        assert_eq!(info[0].max_values, None);
+        // `Blake2_128Concat` hash and account id, then the free, reserved and frozen balances.
+        assert_eq!(info[0].max_size, Some(16 + 4 + 3 * 8));
+        // The length of the locks, then each with its id, amount and reasons.
+        assert_eq!(info[1].storage_name, b"Locks".to_vec());
+        assert_eq!(info[1].max_size, Some(16 + 4 + 1 + MAX_LOCKS * (8 + 8 + 1)));
+        // The length of the reserves, then each with its name and amount.
+        assert_eq!(info[2].storage_name, b"Reserves".to_vec());
+        assert_eq!(info[2].max_size, Some(16 + 4 + 1 + MAX_RESERVES * (8 + 8)));
+        assert_eq!(info[3].storage_name, b"TotalIssuance".to_vec());
+        assert_eq!(info[3].max_values, Some(1));
+        assert_eq!(info[3].max_size, Some(8));
+    }
+
+    #[test]
+    fn storage_metadata_works() {
+        let metadata = pallet::Pallet::<Test>::storage_metadata();
+        assert_eq!(metadata.prefix, "Balances");
+        let account = metadata.entry("Account").unwrap();
+        assert_eq!(account.docs, vec!["The balances of every account."]);
+        assert_eq!(account.modifier, StorageEntryModifier::Default);
+        assert_eq!(account.default, vec![0; 24]);
+        assert_eq!(
+            account.ty,
+            StorageEntryType::Map {
+                hashers: vec![StorageHasher::Blake2_128Concat],
+                key: meta_type::<i32>(),
+                value: meta_type::<AccountData<u64>>(),
+            }
+        );
+    }
+
+    #[test]
+    fn constants_are_described() {
+        let constants = Balances::constants_metadata();
+        assert_eq!(constants[0].name, "MaxLocks");
+        assert_eq!(constants[0].value, MAX_LOCKS.encode());
+        assert_eq!(constants[0].docs, vec!["The maximum number of locks on an account."]);
+        assert_eq!(constants[1].name, "MaxReserves");
+        assert_eq!(constants[1].value, MAX_RESERVES.encode());
+        assert_eq!(constants[2].name, "ExistentialDeposit");
+        assert_eq!(constants[2].value, 1u64.encode());
+    }
+}
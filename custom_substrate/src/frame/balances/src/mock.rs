@@ -0,0 +1,94 @@
+//! A runtime with the system and the balances pallets, to test the latter.
+
+use crate::{self as pallet_balances, NegativeImbalance};
+use frame_support::{
+    hashing::BlakeTwo256,
+    storage::TestExternalities,
+    traits::{ConstU32, Everything, Get, IdentityLookup, Imbalance, OnUnbalanced},
+};
+use std::cell::Cell;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system::{Pallet, Call, Storage, Origin<T>, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+pub const MAX_LOCKS: u32 = 4;
+pub const MAX_RESERVES: u32 = 2;
+
+thread_local! {
+    static EXISTENTIAL_DEPOSIT: Cell<u64> = const { Cell::new(1) };
+    static DUST: Cell<u64> = const { Cell::new(0) };
+}
+
+/// The existential deposit of the test, 1 unless set by [`new_test_ext_with_existential_deposit`].
+pub struct ExistentialDeposit;
+
+impl Get<u64> for ExistentialDeposit {
+    fn get() -> u64 {
+        EXISTENTIAL_DEPOSIT.with(|ed| ed.get())
+    }
+}
+
+/// Counts the dust of the reaped accounts, then destroys it.
+pub struct DustTrap;
+
+impl DustTrap {
+    /// The dust collected so far by the test.
+    pub fn collected() -> u64 {
+        DUST.with(|dust| dust.get())
+    }
+}
+
+impl OnUnbalanced<NegativeImbalance<Test>> for DustTrap {
+    fn on_nonzero_unbalanced(amount: NegativeImbalance<Test>) {
+        DUST.with(|dust| dust.set(dust.get() + amount.peek()));
+    }
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = [u8; 32];
+    type Hashing = BlakeTwo256;
+    type AccountId = i32;
+    type Lookup = IdentityLookup<i32>;
+    type RuntimeEvent = RuntimeEvent;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type SS58Prefix = ();
+    type SystemWeightInfo = ();
+}
+
+impl pallet_balances::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balances = u64;
+    type MaxLocks = ConstU32<MAX_LOCKS>;
+    type ReserveIdentifier = [u8; 8];
+    type MaxReserves = ConstU32<MAX_RESERVES>;
+    type ExistentialDeposit = ExistentialDeposit;
+    type DustRemoval = DustTrap;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> TestExternalities {
+    new_test_ext_with_existential_deposit(1)
+}
+
+pub fn new_test_ext_with_existential_deposit(existential_deposit: u64) -> TestExternalities {
+    EXISTENTIAL_DEPOSIT.with(|ed| ed.set(existential_deposit));
+    let mut ext = TestExternalities::default();
+    // Events are not recorded at genesis.
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
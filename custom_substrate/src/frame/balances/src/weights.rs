@@ -0,0 +1,47 @@
+//! The weights of the balances dispatchables.
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+
+pub trait WeightInfo {
+    fn transfer() -> Weight;
+    fn set_balance() -> Weight;
+    fn force_transfer() -> Weight;
+    fn transfer_keep_alive() -> Weight;
+    fn transfer_all() -> Weight;
+}
+
+/// Estimated from the storage accesses, until the dispatchables are benchmarked.
+impl WeightInfo for () {
+    fn transfer() -> Weight {
+        Weight::from_parts(40_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(4, 4))
+            .saturating_add(Weight::from_parts(0, 256))
+    }
+
+    fn set_balance() -> Weight {
+        Weight::from_parts(20_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(3, 3))
+            .saturating_add(Weight::from_parts(0, 192))
+    }
+
+    fn force_transfer() -> Weight {
+        Weight::from_parts(40_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(4, 4))
+            .saturating_add(Weight::from_parts(0, 256))
+    }
+
+    fn transfer_keep_alive() -> Weight {
+        Weight::from_parts(30_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(4, 4))
+            .saturating_add(Weight::from_parts(0, 256))
+    }
+
+    fn transfer_all() -> Weight {
+        Weight::from_parts(40_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(5, 4))
+            .saturating_add(Weight::from_parts(0, 320))
+    }
+}
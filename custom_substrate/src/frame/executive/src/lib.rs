@@ -0,0 +1,511 @@
+//! The executive: runs the blocks of a runtime.
+//!
+//! A block starts with [`Executive::initialize_block`], which sets up `frame_system` for its
+//! header and runs the `on_initialize` hooks of the pallets. Its extrinsics are then applied one
+//! by one with [`Executive::apply_extrinsic`], and [`Executive::finalize_block`] runs the
+//! `on_finalize` hooks and gives the header of the block, with the roots of its state and
+//! extrinsics. [`Executive::execute_block`] does all of this for a block produced elsewhere and
+//! checks it ends with the same header.
+//!
+//! Out of blocks, [`Executive::validate_transaction`] tells the transaction pool whether and how
+//! an extrinsic can be included.
+
+use frame_support::{
+    codec::Encode,
+    dispatch::{ApplyExtrinsicResult, DispatchClass, GetDispatchInfo},
+    generic::{Block, Digest, DigestItem, Header},
+    pallet_prelude::{One, Saturating},
+    storage::with_transaction,
+    traits::{Applyable, Checkable, Get, Hooks},
+    transaction_validity::{InvalidTransaction, TransactionValidity},
+};
+use std::marker::PhantomData;
+
+/// The header of the blocks of `System`.
+pub type HeaderOf<System> =
+    Header<<System as frame_system::Config>::BlockNumber, <System as frame_system::Config>::Hash>;
+
+/// The blocks of `System`, made of `Extrinsic`s.
+pub type BlockOf<System, Extrinsic> = Block<HeaderOf<System>, Extrinsic>;
+
+/// The extrinsic `Extrinsic` becomes once checked in the runtime `System`.
+pub type CheckedOf<Extrinsic, System> =
+    <Extrinsic as Checkable<<System as frame_system::Config>::Lookup>>::Checked;
+
+/// Runs the blocks of the runtime `System`, made of `Extrinsic`s, e.g. an
+/// `UncheckedExtrinsic`, whose pallets are `AllPalletsWithSystem` as generated by
+/// `construct_runtime!`.
+pub struct Executive<System, Extrinsic, AllPalletsWithSystem>(
+    PhantomData<(System, Extrinsic, AllPalletsWithSystem)>,
+);
+
+impl<System, Extrinsic, AllPalletsWithSystem> Executive<System, Extrinsic, AllPalletsWithSystem>
+where
+    System: frame_system::Config,
+    Extrinsic: Checkable<System::Lookup> + Encode,
+    CheckedOf<Extrinsic, System>: Applyable + GetDispatchInfo,
+    AllPalletsWithSystem: Hooks<System::BlockNumber>,
+{
+    /// Start the block of `header`, with the pre-runtime logs of its digest, and run the
+    /// `on_initialize` hooks of the pallets.
+    pub fn initialize_block(header: &HeaderOf<System>) {
+        // The other logs are deposited again while the block executes.
+        let logs = header.digest.logs().iter();
+        let pre_runtime = logs.filter(|item| matches!(item, DigestItem::PreRuntime(..)));
+        let digest = Digest { logs: pre_runtime.cloned().collect() };
+        frame_system::Pallet::<System>::initialize(&header.number, &header.parent_hash, &digest);
+        let weight = AllPalletsWithSystem::on_initialize(header.number)
+            .saturating_add(System::BlockWeights::get().base_block);
+        frame_system::Pallet::<System>::register_extra_weight_unchecked(
+            weight,
+            DispatchClass::Mandatory,
+        );
+        frame_system::Pallet::<System>::note_finished_initialize();
+    }
+
+    /// Check the signature of `uxt`, then apply it to the block between the checks of its
+    /// signed extensions.
+    ///
+    /// An extrinsic which is refused, e.g. because it doesn't fit in the block, doesn't change
+    /// the state. A call which fails is still part of the block and reported by
+    /// `ExtrinsicFailed`.
+    pub fn apply_extrinsic(uxt: Extrinsic) -> ApplyExtrinsicResult {
+        let encoded = uxt.encode();
+        let len = encoded.len();
+        with_transaction(|| {
+            let xt = uxt.check()?;
+            let info = xt.get_dispatch_info();
+            frame_system::Pallet::<System>::note_extrinsic(encoded);
+
+            let res = xt.apply(&info, len)?;
+
+            // A mandatory call which fails makes the whole block invalid.
+            if res.is_err() && info.class == DispatchClass::Mandatory {
+                return Err(InvalidTransaction::BadMandatory.into())
+            }
+
+            frame_system::Pallet::<System>::note_applied_extrinsic(&res, info);
+            Ok(res.map(|_| ()).map_err(|err| err.error))
+        })
+    }
+
+    /// Validate `uxt` for the block following the one of hash `block_hash`, which ended the
+    /// current state.
+    ///
+    /// The state is changed to start the next block: it must be discarded afterwards, e.g. by
+    /// validating on a copy of it.
+    pub fn validate_transaction(uxt: Extrinsic, block_hash: System::Hash) -> TransactionValidity {
+        let number = frame_system::Pallet::<System>::block_number().saturating_add(One::one());
+        frame_system::Pallet::<System>::initialize(&number, &block_hash, &Default::default());
+
+        let len = uxt.encode().len();
+        let xt = uxt.check()?;
+        let info = xt.get_dispatch_info();
+        if info.class == DispatchClass::Mandatory {
+            return Err(InvalidTransaction::MandatoryValidation.into())
+        }
+        xt.validate(&info, len)
+    }
+
+    /// Run the `on_finalize` hooks of the pallets and end the block, giving its header.
+    pub fn finalize_block() -> HeaderOf<System> {
+        frame_system::Pallet::<System>::note_finished_extrinsics();
+        let number = frame_system::Pallet::<System>::block_number();
+        AllPalletsWithSystem::on_finalize(number);
+        frame_system::Pallet::<System>::finalize()
+    }
+
+    /// Execute `block`, panicking if it is invalid, i.e. if an extrinsic is refused or if it
+    /// doesn't end with the state and extrinsics roots and the digest of its header.
+    pub fn execute_block(block: BlockOf<System, Extrinsic>) {
+        let Block { header, extrinsics } = block;
+        Self::initialize_block(&header);
+        for xt in extrinsics {
+            if let Err(err) = Self::apply_extrinsic(xt) {
+                panic!("the extrinsics of a block must be valid: {:?}", err)
+            }
+        }
+        let new_header = Self::finalize_block();
+
+        assert!(header.digest == new_header.digest, "Digest must match that calculated.");
+        assert!(
+            header.extrinsics_root == new_header.extrinsics_root,
+            "Extrinsics root must match that calculated."
+        );
+        assert!(
+            header.state_root == new_header.state_root,
+            "Storage root must match that calculated."
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{
+        crypto::AccountId32,
+        dispatch::{DispatchError, DispatchInfo},
+        generic::{Era, SignedPayload, UncheckedExtrinsic},
+        hashing::BlakeTwo256,
+        keyring::Keyring::{self, Alice, Bob},
+        limits::BlockWeights,
+        multi_signature::MultiSignature,
+        storage::TestExternalities,
+        traits::{Everything, IdentityLookup},
+        transaction_validity::{TransactionValidityError, ValidTransaction},
+        weights::Weight,
+    };
+    use frame_system::{
+        CheckGenesis, CheckMortality, CheckNonce, CheckSpecVersion, CheckWeight, EventRecord, Phase,
+    };
+
+    /// A pallet with hooks, and a call which fails after writing to the storage.
+    mod custom {
+        use frame_support::{
+            dispatch::annotated_dispatch_info, generic::DigestItem, pallet_prelude::*,
+        };
+        use std::convert::Infallible;
+
+        pub trait Config: frame_system::Config {}
+
+        pub struct Pallet<T: Config>(PhantomData<T>);
+
+        frame_support::impl_constant_metadata!(Pallet<T: Config> {});
+
+        frame_support::storage_prefix!(pub InitializedPrefix => "Custom", "Initialized");
+        frame_support::storage_prefix!(pub FinalizedPrefix => "Custom", "Finalized");
+        frame_support::storage_prefix!(pub WrittenPrefix => "Custom", "Written");
+
+        pub type Initialized<T> =
+            StorageValue<InitializedPrefix, <T as frame_system::Config>::BlockNumber>;
+        pub type Finalized<T> =
+            StorageValue<FinalizedPrefix, <T as frame_system::Config>::BlockNumber>;
+        pub type Written = StorageValue<WrittenPrefix, bool>;
+
+        impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+            fn on_initialize(n: T::BlockNumber) -> Weight {
+                Initialized::<T>::put(n);
+                frame_system::Pallet::<T>::deposit_log(DigestItem::Other(n.encode()));
+                Weight::from_parts(100, 0)
+            }
+
+            fn on_finalize(n: T::BlockNumber) {
+                // The extrinsics are done by now.
+                let phase = frame_system::ExecutionPhase::get();
+                assert_eq!(phase, Some(frame_system::Phase::Finalization));
+                Finalized::<T>::put(n);
+            }
+        }
+
+        #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+        #[scale_info(skip_type_params(T))]
+        #[allow(non_camel_case_types)]
+        pub enum Call<T: Config> {
+            #[codec(index = 0)]
+            write_then_fail,
+            #[doc(hidden)]
+            #[codec(skip)]
+            __Ignore(PhantomData<T>, Infallible),
+        }
+
+        impl<T: Config> GetDispatchInfo for Call<T> {
+            fn get_dispatch_info(&self) -> DispatchInfo {
+                annotated_dispatch_info(&Weight::from_parts(10, 0), ())
+            }
+        }
+
+        impl<T: Config> UnfilteredDispatchable for Call<T> {
+            type RuntimeOrigin = T::RuntimeOrigin;
+
+            fn dispatch_bypass_filter(self, _: Self::RuntimeOrigin) -> DispatchResultWithPostInfo {
+                match self {
+                    Call::write_then_fail => {
+                        Written::put(true);
+                        Err("failed after writing".into())
+                    }
+                    Call::__Ignore(_, never) => match never {},
+                }
+            }
+        }
+    }
+
+    frame_support::construct_runtime!(
+        pub enum Runtime {
+            System: frame_system::{Pallet, Call, Storage, Origin<T>, Event<T>},
+            Custom: custom::{Pallet, Call},
+        }
+    );
+
+    pub struct TestBlockWeights;
+
+    impl Get<BlockWeights> for TestBlockWeights {
+        fn get() -> BlockWeights {
+            BlockWeights::simple_max(Weight::from_parts(10_000_000, u64::MAX))
+        }
+    }
+
+    impl frame_system::Config for Runtime {
+        type BaseCallFilter = Everything;
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = [u8; 32];
+        type Hashing = BlakeTwo256;
+        type AccountId = AccountId32;
+        type Lookup = IdentityLookup<AccountId32>;
+        type RuntimeEvent = RuntimeEvent;
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type BlockWeights = TestBlockWeights;
+        type BlockLength = ();
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type SS58Prefix = ();
+        type SystemWeightInfo = ();
+    }
+
+    impl custom::Config for Runtime {}
+
+    type SignedExtra = (
+        CheckSpecVersion<Runtime>,
+        CheckGenesis<Runtime>,
+        CheckMortality<Runtime>,
+        CheckNonce<Runtime>,
+        CheckWeight<Runtime>,
+    );
+    type Extrinsic = UncheckedExtrinsic<AccountId32, RuntimeCall, MultiSignature, SignedExtra>;
+    type Executive = super::Executive<Runtime, Extrinsic, AllPalletsWithSystem>;
+
+    fn remark(len: usize) -> RuntimeCall {
+        RuntimeCall::System(frame_system::Call::remark { remark: vec![0; len] })
+    }
+
+    /// `call` signed by `who` with `nonce`, for the current state.
+    fn sign(who: Keyring, nonce: u64, call: RuntimeCall) -> Extrinsic {
+        let extra = (
+            CheckSpecVersion::new(),
+            CheckGenesis::new(),
+            CheckMortality::from(Era::immortal()),
+            CheckNonce::from(nonce),
+            CheckWeight::new(),
+        );
+        let payload = SignedPayload::new(call.clone(), extra.clone()).unwrap();
+        let signature = MultiSignature::from(who.sign(&payload.encode()));
+        UncheckedExtrinsic::new_signed(call, who.into(), signature, extra)
+    }
+
+    /// Produce block 1 on a fresh state, with remarks of `lens` bytes signed by Alice.
+    fn produce_block(lens: &[usize]) -> BlockOf<Runtime, Extrinsic> {
+        TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&Header::new_unfinalized(1, [1; 32]));
+            let extrinsics: Vec<_> =
+                lens.iter().zip(0..).map(|(len, nonce)| sign(Alice, nonce, remark(*len))).collect();
+            for xt in extrinsics.clone() {
+                Executive::apply_extrinsic(xt).unwrap().unwrap();
+            }
+            Block { header: Executive::finalize_block(), extrinsics }
+        })
+    }
+
+    #[test]
+    fn hooks_run_around_the_extrinsics() {
+        TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&Header::new_unfinalized(1, [1; 32]));
+            assert_eq!(custom::Initialized::<Runtime>::get(), Some(1));
+            assert_eq!(
+                *System::block_weight().get(DispatchClass::Mandatory),
+                Weight::from_parts(100, 0)
+            );
+
+            assert_eq!(Executive::apply_extrinsic(sign(Alice, 0, remark(0))), Ok(Ok(())));
+            assert_eq!(custom::Finalized::<Runtime>::get(), None);
+            let header = Executive::finalize_block();
+            assert_eq!(custom::Finalized::<Runtime>::get(), Some(1));
+            assert_eq!((header.number, header.parent_hash), (1, [1; 32]));
+        });
+    }
+
+    #[test]
+    fn extrinsics_are_applied_from_their_origin() {
+        TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&Header::new_unfinalized(1, [1; 32]));
+            assert_eq!(Executive::apply_extrinsic(sign(Alice, 0, remark(0))), Ok(Ok(())));
+            assert_eq!(System::account_nonce(&Alice.into()), 1);
+            // Unsigned extrinsics can't make remarks, but are still part of the block.
+            assert_eq!(
+                Executive::apply_extrinsic(Extrinsic::new_unsigned(remark(0))),
+                Ok(Err(DispatchError::BadOrigin))
+            );
+
+            let info = DispatchInfo {
+                weight: <() as frame_system::WeightInfo>::remark(0),
+                ..Default::default()
+            };
+            assert_eq!(
+                System::events(),
+                vec![
+                    EventRecord {
+                        phase: Phase::ApplyExtrinsic(0),
+                        event: frame_system::Event::ExtrinsicSuccess { dispatch_info: info }
+                            .into(),
+                        topics: vec![],
+                    },
+                    EventRecord {
+                        phase: Phase::ApplyExtrinsic(1),
+                        event: frame_system::Event::ExtrinsicFailed {
+                            dispatch_error: DispatchError::BadOrigin,
+                            dispatch_info: info,
+                        }
+                        .into(),
+                        topics: vec![],
+                    },
+                ]
+            );
+            assert_eq!(*System::block_weight().get(DispatchClass::Normal), info.weight * 2);
+        });
+    }
+
+    #[test]
+    fn failed_calls_are_undone() {
+        TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&Header::new_unfinalized(1, [1; 32]));
+            let function = RuntimeCall::Custom(custom::Call::write_then_fail);
+            assert_eq!(
+                Executive::apply_extrinsic(sign(Alice, 0, function)),
+                Ok(Err(DispatchError::Other("failed after writing")))
+            );
+            assert_eq!(custom::Written::get(), None);
+
+            // The extrinsic is still part of the block, with its nonce, weight and failure.
+            assert_eq!(System::account_nonce(&Alice.into()), 1);
+            assert_eq!(
+                *System::block_weight().get(DispatchClass::Normal),
+                Weight::from_parts(10, 0)
+            );
+            assert!(matches!(
+                System::events()[0].event,
+                RuntimeEvent::System(frame_system::Event::ExtrinsicFailed { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn refused_extrinsics_do_not_change_the_state() {
+        TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&Header::new_unfinalized(1, [1; 32]));
+            let before = frame_support::storage::root::<BlakeTwo256>();
+            let refuse = |xt| {
+                let err = Executive::apply_extrinsic(xt).unwrap_err();
+                assert_eq!(frame_support::storage::root::<BlakeTwo256>(), before);
+                err
+            };
+            let invalid = TransactionValidityError::Invalid;
+
+            // Too heavy for the block.
+            assert_eq!(
+                refuse(sign(Alice, 0, remark(30_000))),
+                invalid(InvalidTransaction::ExhaustsResources)
+            );
+            // Not the next nonce of the signer.
+            assert_eq!(refuse(sign(Alice, 1, remark(0))), invalid(InvalidTransaction::Future));
+            // Signed by someone else.
+            let mut xt = sign(Alice, 0, remark(0));
+            xt.signature.as_mut().unwrap().0 = Bob.into();
+            assert_eq!(refuse(xt), invalid(InvalidTransaction::BadProof));
+            // Tampered with after it was signed.
+            let mut xt = sign(Alice, 0, remark(0));
+            xt.function = remark(1);
+            assert_eq!(refuse(xt), invalid(InvalidTransaction::BadProof));
+        });
+    }
+
+    #[test]
+    fn extrinsics_must_fit_in_the_block() {
+        TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&Header::new_unfinalized(1, [1; 32]));
+            // Two remarks fit, not three.
+            for nonce in 0..2 {
+                let xt = sign(Alice, nonce, remark(4_000));
+                assert_eq!(Executive::apply_extrinsic(xt), Ok(Ok(())));
+            }
+            assert_eq!(
+                Executive::apply_extrinsic(sign(Alice, 2, remark(4_000))),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+        });
+    }
+
+    #[test]
+    fn transactions_are_validated_for_the_next_block() {
+        // Signed for the chain whose genesis has hash [1; 32].
+        let (now, ahead) = TestExternalities::default().execute_with(|| {
+            System::initialize(&1, &[1; 32], &Default::default());
+            (sign(Alice, 0, remark(0)), sign(Alice, 1, remark(0)))
+        });
+        let validate = |xt, block_hash| {
+            TestExternalities::default()
+                .execute_with(|| Executive::validate_transaction(xt, block_hash))
+        };
+
+        let valid = ValidTransaction {
+            provides: vec![(AccountId32::from(Alice), 0u64).encode()],
+            longevity: u64::MAX - 1,
+            ..Default::default()
+        };
+        assert_eq!(validate(now.clone(), [1; 32]), Ok(valid));
+        // The next nonce waits for the transaction of the current one.
+        let valid = validate(ahead, [1; 32]).unwrap();
+        assert_eq!(valid.requires, vec![(AccountId32::from(Alice), 0u64).encode()]);
+        // On another chain, the signature doesn't match.
+        assert_eq!(validate(now, [2; 32]), Err(InvalidTransaction::BadProof.into()));
+    }
+
+    #[test]
+    fn produced_blocks_are_executed() {
+        let block = produce_block(&[1, 2]);
+        assert_ne!(block.header.state_root, [0; 32]);
+
+        let mut ext = TestExternalities::default();
+        ext.execute_with(|| Executive::execute_block(block));
+        assert_eq!(ext.execute_with(System::block_number), 1);
+        assert_eq!(ext.execute_with(|| System::account_nonce(&Alice.into())), 2);
+    }
+
+    #[test]
+    fn digests_are_kept_in_the_header() {
+        let mut header = Header::new_unfinalized(1, [1; 32]);
+        header.digest.push(DigestItem::PreRuntime(*b"test", vec![1]));
+        let header = TestExternalities::default().execute_with(|| {
+            Executive::initialize_block(&header);
+            Executive::finalize_block()
+        });
+        let logs = [DigestItem::PreRuntime(*b"test", vec![1]), DigestItem::Other(1u64.encode())];
+        assert_eq!(header.digest.logs(), &logs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Digest must match that calculated.")]
+    fn blocks_with_another_digest_are_refused() {
+        let mut block = produce_block(&[1]);
+        // Without the log of the hook.
+        block.header.digest = Default::default();
+        TestExternalities::default().execute_with(|| Executive::execute_block(block));
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage root must match that calculated.")]
+    fn blocks_with_another_state_root_are_refused() {
+        let mut block = produce_block(&[1]);
+        block.header.state_root = [7; 32];
+        TestExternalities::default().execute_with(|| Executive::execute_block(block));
+    }
+
+    #[test]
+    #[should_panic(expected = "Extrinsics root must match that calculated.")]
+    fn blocks_with_other_extrinsics_are_refused() {
+        let block = Block { extrinsics: produce_block(&[2]).extrinsics, ..produce_block(&[1]) };
+        TestExternalities::default().execute_with(|| Executive::execute_block(block));
+    }
+}
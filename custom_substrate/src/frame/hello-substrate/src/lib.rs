@@ -5,11 +5,40 @@ pub mod pallet {
 
     use frame_support::pallet_prelude::*;
     
-    pub trait Config: Sized {
-        type Event: From<Event<Self>>;
+    pub trait Config: frame_system::Config {
+        /// The event type of the runtime.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
     }
 
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
     pub enum Event<T: Config> {
         Dummy(PhantomData<T>)
     }
+
+    pub struct Pallet<T: Config>(PhantomData<T>);
+
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+    /// The custom origin of the pallet.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    pub enum Origin {
+        /// A call made on behalf of the greeters of the chain.
+        Greeters,
+    }
+
+    /// Accepts the [`Origin::Greeters`] origin.
+    pub struct EnsureGreeters;
+
+    impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for EnsureGreeters {
+        type Success = ();
+
+        fn try_origin(o: O) -> Result<Self::Success, O> {
+            o.into().map(|Origin::Greeters| ())
+        }
+    }
+
+    frame_support::impl_storage_info!(Pallet<T: Config> {});
+
+    frame_support::impl_constant_metadata!(Pallet<T: Config> {});
 }
\ No newline at end of file
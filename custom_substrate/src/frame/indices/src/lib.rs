@@ -0,0 +1,588 @@
+//! Short indices aliasing accounts, so that calls can name accounts in a few bytes.
+//!
+//! An index is claimed against a deposit, which is given back when it is freed and lost when it
+//! is frozen, i.e. made the permanent alias of its account. Used as the `Lookup` of the runtime,
+//! the pallet resolves a [`MultiAddress`] given either as an account id or as an index.
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+pub mod pallet {
+
+    use frame_support::{
+        codec::HasCompact,
+        dispatch::{annotated_dispatch_info, ensure_root, ensure_signed, ModuleError},
+        multiaddress::MultiAddress,
+        pallet_prelude::*,
+        traits::{BalanceStatus, Currency, LookupError, ReservableCurrency, StaticLookup},
+    };
+    use crate::weights::WeightInfo;
+    use std::convert::Infallible;
+
+    /// The balance type of the currency deposits are reserved in.
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// The way calls name an account.
+    pub type AccountIdLookupOf<T> =
+        <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
+
+    pub trait Config: frame_system::Config {
+        /// The event type of the runtime.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// The index of an account, compactly encoded in addresses.
+        type AccountIndex: Parameter + Member + MaxEncodedLen + HasCompact + Copy + Default;
+        /// The currency deposits are reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// The deposit needed to claim an index.
+        type Deposit: Get<BalanceOf<Self>>;
+        type WeightInfo: WeightInfo;
+    }
+
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    pub enum Event<T: Config> {
+        /// An index was assigned to an account.
+        IndexAssigned { who: T::AccountId, index: T::AccountIndex },
+        /// An index was freed.
+        IndexFreed { index: T::AccountIndex },
+        /// An index was frozen to its current account.
+        IndexFrozen { index: T::AccountIndex, who: T::AccountId },
+    }
+
+    /// Why a call of the pallet failed.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    pub enum Error<T> {
+        /// The index was not claimed.
+        NotAssigned,
+        /// The index is assigned to another account.
+        NotOwner,
+        /// The index is already claimed.
+        InUse,
+        /// The index can't be transferred to the account it is assigned to.
+        NotTransfer,
+        /// The index is frozen and can't be changed.
+        Permanent,
+        #[doc(hidden)]
+        #[codec(skip)]
+        __Ignore(PhantomData<T>, Infallible),
+    }
+
+    impl<T> Error<T> {
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Error::NotAssigned => "NotAssigned",
+                Error::NotOwner => "NotOwner",
+                Error::InUse => "InUse",
+                Error::NotTransfer => "NotTransfer",
+                Error::Permanent => "Permanent",
+                Error::__Ignore(_, never) => match *never {},
+            }
+        }
+    }
+
+    impl<T: Config> From<Error<T>> for DispatchError {
+        fn from(error: Error<T>) -> Self {
+            // Out of a runtime which knows the pallet, only the message is left.
+            ModuleError::new::<T::PalletInfo, Pallet<T>>(&error, error.as_str())
+                .map_or(DispatchError::Other(error.as_str()), DispatchError::Module)
+        }
+    }
+
+    /// The dispatchable functions of the pallet, with their arguments.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    #[allow(non_camel_case_types)]
+    pub enum Call<T: Config> {
+        /// Assign the free `index` to the sender, reserving the deposit.
+        #[codec(index = 0)]
+        claim { index: T::AccountIndex },
+        /// Assign `index` of the sender to `new`, moving the deposit along.
+        #[codec(index = 1)]
+        transfer { new: AccountIdLookupOf<T>, index: T::AccountIndex },
+        /// Free `index` of the sender, unreserving the deposit.
+        #[codec(index = 2)]
+        free { index: T::AccountIndex },
+        /// Assign `index` to `new` without a deposit, freezing it if `freeze`. Root only.
+        #[codec(index = 3)]
+        force_transfer { new: AccountIdLookupOf<T>, index: T::AccountIndex, freeze: bool },
+        /// Make `index` the permanent alias of the sender, slashing the deposit.
+        #[codec(index = 4)]
+        freeze { index: T::AccountIndex },
+    }
+
+    impl<T: Config> GetDispatchInfo for Call<T> {
+        fn get_dispatch_info(&self) -> DispatchInfo {
+            match self {
+                Call::claim { index } => {
+                    annotated_dispatch_info(&T::WeightInfo::claim(), (index,))
+                }
+                Call::transfer { new, index } => {
+                    annotated_dispatch_info(&T::WeightInfo::transfer(), (new, index))
+                }
+                Call::free { index } => annotated_dispatch_info(&T::WeightInfo::free(), (index,)),
+                Call::force_transfer { new, index, freeze } => annotated_dispatch_info(
+                    &(T::WeightInfo::force_transfer(), DispatchClass::Operational),
+                    (new, index, freeze),
+                ),
+                Call::freeze { index } => {
+                    annotated_dispatch_info(&T::WeightInfo::freeze(), (index,))
+                }
+            }
+        }
+    }
+
+    impl<T: Config> UnfilteredDispatchable for Call<T> {
+        type RuntimeOrigin = T::RuntimeOrigin;
+
+        fn dispatch_bypass_filter(self, origin: Self::RuntimeOrigin) -> DispatchResultWithPostInfo {
+            match self {
+                Call::claim { index } => Pallet::<T>::claim(origin, index)?,
+                Call::transfer { new, index } => Pallet::<T>::transfer(origin, new, index)?,
+                Call::free { index } => Pallet::<T>::free(origin, index)?,
+                Call::force_transfer { new, index, freeze } => {
+                    Pallet::<T>::force_transfer(origin, new, index, freeze)?
+                }
+                Call::freeze { index } => Pallet::<T>::freeze(origin, index)?,
+            }
+            Ok(().into())
+        }
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Pallet<T: Config>(PhantomData<T>);
+
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+    frame_support::storage_prefix!(pub AccountsPrefix => "Indices", "Accounts");
+
+    /// The account of every claimed index, with the deposit reserved for it and whether it is
+    /// frozen.
+    pub type Accounts<T> = StorageMap<
+        AccountsPrefix,
+        Blake2_128Concat,
+        <T as Config>::AccountIndex,
+        (<T as frame_system::Config>::AccountId, BalanceOf<T>, bool),
+    >;
+
+    frame_support::impl_storage_info!(Pallet<T: Config> { Accounts<T> });
+
+    frame_support::impl_constant_metadata!(Pallet<T: Config> {
+        /// The deposit needed to claim an index.
+        Deposit: BalanceOf<T> = T::Deposit::get(),
+    });
+
+    frame_support::impl_storage_metadata!(Pallet<T: Config> => "Indices" {
+        /// The account of every claimed index, with the deposit reserved for it and whether it
+        /// is frozen.
+        Accounts<T>,
+    });
+
+    impl<T: Config> Pallet<T> {
+        /// Assign the free `index` to the sender, reserving the deposit.
+        pub fn claim(origin: T::RuntimeOrigin, index: T::AccountIndex) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Accounts::<T>::try_mutate(index, |maybe_value| {
+                if maybe_value.is_some() {
+                    return Err(Error::<T>::InUse.into())
+                }
+                *maybe_value = Some((who.clone(), T::Deposit::get(), false));
+                T::Currency::reserve(&who, T::Deposit::get())
+            })?;
+            Self::deposit_event(Event::IndexAssigned { who, index });
+            Ok(())
+        }
+
+        /// Assign `index` of the sender to `new`, moving the deposit along.
+        pub fn transfer(
+            origin: T::RuntimeOrigin,
+            new: AccountIdLookupOf<T>,
+            index: T::AccountIndex,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let new = T::Lookup::lookup(new)?;
+            if who == new {
+                return Err(Error::<T>::NotTransfer.into())
+            }
+            Accounts::<T>::try_mutate(index, |maybe_value| -> DispatchResult {
+                let (account, amount, frozen) = maybe_value.take().ok_or(Error::<T>::NotAssigned)?;
+                if frozen {
+                    return Err(Error::<T>::Permanent.into())
+                }
+                if account != who {
+                    return Err(Error::<T>::NotOwner.into())
+                }
+                let lost =
+                    T::Currency::repatriate_reserved(&who, &new, amount, BalanceStatus::Reserved)?;
+                *maybe_value = Some((new.clone(), amount.saturating_sub(lost), false));
+                Ok(())
+            })?;
+            Self::deposit_event(Event::IndexAssigned { who: new, index });
+            Ok(())
+        }
+
+        /// Free `index` of the sender, unreserving the deposit.
+        pub fn free(origin: T::RuntimeOrigin, index: T::AccountIndex) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Accounts::<T>::try_mutate(index, |maybe_value| -> DispatchResult {
+                let (account, amount, frozen) = maybe_value.take().ok_or(Error::<T>::NotAssigned)?;
+                if frozen {
+                    return Err(Error::<T>::Permanent.into())
+                }
+                if account != who {
+                    return Err(Error::<T>::NotOwner.into())
+                }
+                T::Currency::unreserve(&who, amount);
+                Ok(())
+            })?;
+            Self::deposit_event(Event::IndexFreed { index });
+            Ok(())
+        }
+
+        /// Assign `index` to `new` without a deposit, freezing it if `freeze`, which only root
+        /// can do. The deposit of the previous account is unreserved.
+        pub fn force_transfer(
+            origin: T::RuntimeOrigin,
+            new: AccountIdLookupOf<T>,
+            index: T::AccountIndex,
+            freeze: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let new = T::Lookup::lookup(new)?;
+            Accounts::<T>::mutate(index, |maybe_value| {
+                if let Some((account, amount, _)) = maybe_value.take() {
+                    T::Currency::unreserve(&account, amount);
+                }
+                *maybe_value = Some((new.clone(), Zero::zero(), freeze));
+            });
+            Self::deposit_event(Event::IndexAssigned { who: new, index });
+            Ok(())
+        }
+
+        /// Make `index` the permanent alias of the sender, slashing the deposit.
+        pub fn freeze(origin: T::RuntimeOrigin, index: T::AccountIndex) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Accounts::<T>::try_mutate(index, |maybe_value| -> DispatchResult {
+                let (account, amount, frozen) = maybe_value.take().ok_or(Error::<T>::NotAssigned)?;
+                if frozen {
+                    return Err(Error::<T>::Permanent.into())
+                }
+                if account != who {
+                    return Err(Error::<T>::NotOwner.into())
+                }
+                // The deposit is destroyed, and so leaves the total issuance.
+                drop(T::Currency::slash_reserved(&who, amount));
+                *maybe_value = Some((account, Zero::zero(), true));
+                Ok(())
+            })?;
+            Self::deposit_event(Event::IndexFrozen { index, who });
+            Ok(())
+        }
+
+        /// The account `index` is assigned to.
+        pub fn lookup_index(index: T::AccountIndex) -> Option<T::AccountId> {
+            Accounts::<T>::get(index).map(|(account, _, _)| account)
+        }
+
+        /// The account named by `address`, if it names one by its id or by its index.
+        pub fn lookup_address(
+            address: MultiAddress<T::AccountId, T::AccountIndex>,
+        ) -> Option<T::AccountId> {
+            match address {
+                MultiAddress::Id(account) => Some(account),
+                MultiAddress::Index(index) => Self::lookup_index(index),
+                _ => None,
+            }
+        }
+
+        pub(crate) fn deposit_event(event: Event<T>) {
+            let event = <T as Config>::RuntimeEvent::from(event);
+            frame_system::Pallet::<T>::deposit_event(event.into());
+        }
+    }
+
+    impl<T: Config> StaticLookup for Pallet<T> {
+        type Source = MultiAddress<T::AccountId, T::AccountIndex>;
+        type Target = T::AccountId;
+
+        fn lookup(address: Self::Source) -> Result<T::AccountId, LookupError> {
+            Self::lookup_address(address).ok_or(LookupError)
+        }
+
+        fn unlookup(account: T::AccountId) -> Self::Source {
+            MultiAddress::Id(account)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as pallet_indices;
+    use frame_support::{
+        hashing::BlakeTwo256,
+        multiaddress::MultiAddress,
+        pallet_prelude::*,
+        storage::TestExternalities,
+        traits::{Currency, Everything, LookupError, ReservableCurrency, StaticLookup},
+    };
+
+    frame_support::construct_runtime!(
+        pub enum Test {
+            System: frame_system::{Pallet, Call, Storage, Origin<T>, Event<T>},
+            Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
+            Indices: pallet_indices::{Pallet, Call, Storage, Event<T>},
+        }
+    );
+
+    impl frame_system::Config for Test {
+        type BaseCallFilter = Everything;
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = [u8; 32];
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = Indices;
+        type RuntimeEvent = RuntimeEvent;
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type BlockWeights = ();
+        type BlockLength = ();
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type SS58Prefix = ();
+        type SystemWeightInfo = ();
+    }
+
+    impl pallet_balances::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type Balances = u64;
+        type MaxLocks = ConstU32<10>;
+        type ReserveIdentifier = [u8; 8];
+        type MaxReserves = ConstU32<10>;
+        type ExistentialDeposit = ConstU64<1>;
+        type DustRemoval = ();
+        type WeightInfo = ();
+    }
+
+    impl pallet::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type AccountIndex = u64;
+        type Currency = Balances;
+        type Deposit = ConstU64<1>;
+        type WeightInfo = ();
+    }
+
+    fn new_test_ext() -> TestExternalities {
+        let mut ext = TestExternalities::default();
+        ext.execute_with(|| {
+            // Events are not recorded at genesis.
+            System::set_block_number(1);
+            for account in 1..=6 {
+                drop(Balances::deposit_creating(&account, account * 10));
+            }
+        });
+        ext
+    }
+
+    /// The free and the reserved balance of `who`.
+    fn funds(who: u64) -> (u64, u64) {
+        (Balances::free_balance(&who), Balances::reserved_balance(&who))
+    }
+
+    #[test]
+    fn claiming_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(1), 0), Ok(()));
+            System::assert_last_event(RuntimeEvent::Indices(Event::IndexAssigned {
+                who: 1,
+                index: 0,
+            }));
+            assert_eq!(
+                Indices::claim(RuntimeOrigin::signed(2), 0),
+                Err(Error::<Test>::InUse.into())
+            );
+            assert_eq!(Balances::reserved_balance(&1), 1);
+            assert_eq!(funds(1), (9, 1));
+        });
+    }
+
+    #[test]
+    fn claiming_needs_the_deposit() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                Indices::claim(RuntimeOrigin::signed(7), 0),
+                Err(pallet_balances::Error::<Test>::InsufficientBalance.into())
+            );
+            assert_eq!(Indices::lookup_index(0), None);
+        });
+    }
+
+    #[test]
+    fn freeing_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(1), 0), Ok(()));
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(2), 1), Ok(()));
+            assert_eq!(
+                Indices::free(RuntimeOrigin::signed(1), 1),
+                Err(Error::<Test>::NotOwner.into())
+            );
+            assert_eq!(
+                Indices::free(RuntimeOrigin::signed(1), 2),
+                Err(Error::<Test>::NotAssigned.into())
+            );
+            assert_eq!(Indices::free(RuntimeOrigin::signed(1), 0), Ok(()));
+            System::assert_last_event(RuntimeEvent::Indices(Event::IndexFreed { index: 0 }));
+            assert_eq!(Balances::reserved_balance(&1), 0);
+            assert_eq!(Indices::lookup_index(0), None);
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(3), 0), Ok(()));
+        });
+    }
+
+    #[test]
+    fn indices_lookup_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(5), 0), Ok(()));
+            assert_eq!(Indices::lookup_index(0), Some(5));
+            assert_eq!(Indices::lookup(MultiAddress::Index(0)), Ok(5));
+            assert_eq!(Indices::lookup(MultiAddress::Index(1)), Err(LookupError));
+            assert_eq!(Indices::lookup(MultiAddress::Id(3)), Ok(3));
+            assert_eq!(Indices::lookup(MultiAddress::Raw(vec![3])), Err(LookupError));
+            assert_eq!(Indices::unlookup(5), MultiAddress::Id(5));
+        });
+    }
+
+    #[test]
+    fn reclaim_index_on_accounts_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(1), 0), Ok(()));
+            assert_eq!(Indices::free(RuntimeOrigin::signed(1), 0), Ok(()));
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(2), 0), Ok(()));
+            assert_eq!(Indices::lookup_index(0), Some(2));
+        });
+    }
+
+    #[test]
+    fn transfer_index_on_accounts_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(1), 0), Ok(()));
+            assert_eq!(
+                Indices::transfer(RuntimeOrigin::signed(2), MultiAddress::Id(3), 0),
+                Err(Error::<Test>::NotOwner.into())
+            );
+            assert_eq!(
+                Indices::transfer(RuntimeOrigin::signed(1), MultiAddress::Index(0), 0),
+                Err(Error::<Test>::NotTransfer.into())
+            );
+            assert_eq!(
+                Indices::transfer(RuntimeOrigin::signed(1), MultiAddress::Index(1), 0),
+                Err(DispatchError::CannotLookup)
+            );
+            assert_eq!(Indices::transfer(RuntimeOrigin::signed(1), MultiAddress::Id(3), 0), Ok(()));
+            System::assert_last_event(RuntimeEvent::Indices(Event::IndexAssigned {
+                who: 3,
+                index: 0,
+            }));
+            // The deposit moved along with the index.
+            assert_eq!(funds(1), (9, 0));
+            assert_eq!(funds(3), (30, 1));
+            assert_eq!(Indices::lookup_index(0), Some(3));
+        });
+    }
+
+    #[test]
+    fn force_transfer_index_on_preowned_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(1), 0), Ok(()));
+            assert_eq!(
+                Indices::force_transfer(RuntimeOrigin::signed(1), MultiAddress::Id(3), 0, false),
+                Err(DispatchError::BadOrigin)
+            );
+            assert_eq!(
+                Indices::force_transfer(RuntimeOrigin::root(), MultiAddress::Id(3), 0, false),
+                Ok(())
+            );
+            assert_eq!(funds(1), (10, 0));
+            assert_eq!(funds(3), (30, 0));
+            assert_eq!(Indices::lookup_index(0), Some(3));
+            assert_eq!(Accounts::<Test>::get(0), Some((3, 0, false)));
+        });
+    }
+
+    #[test]
+    fn freezing_should_work() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Indices::claim(RuntimeOrigin::signed(1), 0), Ok(()));
+            assert_eq!(
+                Indices::freeze(RuntimeOrigin::signed(2), 0),
+                Err(Error::<Test>::NotOwner.into())
+            );
+            assert_eq!(Indices::freeze(RuntimeOrigin::signed(1), 0), Ok(()));
+            System::assert_last_event(RuntimeEvent::Indices(Event::IndexFrozen {
+                index: 0,
+                who: 1,
+            }));
+            // The deposit is lost.
+            assert_eq!(funds(1), (9, 0));
+            assert_eq!(Balances::total_issuance(), 209);
+            assert_eq!(
+                Indices::freeze(RuntimeOrigin::signed(1), 0),
+                Err(Error::<Test>::Permanent.into())
+            );
+            assert_eq!(
+                Indices::free(RuntimeOrigin::signed(1), 0),
+                Err(Error::<Test>::Permanent.into())
+            );
+            assert_eq!(
+                Indices::transfer(RuntimeOrigin::signed(1), MultiAddress::Id(2), 0),
+                Err(Error::<Test>::Permanent.into())
+            );
+            assert_eq!(Indices::lookup_index(0), Some(1));
+        });
+    }
+
+    #[test]
+    fn calls_dispatch_to_the_pallet() {
+        new_test_ext().execute_with(|| {
+            let call = pallet::Call::<Test>::claim { index: 7 };
+            assert_eq!(
+                call.clone().dispatch_bypass_filter(RuntimeOrigin::root()),
+                Err(DispatchError::BadOrigin.into())
+            );
+            assert_eq!(call.dispatch_bypass_filter(RuntimeOrigin::signed(4)), Ok(().into()));
+            assert_eq!(Indices::lookup_index(7), Some(4));
+
+            let call = pallet::Call::<Test>::force_transfer {
+                new: MultiAddress::Index(7),
+                index: 8,
+                freeze: true,
+            };
+            assert_eq!(call.get_dispatch_info().class, DispatchClass::Operational);
+            assert_eq!(call.dispatch_bypass_filter(RuntimeOrigin::root()), Ok(().into()));
+            assert_eq!(Accounts::<Test>::get(8), Some((4, 0, true)));
+        });
+    }
+
+    #[test]
+    fn errors_keep_their_pallet_and_variant() {
+        // The pallet is the third of the runtime, and `InUse` its third error.
+        let error = DispatchError::from(Error::<Test>::InUse);
+        assert_eq!(error.encode(), vec![3, 2, 2, 0, 0, 0]);
+        assert_eq!(DispatchError::decode(&mut &error.encode()[..]), Ok(error));
+    }
+
+    #[test]
+    fn storage_info_is_bounded() {
+        let info = Indices::storage_info();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].storage_name, b"Accounts".to_vec());
+        // `Blake2_128Concat` hash and index, then the account, the deposit and the frozen flag.
+        assert_eq!(info[0].max_size, Some(16 + 8 + 8 + 8 + 1));
+    }
+}
@@ -0,0 +1,47 @@
+//! The weights of the indices dispatchables.
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+
+pub trait WeightInfo {
+    fn claim() -> Weight;
+    fn transfer() -> Weight;
+    fn free() -> Weight;
+    fn force_transfer() -> Weight;
+    fn freeze() -> Weight;
+}
+
+/// Estimated from the storage accesses, until the dispatchables are benchmarked.
+impl WeightInfo for () {
+    fn claim() -> Weight {
+        Weight::from_parts(20_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+            .saturating_add(Weight::from_parts(0, 128))
+    }
+
+    fn transfer() -> Weight {
+        Weight::from_parts(30_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(2, 2))
+            .saturating_add(Weight::from_parts(0, 192))
+    }
+
+    fn free() -> Weight {
+        Weight::from_parts(20_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+            .saturating_add(Weight::from_parts(0, 128))
+    }
+
+    fn force_transfer() -> Weight {
+        Weight::from_parts(20_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(2, 2))
+            .saturating_add(Weight::from_parts(0, 192))
+    }
+
+    fn freeze() -> Weight {
+        Weight::from_parts(20_000_000, 0)
+            .saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+            .saturating_add(Weight::from_parts(0, 128))
+    }
+}
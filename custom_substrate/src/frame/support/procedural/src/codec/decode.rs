@@ -0,0 +1,94 @@
+//! Implementation of `#[derive(Decode)]`.
+
+use super::utils::{self, codec_crate};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Result};
+
+pub fn derive_decode(mut input: DeriveInput) -> Result<TokenStream> {
+    let codec = codec_crate();
+    utils::reject_union(&input.data, input.ident.span())?;
+    utils::add_field_bounds(&mut input.generics, &input.data, |ty, attrs| match attrs {
+        attrs if attrs.skip => Some(quote!(#ty: ::core::default::Default)),
+        attrs if attrs.compact => Some(quote!(#ty: #codec::HasCompact)),
+        _ => Some(quote!(#ty: #codec::Decode)),
+    })?;
+
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let construct = decode_fields(quote!(#name), &type_name, &data.fields)?;
+            quote!(::core::result::Result::Ok(#construct))
+        },
+        Data::Enum(data) => {
+            let variants = utils::indexed_variants(&data.variants)?;
+            let arms = variants
+                .into_iter()
+                .map(|utils::IndexedVariant { variant, index }| {
+                    let ident = &variant.ident;
+                    let variant_name = format!("{}::{}", type_name, ident);
+                    let construct =
+                        decode_fields(quote!(#name::#ident), &variant_name, &variant.fields)?;
+                    Ok(quote!(#index => ::core::result::Result::Ok(#construct),))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let read_err = format!("Could not decode `{}`, failed to read variant byte", type_name);
+            let invalid_err = format!("Could not decode `{}`, variant doesn't exist", type_name);
+            quote! {
+                match __input.read_byte().map_err(|e| e.chain(#read_err))? {
+                    #( #arms )*
+                    _ => ::core::result::Result::Err(#invalid_err.into()),
+                }
+            }
+        },
+        Data::Union(_) => unreachable!("rejected above"),
+    };
+
+    Ok(quote! {
+        const _: () = {
+            impl #impl_generics #codec::Decode for #name #ty_generics #where_clause {
+                fn decode<__CodecInput: #codec::Input>(
+                    __input: &mut __CodecInput,
+                ) -> ::core::result::Result<Self, #codec::Error> {
+                    #codec::decode_nested(__input, |__input| { #body })
+                }
+            }
+        };
+    })
+}
+
+/// An expression constructing `path` with every field decoded from `__input`.
+fn decode_fields(path: TokenStream, type_name: &str, fields: &Fields) -> Result<TokenStream> {
+    let codec = codec_crate();
+    let decoded = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = utils::field_attrs(field)?;
+            let ty = &field.ty;
+            let err = format!("Could not decode `{}`", utils::field_name(type_name, field, i));
+            let value = if attrs.skip {
+                quote!(::core::default::Default::default())
+            } else if attrs.compact {
+                quote!(
+                    <#ty as #codec::HasCompact>::decode_compact(__input).map_err(|e| e.chain(#err))?
+                )
+            } else {
+                quote!(<#ty as #codec::Decode>::decode(__input).map_err(|e| e.chain(#err))?)
+            };
+            Ok(match &field.ident {
+                Some(ident) => quote!(#ident: #value),
+                None => value,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(match fields {
+        Fields::Named(_) => quote!(#path { #( #decoded, )* }),
+        Fields::Unnamed(_) => quote!(#path ( #( #decoded, )* )),
+        Fields::Unit => quote!(#path),
+    })
+}
@@ -0,0 +1,115 @@
+//! Implementation of `#[derive(Encode)]`.
+
+use super::utils::{self, codec_crate};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Result};
+
+pub fn derive_encode(mut input: DeriveInput) -> Result<TokenStream> {
+    let codec = codec_crate();
+    utils::reject_union(&input.data, input.ident.span())?;
+    utils::add_field_bounds(&mut input.generics, &input.data, |ty, attrs| match attrs {
+        attrs if attrs.skip => None,
+        attrs if attrs.compact => Some(quote!(#ty: #codec::HasCompact)),
+        _ => Some(quote!(#ty: #codec::Encode)),
+    })?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (size_hint, encode_to) = match &input.data {
+        Data::Struct(data) => {
+            let (bindings, size, encode) = encode_fields(&data.fields)?;
+            let pattern = pattern(quote!(#name), &data.fields, &bindings);
+            (
+                quote!(let #pattern = self; 0 #( + #size )*),
+                quote!(let #pattern = self; #( #encode )*),
+            )
+        },
+        Data::Enum(data) => {
+            let variants = utils::indexed_variants(&data.variants)?;
+            let mut size_arms = Vec::new();
+            let mut encode_arms = Vec::new();
+            for utils::IndexedVariant { variant, index } in variants {
+                let ident = &variant.ident;
+                let (bindings, size, encode) = encode_fields(&variant.fields)?;
+                let pattern = pattern(quote!(#name::#ident), &variant.fields, &bindings);
+                size_arms.push(quote!(#pattern => 1 #( + #size )*,));
+                encode_arms.push(quote!(#pattern => {
+                    __dest.push_byte(#index);
+                    #( #encode )*
+                }));
+            }
+            if data.variants.is_empty() {
+                (quote!(match *self {}), quote!(match *self {}))
+            } else {
+                // Skipped variants encode to nothing, decoding them back is impossible anyway.
+                (
+                    quote!(match self { #( #size_arms )* _ => 0, }),
+                    quote!(match self { #( #encode_arms )* _ => (), }),
+                )
+            }
+        },
+        Data::Union(_) => unreachable!("rejected above"),
+    };
+
+    Ok(quote! {
+        const _: () = {
+            impl #impl_generics #codec::Encode for #name #ty_generics #where_clause {
+                #[allow(unreachable_patterns)]
+                fn size_hint(&self) -> usize {
+                    #size_hint
+                }
+
+                #[allow(unreachable_patterns)]
+                fn encode_to<__CodecOutput: #codec::Output + ?Sized>(
+                    &self,
+                    __dest: &mut __CodecOutput,
+                ) {
+                    #encode_to
+                }
+            }
+        };
+    })
+}
+
+/// A pattern destructuring `path` by reference into `bindings`.
+pub fn pattern(path: TokenStream, fields: &Fields, bindings: &[TokenStream]) -> TokenStream {
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #( #names: #bindings ),* })
+        },
+        Fields::Unnamed(_) => quote!(#path ( #( #bindings ),* )),
+        Fields::Unit => quote!(#path),
+    }
+}
+
+type EncodedFields = (Vec<TokenStream>, Vec<TokenStream>, Vec<TokenStream>);
+
+/// Pattern bindings, size hints and encoding statements for the fields.
+///
+/// Skipped fields are bound to `_` and take no part in the encoding.
+fn encode_fields(fields: &Fields) -> Result<EncodedFields> {
+    let codec = codec_crate();
+    let mut bindings = Vec::new();
+    let mut size = Vec::new();
+    let mut encode = Vec::new();
+    for (field, binding) in fields.iter().zip(utils::field_bindings(fields)) {
+        let attrs = utils::field_attrs(field)?;
+        let ty = &field.ty;
+        if attrs.skip {
+            bindings.push(quote!(_));
+            continue
+        }
+        bindings.push(quote!(#binding));
+        if attrs.compact {
+            size.push(quote!(<#ty as #codec::HasCompact>::compact_len(#binding)));
+            encode.push(quote!(<#ty as #codec::HasCompact>::encode_compact_to(#binding, __dest);));
+        } else {
+            size.push(quote!(<#ty as #codec::Encode>::size_hint(#binding)));
+            encode.push(quote!(<#ty as #codec::Encode>::encode_to(#binding, __dest);));
+        }
+    }
+    Ok((bindings, size, encode))
+}
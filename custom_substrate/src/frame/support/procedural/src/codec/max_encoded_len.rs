@@ -0,0 +1,72 @@
+//! Implementation of `#[derive(MaxEncodedLen)]`.
+
+use super::utils::{self, codec_crate};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Result};
+
+pub fn derive_max_encoded_len(mut input: DeriveInput) -> Result<TokenStream> {
+    let codec = codec_crate();
+    utils::reject_union(&input.data, input.ident.span())?;
+    utils::add_field_bounds(&mut input.generics, &input.data, |ty, attrs| match attrs {
+        attrs if attrs.skip => None,
+        // Compact fields are bounded through their `Compact` wrapper, which needs the field to
+        // be compact encodable in the first place.
+        attrs if attrs.compact => Some(quote!(#ty: #codec::HasCompact)),
+        _ => Some(quote!(#ty: #codec::MaxEncodedLen)),
+    })?;
+    utils::add_field_bounds(&mut input.generics, &input.data, |ty, attrs| {
+        attrs.compact.then(|| quote!(#codec::Compact<#ty>: #codec::MaxEncodedLen))
+    })?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => fields_max_len(&data.fields)?,
+        Data::Enum(data) => {
+            let variants = utils::indexed_variants(&data.variants)?;
+            if variants.is_empty() {
+                quote!(0)
+            } else {
+                let lens = variants
+                    .iter()
+                    .map(|v| fields_max_len(&v.variant.fields))
+                    .collect::<Result<Vec<_>>>()?;
+                quote!(1usize.saturating_add(0usize #( .max(#lens) )*))
+            }
+        },
+        Data::Union(_) => unreachable!("rejected above"),
+    };
+
+    Ok(quote! {
+        const _: () = {
+            impl #impl_generics #codec::MaxEncodedLen for #name #ty_generics #where_clause {
+                fn max_encoded_len() -> usize {
+                    #body
+                }
+            }
+        };
+    })
+}
+
+/// The sum of the maximum encoded length of every encoded field.
+fn fields_max_len(fields: &Fields) -> Result<TokenStream> {
+    let codec = codec_crate();
+    let lens = fields
+        .iter()
+        .filter_map(|field| match utils::field_attrs(field) {
+            Ok(attrs) if attrs.skip => None,
+            Ok(attrs) => {
+                let ty = &field.ty;
+                Some(Ok(if attrs.compact {
+                    quote!(<#codec::Compact<#ty> as #codec::MaxEncodedLen>::max_encoded_len())
+                } else {
+                    quote!(<#ty as #codec::MaxEncodedLen>::max_encoded_len())
+                }))
+            },
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(quote!(0usize #( .saturating_add(#lens) )*))
+}
@@ -0,0 +1,23 @@
+//! Derive macros of the SCALE compatible codec exposed in `frame_support::codec`.
+
+mod decode;
+mod encode;
+mod max_encoded_len;
+pub(crate) mod utils;
+
+use proc_macro::TokenStream;
+
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    encode::derive_encode(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    decode::derive_decode(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+pub fn derive_max_encoded_len(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    max_encoded_len::derive_max_encoded_len(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
@@ -0,0 +1,201 @@
+//! Attribute parsing and bound generation shared by the codec derives.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashSet;
+use syn::{
+    spanned::Spanned, visit::Visit, Attribute, Data, Error, Field, Fields, Generics, Ident, Lit,
+    Meta, NestedMeta, Result, Type, Variant,
+};
+
+/// Path to the codec module of `frame_support`, used by all generated code.
+pub fn codec_crate() -> TokenStream {
+    quote!(::frame_support::codec)
+}
+
+/// The `#[codec(..)]` options that can be put on a field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub compact: bool,
+    pub skip: bool,
+}
+
+/// The `#[codec(..)]` options that can be put on a variant.
+#[derive(Default)]
+pub struct VariantAttrs {
+    pub index: Option<u8>,
+    pub skip: bool,
+}
+
+fn codec_metas(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut metas = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => return Err(Error::new(meta.span(), "expected `#[codec(..)]`")),
+        }
+    }
+    Ok(metas)
+}
+
+pub fn field_attrs(field: &Field) -> Result<FieldAttrs> {
+    let mut res = FieldAttrs::default();
+    for meta in codec_metas(&field.attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("compact") => res.compact = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => res.skip = true,
+            meta => {
+                let msg = "invalid field attribute, expected `compact` or `skip`";
+                return Err(Error::new(meta.span(), msg))
+            },
+        }
+    }
+    if res.compact && res.skip {
+        return Err(Error::new(field.span(), "`compact` and `skip` cannot be used together"))
+    }
+    Ok(res)
+}
+
+pub fn variant_attrs(variant: &Variant) -> Result<VariantAttrs> {
+    let mut res = VariantAttrs::default();
+    for meta in codec_metas(&variant.attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("index") => match nv.lit {
+                Lit::Int(index) => res.index = Some(index.base10_parse::<u8>()?),
+                lit => return Err(Error::new(lit.span(), "expected an integer between 0 and 255")),
+            },
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => res.skip = true,
+            meta => {
+                let msg = "invalid variant attribute, expected `index = $n` or `skip`";
+                return Err(Error::new(meta.span(), msg))
+            },
+        }
+    }
+    Ok(res)
+}
+
+/// A variant which takes part in the encoding, together with its resolved index.
+pub struct IndexedVariant<'a> {
+    pub variant: &'a Variant,
+    pub index: u8,
+}
+
+/// Resolve the index of every non skipped variant.
+///
+/// The index is taken from `#[codec(index = n)]`, then from an explicit discriminant, and
+/// falls back to the position of the variant. Conflicting indices are rejected.
+pub fn indexed_variants<'a>(
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<Vec<IndexedVariant<'a>>> {
+    let mut used = HashSet::new();
+    let mut res = Vec::new();
+    for (position, variant) in variants.into_iter().enumerate() {
+        let attrs = variant_attrs(variant)?;
+        if attrs.skip {
+            continue
+        }
+        let index = match (attrs.index, &variant.discriminant) {
+            (Some(index), _) => index,
+            (None, Some((_, syn::Expr::Lit(syn::ExprLit { lit: Lit::Int(lit), .. })))) =>
+                lit.base10_parse::<u8>()?,
+            (None, Some((_, expr))) => {
+                let msg =
+                    "only integer literal discriminants are supported, use `#[codec(index = n)]`";
+                return Err(Error::new(expr.span(), msg))
+            },
+            (None, None) => u8::try_from(position).map_err(|_| {
+                Error::new(variant.span(), "enums with more than 256 variants are not supported")
+            })?,
+        };
+        if !used.insert(index) {
+            let msg = format!("index {} is used by more than one variant", index);
+            return Err(Error::new(variant.span(), msg))
+        }
+        res.push(IndexedVariant { variant, index });
+    }
+    Ok(res)
+}
+
+/// The identifiers used to bind the fields of a variant or struct in a pattern.
+pub fn field_bindings(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len()).map(|i| Ident::new(&format!("__field_{}", i), Span::call_site())).collect()
+}
+
+/// Name of the field used in error messages, `Type::field` or `Type.0`.
+pub fn field_name(type_name: &str, field: &Field, index: usize) -> String {
+    match &field.ident {
+        Some(ident) => format!("{}::{}", type_name, ident),
+        None => format!("{}.{}", type_name, index),
+    }
+}
+
+struct TypeParamVisitor<'a> {
+    params: &'a HashSet<Ident>,
+    found: bool,
+}
+
+impl<'a, 'ast> Visit<'ast> for TypeParamVisitor<'a> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if let Some(segment) = path.segments.first() {
+            if self.params.contains(&segment.ident) {
+                self.found = true;
+            }
+        }
+        syn::visit::visit_path(self, path);
+    }
+}
+
+fn uses_type_params(ty: &Type, params: &HashSet<Ident>) -> bool {
+    let mut visitor = TypeParamVisitor { params, found: false };
+    visitor.visit_type(ty);
+    visitor.found
+}
+
+/// Every field of the input, whatever the data kind.
+pub fn all_fields(data: &Data) -> Vec<&Field> {
+    match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    }
+}
+
+/// Add a where clause predicate for every field type that depends on a type parameter.
+///
+/// `predicate` builds the predicate of a field, returning `None` leaves it unconstrained.
+pub fn add_field_bounds(
+    generics: &mut Generics,
+    data: &Data,
+    predicate: impl Fn(&Type, &FieldAttrs) -> Option<TokenStream>,
+) -> Result<()> {
+    let params: HashSet<Ident> = generics.type_params().map(|p| p.ident.clone()).collect();
+    if params.is_empty() {
+        return Ok(())
+    }
+
+    let mut seen = HashSet::new();
+    let mut predicates = Vec::new();
+    for field in all_fields(data) {
+        let attrs = field_attrs(field)?;
+        if !uses_type_params(&field.ty, &params) {
+            continue
+        }
+        if let Some(predicate) = predicate(&field.ty, &attrs) {
+            let predicate: syn::WherePredicate = syn::parse2(predicate)?;
+            if seen.insert(quote!(#predicate).to_string()) {
+                predicates.push(predicate);
+            }
+        }
+    }
+
+    generics.make_where_clause().predicates.extend(predicates);
+    Ok(())
+}
+
+/// Unions cannot be encoded, they carry no information on which field is set.
+pub fn reject_union(data: &Data, span: Span) -> Result<()> {
+    match data {
+        Data::Union(_) => Err(Error::new(span, "unions are not supported by the codec derives")),
+        _ => Ok(()),
+    }
+}
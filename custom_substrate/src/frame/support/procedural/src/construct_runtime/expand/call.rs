@@ -0,0 +1,101 @@
+use crate::construct_runtime::parse::Pallet;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+use super::metadata::generic_type;
+
+/// `RuntimeCall`, gathering the calls of every pallet with a `Call` part, dispatched with or
+/// without the call filter of the origin.
+pub fn expand_outer_dispatch(runtime: &Ident, pallets: &[Pallet]) -> TokenStream {
+    let mut variants = TokenStream::new();
+    let mut dispatch_arms = TokenStream::new();
+    let mut info_arms = TokenStream::new();
+    let mut conversions = TokenStream::new();
+    for pallet in pallets {
+        let Some(part) = pallet.find_part("Call") else { continue };
+        let name = &pallet.name;
+        let index = pallet.index;
+        let ty = Ident::new("Call", part.keyword.span());
+        let call = generic_type(runtime, &pallet.path, pallet.instance.as_ref(), &ty);
+
+        variants.extend(quote!(#[codec(index = #index)] #name(#call),));
+        dispatch_arms.extend(quote! {
+            RuntimeCall::#name(call) => {
+                ::frame_support::traits::UnfilteredDispatchable::dispatch_bypass_filter(
+                    call,
+                    origin,
+                )
+            },
+        });
+        info_arms.extend(quote! {
+            RuntimeCall::#name(ref call) => {
+                ::frame_support::dispatch::GetDispatchInfo::get_dispatch_info(call)
+            },
+        });
+        conversions.extend(quote! {
+            impl From<#call> for RuntimeCall {
+                fn from(call: #call) -> Self {
+                    RuntimeCall::#name(call)
+                }
+            }
+        });
+    }
+
+    quote! {
+        /// Every call of the runtime.
+        #[derive(
+            Clone, PartialEq, Eq, Debug,
+            ::frame_support::codec::Encode,
+            ::frame_support::codec::Decode,
+            ::frame_support::scale_info::TypeInfo,
+        )]
+        pub enum RuntimeCall {
+            #variants
+        }
+
+        impl ::frame_support::dispatch::GetDispatchInfo for RuntimeCall {
+            fn get_dispatch_info(&self) -> ::frame_support::dispatch::DispatchInfo {
+                // Dereferenced, so that a runtime without calls matches the empty enum.
+                match *self {
+                    #info_arms
+                }
+            }
+        }
+
+        impl ::frame_support::traits::Dispatchable for RuntimeCall {
+            type RuntimeOrigin = RuntimeOrigin;
+            type Info = ::frame_support::dispatch::DispatchInfo;
+            type PostInfo = ::frame_support::dispatch::PostDispatchInfo;
+
+            fn dispatch(
+                self,
+                origin: RuntimeOrigin,
+            ) -> ::frame_support::dispatch::DispatchResultWithPostInfo {
+                use ::frame_support::traits::OriginTrait;
+                if !origin.filter_call(&self) {
+                    return Err(::frame_support::dispatch::DispatchError::CallFiltered.into())
+                }
+                ::frame_support::traits::UnfilteredDispatchable::dispatch_bypass_filter(
+                    self,
+                    origin,
+                )
+            }
+        }
+
+        impl ::frame_support::traits::UnfilteredDispatchable for RuntimeCall {
+            type RuntimeOrigin = RuntimeOrigin;
+
+            fn dispatch_bypass_filter(
+                self,
+                origin: RuntimeOrigin,
+            ) -> ::frame_support::dispatch::DispatchResultWithPostInfo {
+                match self {
+                    #dispatch_arms
+                }
+            }
+        }
+
+        #conversions
+    }
+}
@@ -0,0 +1,60 @@
+use crate::construct_runtime::parse::Pallet;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+use super::metadata::generic_type;
+
+/// `RuntimeEvent`, gathering the events of every pallet with an `Event` part.
+pub fn expand_outer_event(runtime: &Ident, pallets: &[Pallet]) -> TokenStream {
+    let mut variants = TokenStream::new();
+    let mut conversions = TokenStream::new();
+    for pallet in pallets {
+        let Some(part) = pallet.find_part("Event") else { continue };
+        let name = &pallet.name;
+        let index = pallet.index;
+        let path = &pallet.path;
+        let ty = Ident::new("Event", part.keyword.span());
+        let event = if part.generics.params.is_empty() {
+            quote!(#path::#ty)
+        } else {
+            generic_type(runtime, path, pallet.instance.as_ref(), &ty)
+        };
+
+        variants.extend(quote!(#[codec(index = #index)] #name(#event),));
+        conversions.extend(quote! {
+            impl From<#event> for RuntimeEvent {
+                fn from(x: #event) -> Self {
+                    RuntimeEvent::#name(x)
+                }
+            }
+
+            impl TryInto<#event> for RuntimeEvent {
+                type Error = ();
+
+                fn try_into(self) -> Result<#event, Self::Error> {
+                    match self {
+                        Self::#name(evt) => Ok(evt),
+                        _ => Err(()),
+                    }
+                }
+            }
+        });
+    }
+
+    quote! {
+        /// Every event of the runtime.
+        #[derive(
+            Clone, PartialEq, Eq, Debug,
+            ::frame_support::codec::Encode,
+            ::frame_support::codec::Decode,
+            ::frame_support::scale_info::TypeInfo,
+        )]
+        #[allow(non_camel_case_types)]
+        pub enum RuntimeEvent {
+            #variants
+        }
+
+        #conversions
+    }
+}
@@ -0,0 +1,74 @@
+use crate::construct_runtime::parse::{Pallet, PalletPath};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+pub fn expand_runtime_metadata(runtime: &Ident, pallet_declarations: &[Pallet]) -> TokenStream {
+    let pallets = pallet_declarations.iter().map(|decl| {
+        let name = decl.name.to_string();
+        let index = decl.index;
+        let path = &decl.path;
+        let instance = decl.instance.as_ref().into_iter();
+
+        let storage = if decl.exists_part("Storage") {
+            let instance = instance.clone();
+            quote!(Some(#path::Pallet::<#runtime #(, #path::#instance)*>::storage_metadata()))
+        } else {
+            quote!(None)
+        };
+        let constants = if decl.exists_part("Pallet") {
+            let instance = instance.clone();
+            quote!(#path::Pallet::<#runtime #(, #path::#instance)*>::constants_metadata())
+        } else {
+            quote!(Vec::new())
+        };
+        let calls = expand_pallet_metadata_type(runtime, decl, "Call");
+        let event = expand_pallet_metadata_type(runtime, decl, "Event");
+
+        quote! {
+            ::frame_support::metadata::PalletMetadata {
+                name: #name,
+                storage: #storage,
+                calls: #calls,
+                event: #event,
+                constants: #constants,
+                index: #index,
+            }
+        }
+    });
+
+    quote! {
+        impl #runtime {
+            /// The metadata of the runtime, describing every pallet with the types it uses.
+            pub fn metadata() -> ::frame_support::metadata::RuntimeMetadataPrefixed {
+                ::frame_support::metadata::RuntimeMetadataPrefixed::new(vec![ #( #pallets ),* ])
+            }
+        }
+    }
+}
+
+/// `Some(meta_type::<pallet::Call<Runtime>>().into())` when the pallet has the `part`.
+fn expand_pallet_metadata_type(runtime: &Ident, decl: &Pallet, part: &str) -> TokenStream {
+    let Some(pallet_part) = decl.find_part(part) else { return quote!(None) };
+    let ty = Ident::new(part, pallet_part.keyword.span());
+    let path = &decl.path;
+    // The part is declared as `Call`, but the enum is always generic over the runtime.
+    let ty = if pallet_part.generics.params.is_empty() && part != "Call" {
+        quote!(#path::#ty)
+    } else {
+        generic_type(runtime, path, decl.instance.as_ref(), &ty)
+    };
+    quote!(Some(::frame_support::scale_info::meta_type::<#ty>().into()))
+}
+
+pub(super) fn generic_type(
+    runtime: &Ident,
+    path: &PalletPath,
+    instance: Option<&Ident>,
+    ty: &Ident,
+) -> TokenStream {
+    match instance {
+        Some(instance) => quote!(#path::#ty<#runtime, #path::#instance>),
+        None => quote!(#path::#ty<#runtime>),
+    }
+}
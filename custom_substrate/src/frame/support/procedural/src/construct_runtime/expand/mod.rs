@@ -0,0 +1,9 @@
+mod call;
+mod event;
+mod metadata;
+mod origin;
+
+pub use call::expand_outer_dispatch;
+pub use event::expand_outer_event;
+pub use metadata::expand_runtime_metadata;
+pub use origin::expand_outer_origin;
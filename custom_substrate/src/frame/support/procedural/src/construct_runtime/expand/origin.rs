@@ -0,0 +1,209 @@
+use crate::construct_runtime::parse::Pallet;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+use super::metadata::generic_type;
+
+/// `RuntimeOrigin`, the origin of every call of the runtime with its call filter, and
+/// `OriginCaller`, which gathers the raw origin of `system` and the custom origins of the
+/// pallets with an `Origin` part.
+pub fn expand_outer_origin(
+    runtime: &Ident,
+    system_pallet: &Pallet,
+    pallets: &[Pallet],
+) -> TokenStream {
+    let system_path = &system_pallet.path;
+    let system_index = system_pallet.index;
+
+    let mut caller_variants = TokenStream::new();
+    let mut conversions = TokenStream::new();
+    for pallet in pallets.iter().filter(|pallet| pallet.name != system_pallet.name) {
+        let Some(part) = pallet.find_part("Origin") else { continue };
+        let name = &pallet.name;
+        let index = pallet.index;
+        let ty = Ident::new("Origin", part.keyword.span());
+        let origin = if part.generics.params.is_empty() {
+            let path = &pallet.path;
+            quote!(#path::#ty)
+        } else {
+            generic_type(runtime, &pallet.path, pallet.instance.as_ref(), &ty)
+        };
+
+        caller_variants.extend(quote!(#[codec(index = #index)] #name(#origin),));
+        conversions.extend(quote! {
+            impl From<#origin> for OriginCaller {
+                fn from(x: #origin) -> Self {
+                    OriginCaller::#name(x)
+                }
+            }
+
+            impl From<#origin> for RuntimeOrigin {
+                fn from(x: #origin) -> Self {
+                    OriginCaller::#name(x).into()
+                }
+            }
+
+            impl From<RuntimeOrigin> for Result<#origin, RuntimeOrigin> {
+                /// The custom origin of the pallet, or the origin back if it is another one.
+                fn from(val: RuntimeOrigin) -> Self {
+                    match val.caller {
+                        OriginCaller::#name(l) => Ok(l),
+                        _ => Err(val),
+                    }
+                }
+            }
+        });
+    }
+
+    quote! {
+        /// The origin of a call: who made it, and which calls it can make.
+        #[derive(Clone)]
+        pub struct RuntimeOrigin {
+            caller: OriginCaller,
+            filter: ::std::rc::Rc<Box<dyn Fn(&RuntimeCall) -> bool>>,
+        }
+
+        impl ::std::fmt::Debug for RuntimeOrigin {
+            fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                fmt.debug_struct("Origin")
+                    .field("caller", &self.caller)
+                    .field("filter", &"[function ptr]")
+                    .finish()
+            }
+        }
+
+        impl ::frame_support::traits::OriginTrait for RuntimeOrigin {
+            type Call = RuntimeCall;
+            type PalletsOrigin = OriginCaller;
+            type AccountId = <#runtime as #system_path::Config>::AccountId;
+
+            fn add_filter(&mut self, filter: impl Fn(&Self::Call) -> bool + 'static) {
+                let f = self.filter.clone();
+                self.filter = ::std::rc::Rc::new(Box::new(move |call| f(call) && filter(call)));
+            }
+
+            fn reset_filter(&mut self) {
+                let filter = <
+                    <#runtime as #system_path::Config>::BaseCallFilter
+                    as ::frame_support::traits::Contains<RuntimeCall>
+                >::contains;
+                self.filter = ::std::rc::Rc::new(Box::new(filter));
+            }
+
+            fn set_caller_from(&mut self, other: impl Into<Self>) {
+                self.caller = other.into().caller;
+            }
+
+            fn filter_call(&self, call: &Self::Call) -> bool {
+                match self.caller {
+                    OriginCaller::system(#system_path::RawOrigin::Root) => true,
+                    _ => (self.filter)(call),
+                }
+            }
+
+            fn caller(&self) -> &Self::PalletsOrigin {
+                &self.caller
+            }
+
+            fn into_caller(self) -> Self::PalletsOrigin {
+                self.caller
+            }
+
+            fn root() -> Self {
+                #system_path::RawOrigin::Root.into()
+            }
+
+            fn signed(by: Self::AccountId) -> Self {
+                #system_path::RawOrigin::Signed(by).into()
+            }
+
+            fn none() -> Self {
+                #system_path::RawOrigin::None.into()
+            }
+        }
+
+        /// Every kind of origin of the runtime: the one of `system` or a custom pallet origin.
+        #[derive(
+            Clone, PartialEq, Eq, Debug,
+            ::frame_support::codec::Encode,
+            ::frame_support::codec::Decode,
+            ::frame_support::scale_info::TypeInfo,
+        )]
+        #[allow(non_camel_case_types)]
+        pub enum OriginCaller {
+            #[codec(index = #system_index)]
+            system(#system_path::Origin<#runtime>),
+            #caller_variants
+        }
+
+        impl RuntimeOrigin {
+            pub fn root() -> Self {
+                #system_path::RawOrigin::Root.into()
+            }
+
+            pub fn signed(by: <#runtime as #system_path::Config>::AccountId) -> Self {
+                #system_path::RawOrigin::Signed(by).into()
+            }
+
+            pub fn none() -> Self {
+                #system_path::RawOrigin::None.into()
+            }
+
+            pub fn caller(&self) -> &OriginCaller {
+                &self.caller
+            }
+
+            pub fn into_caller(self) -> OriginCaller {
+                self.caller
+            }
+        }
+
+        impl From<OriginCaller> for RuntimeOrigin {
+            /// The origin of `caller`, which can make the calls of the base call filter.
+            fn from(caller: OriginCaller) -> Self {
+                let mut origin =
+                    RuntimeOrigin { caller, filter: ::std::rc::Rc::new(Box::new(|_| true)) };
+                ::frame_support::traits::OriginTrait::reset_filter(&mut origin);
+                origin
+            }
+        }
+
+        impl From<RuntimeOrigin> for OriginCaller {
+            fn from(origin: RuntimeOrigin) -> Self {
+                origin.caller
+            }
+        }
+
+        impl From<#system_path::Origin<#runtime>> for OriginCaller {
+            fn from(x: #system_path::Origin<#runtime>) -> Self {
+                OriginCaller::system(x)
+            }
+        }
+
+        impl From<#system_path::Origin<#runtime>> for RuntimeOrigin {
+            fn from(x: #system_path::Origin<#runtime>) -> Self {
+                OriginCaller::system(x).into()
+            }
+        }
+
+        impl From<Option<<#runtime as #system_path::Config>::AccountId>> for RuntimeOrigin {
+            /// `Signed` by the account if there is one, `None` otherwise.
+            fn from(x: Option<<#runtime as #system_path::Config>::AccountId>) -> Self {
+                #system_path::RawOrigin::from(x).into()
+            }
+        }
+
+        impl From<RuntimeOrigin> for Result<#system_path::Origin<#runtime>, RuntimeOrigin> {
+            /// The raw origin of `system`, or the origin back if it is a custom one.
+            fn from(val: RuntimeOrigin) -> Self {
+                match val.caller {
+                    OriginCaller::system(l) => Ok(l),
+                    _ => Err(val),
+                }
+            }
+        }
+
+        #conversions
+    }
+}
@@ -1,23 +1,145 @@
-
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+mod expand;
+// Some of the parsed declaration (e.g. the where section) is not consumed yet.
+#[allow(dead_code)]
 mod parse;
 
-use parse::{RuntimeDeclaration};
+use parse::{ExplicitRuntimeDeclaration, ImplicitRuntimeDeclaration, Pallet, RuntimeDeclaration};
+
+/// The name of the pallet every runtime must declare, whose raw origin is used by the others.
+const SYSTEM_PALLET_NAME: &str = "System";
 
 pub fn construct_runtime(input: TokenStream) -> TokenStream {
-    let input_copy = input.clone();
     let definition = syn::parse_macro_input!(input as RuntimeDeclaration);
 
     let res = match definition {
+        RuntimeDeclaration::Implicit(implicit_def) => reject_implicit_parts(implicit_def),
+        RuntimeDeclaration::Explicit(explicit_def) => {
+            construct_runtime_final_expansion(explicit_def)
+        },
+    };
+
+    res.unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+/// Our pallets are written by hand and can't tell which parts they have, so every pallet must
+/// list them.
+fn reject_implicit_parts(definition: ImplicitRuntimeDeclaration) -> syn::Result<TokenStream2> {
+    let pallet = definition
+        .pallets
+        .iter()
+        .find(|pallet| pallet.pallet_parts.is_none())
+        .expect("a declaration is implicit only if a pallet has no parts; qed");
+    let path = &pallet.path;
+    let msg = format!(
+        "The parts of `{}` must be declared explicitly, \
+         e.g. `{}: {}::{{Pallet, Storage, Event<T>}}`",
+        pallet.name,
+        pallet.name,
+        quote!(#path).to_string().replace(' ', ""),
+    );
+    Err(syn::Error::new(pallet.name.span(), msg))
+}
+
+fn construct_runtime_final_expansion(
+    definition: ExplicitRuntimeDeclaration,
+) -> syn::Result<TokenStream2> {
+    let ExplicitRuntimeDeclaration { name, pallets, pallet_token, .. } = definition;
+
+    let system_pallet =
+        pallets.iter().find(|pallet| pallet.name == SYSTEM_PALLET_NAME).ok_or_else(|| {
+            syn::Error::new(
+                pallet_token.span,
+                "`System` pallet declaration is missing. \
+                 Please add this line: `System: frame_system::{Pallet, Origin<T>},`",
+            )
+        })?;
+
+    let pallets_with_struct: Vec<_> =
+        pallets.iter().filter(|pallet| pallet.exists_part("Pallet")).collect();
+    let pallet_aliases = decl_pallet_aliases(&name, &pallets_with_struct);
+    let all_pallets = decl_all_pallets(&pallets_with_struct);
+    let pallet_info = decl_pallet_info(&pallets_with_struct);
+    let outer_origin = expand::expand_outer_origin(&name, system_pallet, &pallets);
+    let outer_dispatch = expand::expand_outer_dispatch(&name, &pallets);
+    let outer_event = expand::expand_outer_event(&name, &pallets);
+    let metadata = expand::expand_runtime_metadata(&name, &pallets);
+
+    Ok(quote!(
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub struct #name;
 
-        RuntimeDeclaration::Implicit(implicit_def) => {
+        #pallet_aliases
 
+        #all_pallets
+
+        #pallet_info
+
+        #outer_origin
+
+        #outer_dispatch
+
+        #outer_event
+
+        #metadata
+    ))
+}
+
+/// `pub type Balances = pallet_balances::Pallet<Runtime>;` for every pallet.
+fn decl_pallet_aliases(runtime: &syn::Ident, pallets: &[&Pallet]) -> TokenStream2 {
+    let aliases = pallets.iter().map(|pallet| {
+        let name = &pallet.name;
+        let path = &pallet.path;
+        match &pallet.instance {
+            Some(instance) => quote!(pub type #name = #path::Pallet<#runtime, #path::#instance>;),
+            None => quote!(pub type #name = #path::Pallet<#runtime>;),
         }
+    });
+    quote!(#( #aliases )*)
+}
 
-        RuntimeDeclaration::Explicit(explicit_def) => {
+/// The tuple of all pallets, in declaration order, e.g. to gather their storage info.
+fn decl_all_pallets(pallets: &[&Pallet]) -> TokenStream2 {
+    let names = pallets.iter().map(|pallet| &pallet.name);
+    quote!(
+        /// All pallets of the runtime, in the order they are declared.
+        pub type AllPalletsWithSystem = ( #( #names, )* );
+    )
+}
+
+/// `PalletInfo`, telling the index and name of every pallet by its type.
+fn decl_pallet_info(pallets: &[&Pallet]) -> TokenStream2 {
+    let (indices, names): (Vec<_>, Vec<_>) = pallets
+        .iter()
+        .map(|pallet| {
+            let name = &pallet.name;
+            let index = pallet.index as usize;
+            let name_str = name.to_string();
+            let is_pallet = quote!(
+                ::std::any::TypeId::of::<P>() == ::std::any::TypeId::of::<#name>()
+            );
+            (
+                quote!(if #is_pallet { return Some(#index) }),
+                quote!(if #is_pallet { return Some(#name_str) }),
+            )
+        })
+        .unzip();
+    quote!(
+        /// Where the pallets are in the runtime.
+        pub struct PalletInfo;
 
+        impl ::frame_support::traits::PalletInfo for PalletInfo {
+            fn index<P: 'static>() -> Option<usize> {
+                #( #indices )*
+                None
+            }
+
+            fn name<P: 'static>() -> Option<&'static str> {
+                #( #names )*
+                None
+            }
         }
-    };
-    
-    "test".parse().unwrap()
-}
\ No newline at end of file
+    )
+}
@@ -1,7 +1,12 @@
 
+use crate::syn_ext as ext;
+use proc_macro2::Span;
 use syn::{
-    parse::{Parse, ParseStream},    
-    token, Ident, Path, Token
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token, Error, Ident, Path, Result, Token,
 };
 
 use std::collections::{HashMap, HashSet};
@@ -32,14 +37,14 @@ pub enum RuntimeDeclaration {
 #[derive(Debug)]
 pub struct ImplicitRuntimeDeclaration {
     pub name: Ident,
-    pub where_section: WhereSection,
+    pub where_section: Option<WhereSection>,
     pub pallets: Vec<PalletDeclaration>,
 }
 
 #[derive(Debug)]
 pub struct ExplicitRuntimeDeclaration {
     pub name: Ident,
-    pub where_section: WhereSection,
+    pub where_section: Option<WhereSection>,
     pub pallets: Vec<Pallet>,
     pub pallet_token: token::Brace,
 }
@@ -49,7 +54,7 @@ impl Parse for RuntimeDeclaration {
 		input.parse::<Token![pub]>()?;
 		input.parse::<Token![enum]>()?;
 		let name = input.parse::<syn::Ident>()?;
-		let where_section = input.parse()?;
+		let where_section = if input.peek(token::Where) { Some(input.parse()?) } else { None };
 		let pallets =
 			input.parse::<ext::Braces<ext::Punctuated<PalletDeclaration, Token![,]>>>()?;
 		let pallets_token = pallets.token;
@@ -66,7 +71,7 @@ impl Parse for RuntimeDeclaration {
 					name,
 					where_section,
 					pallets,
-					pallets_token,
+					pallet_token: pallets_token,
 				})),
 		}
 	}
@@ -98,11 +103,221 @@ pub struct WhereSection {
     pub unchecked_extrinsic: syn::TypePath,
 }
 
+impl Parse for WhereSection {
+	fn parse(input: ParseStream) -> Result<Self> {
+		input.parse::<token::Where>()?;
+		let mut definitions = Vec::new();
+		while !input.peek(token::Brace) {
+			let definition: WhereDefinition = input.parse()?;
+			definitions.push(definition);
+			if !input.peek(Token![,]) {
+				if !input.peek(token::Brace) {
+					return Err(input.error("Expected `,` or `{`"))
+				}
+				break
+			}
+			input.parse::<Token![,]>()?;
+		}
+		let block = remove_kind(input, WhereKind::Block, &mut definitions)?.value;
+		let node_block = remove_kind(input, WhereKind::NodeBlock, &mut definitions)?.value;
+		let unchecked_extrinsic =
+			remove_kind(input, WhereKind::UncheckedExtrinsic, &mut definitions)?.value;
+		if let Some(WhereDefinition { ref kind_span, ref kind, .. }) = definitions.first() {
+			let msg = format!(
+				"`{:?}` was declared above. Please use exactly one declaration for `{:?}`.",
+				kind, kind
+			);
+			return Err(Error::new(*kind_span, msg))
+		}
+		Ok(Self { block, node_block, unchecked_extrinsic })
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WhereKind {
+	Block,
+	NodeBlock,
+	UncheckedExtrinsic,
+}
+
+#[derive(Debug)]
+pub struct WhereDefinition {
+	pub kind_span: Span,
+	pub kind: WhereKind,
+	pub value: syn::TypePath,
+}
+
+impl Parse for WhereDefinition {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let lookahead = input.lookahead1();
+		let (kind_span, kind) = if lookahead.peek(keyword::Block) {
+			(input.parse::<keyword::Block>()?.span(), WhereKind::Block)
+		} else if lookahead.peek(keyword::NodeBlock) {
+			(input.parse::<keyword::NodeBlock>()?.span(), WhereKind::NodeBlock)
+		} else if lookahead.peek(keyword::UncheckedExtrinsic) {
+			(input.parse::<keyword::UncheckedExtrinsic>()?.span(), WhereKind::UncheckedExtrinsic)
+		} else {
+			return Err(lookahead.error())
+		};
+
+		Ok(Self {
+			kind_span,
+			kind,
+			value: {
+				let _: Token![=] = input.parse()?;
+				input.parse()?
+			},
+		})
+	}
+}
+
+/// Remove the definition of the given kind, erroring if it is missing.
+fn remove_kind(
+	input: ParseStream,
+	kind: WhereKind,
+	definitions: &mut Vec<WhereDefinition>,
+) -> Result<WhereDefinition> {
+	if let Some(pos) = definitions.iter().position(|d| d.kind == kind) {
+		Ok(definitions.remove(pos))
+	} else {
+		let msg = format!(
+			"Missing associated type for `{:?}`. Add `{:?}` = ... to where section.",
+			kind, kind
+		);
+		Err(input.error(msg))
+	}
+}
+
+impl Parse for PalletDeclaration {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let name = input.parse()?;
+		let _: Token![:] = input.parse()?;
+		let path = input.parse()?;
+
+		// Parse for instance.
+		let instance = if input.peek(Token![::]) && input.peek3(Token![<]) {
+			let _: Token![::] = input.parse()?;
+			let _: Token![<] = input.parse()?;
+			let res = Some(input.parse()?);
+			let _: Token![>] = input.parse()?;
+			res
+		} else if !(input.peek(Token![::]) && input.peek3(token::Brace) ||
+			input.peek(keyword::exclude_parts) ||
+			input.peek(keyword::use_parts) ||
+			input.peek(Token![=]) ||
+			input.peek(Token![,]) ||
+			input.is_empty())
+		{
+			return Err(input.error(
+				"Unexpected tokens, expected one of `::$ident` `::{`, `exclude_parts`, `use_parts`, `=`, `,`",
+			))
+		} else {
+			None
+		};
+
+		// Parse for explicit parts
+		let pallet_parts = if input.peek(Token![::]) && input.peek3(token::Brace) {
+			let _: Token![::] = input.parse()?;
+			Some(parse_pallet_parts(input)?)
+		} else if !input.peek(keyword::exclude_parts) &&
+			!input.peek(keyword::use_parts) &&
+			!input.peek(Token![=]) &&
+			!input.peek(Token![,]) &&
+			!input.is_empty()
+		{
+			return Err(input.error(
+				"Unexpected tokens, expected one of `::{`, `exclude_parts`, `use_parts`, `=`, `,`",
+			))
+		} else {
+			None
+		};
+
+		// Parse for specified parts
+		let specified_parts = if input.peek(keyword::exclude_parts) {
+			let _: keyword::exclude_parts = input.parse()?;
+			SpecifiedParts::Exclude(parse_pallet_parts_no_generic(input)?)
+		} else if input.peek(keyword::use_parts) {
+			let _: keyword::use_parts = input.parse()?;
+			SpecifiedParts::Use(parse_pallet_parts_no_generic(input)?)
+		} else if !input.peek(Token![=]) && !input.peek(Token![,]) && !input.is_empty() {
+			return Err(input.error("Unexpected tokens, expected one of `exclude_parts`, `=`, `,`"))
+		} else {
+			SpecifiedParts::All
+		};
+
+		// Parse for pallet index
+		let index = if input.peek(Token![=]) {
+			input.parse::<Token![=]>()?;
+			let index = input.parse::<syn::LitInt>()?;
+			let index = index.base10_parse::<u8>()?;
+			Some(index)
+		} else if !input.peek(Token![,]) && !input.is_empty() {
+			return Err(input.error("Unexpected tokens, expected one of `=`, `,`"))
+		} else {
+			None
+		};
+
+		Ok(Self { name, path, instance, pallet_parts, specified_parts, index })
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct PalletPath {
 	pub inner: Path,
 }
 
+impl Parse for PalletPath {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let mut res =
+			PalletPath { inner: Path { leading_colon: None, segments: Punctuated::new() } };
+
+		let lookahead = input.lookahead1();
+		if lookahead.peek(Token![crate]) ||
+			lookahead.peek(Token![self]) ||
+			lookahead.peek(Token![super]) ||
+			lookahead.peek(Ident)
+		{
+			let ident = input.call(Ident::parse_any)?;
+			res.inner.segments.push(ident.into());
+		} else {
+			return Err(lookahead.error())
+		}
+
+		while input.peek(Token![::]) && input.peek3(Ident) {
+			input.parse::<Token![::]>()?;
+			let ident = input.parse::<Ident>()?;
+			res.inner.segments.push(ident.into());
+		}
+		Ok(res)
+	}
+}
+
+impl quote::ToTokens for PalletPath {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		self.inner.to_tokens(tokens)
+	}
+}
+
+/// Parse [`PalletPart`]'s from a braces enclosed list that is split by commas, e.g.
+///
+/// `{ Call, Event }`
+fn parse_pallet_parts(input: ParseStream) -> Result<Vec<PalletPart>> {
+	let pallet_parts: ext::Braces<ext::Punctuated<PalletPart, Token![,]>> = input.parse()?;
+
+	let mut resolved = HashSet::new();
+	for part in pallet_parts.content.inner.iter() {
+		if !resolved.insert(part.name()) {
+			let msg = format!(
+				"`{}` was already declared before. Please remove the duplicate declaration",
+				part.name(),
+			);
+			return Err(Error::new(part.keyword.span(), msg))
+		}
+	}
+
+	Ok(pallet_parts.content.inner.into_iter().collect())
+}
+
 #[derive(Debug, Clone)]
 pub enum SpecifiedParts {
 	/// Use all the pallet parts except those specified.
@@ -119,12 +334,71 @@ pub struct PalletPart {
 	pub generics: syn::Generics,
 }
 
+impl Parse for PalletPart {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let keyword: PalletPartKeyword = input.parse()?;
+
+		let generics: syn::Generics = input.parse()?;
+		if !generics.params.is_empty() && !keyword.is_generic_allowed() {
+			let valid_generics = PalletPart::format_names(keyword.allowed_generics());
+			let msg = format!(
+				"`{}` is not allowed to have generics. \
+				 Only the following pallets are allowed to have generics: {}.",
+				keyword.name(),
+				valid_generics.join(", "),
+			);
+			return Err(syn::Error::new(keyword.span(), msg))
+		}
+
+		Ok(Self { keyword, generics })
+	}
+}
+
+impl PalletPart {
+	pub fn format_names(names: &[&'static str]) -> Vec<String> {
+		let res: Vec<_> = names.iter().map(|s| format!("`{}`", s)).collect();
+		res
+	}
+
+	/// The name of this pallet part.
+	pub fn name(&self) -> &'static str {
+		self.keyword.name()
+	}
+}
+
 /// The declaration of a part without its generics
 #[derive(Debug, Clone)]
 pub struct PalletPartNoGeneric {
 	keyword: PalletPartKeyword,
 }
 
+impl Parse for PalletPartNoGeneric {
+	fn parse(input: ParseStream) -> Result<Self> {
+		Ok(Self { keyword: input.parse()? })
+	}
+}
+
+/// Parse [`PalletPartNoGeneric`]'s from a braces enclosed list that is split by commas, e.g.
+///
+/// `{ Call, Event }`
+fn parse_pallet_parts_no_generic(input: ParseStream) -> Result<Vec<PalletPartNoGeneric>> {
+	let pallet_parts: ext::Braces<ext::Punctuated<PalletPartNoGeneric, Token![,]>> =
+		input.parse()?;
+
+	let mut resolved = HashSet::new();
+	for part in pallet_parts.content.inner.iter() {
+		if !resolved.insert(part.keyword.name()) {
+			let msg = format!(
+				"`{}` was already declared before. Please remove the duplicate declaration",
+				part.keyword.name(),
+			);
+			return Err(Error::new(part.keyword.span(), msg))
+		}
+	}
+
+	Ok(pallet_parts.content.inner.into_iter().collect())
+}
+
 #[derive(Debug, Clone)]
 pub enum PalletPartKeyword {
 	Pallet(keyword::Pallet),
@@ -137,6 +411,76 @@ pub enum PalletPartKeyword {
 	ValidateUnsigned(keyword::ValidateUnsigned),
 }
 
+impl Parse for PalletPartKeyword {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let lookahead = input.lookahead1();
+
+		if lookahead.peek(keyword::Pallet) {
+			Ok(Self::Pallet(input.parse()?))
+		} else if lookahead.peek(keyword::Call) {
+			Ok(Self::Call(input.parse()?))
+		} else if lookahead.peek(keyword::Storage) {
+			Ok(Self::Storage(input.parse()?))
+		} else if lookahead.peek(keyword::Event) {
+			Ok(Self::Event(input.parse()?))
+		} else if lookahead.peek(keyword::Config) {
+			Ok(Self::Config(input.parse()?))
+		} else if lookahead.peek(keyword::Origin) {
+			Ok(Self::Origin(input.parse()?))
+		} else if lookahead.peek(keyword::Inherent) {
+			Ok(Self::Inherent(input.parse()?))
+		} else if lookahead.peek(keyword::ValidateUnsigned) {
+			Ok(Self::ValidateUnsigned(input.parse()?))
+		} else {
+			Err(lookahead.error())
+		}
+	}
+}
+
+impl PalletPartKeyword {
+	/// Returns the name of `Self`.
+	fn name(&self) -> &'static str {
+		match self {
+			Self::Pallet(_) => "Pallet",
+			Self::Call(_) => "Call",
+			Self::Storage(_) => "Storage",
+			Self::Event(_) => "Event",
+			Self::Config(_) => "Config",
+			Self::Origin(_) => "Origin",
+			Self::Inherent(_) => "Inherent",
+			Self::ValidateUnsigned(_) => "ValidateUnsigned",
+		}
+	}
+
+	/// Returns `true` if this pallet part is allowed to have generic arguments.
+	fn is_generic_allowed(&self) -> bool {
+		!self.allowed_generics().is_empty()
+	}
+
+	/// Returns the names of the generic arguments this pallet part is allowed to have.
+	fn allowed_generics(&self) -> &'static [&'static str] {
+		match self {
+			Self::Event(_) | Self::Origin(_) | Self::Config(_) => &["T"],
+			_ => &[],
+		}
+	}
+}
+
+impl Spanned for PalletPartKeyword {
+	fn span(&self) -> Span {
+		match self {
+			Self::Pallet(inner) => inner.span(),
+			Self::Call(inner) => inner.span(),
+			Self::Storage(inner) => inner.span(),
+			Self::Event(inner) => inner.span(),
+			Self::Config(inner) => inner.span(),
+			Self::Origin(inner) => inner.span(),
+			Self::Inherent(inner) => inner.span(),
+			Self::ValidateUnsigned(inner) => inner.span(),
+		}
+	}
+}
+
 /// The final definition of a pallet with the resulting fixed index and explicit parts.
 #[derive(Debug, Clone)]
 pub struct Pallet {
@@ -152,6 +496,23 @@ pub struct Pallet {
 	pub pallet_parts: Vec<PalletPart>,
 }
 
+impl Pallet {
+	/// Get resolved pallet parts
+	pub fn pallet_parts(&self) -> &[PalletPart] {
+		&self.pallet_parts
+	}
+
+	/// Find matching parts
+	pub fn find_part(&self, name: &str) -> Option<&PalletPart> {
+		self.pallet_parts.iter().find(|part| part.name() == name)
+	}
+
+	/// Return whether pallet contains part
+	pub fn exists_part(&self, name: &str) -> bool {
+		self.find_part(name).is_some()
+	}
+}
+
 enum PalletsConversion {
 	Implicit(Vec<PalletDeclaration>),
 	Explicit(Vec<Pallet>),
@@ -192,8 +553,8 @@ fn convert_pallets(pallets: Vec<PalletDeclaration>) -> syn::Result<PalletsConver
 			if let Some(used_pallet) = names.insert(pallet.name.clone(), pallet.name.span()) {
 				let msg = "Two pallets with the same name!";
 
-				let mut err = syn::Error::new(used_pallet, &msg);
-				err.combine(syn::Error::new(pallet.name.span(), &msg));
+				let mut err = syn::Error::new(used_pallet, msg);
+				err.combine(syn::Error::new(pallet.name.span(), msg));
 				return Err(err)
 			}
 
@@ -1,8 +1,51 @@
-
+mod codec;
 mod construct_runtime;
+mod syn_ext;
+mod type_info;
 use proc_macro::TokenStream;
 
 #[proc_macro]
 pub fn construct_runtime(input: TokenStream) -> TokenStream {
     construct_runtime::construct_runtime(input)
-}
\ No newline at end of file
+}
+
+/// Derive `frame_support::codec::Encode`.
+///
+/// Structs encode their fields in declaration order. Enums encode the variant index as one
+/// byte followed by the fields of the variant. Supported attributes:
+/// * `#[codec(index = n)]` on a variant overrides its index,
+/// * `#[codec(compact)]` on a field encodes it with the compact integer encoding,
+/// * `#[codec(skip)]` on a field or a variant leaves it out of the encoding.
+#[proc_macro_derive(Encode, attributes(codec))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    codec::derive_encode(input)
+}
+
+/// Derive `frame_support::codec::Decode`, the counterpart of [`macro@Encode`].
+///
+/// Skipped fields are filled with their `Default` value. Every decoded type counts as one
+/// level of nesting for `DecodeLimit`.
+#[proc_macro_derive(Decode, attributes(codec))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    codec::derive_decode(input)
+}
+
+/// Derive `frame_support::codec::MaxEncodedLen`.
+///
+/// Structs are bounded by the sum of their fields, enums by one byte for the index plus their
+/// largest variant. The `#[codec(..)]` attributes of [`macro@Encode`] are taken into account.
+#[proc_macro_derive(MaxEncodedLen, attributes(codec))]
+pub fn derive_max_encoded_len(input: TokenStream) -> TokenStream {
+    codec::derive_max_encoded_len(input)
+}
+
+/// Derive `frame_support::scale_info::TypeInfo`.
+///
+/// The description follows the SCALE encoding of the type, so the `#[codec(..)]` attributes
+/// of [`macro@Encode`] are taken into account. Doc comments are kept. Type parameters which
+/// don't implement `TypeInfo`, e.g. the runtime of a pallet type, can be excluded with
+/// `#[scale_info(skip_type_params(T))]`.
+#[proc_macro_derive(TypeInfo, attributes(scale_info, codec))]
+pub fn derive_type_info(input: TokenStream) -> TokenStream {
+    type_info::derive_type_info(input.into()).unwrap_or_else(|e| e.to_compile_error()).into()
+}
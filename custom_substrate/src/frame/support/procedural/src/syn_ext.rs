@@ -0,0 +1,35 @@
+//! Small parsing helpers on top of `syn`, shared by the macros of this crate.
+
+use syn::{
+    braced,
+    parse::{Parse, ParseStream, Result},
+    token,
+};
+
+/// Content wrapped in `{ }`, keeping the brace token for error spans.
+#[derive(Debug)]
+pub struct Braces<P> {
+    pub token: token::Brace,
+    pub content: P,
+}
+
+impl<P: Parse> Parse for Braces<P> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let token = braced!(content in input);
+        let content = content.parse()?;
+        Ok(Self { token, content })
+    }
+}
+
+/// A `syn::punctuated::Punctuated` that parses itself with an optional trailing separator.
+#[derive(Debug)]
+pub struct Punctuated<P, T> {
+    pub inner: syn::punctuated::Punctuated<P, T>,
+}
+
+impl<P: Parse, T: Parse + token::Token> Parse for Punctuated<P, T> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { inner: syn::punctuated::Punctuated::parse_terminated(input)? })
+    }
+}
@@ -0,0 +1,191 @@
+//! Implementation of `#[derive(TypeInfo)]`.
+
+use crate::codec::utils;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashSet;
+use syn::{
+    spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, Ident, Lit, Meta, NestedMeta,
+    Result,
+};
+
+/// Path to the type information module of `frame_support`, used by all generated code.
+fn scale_info_crate() -> TokenStream {
+    quote!(::frame_support::scale_info)
+}
+
+pub fn derive_type_info(input: TokenStream) -> Result<TokenStream> {
+    let mut input: DeriveInput = syn::parse2(input)?;
+    let scale_info = scale_info_crate();
+    let codec = utils::codec_crate();
+    utils::reject_union(&input.data, input.ident.span())?;
+
+    let skipped = skip_type_params(&input.attrs)?;
+    for param in &skipped {
+        if !input.generics.type_params().any(|p| p.ident == *param) {
+            return Err(Error::new(param.span(), "unknown type parameter"))
+        }
+    }
+
+    let type_params = input.generics.type_params().map(|param| {
+        let ident = &param.ident;
+        let name = ident.to_string();
+        if skipped.contains(ident) {
+            quote!(#scale_info::TypeParameter { name: #name, ty: None })
+        } else {
+            quote!(#scale_info::TypeParameter {
+                name: #name,
+                ty: Some(#scale_info::meta_type::<#ident>()),
+            })
+        }
+    });
+    let type_params = quote!(vec![ #( #type_params ),* ]);
+
+    // Skipped parameters only need to outlive the `Identity`, the others describe themselves.
+    let param_bounds: Vec<syn::WherePredicate> = input
+        .generics
+        .type_params()
+        .map(|param| {
+            let ident = &param.ident;
+            if skipped.contains(ident) {
+                syn::parse_quote!(#ident: 'static)
+            } else {
+                syn::parse_quote!(#ident: #scale_info::TypeInfo + 'static)
+            }
+        })
+        .collect();
+    input.generics.make_where_clause().predicates.extend(param_bounds);
+    utils::add_field_bounds(&mut input.generics, &input.data, |ty, attrs| match attrs {
+        attrs if attrs.skip => None,
+        attrs if attrs.compact => Some(quote!(#codec::Compact<#ty>: #scale_info::TypeInfo)),
+        _ => Some(quote!(#ty: #scale_info::TypeInfo + 'static)),
+    })?;
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let type_docs = docs(&input.attrs);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let type_def = match &input.data {
+        Data::Struct(data) => {
+            let fields = fields(&data.fields)?;
+            quote!(#scale_info::TypeDef::Composite(vec![ #( #fields ),* ]))
+        },
+        Data::Enum(data) => {
+            let variants = utils::indexed_variants(&data.variants)?
+                .into_iter()
+                .map(|utils::IndexedVariant { variant, index }| {
+                    let name = variant.ident.to_string();
+                    let fields = fields(&variant.fields)?;
+                    let variant_docs = docs(&variant.attrs);
+                    Ok(quote!(#scale_info::Variant {
+                        name: #name,
+                        fields: vec![ #( #fields ),* ],
+                        index: #index,
+                        docs: vec![ #( #variant_docs ),* ],
+                    }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote!(#scale_info::TypeDef::Variant(vec![ #( #variants ),* ]))
+        },
+        Data::Union(_) => unreachable!("rejected above"),
+    };
+
+    Ok(quote! {
+        const _: () = {
+            impl #impl_generics #scale_info::TypeInfo for #name #ty_generics #where_clause {
+                type Identity = Self;
+
+                fn type_info() -> #scale_info::Type {
+                    #scale_info::Type {
+                        path: #scale_info::Path::new(#name_str, ::core::module_path!()),
+                        type_params: #type_params,
+                        type_def: #type_def,
+                        docs: vec![ #( #type_docs ),* ],
+                    }
+                }
+            }
+        };
+    })
+}
+
+/// The description of every encoded field.
+fn fields(fields: &Fields) -> Result<Vec<TokenStream>> {
+    let scale_info = scale_info_crate();
+    let codec = utils::codec_crate();
+    let mut res = Vec::new();
+    for field in fields {
+        let attrs = utils::field_attrs(field)?;
+        if attrs.skip {
+            continue
+        }
+        let ty = &field.ty;
+        let meta_type = if attrs.compact {
+            quote!(#scale_info::meta_type::<#codec::Compact<#ty>>())
+        } else {
+            quote!(#scale_info::meta_type::<#ty>())
+        };
+        let name = match &field.ident {
+            Some(ident) => {
+                let ident = ident.to_string();
+                quote!(Some(#ident))
+            },
+            None => quote!(None),
+        };
+        let type_name = quote!(#ty).to_string().replace(' ', "");
+        let field_docs = docs(&field.attrs);
+        res.push(quote!(#scale_info::Field {
+            name: #name,
+            ty: #meta_type,
+            type_name: Some(#type_name),
+            docs: vec![ #( #field_docs ),* ],
+        }));
+    }
+    Ok(res)
+}
+
+/// The lines of the doc comments, without the space following `///`.
+fn docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(nv)) => match nv.lit {
+                Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The type parameters listed in `#[scale_info(skip_type_params(..))]`.
+fn skip_type_params(attrs: &[Attribute]) -> Result<HashSet<Ident>> {
+    let mut res = HashSet::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("scale_info")) {
+        let Meta::List(list) = attr.parse_meta()? else {
+            return Err(Error::new(attr.span(), "expected `#[scale_info(..)]`"))
+        };
+        for meta in list.nested {
+            match meta {
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("skip_type_params") =>
+                    for param in list.nested {
+                        match param {
+                            NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                                res.insert(path.get_ident().expect("checked above").clone());
+                            },
+                            param => {
+                                let msg = "expected a type parameter";
+                                return Err(Error::new(param.span(), msg))
+                            },
+                        }
+                    },
+                meta => {
+                    let msg = "invalid attribute, expected `skip_type_params(..)`";
+                    return Err(Error::new(meta.span(), msg))
+                },
+            }
+        }
+    }
+    Ok(res)
+}
@@ -0,0 +1,494 @@
+//! Numbers with a fixed number of decimals, stored as integers counting the smallest decimal.
+
+use super::{multiply_by_rational_with_rounding, Rounding};
+use crate::{
+    codec::{Decode, Encode, MaxEncodedLen},
+    scale_info::TypeInfo,
+};
+use num::{Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Saturating, Zero};
+use std::{
+    fmt::{self, Debug},
+    ops::{Add, Div, Mul, Sub},
+};
+
+/// An integer fixed point numbers can be built from or multiplied with, e.g. a balance.
+pub trait FixedPointOperand: Copy + Bounded + Zero + PartialOrd + Debug {
+    /// Whether the value is negative, and its absolute value.
+    fn into_magnitude(self) -> (bool, u128);
+
+    /// The value with the sign and the absolute value given, `None` if it does not fit.
+    fn from_magnitude(negative: bool, magnitude: u128) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned_operand {
+    ( $( $t:ty ),* ) => { $(
+        impl FixedPointOperand for $t {
+            fn into_magnitude(self) -> (bool, u128) {
+                (false, self as u128)
+            }
+
+            fn from_magnitude(negative: bool, magnitude: u128) -> Option<Self> {
+                if negative && magnitude != 0 {
+                    return None
+                }
+                magnitude.try_into().ok()
+            }
+        }
+    )* };
+}
+
+macro_rules! impl_signed_operand {
+    ( $( $t:ty ),* ) => { $(
+        impl FixedPointOperand for $t {
+            fn into_magnitude(self) -> (bool, u128) {
+                (self < 0, (self as i128).unsigned_abs())
+            }
+
+            fn from_magnitude(negative: bool, magnitude: u128) -> Option<Self> {
+                let value = if negative {
+                    0i128.checked_sub_unsigned(magnitude)?
+                } else {
+                    i128::try_from(magnitude).ok()?
+                };
+                value.try_into().ok()
+            }
+        }
+    )* };
+}
+
+impl_unsigned_operand!(u8, u16, u32, u64, u128);
+impl_signed_operand!(i8, i16, i32, i64, i128);
+
+/// A number with a fixed number of decimals: `into_inner() / DIV`.
+///
+/// Products and quotients are rounded towards zero, and saturating operations saturate at
+/// the bounds of the inner type.
+pub trait FixedPointNumber:
+    Sized
+    + Copy
+    + Default
+    + Ord
+    + Debug
+    + Zero
+    + One
+    + Bounded
+    + Saturating
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+{
+    /// The integer counting the smallest decimal.
+    type Inner: FixedPointOperand;
+
+    /// The inner value of one.
+    const DIV: Self::Inner;
+
+    fn from_inner(inner: Self::Inner) -> Self;
+
+    fn into_inner(self) -> Self::Inner;
+
+    /// The inner value of one, as a `u128`.
+    fn accuracy() -> u128 {
+        Self::DIV.into_magnitude().1
+    }
+
+    /// The number with the sign and the absolute inner value given, `None` if it does not fit.
+    fn checked_from_magnitude(negative: bool, magnitude: u128) -> Option<Self> {
+        Self::Inner::from_magnitude(negative, magnitude).map(Self::from_inner)
+    }
+
+    /// The integer `int`, `None` if it is out of the bounds.
+    fn checked_from_integer<N: FixedPointOperand>(int: N) -> Option<Self> {
+        let (negative, magnitude) = int.into_magnitude();
+        Self::checked_from_magnitude(negative, magnitude.checked_mul(Self::accuracy())?)
+    }
+
+    /// The integer `int`, saturating at the bounds.
+    fn saturating_from_integer<N: FixedPointOperand>(int: N) -> Self {
+        Self::checked_from_integer(int).unwrap_or_else(|| saturated(int < N::zero()))
+    }
+
+    /// `n / d`, `None` if `d` is zero or the ratio is out of the bounds.
+    fn checked_from_rational<N: FixedPointOperand, D: FixedPointOperand>(
+        n: N,
+        d: D,
+    ) -> Option<Self> {
+        let ((n_negative, n), (d_negative, d)) = (n.into_magnitude(), d.into_magnitude());
+        let magnitude = multiply_by_rational_with_rounding(n, Self::accuracy(), d, Rounding::Down)?;
+        Self::checked_from_magnitude(n_negative != d_negative, magnitude)
+    }
+
+    /// `n / d`, saturating at the bounds, also when `d` is zero.
+    fn saturating_from_rational<N: FixedPointOperand, D: FixedPointOperand>(n: N, d: D) -> Self {
+        Self::checked_from_rational(n, d)
+            .unwrap_or_else(|| saturated((n < N::zero()) != (d < D::zero())))
+    }
+
+    /// `self * n` as an integer, `None` if it does not fit in `N`.
+    fn checked_mul_int<N: FixedPointOperand>(self, n: N) -> Option<N> {
+        let ((negative, magnitude), (n_negative, n)) =
+            (self.into_inner().into_magnitude(), n.into_magnitude());
+        let product =
+            multiply_by_rational_with_rounding(n, magnitude, Self::accuracy(), Rounding::Down)?;
+        N::from_magnitude(negative != n_negative, product)
+    }
+
+    /// `self * n` as an integer, saturating at the bounds of `N`.
+    fn saturating_mul_int<N: FixedPointOperand>(self, n: N) -> N {
+        self.checked_mul_int(n).unwrap_or_else(|| {
+            if self.is_negative() != (n < N::zero()) {
+                N::min_value()
+            } else {
+                N::max_value()
+            }
+        })
+    }
+
+    /// `self * rhs`, saturating at the bounds.
+    fn saturating_mul(self, rhs: Self) -> Self {
+        self.checked_mul(&rhs).unwrap_or_else(|| saturated(self.is_negative() != rhs.is_negative()))
+    }
+
+    /// `self` multiplied by itself `exp` times, saturating at the bounds.
+    fn saturating_pow(self, exp: usize) -> Self {
+        (0..exp).fold(Self::one(), |acc, _| acc.saturating_mul(self))
+    }
+
+    /// `1 / self`, `None` if `self` is zero.
+    fn reciprocal(self) -> Option<Self> {
+        Self::one().checked_div(&self)
+    }
+
+    fn is_positive(self) -> bool {
+        self.into_inner() > Self::Inner::zero()
+    }
+
+    fn is_negative(self) -> bool {
+        self.into_inner() < Self::Inner::zero()
+    }
+
+    /// The integer part, i.e. `self` rounded towards zero.
+    fn trunc(self) -> Self {
+        self.round_magnitude(false)
+    }
+
+    /// The fractional part, `self - self.trunc()`, with the sign of `self`.
+    fn frac(self) -> Self {
+        let (negative, magnitude) = self.into_inner().into_magnitude();
+        Self::checked_from_magnitude(negative, magnitude % Self::accuracy())
+            .expect("smaller than self; qed")
+    }
+
+    /// The largest integer not greater than `self`, saturating at the bounds.
+    fn floor(self) -> Self {
+        self.round_magnitude(self.is_negative())
+    }
+
+    /// The smallest integer not less than `self`, saturating at the bounds.
+    fn ceil(self) -> Self {
+        self.round_magnitude(self.is_positive())
+    }
+
+    /// The nearest integer, away from zero from half way, saturating at the bounds.
+    fn round(self) -> Self {
+        let magnitude = self.into_inner().into_magnitude().1;
+        self.round_magnitude(magnitude % Self::accuracy() >= Self::accuracy() / 2)
+    }
+
+    /// The integer next to `self`, away from zero if `up` and if `self` is not an integer.
+    fn round_magnitude(self, up: bool) -> Self {
+        let (negative, magnitude) = self.into_inner().into_magnitude();
+        let remainder = magnitude % Self::accuracy();
+        let down = magnitude - remainder;
+        if !up || remainder == 0 {
+            return Self::checked_from_magnitude(negative, down).expect("smaller than self; qed")
+        }
+        down.checked_add(Self::accuracy())
+            .and_then(|magnitude| Self::checked_from_magnitude(negative, magnitude))
+            .unwrap_or_else(|| saturated(negative))
+    }
+}
+
+/// The lower bound if `negative`, the upper bound otherwise.
+fn saturated<F: Bounded>(negative: bool) -> F {
+    if negative {
+        F::min_value()
+    } else {
+        F::max_value()
+    }
+}
+
+macro_rules! implement_fixed {
+    (
+        $name:ident,
+        $inner:ty,
+        $decimals:expr,
+        $(#[$attr:meta])*
+    ) => {
+        $(#[$attr])*
+        #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, PartialOrd)]
+        #[derive(Ord, Default, Hash, Debug)]
+        pub struct $name($inner);
+
+        impl $name {
+            /// The inner value of one.
+            pub const DIV: $inner = (10 as $inner).pow($decimals);
+
+            pub const fn from_inner(inner: $inner) -> Self {
+                Self(inner)
+            }
+
+            pub const fn into_inner(self) -> $inner {
+                self.0
+            }
+
+            /// `n / d`, saturating at the bounds, also when `d` is zero.
+            pub fn from_rational(n: u128, d: u128) -> Self {
+                Self::saturating_from_rational(n, d)
+            }
+
+            /// The number of the given integer and fractional parts, `1.5` being
+            /// `from_parts(1, DIV / 2)`.
+            pub const fn from_parts(integer: $inner, fraction: $inner) -> Self {
+                Self(integer * Self::DIV + fraction)
+            }
+        }
+
+        impl FixedPointNumber for $name {
+            type Inner = $inner;
+
+            const DIV: $inner = Self::DIV;
+
+            fn from_inner(inner: $inner) -> Self {
+                Self(inner)
+            }
+
+            fn into_inner(self) -> $inner {
+                self.0
+            }
+        }
+
+        impl Bounded for $name {
+            fn min_value() -> Self {
+                Self(<$inner>::MIN)
+            }
+
+            fn max_value() -> Self {
+                Self(<$inner>::MAX)
+            }
+        }
+
+        impl Zero for $name {
+            fn zero() -> Self {
+                Self(0)
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl One for $name {
+            fn one() -> Self {
+                Self(Self::DIV)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                self.checked_mul(&rhs).expect("attempt to multiply with overflow")
+            }
+        }
+
+        impl Div for $name {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                self.checked_div(&rhs).expect("attempt to divide by zero or with overflow")
+            }
+        }
+
+        impl CheckedAdd for $name {
+            fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                self.0.checked_add(rhs.0).map(Self)
+            }
+        }
+
+        impl CheckedSub for $name {
+            fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                self.0.checked_sub(rhs.0).map(Self)
+            }
+        }
+
+        impl CheckedMul for $name {
+            fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                let ((negative, lhs), (rhs_negative, rhs)) =
+                    (self.0.into_magnitude(), rhs.0.into_magnitude());
+                let product =
+                    multiply_by_rational_with_rounding(lhs, rhs, Self::accuracy(), Rounding::Down)?;
+                Self::checked_from_magnitude(negative != rhs_negative, product)
+            }
+        }
+
+        impl CheckedDiv for $name {
+            fn checked_div(&self, rhs: &Self) -> Option<Self> {
+                let ((negative, lhs), (rhs_negative, rhs)) =
+                    (self.0.into_magnitude(), rhs.0.into_magnitude());
+                let quotient =
+                    multiply_by_rational_with_rounding(lhs, Self::accuracy(), rhs, Rounding::Down)?;
+                Self::checked_from_magnitude(negative != rhs_negative, quotient)
+            }
+        }
+
+        impl Saturating for $name {
+            fn saturating_add(self, rhs: Self) -> Self {
+                Self(self.0.saturating_add(rhs.0))
+            }
+
+            fn saturating_sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        /// All the decimals, e.g. `1.500000000` for a `FixedI64` of one and a half.
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let (negative, magnitude) = self.0.into_magnitude();
+                let accuracy = Self::accuracy();
+                let sign = if negative { "-" } else { "" };
+                write!(f, "{}{}.{:0>width$}", sign, magnitude / accuracy, magnitude % accuracy,
+                    width = $decimals as usize)
+            }
+        }
+    };
+}
+
+implement_fixed!(
+    FixedU128,
+    u128,
+    18,
+    /// An unsigned number with 18 decimals, e.g. a multiplier of fees.
+);
+
+implement_fixed!(
+    FixedI64,
+    i64,
+    9,
+    /// A signed number with 9 decimals.
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_i64(n: i64, d: i64) -> FixedI64 {
+        FixedI64::saturating_from_rational(n, d)
+    }
+
+    #[test]
+    fn construction_works() {
+        assert_eq!(FixedU128::from_rational(3, 2), FixedU128::from_parts(1, FixedU128::DIV / 2));
+        assert_eq!(FixedU128::saturating_from_integer(7u32).into_inner(), 7 * FixedU128::DIV);
+        let minus_two = FixedI64::from_inner(-2_000_000_000);
+        assert_eq!(FixedI64::checked_from_integer(-2i32), Some(minus_two));
+        assert_eq!(FixedI64::checked_from_integer(u64::MAX), None);
+        assert_eq!(FixedI64::saturating_from_integer(i64::MIN), FixedI64::min_value());
+        assert_eq!(FixedU128::saturating_from_integer(-1i8), FixedU128::zero());
+        assert_eq!(fixed_i64(-1, 4), FixedI64::from_inner(-250_000_000));
+        assert_eq!(fixed_i64(1, -4), fixed_i64(-1, 4));
+        assert_eq!(FixedI64::checked_from_rational(1, 0), None);
+        assert_eq!(FixedI64::saturating_from_rational(-1, 0), FixedI64::min_value());
+        assert_eq!(FixedU128::checked_from_rational(-1, 2), None);
+        assert_eq!(FixedU128::from_rational(1, 3).into_inner(), 333_333_333_333_333_333);
+    }
+
+    #[test]
+    fn arithmetic_works() {
+        let (half, two) = (FixedU128::from_rational(1, 2), FixedU128::saturating_from_integer(2));
+        assert_eq!(half + half, FixedU128::one());
+        assert_eq!(two - half, FixedU128::from_rational(3, 2));
+        assert_eq!(half * two, FixedU128::one());
+        assert_eq!(half / two, FixedU128::from_rational(1, 4));
+        assert_eq!(fixed_i64(-3, 2) * fixed_i64(-2, 1), fixed_i64(3, 1));
+        assert_eq!(fixed_i64(3, 1) / fixed_i64(-2, 1), fixed_i64(-3, 2));
+        assert_eq!(two.reciprocal(), Some(half));
+        assert_eq!(FixedU128::zero().reciprocal(), None);
+    }
+
+    #[test]
+    fn checked_and_saturating_operations_work() {
+        let max = FixedU128::max_value();
+        assert_eq!(max.checked_add(&FixedU128::one()), None);
+        assert_eq!(max.saturating_add(FixedU128::one()), max);
+        assert_eq!(FixedU128::zero().checked_sub(&FixedU128::one()), None);
+        assert_eq!(FixedU128::zero().saturating_sub(FixedU128::one()), FixedU128::zero());
+        assert_eq!(max.checked_mul(&FixedU128::saturating_from_integer(2)), None);
+        assert_eq!(max.saturating_mul(FixedU128::saturating_from_integer(2)), max);
+        assert_eq!(FixedI64::max_value().saturating_mul(fixed_i64(-2, 1)), FixedI64::min_value());
+        assert_eq!(FixedU128::one().checked_div(&FixedU128::zero()), None);
+        assert_eq!(fixed_i64(3, 2).saturating_pow(2), fixed_i64(9, 4));
+        assert_eq!(fixed_i64(-2, 1).saturating_pow(63), FixedI64::min_value());
+    }
+
+    #[test]
+    fn multiplying_integers_works() {
+        let rate = FixedU128::from_rational(3, 2);
+        assert_eq!(rate.checked_mul_int(10u64), Some(15));
+        assert_eq!(rate.checked_mul_int(u64::MAX), None);
+        assert_eq!(rate.saturating_mul_int(u64::MAX), u64::MAX);
+        assert_eq!(FixedU128::from_rational(1, 3).saturating_mul_int(10u32), 3);
+        assert_eq!(fixed_i64(-3, 2).checked_mul_int(3i32), Some(-4));
+        assert_eq!(fixed_i64(-3, 2).checked_mul_int(3u32), None);
+        assert_eq!(fixed_i64(-3, 2).saturating_mul_int(3u32), 0);
+        assert_eq!(fixed_i64(-3, 2).saturating_mul_int(i32::MAX), i32::MIN);
+    }
+
+    #[test]
+    fn rounding_to_integers_works() {
+        let minus_one_and_a_half = fixed_i64(-3, 2);
+        assert_eq!(minus_one_and_a_half.trunc(), fixed_i64(-1, 1));
+        assert_eq!(minus_one_and_a_half.frac(), fixed_i64(-1, 2));
+        assert_eq!(minus_one_and_a_half.floor(), fixed_i64(-2, 1));
+        assert_eq!(minus_one_and_a_half.ceil(), fixed_i64(-1, 1));
+        assert_eq!(minus_one_and_a_half.round(), fixed_i64(-2, 1));
+        assert_eq!(fixed_i64(5, 4).round(), fixed_i64(1, 1));
+        assert_eq!(fixed_i64(5, 4).ceil(), fixed_i64(2, 1));
+        assert_eq!(fixed_i64(2, 1).ceil(), fixed_i64(2, 1));
+        assert_eq!(FixedI64::max_value().ceil(), FixedI64::max_value());
+        assert_eq!(FixedU128::max_value().floor().frac(), FixedU128::zero());
+    }
+
+    #[test]
+    fn display_shows_every_decimal() {
+        assert_eq!(fixed_i64(-3, 2).to_string(), "-1.500000000");
+        assert_eq!(FixedU128::from_rational(1, 100).to_string(), "0.010000000000000000");
+        assert_eq!(FixedI64::from_inner(7).to_string(), "0.000000007");
+    }
+
+    #[test]
+    fn encoding_is_the_inner_value() {
+        let rate = FixedU128::from_rational(1, 2);
+        assert_eq!(rate.encode(), (FixedU128::DIV / 2).encode());
+        assert_eq!(FixedU128::decode(&mut &rate.encode()[..]), Ok(rate));
+        assert_eq!(FixedI64::max_encoded_len(), 8);
+    }
+}
@@ -0,0 +1,80 @@
+//! Exact arithmetic for balances and the rates applied to them.
+//!
+//! * [`PerThing`]s, e.g. [`Perbill`], are ratios between zero and one, to take a part of an
+//!   amount, e.g. the share of a fee or a slash,
+//! * [`FixedPointNumber`]s, e.g. [`FixedU128`], are numbers with a fixed number of decimals,
+//!   e.g. a fee multiplier or an exchange rate,
+//! * the [`traits`] bundle what pallets need of an integer, e.g. of a balance.
+//!
+//! Everything rounds as told or saturates: nothing is ever computed with floats.
+
+mod fixed_point;
+mod per_things;
+pub mod traits;
+
+pub use fixed_point::{FixedI64, FixedPointNumber, FixedPointOperand, FixedU128};
+pub use per_things::{PerThing, Perbill, Percent, Permill};
+
+use num::{BigUint, ToPrimitive, Zero};
+
+/// Which way a division with a remainder rounds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest value, towards zero from half way.
+    NearestPrefDown,
+    /// To the nearest value, away from zero from half way.
+    NearestPrefUp,
+}
+
+/// `a * b / c` rounded as told, without overflowing in between. `None` if `c` is zero or the
+/// result does not fit in 128 bits.
+pub fn multiply_by_rational_with_rounding(
+    a: u128,
+    b: u128,
+    c: u128,
+    rounding: Rounding,
+) -> Option<u128> {
+    if c == 0 {
+        return None
+    }
+    let product = BigUint::from(a) * BigUint::from(b);
+    let c = BigUint::from(c);
+    let (quotient, remainder) = (&product / &c, &product % &c);
+    let round_up = !remainder.is_zero() &&
+        match rounding {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::NearestPrefDown => remainder * 2u8 > c,
+            Rounding::NearestPrefUp => remainder * 2u8 >= c,
+        };
+    (quotient + u8::from(round_up)).to_u128()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiply_by_rational_rounds_as_told() {
+        let mul = |a, b, c, rounding| multiply_by_rational_with_rounding(a, b, c, rounding);
+        assert_eq!(mul(7, 1, 2, Rounding::Down), Some(3));
+        assert_eq!(mul(7, 1, 2, Rounding::Up), Some(4));
+        assert_eq!(mul(7, 1, 2, Rounding::NearestPrefDown), Some(3));
+        assert_eq!(mul(7, 1, 2, Rounding::NearestPrefUp), Some(4));
+        assert_eq!(mul(8, 1, 3, Rounding::NearestPrefDown), Some(3));
+        assert_eq!(mul(6, 1, 3, Rounding::Up), Some(2));
+        assert_eq!(mul(1, 1, 0, Rounding::Down), None);
+    }
+
+    #[test]
+    fn multiply_by_rational_does_not_overflow_in_between() {
+        let mul = |a, b, c| multiply_by_rational_with_rounding(a, b, c, Rounding::Down);
+        assert_eq!(mul(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul(u128::MAX, 3, 4), Some(u128::MAX / 4 * 3 + 2));
+        assert_eq!(mul(u128::MAX, 2, 1), None);
+    }
+}
@@ -0,0 +1,380 @@
+//! Ratios between zero and one, counted in a fixed number of parts.
+
+use super::{
+    multiply_by_rational_with_rounding,
+    traits::{BaseArithmetic, UniqueSaturatedFrom},
+    Rounding,
+};
+use crate::{
+    codec::{self, Decode, Encode, Input, MaxEncodedLen},
+    scale_info::TypeInfo,
+};
+use num::{Bounded, CheckedAdd, CheckedSub, One, Saturating, Unsigned, Zero};
+use std::{
+    fmt::Debug,
+    ops::{Add, Mul, Sub},
+};
+
+/// A ratio between zero and one, `deconstruct() / ACCURACY`.
+pub trait PerThing: Sized + Copy + Default + Ord + Debug + Zero + One + Bounded {
+    /// The type the parts are counted in.
+    type Inner: BaseArithmetic + Unsigned + Into<u128>;
+
+    /// The number of parts of one.
+    const ACCURACY: Self::Inner;
+
+    /// The ratio of `parts` parts, saturating at one.
+    fn from_parts(parts: Self::Inner) -> Self;
+
+    /// The number of parts of the ratio.
+    fn deconstruct(self) -> Self::Inner;
+
+    /// The ratio of `x` percent, saturating at one.
+    fn from_percent(x: Self::Inner) -> Self {
+        let x: u128 = x.min(100u8.into()).into();
+        Self::from_parts(Self::Inner::unique_saturated_from(x * Self::ACCURACY.into() / 100))
+    }
+
+    /// `self * self`, rounded down.
+    fn square(self) -> Self {
+        Self::from_parts(self.mul_floor(self.deconstruct()))
+    }
+
+    /// `1 - self`.
+    fn left_from_one(self) -> Self {
+        Self::from_parts(Self::ACCURACY - self.deconstruct())
+    }
+
+    /// `self * b`, rounded down.
+    fn mul_floor<N: BaseArithmetic + Unsigned>(self, b: N) -> N {
+        self.mul_with_rounding(b, Rounding::Down)
+    }
+
+    /// `self * b`, rounded up.
+    fn mul_ceil<N: BaseArithmetic + Unsigned>(self, b: N) -> N {
+        self.mul_with_rounding(b, Rounding::Up)
+    }
+
+    /// `self * b`, rounded as told. Never more than `b`, so it can't overflow.
+    fn mul_with_rounding<N: BaseArithmetic + Unsigned>(self, b: N, rounding: Rounding) -> N {
+        let b: u128 = b.unique_saturated_into();
+        let parts: u128 = self.deconstruct().into();
+        multiply_by_rational_with_rounding(b, parts, Self::ACCURACY.into(), rounding)
+            .map(N::unique_saturated_from)
+            .expect("the result is at most b, which fits; qed")
+    }
+
+    /// `b / self`, rounded down and saturating at the largest `N`.
+    fn saturating_reciprocal_mul_floor<N: BaseArithmetic + Unsigned>(self, b: N) -> N {
+        self.saturating_reciprocal_mul_with_rounding(b, Rounding::Down)
+    }
+
+    /// `b / self`, rounded up and saturating at the largest `N`.
+    fn saturating_reciprocal_mul_ceil<N: BaseArithmetic + Unsigned>(self, b: N) -> N {
+        self.saturating_reciprocal_mul_with_rounding(b, Rounding::Up)
+    }
+
+    /// `b / self`, rounded as told and saturating at the largest `N`, e.g. when `self` is zero.
+    fn saturating_reciprocal_mul_with_rounding<N: BaseArithmetic + Unsigned>(
+        self,
+        b: N,
+        rounding: Rounding,
+    ) -> N {
+        let b: u128 = b.unique_saturated_into();
+        let parts: u128 = self.deconstruct().into();
+        multiply_by_rational_with_rounding(b, Self::ACCURACY.into(), parts, rounding)
+            .map_or_else(N::max_value, N::unique_saturated_from)
+    }
+
+    /// `p / q` rounded down, saturating at one, e.g. when `q` is zero.
+    fn from_rational<N: BaseArithmetic + Unsigned>(p: N, q: N) -> Self {
+        Self::from_rational_with_rounding(p, q, Rounding::Down).unwrap_or_else(Self::one)
+    }
+
+    /// `p / q` rounded as told, `None` if it is more than one or `q` is zero.
+    fn from_rational_with_rounding<N: BaseArithmetic + Unsigned>(
+        p: N,
+        q: N,
+        rounding: Rounding,
+    ) -> Option<Self> {
+        if p > q {
+            return None
+        }
+        let (p, q): (u128, u128) = (p.unique_saturated_into(), q.unique_saturated_into());
+        multiply_by_rational_with_rounding(p, Self::ACCURACY.into(), q, rounding)
+            .map(|parts| Self::from_parts(Self::Inner::unique_saturated_from(parts)))
+    }
+
+    /// `self` multiplied by itself `exp` times, rounded down at every step.
+    fn saturating_pow(self, exp: usize) -> Self {
+        (0..exp).fold(Self::one(), |acc, _| Self::from_parts(acc.mul_floor(self.deconstruct())))
+    }
+}
+
+macro_rules! implement_per_thing {
+    (
+        $name:ident,
+        $inner:ty,
+        $accuracy:expr,
+        $(#[$attr:meta])*
+    ) => {
+        $(#[$attr])*
+        #[derive(Encode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        #[derive(Default, Hash, Debug)]
+        pub struct $name($inner);
+
+        impl $name {
+            /// The ratio of `parts` parts, saturating at one.
+            pub const fn from_parts(parts: $inner) -> Self {
+                Self(if parts > $accuracy { $accuracy } else { parts })
+            }
+
+            /// The ratio of `x` percent, saturating at one.
+            pub const fn from_percent(x: $inner) -> Self {
+                Self((if x > 100 { 100 } else { x }) * ($accuracy / 100))
+            }
+
+            pub const fn deconstruct(self) -> $inner {
+                self.0
+            }
+
+            pub const fn zero() -> Self {
+                Self(0)
+            }
+
+            pub const fn one() -> Self {
+                Self($accuracy)
+            }
+
+            pub const fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+
+            pub const fn is_one(&self) -> bool {
+                self.0 == $accuracy
+            }
+        }
+
+        impl PerThing for $name {
+            type Inner = $inner;
+
+            const ACCURACY: $inner = $accuracy;
+
+            fn from_parts(parts: $inner) -> Self {
+                Self::from_parts(parts)
+            }
+
+            fn deconstruct(self) -> $inner {
+                self.0
+            }
+        }
+
+        /// Values of more than one part are refused.
+        impl Decode for $name {
+            fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+                let parts = <$inner>::decode(input)?;
+                if parts > $accuracy {
+                    return Err(concat!("out of range decoding ", stringify!($name)).into())
+                }
+                Ok(Self(parts))
+            }
+        }
+
+        impl Bounded for $name {
+            fn min_value() -> Self {
+                Self::zero()
+            }
+
+            fn max_value() -> Self {
+                Self::one()
+            }
+        }
+
+        impl Zero for $name {
+            fn zero() -> Self {
+                Self::zero()
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl One for $name {
+            fn one() -> Self {
+                Self::one()
+            }
+        }
+
+        /// Saturates at one in release builds.
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                let parts = self.0 + rhs.0;
+                debug_assert!(parts <= $accuracy, "addition overflowed one");
+                Self::from_parts(parts)
+            }
+        }
+
+        /// Saturates at zero.
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self::from_parts(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        /// The product of the ratios, rounded down.
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                Self(self.mul_floor(rhs.0))
+            }
+        }
+
+        /// `self * b` rounded to the nearest integer, down from half way.
+        impl<N: BaseArithmetic + Unsigned> Mul<N> for $name {
+            type Output = N;
+
+            fn mul(self, b: N) -> N {
+                self.mul_with_rounding(b, Rounding::NearestPrefDown)
+            }
+        }
+
+        impl CheckedAdd for $name {
+            fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                self.0.checked_add(rhs.0).filter(|parts| *parts <= $accuracy).map(Self)
+            }
+        }
+
+        impl CheckedSub for $name {
+            fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                self.0.checked_sub(rhs.0).map(Self)
+            }
+        }
+
+        impl Saturating for $name {
+            fn saturating_add(self, rhs: Self) -> Self {
+                Self::from_parts(self.0.saturating_add(rhs.0))
+            }
+
+            fn saturating_sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0))
+            }
+        }
+    };
+}
+
+implement_per_thing!(
+    Percent,
+    u8,
+    100,
+    /// A ratio counted in hundredths.
+);
+
+implement_per_thing!(
+    Permill,
+    u32,
+    1_000_000,
+    /// A ratio counted in millionths.
+);
+
+implement_per_thing!(
+    Perbill,
+    u32,
+    1_000_000_000,
+    /// A ratio counted in billionths.
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn construction_saturates_at_one() {
+        assert_eq!(Percent::from_parts(101), Percent::one());
+        assert_eq!(Perbill::from_percent(150), Perbill::one());
+        assert_eq!(Permill::from_percent(25).deconstruct(), 250_000);
+        assert_eq!(Perbill::from_percent(1).deconstruct(), 10_000_000);
+        assert_eq!(<Percent as PerThing>::from_percent(7), Percent::from_parts(7));
+        assert!(Perbill::zero().is_zero() && Perbill::one().is_one());
+    }
+
+    #[test]
+    fn multiplication_rounds_as_told() {
+        let third = Percent::from_percent(33);
+        assert_eq!(third.mul_floor(10u32), 3);
+        assert_eq!(third.mul_ceil(10u32), 4);
+        assert_eq!(Percent::from_percent(50) * 3u64, 1);
+        assert_eq!(Percent::from_percent(50).mul_ceil(3u64), 2);
+        assert_eq!(Percent::from_percent(70) * 3u64, 2);
+        assert_eq!(Perbill::one() * u128::MAX, u128::MAX);
+        assert_eq!(Perbill::from_parts(1).mul_ceil(u128::MAX), u128::MAX / 1_000_000_000 + 1);
+        assert_eq!(Permill::zero().mul_ceil(100u32), 0);
+    }
+
+    #[test]
+    fn from_rational_works() {
+        assert_eq!(Perbill::from_rational(1u32, 3).deconstruct(), 333_333_333);
+        assert_eq!(Percent::from_rational(2u64, 3), Percent::from_parts(66));
+        assert_eq!(
+            Percent::from_rational_with_rounding(2u64, 3, Rounding::NearestPrefUp),
+            Some(Percent::from_parts(67))
+        );
+        assert_eq!(Percent::from_rational_with_rounding(4u64, 3, Rounding::Down), None);
+        assert_eq!(Percent::from_rational_with_rounding(0u64, 0, Rounding::Down), None);
+        assert_eq!(Permill::from_rational(4u32, 3), Permill::one());
+        let almost_one = Perbill::from_rational(u128::MAX - 1, u128::MAX);
+        assert_eq!(almost_one, Perbill::from_parts(999_999_999));
+    }
+
+    #[test]
+    fn reciprocal_saturates() {
+        assert_eq!(Percent::from_percent(50).saturating_reciprocal_mul_floor(10u32), 20);
+        assert_eq!(Percent::from_percent(30).saturating_reciprocal_mul_floor(10u32), 33);
+        assert_eq!(Percent::from_percent(30).saturating_reciprocal_mul_ceil(10u32), 34);
+        assert_eq!(Percent::from_percent(10).saturating_reciprocal_mul_floor(u32::MAX), u32::MAX);
+        assert_eq!(Percent::zero().saturating_reciprocal_mul_floor(1u64), u64::MAX);
+    }
+
+    #[test]
+    fn ratios_compose() {
+        let half = Perbill::from_percent(50);
+        assert_eq!(half * half, Perbill::from_percent(25));
+        assert_eq!(half.square(), Perbill::from_percent(25));
+        assert_eq!(half.saturating_pow(3), Perbill::from_parts(125_000_000));
+        assert_eq!(Perbill::from_percent(30).left_from_one(), Perbill::from_percent(70));
+        assert_eq!(half + half, Perbill::one());
+        assert_eq!(Perbill::one() - half, half);
+    }
+
+    #[test]
+    fn checked_and_saturating_operations_stay_within_one() {
+        let (sixty, fifty) = (Percent::from_percent(60), Percent::from_percent(50));
+        assert_eq!(sixty.checked_add(&fifty), None);
+        assert_eq!(sixty.saturating_add(fifty), Percent::one());
+        assert_eq!(fifty.checked_sub(&sixty), None);
+        assert_eq!(fifty.saturating_sub(sixty), Percent::zero());
+        assert_eq!(sixty.checked_sub(&fifty), Some(Percent::from_percent(10)));
+    }
+
+    #[test]
+    fn subtraction_saturates_at_zero() {
+        let (sixty, fifty) = (Percent::from_percent(60), Percent::from_percent(50));
+        assert_eq!(fifty - sixty, Percent::zero());
+        assert_eq!(Perbill::zero() - Perbill::one(), Perbill::zero());
+        assert_eq!(sixty - fifty, Percent::from_percent(10));
+    }
+
+    #[test]
+    fn decoding_refuses_more_than_one() {
+        let half = Permill::from_percent(50);
+        assert_eq!(half.encode(), 500_000u32.encode());
+        assert_eq!(Permill::decode(&mut &half.encode()[..]), Ok(half));
+        assert!(Permill::decode(&mut &1_000_001u32.encode()[..]).is_err());
+        assert!(Percent::decode(&mut &[101][..]).is_err());
+    }
+}
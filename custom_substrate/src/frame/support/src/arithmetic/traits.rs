@@ -0,0 +1,160 @@
+//! What pallets need of the integers they compute with, e.g. of a balance.
+
+use num::{
+    traits::CheckedRem, Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Saturating,
+    Unsigned, Zero,
+};
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign},
+};
+
+/// Convert from `T`, saturating at the bounds of `Self`.
+pub trait UniqueSaturatedFrom<T>: Sized {
+    fn unique_saturated_from(t: T) -> Self;
+}
+
+/// Convert into `T`, saturating at the bounds of `T`.
+pub trait UniqueSaturatedInto<T>: Sized {
+    fn unique_saturated_into(self) -> T;
+}
+
+impl<T, S: TryFrom<T> + Bounded> UniqueSaturatedFrom<T> for S
+where
+    T: PartialOrd + Zero,
+{
+    fn unique_saturated_from(t: T) -> Self {
+        let negative = t < T::zero();
+        S::try_from(t).unwrap_or_else(|_| if negative { S::min_value() } else { S::max_value() })
+    }
+}
+
+impl<T: UniqueSaturatedFrom<S>, S> UniqueSaturatedInto<T> for S {
+    fn unique_saturated_into(self) -> T {
+        T::unique_saturated_from(self)
+    }
+}
+
+/// Conversions between any integers, saturating at the bounds of the target.
+pub trait SaturatedConversion {
+    fn saturated_from<T>(t: T) -> Self
+    where
+        Self: UniqueSaturatedFrom<T>,
+    {
+        Self::unique_saturated_from(t)
+    }
+
+    fn saturated_into<T>(self) -> T
+    where
+        Self: UniqueSaturatedInto<T>,
+    {
+        self.unique_saturated_into()
+    }
+}
+
+impl<T: Sized> SaturatedConversion for T {}
+
+/// The operations of any primitive integer.
+pub trait BaseArithmetic:
+    From<u8>
+    + Zero
+    + One
+    + Bounded
+    + Ord
+    + Copy
+    + Debug
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + SubAssign
+    + Mul<Output = Self>
+    + MulAssign
+    + Div<Output = Self>
+    + DivAssign
+    + Rem<Output = Self>
+    + RemAssign
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + CheckedRem
+    + Saturating
+    + UniqueSaturatedFrom<u32>
+    + UniqueSaturatedInto<u32>
+    + UniqueSaturatedFrom<u64>
+    + UniqueSaturatedInto<u64>
+    + UniqueSaturatedFrom<u128>
+    + UniqueSaturatedInto<u128>
+{
+}
+
+impl<T> BaseArithmetic for T where
+    T: From<u8>
+        + Zero
+        + One
+        + Bounded
+        + Ord
+        + Copy
+        + Debug
+        + Add<Output = Self>
+        + AddAssign
+        + Sub<Output = Self>
+        + SubAssign
+        + Mul<Output = Self>
+        + MulAssign
+        + Div<Output = Self>
+        + DivAssign
+        + Rem<Output = Self>
+        + RemAssign
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + CheckedRem
+        + Saturating
+        + UniqueSaturatedFrom<u32>
+        + UniqueSaturatedInto<u32>
+        + UniqueSaturatedFrom<u64>
+        + UniqueSaturatedInto<u64>
+        + UniqueSaturatedFrom<u128>
+        + UniqueSaturatedInto<u128>
+{
+}
+
+/// An integer of at least 32 bits, e.g. `i64`.
+pub trait AtLeast32Bit: BaseArithmetic + From<u16> + From<u32> {}
+
+impl<T: BaseArithmetic + From<u16> + From<u32>> AtLeast32Bit for T {}
+
+/// An unsigned integer of at least 32 bits, what a balance is.
+pub trait AtLeast32BitUnsigned: AtLeast32Bit + Unsigned {}
+
+impl<T: AtLeast32Bit + Unsigned> AtLeast32BitUnsigned for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_balance<T: AtLeast32BitUnsigned>() {}
+
+    fn is_at_least_32_bit<T: AtLeast32Bit>() {}
+
+    #[test]
+    fn primitive_integers_are_arithmetic() {
+        is_balance::<u32>();
+        is_balance::<u64>();
+        is_balance::<u128>();
+        is_at_least_32_bit::<i64>();
+        is_at_least_32_bit::<i128>();
+    }
+
+    #[test]
+    fn conversions_saturate() {
+        assert_eq!(u8::saturated_from(300u32), 255);
+        assert_eq!(u8::saturated_from(-3i32), 0);
+        assert_eq!(i8::saturated_from(-300i32), -128);
+        assert_eq!(u64::MAX.saturated_into::<u32>(), u32::MAX);
+        assert_eq!(7u128.saturated_into::<u64>(), 7);
+        assert_eq!(UniqueSaturatedInto::<u16>::unique_saturated_into(70_000u64), u16::MAX);
+    }
+}
@@ -0,0 +1,208 @@
+//! The compact integer encoding.
+//!
+//! The two least significant bits of the first byte give the mode:
+//! * `0b00`: single byte mode, the upper six bits are the value (0 to 63),
+//! * `0b01`: two byte mode, the upper 14 bits are the value (64 to 2^14 - 1),
+//! * `0b10`: four byte mode, the upper 30 bits are the value (2^14 to 2^30 - 1),
+//! * `0b11`: big integer mode, the upper six bits plus four are the number of bytes which
+//!   follow, holding the value in little endian.
+//!
+//! A value always uses the smallest mode it fits in, anything else is rejected on decoding.
+
+use super::{Decode, Encode, Error, Input, Output};
+
+/// Wrapper encoding the inner integer with the compact encoding.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, PartialOrd, Ord)]
+pub struct Compact<T>(pub T);
+
+impl<T> From<T> for Compact<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+/// Types which have a compact encoding, usable with `#[codec(compact)]`.
+pub trait HasCompact: Sized {
+    fn encode_compact_to<W: Output + ?Sized>(&self, dest: &mut W);
+
+    fn decode_compact<I: Input>(input: &mut I) -> Result<Self, Error>;
+
+    /// The length of the compact encoding of `self`.
+    fn compact_len(&self) -> usize;
+}
+
+impl<T: HasCompact> Encode for Compact<T> {
+    fn size_hint(&self) -> usize {
+        self.0.compact_len()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        self.0.encode_compact_to(dest)
+    }
+}
+
+impl<T: HasCompact> Decode for Compact<T> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        T::decode_compact(input).map(Compact)
+    }
+}
+
+const SINGLE_BYTE_MAX: u128 = 0b0011_1111;
+const TWO_BYTE_MAX: u128 = (1 << 14) - 1;
+const FOUR_BYTE_MAX: u128 = (1 << 30) - 1;
+
+fn compact_len(value: u128) -> usize {
+    match value {
+        v if v <= SINGLE_BYTE_MAX => 1,
+        v if v <= TWO_BYTE_MAX => 2,
+        v if v <= FOUR_BYTE_MAX => 4,
+        _ => 1 + big_integer_bytes(value),
+    }
+}
+
+/// The number of bytes needed in big integer mode, never less than four.
+fn big_integer_bytes(value: u128) -> usize {
+    let significant = 16 - (value.leading_zeros() / 8) as usize;
+    significant.max(4)
+}
+
+fn encode_compact<W: Output + ?Sized>(value: u128, dest: &mut W) {
+    match value {
+        v if v <= SINGLE_BYTE_MAX => dest.push_byte((v as u8) << 2),
+        v if v <= TWO_BYTE_MAX => dest.write(&(((v as u16) << 2) | 0b01).to_le_bytes()),
+        v if v <= FOUR_BYTE_MAX => dest.write(&(((v as u32) << 2) | 0b10).to_le_bytes()),
+        _ => {
+            let bytes = big_integer_bytes(value);
+            dest.push_byte((((bytes - 4) as u8) << 2) | 0b11);
+            dest.write(&value.to_le_bytes()[..bytes]);
+        },
+    }
+}
+
+fn decode_compact<I: Input>(input: &mut I) -> Result<u128, Error> {
+    let prefix = input.read_byte()?;
+    let value = match prefix & 0b11 {
+        0b00 => u128::from(prefix >> 2),
+        0b01 => {
+            let value = u16::from_le_bytes([prefix, input.read_byte()?]) >> 2;
+            let value = u128::from(value);
+            if value <= SINGLE_BYTE_MAX {
+                return Err("out of range decoding Compact<u16>".into())
+            }
+            value
+        },
+        0b10 => {
+            let mut buf = [prefix, 0, 0, 0];
+            input.read(&mut buf[1..])?;
+            let value = u128::from(u32::from_le_bytes(buf) >> 2);
+            if value <= TWO_BYTE_MAX {
+                return Err("out of range decoding Compact<u32>".into())
+            }
+            value
+        },
+        _ => {
+            let bytes = (prefix >> 2) as usize + 4;
+            if bytes > 16 {
+                return Err("out of range decoding Compact<u128>".into())
+            }
+            let mut buf = [0u8; 16];
+            input.read(&mut buf[..bytes])?;
+            let value = u128::from_le_bytes(buf);
+            // The most significant byte must be used, otherwise a shorter encoding exists.
+            if value <= FOUR_BYTE_MAX || buf[bytes - 1] == 0 {
+                return Err("out of range decoding Compact<u128>".into())
+            }
+            value
+        },
+    };
+    Ok(value)
+}
+
+macro_rules! impl_has_compact {
+    ( $( $t:ty ),* ) => { $(
+        impl HasCompact for $t {
+            fn encode_compact_to<W: Output + ?Sized>(&self, dest: &mut W) {
+                encode_compact(u128::from(*self), dest)
+            }
+
+            fn decode_compact<I: Input>(input: &mut I) -> Result<Self, Error> {
+                let value = decode_compact(input)?;
+                <$t>::try_from(value).map_err(|_| {
+                    concat!("out of range decoding Compact<", stringify!($t), ">").into()
+                })
+            }
+
+            fn compact_len(&self) -> usize {
+                compact_len(u128::from(*self))
+            }
+        }
+    )* };
+}
+
+impl_has_compact!(u8, u16, u32, u64, u128);
+
+impl HasCompact for () {
+    fn encode_compact_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        dest.push_byte(0)
+    }
+
+    fn decode_compact<I: Input>(input: &mut I) -> Result<Self, Error> {
+        match decode_compact(input)? {
+            0 => Ok(()),
+            _ => Err("out of range decoding Compact<()>".into()),
+        }
+    }
+
+    fn compact_len(&self) -> usize {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::DecodeAll;
+
+    #[test]
+    fn compact_encoding_matches_scale() {
+        let cases: &[(u128, &[u8])] = &[
+            (0, &[0x00]),
+            (1, &[0x04]),
+            (42, &[0xa8]),
+            (63, &[0xfc]),
+            (64, &[0x01, 0x01]),
+            (69, &[0x15, 0x01]),
+            (16383, &[0xfd, 0xff]),
+            (16384, &[0x02, 0x00, 0x01, 0x00]),
+            (65535, &[0xfe, 0xff, 0x03, 0x00]),
+            ((1 << 30) - 1, &[0xfe, 0xff, 0xff, 0xff]),
+            (1 << 30, &[0x03, 0x00, 0x00, 0x00, 0x40]),
+            (u32::MAX as u128, &[0x03, 0xff, 0xff, 0xff, 0xff]),
+            (1 << 32, &[0x07, 0x00, 0x00, 0x00, 0x00, 0x01]),
+            (u64::MAX as u128, &[0x13, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        ];
+        for (value, encoded) in cases {
+            assert_eq!(Compact(*value).encode(), *encoded, "encoding {}", value);
+            assert_eq!(Compact(*value).encoded_size(), encoded.len());
+            assert_eq!(Compact::<u128>::decode_all(&mut &encoded[..]), Ok(Compact(*value)));
+        }
+        assert_eq!(Compact(u128::MAX).encode(), [&[0x33u8][..], &[0xff; 16][..]].concat());
+    }
+
+    #[test]
+    fn compact_decoding_rejects_non_canonical_encodings() {
+        // 1 in two, four and big integer mode.
+        assert!(Compact::<u32>::decode(&mut &[0x05u8, 0x00][..]).is_err());
+        assert!(Compact::<u32>::decode(&mut &[0x06u8, 0x00, 0x00, 0x00][..]).is_err());
+        assert!(Compact::<u64>::decode(&mut &[0x03u8, 0x01, 0x00, 0x00, 0x00][..]).is_err());
+        // 2^32 using six bytes instead of five.
+        assert!(Compact::<u64>::decode(&mut &[0x0bu8, 0, 0, 0, 0, 1, 0][..]).is_err());
+    }
+
+    #[test]
+    fn compact_decoding_checks_the_target_type() {
+        let encoded = Compact(256u16).encode();
+        assert!(Compact::<u8>::decode(&mut &encoded[..]).is_err());
+        assert_eq!(Compact::<u16>::decode(&mut &encoded[..]), Ok(Compact(256)));
+    }
+}
@@ -0,0 +1,311 @@
+//! Encoding of the primitive and standard library types.
+
+use super::{decode_nested, Compact, Decode, Encode, Error, HasCompact, Input, Output};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
+
+macro_rules! impl_fixed_width {
+    ( $( $t:ty ),* ) => { $(
+        impl Encode for $t {
+            fn size_hint(&self) -> usize {
+                std::mem::size_of::<$t>()
+            }
+
+            fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+                dest.write(&self.to_le_bytes())
+            }
+        }
+
+        impl Decode for $t {
+            fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+                let mut buf = [0u8; std::mem::size_of::<$t>()];
+                input.read(&mut buf)?;
+                Ok(<$t>::from_le_bytes(buf))
+            }
+        }
+    )* };
+}
+
+impl_fixed_width!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Encode for bool {
+    fn size_hint(&self) -> usize {
+        1
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        dest.push_byte(*self as u8)
+    }
+}
+
+impl Decode for bool {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        match input.read_byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err("Invalid boolean representation".into()),
+        }
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        (**self).encode_to(dest)
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for Box<T> {
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        (**self).encode_to(dest)
+    }
+}
+
+impl<T: Decode> Decode for Box<T> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        decode_nested(input, |input| T::decode(input).map(Box::new))
+    }
+}
+
+impl<T: ?Sized> Encode for PhantomData<T> {
+    fn encode_to<W: Output + ?Sized>(&self, _dest: &mut W) {}
+}
+
+impl<T: ?Sized> Decode for PhantomData<T> {
+    fn decode<I: Input>(_input: &mut I) -> Result<Self, Error> {
+        Ok(PhantomData)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn size_hint(&self) -> usize {
+        1 + self.as_ref().map_or(0, Encode::size_hint)
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        match self {
+            None => dest.push_byte(0),
+            Some(value) => {
+                dest.push_byte(1);
+                value.encode_to(dest);
+            },
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        match input.read_byte()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(input)?)),
+            _ => Err("unexpected first byte decoding Option".into()),
+        }
+    }
+}
+
+impl<T: Encode, E: Encode> Encode for Result<T, E> {
+    fn size_hint(&self) -> usize {
+        1 + match self {
+            Ok(value) => value.size_hint(),
+            Err(error) => error.size_hint(),
+        }
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        match self {
+            Ok(value) => {
+                dest.push_byte(0);
+                value.encode_to(dest);
+            },
+            Err(error) => {
+                dest.push_byte(1);
+                error.encode_to(dest);
+            },
+        }
+    }
+}
+
+impl<T: Decode, E: Decode> Decode for Result<T, E> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        match input.read_byte()? {
+            0 => Ok(Ok(T::decode(input)?)),
+            1 => Ok(Err(E::decode(input)?)),
+            _ => Err("unexpected first byte decoding Result".into()),
+        }
+    }
+}
+
+/// Encode the compact length prefix of a sequence.
+fn encode_len<W: Output + ?Sized>(len: usize, dest: &mut W) {
+    Compact(len as u64).encode_to(dest)
+}
+
+/// Decode the compact length prefix of a sequence.
+fn decode_len<I: Input>(input: &mut I) -> Result<usize, Error> {
+    let len = Compact::<u32>::decode(input)?.0;
+    Ok(len as usize)
+}
+
+/// Decode `len` items, without trusting `len` for the allocation.
+fn decode_items<I: Input, T: Decode>(input: &mut I, len: usize) -> Result<Vec<T>, Error> {
+    let remaining = input.remaining_len()?.unwrap_or(0);
+    let mut items = Vec::with_capacity(len.min(remaining));
+    for _ in 0..len {
+        items.push(T::decode(input)?);
+    }
+    Ok(items)
+}
+
+impl<T: Encode> Encode for [T] {
+    fn size_hint(&self) -> usize {
+        (self.len() as u64).compact_len() + self.iter().map(Encode::size_hint).sum::<usize>()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        encode_len(self.len(), dest);
+        self.iter().for_each(|item| item.encode_to(dest));
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn size_hint(&self) -> usize {
+        self.as_slice().size_hint()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        self.as_slice().encode_to(dest)
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let len = decode_len(input)?;
+        decode_items(input, len)
+    }
+}
+
+impl Encode for str {
+    fn size_hint(&self) -> usize {
+        self.as_bytes().size_hint()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        self.as_bytes().encode_to(dest)
+    }
+}
+
+impl Encode for String {
+    fn size_hint(&self) -> usize {
+        self.as_str().size_hint()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        self.as_str().encode_to(dest)
+    }
+}
+
+impl Decode for String {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        String::from_utf8(Vec::decode(input)?).map_err(|_| "Invalid utf8 sequence".into())
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn size_hint(&self) -> usize {
+        self.iter().map(Encode::size_hint).sum()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        self.iter().for_each(|item| item.encode_to(dest));
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let items = decode_items::<_, T>(input, N)?;
+        items.try_into().map_err(|_| "Invalid array length".into())
+    }
+}
+
+impl<K: Encode, V: Encode> Encode for BTreeMap<K, V> {
+    fn size_hint(&self) -> usize {
+        (self.len() as u64).compact_len() +
+            self.iter().map(|(k, v)| k.size_hint() + v.size_hint()).sum::<usize>()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        encode_len(self.len(), dest);
+        self.iter().for_each(|item| item.encode_to(dest));
+    }
+}
+
+impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let len = decode_len(input)?;
+        Ok(decode_items::<_, (K, V)>(input, len)?.into_iter().collect())
+    }
+}
+
+impl<T: Encode> Encode for BTreeSet<T> {
+    fn size_hint(&self) -> usize {
+        (self.len() as u64).compact_len() + self.iter().map(Encode::size_hint).sum::<usize>()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        encode_len(self.len(), dest);
+        self.iter().for_each(|item| item.encode_to(dest));
+    }
+}
+
+impl<T: Decode + Ord> Decode for BTreeSet<T> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let len = decode_len(input)?;
+        Ok(decode_items::<_, T>(input, len)?.into_iter().collect())
+    }
+}
+
+macro_rules! impl_tuple {
+    ( $( $name:ident )* ) => {
+        impl<$( $name: Encode ),*> Encode for ( $( $name, )* ) {
+            #[allow(non_snake_case)]
+            fn size_hint(&self) -> usize {
+                let ( $( $name, )* ) = self;
+                0 $( + $name.size_hint() )*
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+                let ( $( $name, )* ) = self;
+                $( $name.encode_to(dest); )*
+            }
+        }
+
+        impl<$( $name: Decode ),*> Decode for ( $( $name, )* ) {
+            #[allow(unused_variables)]
+            fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+                Ok(( $( $name::decode(input)?, )* ))
+            }
+        }
+    };
+}
+
+impl_tuple!();
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+impl_tuple!(A B C D E);
+impl_tuple!(A B C D E F);
+impl_tuple!(A B C D E F G);
+impl_tuple!(A B C D E F G H);
+impl_tuple!(A B C D E F G H J);
+impl_tuple!(A B C D E F G H J K);
@@ -0,0 +1,146 @@
+//! Upper bounds on the size of encoded values.
+
+use super::{Compact, Encode, HasCompact};
+use std::marker::PhantomData;
+
+/// Types with a statically known upper bound on the length of their encoding.
+///
+/// Collections without a bound on their number of items, like `Vec<T>`, can't implement it.
+/// Use `#[derive(MaxEncodedLen)]` for your own types.
+pub trait MaxEncodedLen: Encode {
+    /// The maximum length of the encoding of any value of this type.
+    fn max_encoded_len() -> usize;
+}
+
+macro_rules! impl_fixed_width {
+    ( $( $t:ty ),* ) => { $(
+        impl MaxEncodedLen for $t {
+            fn max_encoded_len() -> usize {
+                std::mem::size_of::<$t>()
+            }
+        }
+    )* };
+}
+
+impl_fixed_width!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool);
+
+macro_rules! impl_compact {
+    ( $( $t:ty ),* ) => { $(
+        impl MaxEncodedLen for Compact<$t> {
+            fn max_encoded_len() -> usize {
+                <$t>::MAX.compact_len()
+            }
+        }
+    )* };
+}
+
+impl_compact!(u8, u16, u32, u64, u128);
+
+impl MaxEncodedLen for Compact<()> {
+    fn max_encoded_len() -> usize {
+        1
+    }
+}
+
+impl<T: ?Sized> MaxEncodedLen for PhantomData<T> {
+    fn max_encoded_len() -> usize {
+        0
+    }
+}
+
+impl<T: MaxEncodedLen> MaxEncodedLen for Box<T> {
+    fn max_encoded_len() -> usize {
+        T::max_encoded_len()
+    }
+}
+
+impl<T: MaxEncodedLen> MaxEncodedLen for Option<T> {
+    fn max_encoded_len() -> usize {
+        1 + T::max_encoded_len()
+    }
+}
+
+impl<T: MaxEncodedLen, E: MaxEncodedLen> MaxEncodedLen for Result<T, E> {
+    fn max_encoded_len() -> usize {
+        1 + T::max_encoded_len().max(E::max_encoded_len())
+    }
+}
+
+impl<T: MaxEncodedLen, const N: usize> MaxEncodedLen for [T; N] {
+    fn max_encoded_len() -> usize {
+        T::max_encoded_len().saturating_mul(N)
+    }
+}
+
+macro_rules! impl_tuple {
+    ( $( $name:ident )* ) => {
+        impl<$( $name: MaxEncodedLen ),*> MaxEncodedLen for ( $( $name, )* ) {
+            fn max_encoded_len() -> usize {
+                0usize $( .saturating_add($name::max_encoded_len()) )*
+            }
+        }
+    };
+}
+
+impl_tuple!();
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+impl_tuple!(A B C D E);
+impl_tuple!(A B C D E F);
+impl_tuple!(A B C D E F G);
+impl_tuple!(A B C D E F G H);
+impl_tuple!(A B C D E F G H J);
+impl_tuple!(A B C D E F G H J K);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{Decode, MaxEncodedLen};
+
+    #[derive(Encode, Decode, MaxEncodedLen)]
+    struct Account<Balance> {
+        nonce: u32,
+        #[codec(compact)]
+        free: Balance,
+        #[codec(skip)]
+        _cache: Option<u64>,
+    }
+
+    #[derive(Encode, Decode, MaxEncodedLen)]
+    enum Reasons {
+        Fee,
+        Misc(u64),
+        All { a: u8, b: [u16; 4] },
+    }
+
+    #[derive(Encode, Decode, MaxEncodedLen)]
+    enum Empty {}
+
+    #[test]
+    fn primitives_max_encoded_len_works() {
+        assert_eq!(u64::max_encoded_len(), 8);
+        assert_eq!(bool::max_encoded_len(), 1);
+        assert_eq!(<()>::max_encoded_len(), 0);
+        assert_eq!(Option::<u32>::max_encoded_len(), 5);
+        assert_eq!(Result::<u8, u128>::max_encoded_len(), 17);
+        assert_eq!(<[u8; 32]>::max_encoded_len(), 32);
+        assert_eq!(<(u8, u16, Option<bool>)>::max_encoded_len(), 5);
+        assert_eq!(Compact::<u8>::max_encoded_len(), Compact(u8::MAX).encode().len());
+        assert_eq!(Compact::<u32>::max_encoded_len(), 5);
+        assert_eq!(Compact::<u64>::max_encoded_len(), 9);
+        assert_eq!(Compact::<u128>::max_encoded_len(), 17);
+    }
+
+    #[test]
+    fn derive_max_encoded_len_works() {
+        assert_eq!(Account::<u128>::max_encoded_len(), 4 + 17);
+        assert_eq!(Account::<u32>::max_encoded_len(), 4 + 5);
+        let account = Account::<u32> { nonce: u32::MAX, free: u32::MAX, _cache: Some(1) };
+        assert_eq!(account.encode().len(), Account::<u32>::max_encoded_len());
+
+        assert_eq!(Reasons::max_encoded_len(), 1 + 9);
+        assert_eq!(Empty::max_encoded_len(), 0);
+    }
+}
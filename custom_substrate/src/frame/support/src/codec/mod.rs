@@ -0,0 +1,446 @@
+//! A SCALE compatible codec.
+//!
+//! Values are encoded without any type information, in little endian, and their encoding is
+//! byte for byte the one of `parity-scale-codec`:
+//! * fixed width integers are written as is, `bool` as a single `0` or `1` byte,
+//! * `Compact<T>` uses the compact integer encoding (see [`Compact`]),
+//! * sequences are prefixed with their length as a compact integer,
+//! * `Option` and `Result` are enums, enums are the variant index followed by the fields,
+//! * structs and tuples are the concatenation of their fields.
+//!
+//! Use `#[derive(Encode, Decode)]` for your own types.
+//!
+//! Decoding untrusted input with recursive types must go through [`DecodeLimit`], which
+//! bounds how deep nested types can be and so protects against stack overflows.
+
+mod compact;
+mod impls;
+mod max_encoded_len;
+
+pub use compact::{Compact, HasCompact};
+pub use frame_support_procedural::{Decode, Encode, MaxEncodedLen};
+pub use max_encoded_len::MaxEncodedLen;
+
+use std::fmt;
+
+/// An error that happened while decoding.
+///
+/// Errors are chained from the innermost value up to the type being decoded.
+#[derive(PartialEq, Eq, Clone)]
+pub struct Error {
+    cause: Option<Box<Error>>,
+    desc: &'static str,
+}
+
+impl Error {
+    /// Wrap `self` into a new error with the given description.
+    pub fn chain(self, desc: &'static str) -> Self {
+        Self { cause: Some(Box::new(self)), desc }
+    }
+
+    /// The description of the outermost error.
+    pub fn what(&self) -> &'static str {
+        self.desc
+    }
+
+    /// The error this one was chained from, if any.
+    pub fn cause(&self) -> Option<&Error> {
+        self.cause.as_deref()
+    }
+}
+
+impl From<&'static str> for Error {
+    fn from(desc: &'static str) -> Self {
+        Self { cause: None, desc }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.desc)?;
+        let mut cause = self.cause();
+        while let Some(error) = cause {
+            write!(f, ": {}", error.desc)?;
+            cause = error.cause();
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Error({})", self)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Where encoded bytes are written to.
+pub trait Output {
+    fn write(&mut self, bytes: &[u8]);
+
+    fn push_byte(&mut self, byte: u8) {
+        self.write(&[byte]);
+    }
+}
+
+impl Output for Vec<u8> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes)
+    }
+
+    fn push_byte(&mut self, byte: u8) {
+        self.push(byte)
+    }
+}
+
+/// An [`Output`] that only counts the bytes written to it.
+struct SizeCounter(usize);
+
+impl Output for SizeCounter {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 += bytes.len();
+    }
+}
+
+/// Where encoded bytes are read from.
+pub trait Input {
+    /// The number of bytes left, if known. Only used as an allocation hint.
+    fn remaining_len(&mut self) -> Result<Option<usize>, Error>;
+
+    /// Fill `into` entirely, or fail without a partial read guarantee.
+    fn read(&mut self, into: &mut [u8]) -> Result<(), Error>;
+
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        let mut buf = [0u8];
+        self.read(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Called when decoding enters a nested type, see [`DecodeLimit`].
+    fn descend_ref(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when decoding leaves a nested type, after [`Input::descend_ref`].
+    fn ascend_ref(&mut self) {}
+}
+
+impl Input for &[u8] {
+    fn remaining_len(&mut self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.len()))
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), Error> {
+        if into.len() > self.len() {
+            return Err("Not enough data to fill buffer".into())
+        }
+        let len = into.len();
+        into.copy_from_slice(&self[..len]);
+        *self = &self[len..];
+        Ok(())
+    }
+}
+
+/// A value that can be encoded.
+pub trait Encode {
+    /// A guess of the encoded size, used to pre-allocate the output.
+    fn size_hint(&self) -> usize {
+        0
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W);
+
+    fn encode(&self) -> Vec<u8> {
+        let mut dest = Vec::with_capacity(self.size_hint());
+        self.encode_to(&mut dest);
+        dest
+    }
+
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        f(&self.encode())
+    }
+
+    /// The exact size of the encoding, computed without allocating it.
+    fn encoded_size(&self) -> usize {
+        let mut counter = SizeCounter(0);
+        self.encode_to(&mut counter);
+        counter.0
+    }
+}
+
+/// A value that can be decoded.
+pub trait Decode: Sized {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error>;
+}
+
+/// A value that can be both encoded and decoded.
+pub trait Codec: Encode + Decode {}
+
+impl<T: Encode + Decode> Codec for T {}
+
+/// Types whose encoding is the same as the one of `T`.
+///
+/// Lets storage accessors take either an owned value or a reference to it.
+pub trait EncodeLike<T: Encode = Self>: Encode {}
+
+impl<T: Encode> EncodeLike<T> for T {}
+impl<T: Encode> EncodeLike<T> for &T {}
+impl<T: Encode> EncodeLike<T> for Box<T> {}
+impl<T: Encode> EncodeLike<Vec<T>> for &[T] {}
+impl EncodeLike<String> for &str {}
+
+/// Decode a value and make sure the input was consumed entirely.
+pub trait DecodeAll: Sized {
+    fn decode_all(input: &mut &[u8]) -> Result<Self, Error>;
+}
+
+impl<T: Decode> DecodeAll for T {
+    fn decode_all(input: &mut &[u8]) -> Result<Self, Error> {
+        let res = T::decode(input)?;
+        ensure_empty(input)?;
+        Ok(res)
+    }
+}
+
+/// Decode a value with a bound on the nesting depth of the decoded types.
+///
+/// Every derived type and every `Box` counts as one level, so a recursive type can not
+/// make the decoder recurse more than `limit` times whatever the input is.
+pub trait DecodeLimit: Sized {
+    fn decode_with_depth_limit<I: Input>(limit: u32, input: &mut I) -> Result<Self, Error>;
+
+    fn decode_all_with_depth_limit(limit: u32, input: &mut &[u8]) -> Result<Self, Error>;
+}
+
+impl<T: Decode> DecodeLimit for T {
+    fn decode_with_depth_limit<I: Input>(limit: u32, input: &mut I) -> Result<Self, Error> {
+        let mut input = DepthTrackingInput { input, depth: 0, max_depth: limit };
+        T::decode(&mut input)
+    }
+
+    fn decode_all_with_depth_limit(limit: u32, input: &mut &[u8]) -> Result<Self, Error> {
+        let res = T::decode_with_depth_limit(limit, input)?;
+        ensure_empty(input)?;
+        Ok(res)
+    }
+}
+
+fn ensure_empty(input: &[u8]) -> Result<(), Error> {
+    if input.is_empty() {
+        Ok(())
+    } else {
+        Err("Input buffer has still data left after decoding!".into())
+    }
+}
+
+struct DepthTrackingInput<'a, I> {
+    input: &'a mut I,
+    depth: u32,
+    max_depth: u32,
+}
+
+impl<'a, I: Input> Input for DepthTrackingInput<'a, I> {
+    fn remaining_len(&mut self) -> Result<Option<usize>, Error> {
+        self.input.remaining_len()
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), Error> {
+        self.input.read(into)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        self.input.read_byte()
+    }
+
+    fn descend_ref(&mut self) -> Result<(), Error> {
+        self.input.descend_ref()?;
+        self.depth += 1;
+        if self.depth > self.max_depth {
+            Err("Maximum recursion depth reached when decoding".into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn ascend_ref(&mut self) {
+        self.input.ascend_ref();
+        self.depth -= 1;
+    }
+}
+
+/// Run `f` one nesting level deeper. Used by the derived `Decode` implementations.
+#[doc(hidden)]
+pub fn decode_nested<I: Input, R>(
+    input: &mut I,
+    f: impl FnOnce(&mut I) -> Result<R, Error>,
+) -> Result<R, Error> {
+    input.descend_ref()?;
+    let res = f(input);
+    input.ascend_ref();
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, marker::PhantomData};
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    struct Unit;
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    struct Named {
+        a: u32,
+        #[codec(compact)]
+        b: u64,
+        c: Vec<u8>,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    struct Tuple(u8, bool);
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    struct Generic<T, Balance> {
+        who: T,
+        #[codec(compact)]
+        amount: Balance,
+        #[codec(skip)]
+        cache: Option<String>,
+        _marker: PhantomData<T>,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    enum Call {
+        Remark(Vec<u8>),
+        #[codec(index = 5)]
+        Transfer {
+            dest: u64,
+            #[codec(compact)]
+            value: u128,
+        },
+        Noop,
+        #[codec(skip)]
+        #[allow(dead_code)]
+        Ignored,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    enum Discriminants {
+        A = 3,
+        B = 7,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Debug)]
+    enum Nested {
+        Leaf,
+        Node(Box<Nested>),
+    }
+
+    fn roundtrip<T: Encode + Decode + PartialEq + std::fmt::Debug>(value: T, encoded: &[u8]) {
+        assert_eq!(value.encode(), encoded);
+        assert_eq!(value.encoded_size(), encoded.len());
+        assert_eq!(T::decode_all(&mut &encoded[..]), Ok(value));
+    }
+
+    #[test]
+    fn primitives_encoding_works() {
+        roundtrip(true, &[1]);
+        roundtrip(false, &[0]);
+        roundtrip(0x2au8, &[0x2a]);
+        roundtrip(-1i8, &[0xff]);
+        roundtrip(0x0102u16, &[0x02, 0x01]);
+        roundtrip(-2i32, &[0xfe, 0xff, 0xff, 0xff]);
+        roundtrip(0x0102_0304_0506_0708u64, &[8, 7, 6, 5, 4, 3, 2, 1]);
+        roundtrip(1u128, &[&[1u8][..], &[0; 15][..]].concat());
+        roundtrip((), &[]);
+        let err = bool::decode(&mut &[2u8][..]).unwrap_err();
+        assert_eq!(err.what(), "Invalid boolean representation");
+    }
+
+    #[test]
+    fn collections_encoding_works() {
+        roundtrip(vec![1u16, 2, 3], &[12, 1, 0, 2, 0, 3, 0]);
+        roundtrip(String::from("Hello"), &[20, b'H', b'e', b'l', b'l', b'o']);
+        roundtrip([1u8, 2, 3], &[1, 2, 3]);
+        roundtrip((1u8, 2u16, vec![true]), &[1, 2, 0, 4, 1]);
+        roundtrip(Some(7u8), &[1, 7]);
+        roundtrip(None::<u8>, &[0]);
+        roundtrip(Ok::<u8, bool>(1), &[0, 1]);
+        roundtrip(Err::<u8, bool>(true), &[1, 1]);
+        roundtrip(Box::new(9u8), &[9]);
+        let map: BTreeMap<u8, bool> = [(2, false), (1, true)].into_iter().collect();
+        roundtrip(map, &[8, 1, 1, 2, 0]);
+        assert_eq!(vec![0u8; 64].encode()[..2], [0x01, 0x01]);
+        assert_eq!(vec![1u8, 2].as_slice().encode(), vec![8, 1, 2]);
+    }
+
+    #[test]
+    fn decoding_errors_work() {
+        // Truncated input.
+        assert!(u32::decode(&mut &[1u8, 2][..]).is_err());
+        // A length prefix larger than the input must not allocate nor succeed.
+        assert!(Vec::<u8>::decode(&mut &[0xfdu8, 0xff, 0xff, 0xff][..]).is_err());
+        // Invalid utf8.
+        assert!(String::decode(&mut &[4u8, 0xff][..]).is_err());
+        // Option tag out of range.
+        assert!(Option::<u8>::decode(&mut &[2u8, 0][..]).is_err());
+        // Trailing bytes.
+        assert_eq!(
+            u8::decode_all(&mut &[1u8, 2][..]).unwrap_err().what(),
+            "Input buffer has still data left after decoding!",
+        );
+    }
+
+    #[test]
+    fn derive_struct_works() {
+        roundtrip(Unit, &[]);
+        roundtrip(Named { a: 1, b: 1, c: vec![9] }, &[1, 0, 0, 0, 4, 4, 9]);
+        roundtrip(Tuple(3, true), &[3, 1]);
+        roundtrip(
+            Generic::<u8, u64> { who: 2, amount: 64, cache: None, _marker: PhantomData },
+            &[2, 0x01, 0x01],
+        );
+        // Skipped fields are not encoded and come back as their default value.
+        let value =
+            Generic::<u8, u64> { who: 2, amount: 1, cache: Some("a".into()), _marker: PhantomData };
+        assert_eq!(value.encode(), vec![2, 4]);
+    }
+
+    #[test]
+    fn derive_enum_works() {
+        roundtrip(Call::Remark(vec![1, 2]), &[0, 8, 1, 2]);
+        roundtrip(Call::Transfer { dest: 1, value: 69 }, &[5, 1, 0, 0, 0, 0, 0, 0, 0, 0x15, 0x01]);
+        roundtrip(Call::Noop, &[2]);
+        assert_eq!(Call::Ignored.encode(), Vec::<u8>::new());
+        roundtrip(Discriminants::A, &[3]);
+        roundtrip(Discriminants::B, &[7]);
+
+        let err = Call::decode(&mut &[1u8][..]).unwrap_err();
+        assert_eq!(err.what(), "Could not decode `Call`, variant doesn't exist");
+        let err = Call::decode(&mut &[5u8, 1][..]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Could not decode `Call::Transfer::dest`: Not enough data to fill buffer"
+        );
+    }
+
+    #[test]
+    fn depth_limit_works() {
+        let mut value = Nested::Leaf;
+        for _ in 0..10 {
+            value = Nested::Node(Box::new(value));
+        }
+        let encoded = value.encode();
+        assert_eq!(encoded, [&[1u8; 10][..], &[0u8][..]].concat());
+
+        // Every level is the derived `Nested` plus the `Box` around it.
+        assert_eq!(Nested::decode_all_with_depth_limit(21, &mut &encoded[..]), Ok(value));
+        let err = Nested::decode_all_with_depth_limit(20, &mut &encoded[..]).unwrap_err();
+        assert!(err.to_string().contains("Maximum recursion depth reached when decoding"));
+
+        // Hostile input only made of `Node` tags is stopped long before the stack overflows.
+        let hostile = vec![1u8; 1_000_000];
+        assert!(Nested::decode_with_depth_limit(256, &mut &hostile[..]).is_err());
+    }
+}
@@ -0,0 +1,547 @@
+//! Ed25519 signatures (RFC 8032), on the twisted Edwards curve `-x² + y² = 1 + d·x²·y²` over
+//! the field of the prime `p = 2²⁵⁵ - 19`.
+//!
+//! This implementation is not constant time: it is meant for tests and dev chains, not to keep
+//! secrets from an attacker measuring how long signing takes.
+
+use crate::{
+    codec::{Decode, Encode, MaxEncodedLen},
+    crypto::{AccountId32, ByteArray, PublicError, Ss58Codec},
+    hashing::sha2_512,
+    scale_info::TypeInfo,
+    traits::{IdentifyAccount, Verify},
+};
+use num::BigUint;
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+/// A public key, i.e. a point of the curve in its compressed encoding.
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Hash, Debug)]
+pub struct Public(pub [u8; 32]);
+
+/// A signature: the encoding of the point `R` followed by the scalar `S`.
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Signature(pub [u8; 64]);
+
+/// The seed a key pair is generated from.
+pub type Seed = [u8; 32];
+
+/// A key pair, able to sign.
+#[derive(Clone)]
+pub struct Pair {
+    public: Public,
+    /// The secret scalar, clamped.
+    scalar: [u8; 32],
+    /// What the nonce of a signature is derived from, with the message.
+    prefix: [u8; 32],
+}
+
+impl super::Pair for Pair {
+    type Public = Public;
+    type Seed = Seed;
+    type Signature = Signature;
+
+    fn from_seed(seed: &Seed) -> Self {
+        let hash = sha2_512(seed);
+        let mut scalar: [u8; 32] = hash[..32].try_into().expect("32 bytes; qed");
+        scalar[0] &= 248;
+        scalar[31] &= 127;
+        scalar[31] |= 64;
+        let public = Public(EdwardsPoint::base().mul(&scalar).compress());
+        Pair { public, scalar, prefix: hash[32..].try_into().expect("32 bytes; qed") }
+    }
+
+    fn public(&self) -> Public {
+        self.public
+    }
+
+    fn sign(&self, message: &[u8]) -> Signature {
+        let r = reduce(&sha2_512(&[&self.prefix[..], message].concat()));
+        let big_r = EdwardsPoint::base().mul(&r).compress();
+        let k = challenge(&big_r, &self.public, message);
+        // S = r + k·s mod L
+        let s = (BigUint::from_bytes_le(&r) +
+            BigUint::from_bytes_le(&k) * BigUint::from_bytes_le(&self.scalar)) %
+            group_order();
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&big_r);
+        signature[32..].copy_from_slice(&to_bytes(&s));
+        Signature(signature)
+    }
+
+    fn verify(signature: &Signature, message: &[u8], public: &Public) -> bool {
+        let Some(a) = EdwardsPoint::decompress(&public.0) else { return false };
+        let big_r: [u8; 32] = signature.0[..32].try_into().expect("32 bytes; qed");
+        let s: [u8; 32] = signature.0[32..].try_into().expect("32 bytes; qed");
+        // Another S would make another valid signature of the same message.
+        if BigUint::from_bytes_le(&s) >= group_order() {
+            return false
+        }
+        let k = challenge(&big_r, public, message);
+        // [S]B = R + [k]A
+        (EdwardsPoint::base().mul(&s) + a.neg().mul(&k)).compress() == big_r
+    }
+}
+
+impl Verify for Signature {
+    type Signer = Public;
+
+    fn verify(&self, msg: &[u8], signer: &Public) -> bool {
+        <Pair as super::Pair>::verify(self, msg, signer)
+    }
+}
+
+impl IdentifyAccount for Public {
+    type AccountId = Self;
+
+    fn into_account(self) -> Self {
+        self
+    }
+}
+
+impl AsRef<[u8]> for Public {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ByteArray for Public {
+    const LEN: usize = 32;
+
+    fn from_slice(data: &[u8]) -> Option<Self> {
+        data.try_into().ok().map(Public)
+    }
+}
+
+/// The SS58 address of the key, with the default prefix.
+impl fmt::Display for Public {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_ss58check())
+    }
+}
+
+impl FromStr for Public {
+    type Err = PublicError;
+
+    fn from_str(s: &str) -> Result<Self, PublicError> {
+        Self::from_ss58check(s)
+    }
+}
+
+impl From<Public> for AccountId32 {
+    fn from(public: Public) -> Self {
+        AccountId32(public.0)
+    }
+}
+
+/// `k = H(R ‖ A ‖ M) mod L`, which the signature proves knowledge of the secret scalar for.
+fn challenge(big_r: &[u8; 32], public: &Public, message: &[u8]) -> [u8; 32] {
+    reduce(&sha2_512(&[&big_r[..], &public.0[..], message].concat()))
+}
+
+/// The order `L = 2²⁵² + 27742317777372353535851937790883648493` of the base point.
+fn group_order() -> BigUint {
+    BigUint::from(1u8) << 252 |
+        BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10)
+            .expect("a decimal number; qed")
+}
+
+/// The little endian bytes of `hash` modulo `L`.
+fn reduce(hash: &[u8]) -> [u8; 32] {
+    to_bytes(&(BigUint::from_bytes_le(hash) % group_order()))
+}
+
+fn to_bytes(scalar: &BigUint) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let le = scalar.to_bytes_le();
+    bytes[..le.len()].copy_from_slice(&le);
+    bytes
+}
+
+const LOW_51_BITS: u64 = (1 << 51) - 1;
+
+/// An element of the field of `p = 2²⁵⁵ - 19`, in five limbs of 51 bits.
+#[derive(Clone, Copy, Debug)]
+struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    const ZERO: Self = FieldElement([0; 5]);
+    const ONE: Self = FieldElement([1, 0, 0, 0, 0]);
+
+    fn from_u64(n: u64) -> Self {
+        FieldElement([n & LOW_51_BITS, n >> 51, 0, 0, 0])
+    }
+
+    /// The element of the lower 255 bits of `bytes`, in little endian.
+    fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().expect("8 bytes"));
+        FieldElement([
+            load(0) & LOW_51_BITS,
+            (load(6) >> 3) & LOW_51_BITS,
+            (load(12) >> 6) & LOW_51_BITS,
+            (load(19) >> 1) & LOW_51_BITS,
+            (load(24) >> 12) & LOW_51_BITS,
+        ])
+    }
+
+    /// The canonical encoding of the element, i.e. of its value below `p`.
+    fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = self.carry().0;
+        // Whether the value is `p` or more, i.e. if adding 19 overflows 2²⁵⁵.
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LOW_51_BITS;
+        }
+        limbs[4] &= LOW_51_BITS;
+
+        let mut bytes = [0u8; 32];
+        let (mut acc, mut bits, mut i) = (0u128, 0, 0);
+        for limb in limbs {
+            acc |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 {
+                bytes[i] = acc as u8;
+                acc >>= 8;
+                bits -= 8;
+                i += 1;
+            }
+        }
+        bytes[i] = acc as u8;
+        bytes
+    }
+
+    /// Bring every limb back to 51 bits, or just over.
+    fn carry(self) -> Self {
+        let mut limbs = self.0;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LOW_51_BITS;
+        }
+        // 2²⁵⁵ = 19 mod p
+        limbs[0] += 19 * (limbs[4] >> 51);
+        limbs[4] &= LOW_51_BITS;
+        FieldElement(limbs)
+    }
+
+    fn square(self) -> Self {
+        self * self
+    }
+
+    /// `self` to the power `exp`, given in little endian.
+    fn pow(self, exp: &[u8; 32]) -> Self {
+        let mut res = Self::ONE;
+        for bit in (0..256).rev() {
+            res = res.square();
+            if exp[bit / 8] >> (bit % 8) & 1 == 1 {
+                res = res * self;
+            }
+        }
+        res
+    }
+
+    /// `1 / self`, i.e. `self^(p - 2)`.
+    fn invert(self) -> Self {
+        let mut exp = [0xff; 32];
+        exp[0] = 0xeb;
+        exp[31] = 0x7f;
+        self.pow(&exp)
+    }
+
+    fn is_zero(self) -> bool {
+        self.to_bytes() == [0; 32]
+    }
+
+    /// Whether the canonical value is odd, which is the sign of the x coordinate of a point.
+    fn is_negative(self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    /// `d = -121665 / 121666`
+    fn d() -> Self {
+        static D: OnceLock<FieldElement> = OnceLock::new();
+        *D.get_or_init(|| -Self::from_u64(121665) * Self::from_u64(121666).invert())
+    }
+
+    /// `√-1 = 2^((p - 1) / 4)`
+    fn sqrt_m1() -> Self {
+        static SQRT_M1: OnceLock<FieldElement> = OnceLock::new();
+        *SQRT_M1.get_or_init(|| {
+            let mut exp = [0xff; 32];
+            exp[0] = 0xfb;
+            exp[31] = 0x1f;
+            Self::from_u64(2).pow(&exp)
+        })
+    }
+}
+
+impl PartialEq for FieldElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Add for FieldElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut limbs = self.0;
+        for (limb, rhs) in limbs.iter_mut().zip(rhs.0) {
+            *limb += rhs;
+        }
+        FieldElement(limbs).carry()
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        // Add 16·p first so that no limb underflows.
+        let sixteen_p = [
+            16 * (LOW_51_BITS - 18),
+            16 * LOW_51_BITS,
+            16 * LOW_51_BITS,
+            16 * LOW_51_BITS,
+            16 * LOW_51_BITS,
+        ];
+        let mut limbs = self.0;
+        for i in 0..5 {
+            limbs[i] = limbs[i] + sixteen_p[i] - rhs.0[i];
+        }
+        FieldElement(limbs).carry()
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let a = self.0.map(u128::from);
+        let b = rhs.0.map(u128::from);
+        // The limbs over 2²⁵⁵ wrap around multiplied by 19.
+        let b19 = b.map(|limb| limb * 19);
+
+        let c = [
+            a[0] * b[0] + a[4] * b19[1] + a[3] * b19[2] + a[2] * b19[3] + a[1] * b19[4],
+            a[1] * b[0] + a[0] * b[1] + a[4] * b19[2] + a[3] * b19[3] + a[2] * b19[4],
+            a[2] * b[0] + a[1] * b[1] + a[0] * b[2] + a[4] * b19[3] + a[3] * b19[4],
+            a[3] * b[0] + a[2] * b[1] + a[1] * b[2] + a[0] * b[3] + a[4] * b19[4],
+            a[4] * b[0] + a[3] * b[1] + a[2] * b[2] + a[1] * b[3] + a[0] * b[4],
+        ];
+
+        let mut limbs = [0u64; 5];
+        let mut carry = 0u128;
+        for i in 0..5 {
+            let limb = c[i] + carry;
+            limbs[i] = (limb as u64) & LOW_51_BITS;
+            carry = limb >> 51;
+        }
+        limbs[0] += (carry * 19) as u64;
+        FieldElement(limbs).carry()
+    }
+}
+
+/// A point of the curve in extended coordinates: `x = X/Z`, `y = Y/Z` and `x·y = T/Z`.
+#[derive(Clone, Copy, Debug)]
+struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl EdwardsPoint {
+    const IDENTITY: Self = EdwardsPoint {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ONE,
+        t: FieldElement::ZERO,
+    };
+
+    /// The base point `B`, whose y coordinate is `4/5`.
+    fn base() -> Self {
+        static BASE: OnceLock<EdwardsPoint> = OnceLock::new();
+        *BASE.get_or_init(|| {
+            let mut encoded = [0x66; 32];
+            encoded[0] = 0x58;
+            Self::decompress(&encoded).expect("the base point is on the curve; qed")
+        })
+    }
+
+    /// The point of the encoding `bytes`: its y coordinate, with the sign of its x coordinate
+    /// in the top bit.
+    fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let y = FieldElement::from_bytes(bytes);
+        let mut canonical = *bytes;
+        canonical[31] &= 0x7f;
+        if y.to_bytes() != canonical {
+            return None
+        }
+
+        // x² = (y² - 1) / (d·y² + 1), whose root is `u·v³·(u·v⁷)^((p - 5) / 8)` if it exists.
+        let u = y.square() - FieldElement::ONE;
+        let v = FieldElement::d() * y.square() + FieldElement::ONE;
+        let v3 = v.square() * v;
+        let mut exp = [0xff; 32];
+        exp[0] = 0xfd;
+        exp[31] = 0x0f;
+        let mut x = u * v3 * (u * v3 * v3 * v).pow(&exp);
+        if v * x.square() != u {
+            if v * x.square() != -u {
+                return None
+            }
+            x = x * FieldElement::sqrt_m1();
+        }
+
+        let negative = bytes[31] >> 7 == 1;
+        if x.is_zero() && negative {
+            return None
+        }
+        if x.is_negative() != negative {
+            x = -x;
+        }
+        Some(EdwardsPoint { x, y, z: FieldElement::ONE, t: x * y })
+    }
+
+    fn compress(self) -> [u8; 32] {
+        let z_inv = self.z.invert();
+        let mut bytes = (self.y * z_inv).to_bytes();
+        bytes[31] |= ((self.x * z_inv).is_negative() as u8) << 7;
+        bytes
+    }
+
+    /// `[scalar]self`, with `scalar` in little endian.
+    fn mul(self, scalar: &[u8; 32]) -> Self {
+        let mut res = Self::IDENTITY;
+        for bit in (0..256).rev() {
+            res = res + res;
+            if scalar[bit / 8] >> (bit % 8) & 1 == 1 {
+                res = res + self;
+            }
+        }
+        res
+    }
+}
+
+impl Add for EdwardsPoint {
+    type Output = Self;
+
+    /// The addition of RFC 8032, which also doubles.
+    fn add(self, rhs: Self) -> Self {
+        let a = (self.y - self.x) * (rhs.y - rhs.x);
+        let b = (self.y + self.x) * (rhs.y + rhs.x);
+        let c = self.t * (FieldElement::d() + FieldElement::d()) * rhs.t;
+        let d = (self.z + self.z) * rhs.z;
+        let (e, f, g, h) = (b - a, d - c, d + c, b + a);
+        EdwardsPoint { x: e * f, y: g * h, z: f * g, t: e * h }
+    }
+}
+
+impl Neg for EdwardsPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        EdwardsPoint { x: -self.x, t: -self.t, ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Pair as _;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn field_arithmetic_works() {
+        let two = FieldElement::from_u64(2);
+        assert_eq!(two * two.invert(), FieldElement::ONE);
+        assert_eq!(FieldElement::sqrt_m1().square(), -FieldElement::ONE);
+        // p - 1 is canonical, p is not.
+        let p_minus_one = -FieldElement::ONE;
+        assert_eq!(p_minus_one + FieldElement::ONE, FieldElement::ZERO);
+        let mut p = p_minus_one.to_bytes();
+        p[0] += 1;
+        assert!(FieldElement::from_bytes(&p).is_zero());
+    }
+
+    #[test]
+    fn points_are_compressed_and_decompressed() {
+        let base = EdwardsPoint::base();
+        let two_b = base + base;
+        let decompressed = EdwardsPoint::decompress(&two_b.compress()).unwrap();
+        assert_eq!(decompressed.compress(), two_b.compress());
+        assert_eq!((two_b + base.neg()).compress(), base.compress());
+        // [L]B is the identity.
+        let order = to_bytes(&group_order());
+        assert_eq!(base.mul(&order).compress(), EdwardsPoint::IDENTITY.compress());
+        // No point has y = 2.
+        assert!(EdwardsPoint::decompress(&FieldElement::from_u64(2).to_bytes()).is_none());
+    }
+
+    /// The first two test vectors of RFC 8032, section 7.1.
+    #[test]
+    fn rfc_8032_vectors_pass() {
+        let vectors = [
+            (
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                "",
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bac\
+                 c61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            ),
+            (
+                "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                "72",
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e\
+                 458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            ),
+        ];
+        for (seed, public, message, signature) in vectors {
+            let pair = Pair::from_seed(&from_hex(seed).try_into().unwrap());
+            let message = from_hex(message);
+            assert_eq!(pair.public(), Public(from_hex(public).try_into().unwrap()));
+            let signature = Signature(from_hex(signature).try_into().unwrap());
+            assert_eq!(pair.sign(&message), signature);
+            assert!(signature.verify(&message, &pair.public()));
+        }
+    }
+
+    #[test]
+    fn other_messages_and_signers_are_refused() {
+        let alice = Pair::from_seed(&[1; 32]);
+        let bob = Pair::from_seed(&[2; 32]);
+        let signature = alice.sign(b"hello");
+        assert!(signature.verify(b"hello", &alice.public()));
+        assert!(!signature.verify(b"hello!", &alice.public()));
+        assert!(!signature.verify(b"hello", &bob.public()));
+
+        // S + L signs too, but is refused.
+        let mut malleable = signature;
+        let s = BigUint::from_bytes_le(&signature.0[32..]) + group_order();
+        malleable.0[32..].copy_from_slice(&to_bytes(&s));
+        assert!(!malleable.verify(b"hello", &alice.public()));
+    }
+}
@@ -0,0 +1,78 @@
+//! Keys, signatures and the accounts they identify.
+
+pub mod ed25519;
+pub mod ss58;
+
+pub use ss58::{PublicError, Ss58AddressFormat, Ss58AddressFormatRegistry, Ss58Codec};
+
+use crate::{
+    codec::{Decode, Encode, MaxEncodedLen},
+    scale_info::TypeInfo,
+};
+use std::{fmt, str::FromStr};
+
+/// A key pair of a signature scheme.
+pub trait Pair: Sized {
+    type Public;
+    /// What the pair is generated from, deterministically.
+    type Seed;
+    type Signature;
+
+    fn from_seed(seed: &Self::Seed) -> Self;
+
+    fn public(&self) -> Self::Public;
+
+    fn sign(&self, message: &[u8]) -> Self::Signature;
+
+    /// Whether `signature` is a signature of `message` by `public`.
+    fn verify(signature: &Self::Signature, message: &[u8], public: &Self::Public) -> bool;
+}
+
+/// A type made of a fixed number of bytes, e.g. a public key.
+pub trait ByteArray: Sized + AsRef<[u8]> {
+    const LEN: usize;
+
+    /// `None` if `data` is not `LEN` bytes long.
+    fn from_slice(data: &[u8]) -> Option<Self>;
+}
+
+/// An account identified by 32 bytes, e.g. a public key.
+#[derive(
+    Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+pub struct AccountId32(pub [u8; 32]);
+
+impl From<[u8; 32]> for AccountId32 {
+    fn from(bytes: [u8; 32]) -> Self {
+        AccountId32(bytes)
+    }
+}
+
+impl AsRef<[u8]> for AccountId32 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ByteArray for AccountId32 {
+    const LEN: usize = 32;
+
+    fn from_slice(data: &[u8]) -> Option<Self> {
+        data.try_into().ok().map(AccountId32)
+    }
+}
+
+/// The SS58 address of the account, with the default prefix.
+impl fmt::Display for AccountId32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_ss58check())
+    }
+}
+
+impl FromStr for AccountId32 {
+    type Err = PublicError;
+
+    fn from_str(s: &str) -> Result<Self, PublicError> {
+        Self::from_ss58check(s)
+    }
+}
@@ -0,0 +1,372 @@
+//! SS58, the text format of addresses: the base58 encoding of a network prefix, the account
+//! and a checksum.
+//!
+//! Prefixes below 64 take one byte, prefixes up to 16383 two bytes. The checksum is the start
+//! of the BLAKE2b-512 hash of `SS58PRE` followed by the prefix and the account.
+
+use super::ByteArray;
+use crate::hashing::blake2_512;
+use num::BigUint;
+use std::{
+    fmt,
+    sync::atomic::{AtomicU16, Ordering},
+};
+
+/// The digits of base58, which leave out `0`, `O`, `I` and `l` as they look alike.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The length of the checksum.
+const CHECKSUM_LEN: usize = 2;
+
+/// The largest prefix, which fits in the 14 bits of a two byte prefix.
+const MAX_PREFIX: u16 = 16_383;
+
+/// The prefix used when none is given.
+static DEFAULT_VERSION: AtomicU16 =
+    AtomicU16::new(Ss58AddressFormatRegistry::SubstrateAccount as u16);
+
+/// Why a text address can't be decoded.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PublicError {
+    /// A character is not a base58 digit.
+    BadBase58,
+    /// The address is too short or too long for the account type.
+    BadLength,
+    /// The prefix is not the one expected.
+    UnknownSs58AddressFormat(Ss58AddressFormat),
+    /// The checksum does not match.
+    InvalidChecksum,
+    /// The first byte is not the start of a prefix.
+    InvalidPrefix,
+    /// The prefix is not allowed for the account type, e.g. a reserved one.
+    FormatNotAllowed,
+}
+
+/// A network prefix, telling which chain an address is meant for.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Ss58AddressFormat {
+    prefix: u16,
+}
+
+impl Ss58AddressFormat {
+    /// Any prefix, known to the registry or not.
+    pub const fn custom(prefix: u16) -> Self {
+        Self { prefix }
+    }
+
+    pub fn prefix(&self) -> u16 {
+        self.prefix
+    }
+
+    /// Whether the prefix is kept for later uses of the format.
+    pub fn is_reserved(&self) -> bool {
+        self.prefix == 46 || self.prefix == 47 || self.prefix > MAX_PREFIX
+    }
+
+    /// Whether the prefix is not in the registry.
+    pub fn is_custom(&self) -> bool {
+        Ss58AddressFormatRegistry::try_from(self.prefix).is_err()
+    }
+}
+
+impl From<u16> for Ss58AddressFormat {
+    fn from(prefix: u16) -> Self {
+        Self::custom(prefix)
+    }
+}
+
+impl From<Ss58AddressFormat> for u16 {
+    fn from(format: Ss58AddressFormat) -> Self {
+        format.prefix
+    }
+}
+
+impl From<Ss58AddressFormatRegistry> for Ss58AddressFormat {
+    fn from(known: Ss58AddressFormatRegistry) -> Self {
+        Self::custom(known as u16)
+    }
+}
+
+/// The name of a known prefix, the number of any other.
+impl fmt::Display for Ss58AddressFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match Ss58AddressFormatRegistry::try_from(self.prefix) {
+            Ok(known) => write!(f, "{}", known.name()),
+            Err(()) => write!(f, "{}", self.prefix),
+        }
+    }
+}
+
+/// The prefixes of well known networks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u16)]
+pub enum Ss58AddressFormatRegistry {
+    PolkadotAccount = 0,
+    BareSr25519Account = 1,
+    KusamaAccount = 2,
+    BareEd25519Account = 3,
+    AstarAccount = 5,
+    AcalaAccount = 10,
+    KiltAccount = 38,
+    /// Any Substrate chain, e.g. a dev chain.
+    SubstrateAccount = 42,
+    BareSecp256k1Account = 43,
+}
+
+impl Ss58AddressFormatRegistry {
+    /// Every registered prefix.
+    pub const ALL: [Self; 9] = [
+        Self::PolkadotAccount,
+        Self::BareSr25519Account,
+        Self::KusamaAccount,
+        Self::BareEd25519Account,
+        Self::AstarAccount,
+        Self::AcalaAccount,
+        Self::KiltAccount,
+        Self::SubstrateAccount,
+        Self::BareSecp256k1Account,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PolkadotAccount => "polkadot",
+            Self::BareSr25519Account => "sr25519",
+            Self::KusamaAccount => "kusama",
+            Self::BareEd25519Account => "ed25519",
+            Self::AstarAccount => "astar",
+            Self::AcalaAccount => "acala",
+            Self::KiltAccount => "kilt",
+            Self::SubstrateAccount => "substrate",
+            Self::BareSecp256k1Account => "secp256k1",
+        }
+    }
+
+    /// The registered prefix called `name`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|known| known.name() == name)
+    }
+}
+
+impl TryFrom<u16> for Ss58AddressFormatRegistry {
+    type Error = ();
+
+    fn try_from(prefix: u16) -> Result<Self, ()> {
+        Self::ALL.into_iter().find(|known| *known as u16 == prefix).ok_or(())
+    }
+}
+
+/// The prefix addresses are encoded with by [`Ss58Codec::to_ss58check`].
+pub fn default_ss58_version() -> Ss58AddressFormat {
+    DEFAULT_VERSION.load(Ordering::Relaxed).into()
+}
+
+/// Set the prefix addresses are encoded with by default, e.g. the `SS58Prefix` of the runtime.
+pub fn set_default_ss58_version(format: Ss58AddressFormat) {
+    DEFAULT_VERSION.store(format.prefix, Ordering::Relaxed)
+}
+
+/// Encoding and decoding of accounts as SS58 text addresses.
+pub trait Ss58Codec: ByteArray {
+    /// Whether addresses of the account type may use `format`.
+    fn format_is_allowed(format: Ss58AddressFormat) -> bool {
+        !format.is_reserved()
+    }
+
+    /// Decode an address with the default prefix or any prefix of the registry.
+    fn from_ss58check(s: &str) -> Result<Self, PublicError> {
+        let (account, format) = Self::from_ss58check_with_version(s)?;
+        if format.is_custom() && format != default_ss58_version() {
+            return Err(PublicError::UnknownSs58AddressFormat(format))
+        }
+        Ok(account)
+    }
+
+    /// Decode an address, with the prefix it was encoded with.
+    fn from_ss58check_with_version(s: &str) -> Result<(Self, Ss58AddressFormat), PublicError> {
+        let data = from_base58(s).ok_or(PublicError::BadBase58)?;
+        if data.len() < 2 {
+            return Err(PublicError::BadLength)
+        }
+        let (prefix_len, prefix) = match data[0] {
+            0..=63 => (1, data[0] as u16),
+            64..=127 => {
+                // The lower six bits of the first byte and the upper two of the second are the
+                // lower byte of the prefix, the rest of the second byte is its upper byte.
+                let lower = (data[0] << 2) | (data[1] >> 6);
+                let upper = data[1] & 0b0011_1111;
+                (2, lower as u16 | (upper as u16) << 8)
+            }
+            _ => return Err(PublicError::InvalidPrefix),
+        };
+        if data.len() != prefix_len + Self::LEN + CHECKSUM_LEN {
+            return Err(PublicError::BadLength)
+        }
+        let format = Ss58AddressFormat::custom(prefix);
+        if !Self::format_is_allowed(format) {
+            return Err(PublicError::FormatNotAllowed)
+        }
+        let (body, checksum) = data.split_at(prefix_len + Self::LEN);
+        if ss58hash(body)[..CHECKSUM_LEN] != *checksum {
+            return Err(PublicError::InvalidChecksum)
+        }
+        let account = Self::from_slice(&body[prefix_len..]).ok_or(PublicError::BadLength)?;
+        Ok((account, format))
+    }
+
+    /// Encode the account as an address for the network of `format`.
+    fn to_ss58check_with_version(&self, format: Ss58AddressFormat) -> String {
+        // Only 14 bits fit in a prefix.
+        let prefix = format.prefix & MAX_PREFIX;
+        let mut data = match prefix {
+            0..=63 => vec![prefix as u8],
+            _ => {
+                let first = ((prefix & 0b0000_0000_1111_1100) as u8) >> 2;
+                let second = ((prefix >> 8) as u8) | ((prefix & 0b0000_0000_0000_0011) as u8) << 6;
+                vec![first | 0b0100_0000, second]
+            }
+        };
+        data.extend_from_slice(self.as_ref());
+        let checksum = ss58hash(&data);
+        data.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+        to_base58(&data)
+    }
+
+    /// Encode the account as an address with the default prefix.
+    fn to_ss58check(&self) -> String {
+        self.to_ss58check_with_version(default_ss58_version())
+    }
+}
+
+impl<T: ByteArray> Ss58Codec for T {}
+
+fn ss58hash(data: &[u8]) -> [u8; 64] {
+    blake2_512(&[&b"SS58PRE"[..], data].concat())
+}
+
+/// The base58 digits of `data` read as a big endian number, one `1` standing for every leading
+/// zero byte.
+fn to_base58(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|byte| **byte == 0).count();
+    let mut res = vec![ALPHABET[0]; zeros];
+    if zeros < data.len() {
+        let digits = BigUint::from_bytes_be(&data[zeros..]).to_radix_be(58);
+        res.extend(digits.into_iter().map(|digit| ALPHABET[digit as usize]));
+    }
+    String::from_utf8(res).expect("base58 digits are ascii; qed")
+}
+
+/// The bytes of the base58 number `s`, `None` if a character is not a base58 digit.
+fn from_base58(s: &str) -> Option<Vec<u8>> {
+    let digits = s
+        .bytes()
+        .map(|c| ALPHABET.iter().position(|digit| *digit == c).map(|digit| digit as u8))
+        .collect::<Option<Vec<_>>>()?;
+    let zeros = digits.iter().take_while(|digit| **digit == 0).count();
+    let mut res = vec![0; zeros];
+    if zeros < digits.len() {
+        res.extend(BigUint::from_radix_be(&digits[zeros..], 58)?.to_bytes_be());
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{ed25519, AccountId32};
+
+    #[test]
+    fn base58_works() {
+        assert_eq!(to_base58(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(to_base58(&[0, 0, 1]), "112");
+        assert_eq!(to_base58(&[]), "");
+        assert_eq!(from_base58("2NEpo7TZRRrLZSi2U"), Some(b"Hello World!".to_vec()));
+        assert_eq!(from_base58("112"), Some(vec![0, 0, 1]));
+        assert_eq!(from_base58("10"), None);
+        assert_eq!(from_base58("1l"), None);
+    }
+
+    #[test]
+    fn known_addresses_work() {
+        let zero = AccountId32([0; 32]);
+        let substrate = "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM";
+        let polkadot = "111111111111111111111111111111111HC1";
+        assert_eq!(zero.to_ss58check(), substrate);
+        assert_eq!(zero.to_ss58check_with_version(Ss58AddressFormat::custom(0)), polkadot);
+        assert_eq!(
+            AccountId32::from_ss58check_with_version(polkadot),
+            Ok((zero.clone(), Ss58AddressFormatRegistry::PolkadotAccount.into()))
+        );
+        assert_eq!(AccountId32::from_ss58check(substrate), Ok(zero));
+    }
+
+    #[test]
+    fn two_byte_prefixes_round_trip() {
+        let account = AccountId32([7; 32]);
+        for prefix in [64, 255, 256, 1284, MAX_PREFIX] {
+            let format = Ss58AddressFormat::custom(prefix);
+            let address = account.to_ss58check_with_version(format);
+            let data = from_base58(&address).unwrap();
+            assert_eq!(data.len(), 2 + 32 + CHECKSUM_LEN);
+            assert_eq!(data[0] & 0b1100_0000, 0b0100_0000);
+            assert_eq!(
+                AccountId32::from_ss58check_with_version(&address),
+                Ok((account.clone(), format))
+            );
+        }
+    }
+
+    #[test]
+    fn bad_addresses_are_rejected() {
+        let account = AccountId32([7; 32]);
+        let address = account.to_ss58check();
+        let mut data = from_base58(&address).unwrap();
+
+        assert_eq!(AccountId32::from_ss58check("0x00"), Err(PublicError::BadBase58));
+        assert_eq!(
+            AccountId32::from_ss58check(&to_base58(&data[..20])),
+            Err(PublicError::BadLength)
+        );
+        assert_eq!(ed25519::Public::from_ss58check(&address), Ok(ed25519::Public([7; 32])));
+
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert_eq!(
+            AccountId32::from_ss58check(&to_base58(&data)),
+            Err(PublicError::InvalidChecksum)
+        );
+        data[0] = 128;
+        assert_eq!(AccountId32::from_ss58check(&to_base58(&data)), Err(PublicError::InvalidPrefix));
+
+        let reserved = account.to_ss58check_with_version(Ss58AddressFormat::custom(46));
+        assert_eq!(
+            AccountId32::from_ss58check_with_version(&reserved),
+            Err(PublicError::FormatNotAllowed)
+        );
+    }
+
+    #[test]
+    fn custom_prefixes_need_to_be_the_default() {
+        let account = AccountId32([7; 32]);
+        let kusama = account.to_ss58check_with_version(Ss58AddressFormat::custom(2));
+        assert_eq!(AccountId32::from_ss58check(&kusama), Ok(account.clone()));
+
+        let custom = Ss58AddressFormat::custom(200);
+        let address = account.to_ss58check_with_version(custom);
+        assert_eq!(
+            AccountId32::from_ss58check(&address),
+            Err(PublicError::UnknownSs58AddressFormat(custom))
+        );
+    }
+
+    #[test]
+    fn the_registry_names_prefixes() {
+        assert_eq!(Ss58AddressFormat::custom(42).to_string(), "substrate");
+        assert_eq!(Ss58AddressFormat::custom(200).to_string(), "200");
+        assert_eq!(
+            Ss58AddressFormatRegistry::from_name("kusama").map(Ss58AddressFormat::from),
+            Some(Ss58AddressFormat::custom(2))
+        );
+        assert!(Ss58AddressFormat::custom(47).is_reserved());
+        assert!(!Ss58AddressFormat::custom(42).is_custom());
+    }
+}
@@ -0,0 +1,639 @@
+//! What the runtime needs to know about a call before dispatching it.
+
+use crate::{
+    codec::{Decode, Encode, MaxEncodedLen},
+    scale_info::TypeInfo,
+    traits::PalletInfo,
+    weights::Weight,
+};
+use std::fmt::Debug;
+
+/// The kind of a call, each kind has its own share of the block weight.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum DispatchClass {
+    /// A normal transaction.
+    #[default]
+    Normal,
+    /// An operational transaction, e.g. from governance, which can use the reserved weight.
+    Operational,
+    /// A call which must be part of the block whatever its weight, e.g. an inherent.
+    Mandatory,
+}
+
+impl DispatchClass {
+    pub fn all() -> &'static [DispatchClass] {
+        &[DispatchClass::Normal, DispatchClass::Operational, DispatchClass::Mandatory]
+    }
+
+    pub fn non_mandatory() -> &'static [DispatchClass] {
+        &[DispatchClass::Normal, DispatchClass::Operational]
+    }
+}
+
+/// Whether the sender of a call pays fees for it.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Pays {
+    #[default]
+    Yes,
+    No,
+}
+
+/// The weight, class and fee payment of a call, known before dispatching it.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct DispatchInfo {
+    pub weight: Weight,
+    pub class: DispatchClass,
+    pub pays_fee: Pays,
+}
+
+/// Implemented by calls, to get their [`DispatchInfo`] without dispatching them.
+pub trait GetDispatchInfo {
+    fn get_dispatch_info(&self) -> DispatchInfo;
+}
+
+/// The weight of a call given its arguments `T`.
+pub trait WeighData<T> {
+    fn weigh_data(&self, target: T) -> Weight;
+}
+
+/// The class of a call given its arguments `T`.
+pub trait ClassifyDispatch<T> {
+    fn classify_dispatch(&self, target: T) -> DispatchClass;
+}
+
+/// Whether a call pays fees given its arguments `T`.
+pub trait PaysFee<T> {
+    fn pays_fee(&self, target: T) -> Pays;
+}
+
+impl<T> WeighData<T> for Weight {
+    fn weigh_data(&self, _: T) -> Weight {
+        *self
+    }
+}
+
+impl<T> ClassifyDispatch<T> for Weight {
+    fn classify_dispatch(&self, _: T) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T> PaysFee<T> for Weight {
+    fn pays_fee(&self, _: T) -> Pays {
+        Pays::Yes
+    }
+}
+
+impl<T> WeighData<T> for (Weight, DispatchClass) {
+    fn weigh_data(&self, _: T) -> Weight {
+        self.0
+    }
+}
+
+impl<T> ClassifyDispatch<T> for (Weight, DispatchClass) {
+    fn classify_dispatch(&self, _: T) -> DispatchClass {
+        self.1
+    }
+}
+
+impl<T> PaysFee<T> for (Weight, DispatchClass) {
+    fn pays_fee(&self, _: T) -> Pays {
+        Pays::Yes
+    }
+}
+
+impl<T> WeighData<T> for (Weight, Pays) {
+    fn weigh_data(&self, _: T) -> Weight {
+        self.0
+    }
+}
+
+impl<T> ClassifyDispatch<T> for (Weight, Pays) {
+    fn classify_dispatch(&self, _: T) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T> PaysFee<T> for (Weight, Pays) {
+    fn pays_fee(&self, _: T) -> Pays {
+        self.1
+    }
+}
+
+impl<T> WeighData<T> for (Weight, DispatchClass, Pays) {
+    fn weigh_data(&self, _: T) -> Weight {
+        self.0
+    }
+}
+
+impl<T> ClassifyDispatch<T> for (Weight, DispatchClass, Pays) {
+    fn classify_dispatch(&self, _: T) -> DispatchClass {
+        self.1
+    }
+}
+
+impl<T> PaysFee<T> for (Weight, DispatchClass, Pays) {
+    fn pays_fee(&self, _: T) -> Pays {
+        self.2
+    }
+}
+
+/// The dispatch info of a call annotated with `annotation`, e.g. `(weight, DispatchClass)`,
+/// and called with the arguments `args`.
+pub fn annotated_dispatch_info<A, T: Copy>(annotation: &A, args: T) -> DispatchInfo
+where
+    A: WeighData<T> + ClassifyDispatch<T> + PaysFee<T>,
+{
+    DispatchInfo {
+        weight: annotation.weigh_data(args),
+        class: annotation.classify_dispatch(args),
+        pays_fee: annotation.pays_fee(args),
+    }
+}
+
+/// What a type needs to be a call argument: encodable, comparable and described in metadata.
+pub trait Parameter: Encode + Decode + Clone + Eq + Debug + TypeInfo {}
+
+impl<T: Encode + Decode + Clone + Eq + Debug + TypeInfo> Parameter for T {}
+
+/// Who made a call.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub enum RawOrigin<AccountId> {
+    /// The system itself, e.g. a governance decision.
+    Root,
+    /// An account, which signed the transaction.
+    Signed(AccountId),
+    /// Nobody, e.g. an inherent or an unsigned transaction.
+    None,
+}
+
+impl<AccountId> From<Option<AccountId>> for RawOrigin<AccountId> {
+    fn from(who: Option<AccountId>) -> Self {
+        match who {
+            Some(who) => RawOrigin::Signed(who),
+            None => RawOrigin::None,
+        }
+    }
+}
+
+/// Lets a raw origin be used where an outer origin is expected, e.g. in pallet tests.
+impl<AccountId> From<RawOrigin<AccountId>> for Result<RawOrigin<AccountId>, RawOrigin<AccountId>> {
+    fn from(o: RawOrigin<AccountId>) -> Self {
+        Ok(o)
+    }
+}
+
+impl<AccountId> RawOrigin<AccountId> {
+    pub fn as_signed(&self) -> Option<&AccountId> {
+        match self {
+            RawOrigin::Signed(who) => Some(who),
+            _ => None,
+        }
+    }
+
+    pub fn is_root(&self) -> bool {
+        matches!(self, RawOrigin::Root)
+    }
+
+    pub fn is_none(&self) -> bool {
+        matches!(self, RawOrigin::None)
+    }
+}
+
+/// The error of a call made by an origin it does not accept.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BadOrigin;
+
+impl From<BadOrigin> for DispatchError {
+    fn from(_: BadOrigin) -> Self {
+        DispatchError::BadOrigin
+    }
+}
+
+/// The account which signed the call, failing for any other origin.
+pub fn ensure_signed<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<AccountId, BadOrigin>
+where
+    OuterOrigin: Into<Result<RawOrigin<AccountId>, OuterOrigin>>,
+{
+    match o.into() {
+        Ok(RawOrigin::Signed(who)) => Ok(who),
+        _ => Err(BadOrigin),
+    }
+}
+
+/// Fail unless the call was made by root.
+pub fn ensure_root<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), BadOrigin>
+where
+    OuterOrigin: Into<Result<RawOrigin<AccountId>, OuterOrigin>>,
+{
+    match o.into() {
+        Ok(RawOrigin::Root) => Ok(()),
+        _ => Err(BadOrigin),
+    }
+}
+
+/// Fail unless the call was made by nobody.
+pub fn ensure_none<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), BadOrigin>
+where
+    OuterOrigin: Into<Result<RawOrigin<AccountId>, OuterOrigin>>,
+{
+    match o.into() {
+        Ok(RawOrigin::None) => Ok(()),
+        _ => Err(BadOrigin),
+    }
+}
+
+/// Why a call failed.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DispatchError {
+    /// Any other error, described by its message.
+    Other(#[codec(skip)] &'static str),
+    /// An account lookup failed.
+    CannotLookup,
+    /// The call was made by the wrong origin.
+    BadOrigin,
+    /// An error declared by a pallet.
+    Module(ModuleError),
+    /// The call was refused by the call filter of its origin.
+    CallFiltered,
+    /// The account can't be removed while other modules depend on it.
+    ConsumerRemaining,
+    /// Nothing allows the account to exist, so no module can depend on it.
+    NoProviders,
+    /// Funds could not be moved.
+    Token(TokenError),
+    /// A computation overflowed or underflowed.
+    Arithmetic(ArithmeticError),
+}
+
+impl From<&'static str> for DispatchError {
+    fn from(message: &'static str) -> Self {
+        DispatchError::Other(message)
+    }
+}
+
+/// Why funds could not be moved.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenError {
+    /// The account does not have the funds.
+    NoFunds,
+    /// The account would be reaped, which is not allowed.
+    WouldDie,
+    /// The account would be left with less than the minimum balance.
+    BelowMinimum,
+    /// The account can't be created.
+    CannotCreate,
+    /// The asset is not known.
+    UnknownAsset,
+    /// The funds are locked.
+    Frozen,
+    /// The operation is not supported by the asset.
+    Unsupported,
+}
+
+impl From<TokenError> for DispatchError {
+    fn from(error: TokenError) -> Self {
+        DispatchError::Token(error)
+    }
+}
+
+/// Why a computation failed.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArithmeticError {
+    /// The result is below the minimum of its type.
+    Underflow,
+    /// The result is above the maximum of its type.
+    Overflow,
+    /// A value was divided by zero.
+    DivisionByZero,
+}
+
+impl From<ArithmeticError> for DispatchError {
+    fn from(error: ArithmeticError) -> Self {
+        DispatchError::Arithmetic(error)
+    }
+}
+
+/// An error of the pallet at `index` in the runtime, `error` being its encoded error enum.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Eq, Debug)]
+pub struct ModuleError {
+    pub index: u8,
+    pub error: [u8; 4],
+    #[codec(skip)]
+    pub message: Option<&'static str>,
+}
+
+impl ModuleError {
+    /// The `error` of the pallet `P`, found in the runtime by `Info`.
+    ///
+    /// `None` if `P` is not a pallet of the runtime, or if its index doesn't fit in a byte, so
+    /// the error can't be told apart from those of the other pallets.
+    pub fn new<Info: PalletInfo, P: 'static>(
+        error: &impl Encode,
+        message: &'static str,
+    ) -> Option<Self> {
+        let index = u8::try_from(Info::index::<P>()?).ok()?;
+        // Errors are at most four bytes, the variant and its fields if it has any.
+        let encoded = error.encode();
+        let mut error = [0; 4];
+        let len = encoded.len().min(error.len());
+        error[..len].copy_from_slice(&encoded[..len]);
+        Some(ModuleError { index, error, message: Some(message) })
+    }
+}
+
+// The message is not encoded, so errors decoded from events compare equal to those raised.
+impl PartialEq for ModuleError {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.error == other.error
+    }
+}
+
+/// The result of a call which does not report what it actually cost.
+pub type DispatchResult = Result<(), DispatchError>;
+
+/// The result of applying an extrinsic: refused if it is not valid, or the result of its call.
+pub type ApplyExtrinsicResult =
+    Result<DispatchResult, crate::transaction_validity::TransactionValidityError>;
+
+/// What is only known once a call was dispatched.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct PostDispatchInfo {
+    /// The weight the call actually used, `None` if it used all its [`DispatchInfo::weight`].
+    pub actual_weight: Option<Weight>,
+    /// Whether the sender pays fees for the call after all.
+    pub pays_fee: Pays,
+}
+
+impl PostDispatchInfo {
+    /// The part of the weight announced by `info` which the call did not use.
+    pub fn calc_unspent(&self, info: &DispatchInfo) -> Weight {
+        info.weight.saturating_sub(self.calc_actual_weight(info))
+    }
+
+    /// The weight the call used, which is never more than announced by `info`.
+    pub fn calc_actual_weight(&self, info: &DispatchInfo) -> Weight {
+        match self.actual_weight {
+            Some(actual_weight) => actual_weight.min(info.weight),
+            None => info.weight,
+        }
+    }
+
+    /// Fees are waived if either the call is declared free or it decided so when dispatched.
+    pub fn pays_fee(&self, info: &DispatchInfo) -> Pays {
+        if info.pays_fee == Pays::No || self.pays_fee == Pays::No {
+            Pays::No
+        } else {
+            Pays::Yes
+        }
+    }
+}
+
+impl From<()> for PostDispatchInfo {
+    fn from(_: ()) -> Self {
+        Self { actual_weight: None, pays_fee: Pays::Yes }
+    }
+}
+
+impl From<Pays> for PostDispatchInfo {
+    fn from(pays_fee: Pays) -> Self {
+        Self { actual_weight: None, pays_fee }
+    }
+}
+
+impl From<Option<Weight>> for PostDispatchInfo {
+    fn from(actual_weight: Option<Weight>) -> Self {
+        Self { actual_weight, pays_fee: Pays::Yes }
+    }
+}
+
+/// The error of a call, with what it cost until it failed.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DispatchErrorWithPostInfo<Info = PostDispatchInfo> {
+    pub post_info: Info,
+    pub error: DispatchError,
+}
+
+impl<E: Into<DispatchError>> From<E> for DispatchErrorWithPostInfo {
+    fn from(error: E) -> Self {
+        Self { post_info: Default::default(), error: error.into() }
+    }
+}
+
+/// The result of a call, reporting what it actually cost whether it failed or not.
+pub type DispatchResultWithPostInfo = Result<PostDispatchInfo, DispatchErrorWithPostInfo>;
+
+/// Attach the weight a call used until it failed to its error, e.g.
+/// `Err(Error::<T>::NotAllowed.with_weight(weight))`.
+pub trait WithPostDispatchInfo {
+    fn with_weight(self, actual_weight: Weight) -> DispatchErrorWithPostInfo;
+}
+
+impl<E: Into<DispatchError>> WithPostDispatchInfo for E {
+    fn with_weight(self, actual_weight: Weight) -> DispatchErrorWithPostInfo {
+        DispatchErrorWithPostInfo {
+            post_info: Some(actual_weight).into(),
+            error: self.into(),
+        }
+    }
+}
+
+/// The weight a call dispatched with `info` actually used according to its `result`.
+pub fn extract_actual_weight(result: &DispatchResultWithPostInfo, info: &DispatchInfo) -> Weight {
+    match result {
+        Ok(post_info) => post_info,
+        Err(err) => &err.post_info,
+    }
+    .calc_actual_weight(info)
+}
+
+/// Whether the sender pays fees for a call dispatched with `info` according to its `result`.
+pub fn extract_actual_pays_fee(result: &DispatchResultWithPostInfo, info: &DispatchInfo) -> Pays {
+    match result {
+        Ok(post_info) => post_info,
+        Err(err) => &err.post_info,
+    }
+    .pays_fee(info)
+}
+
+/// One value for each [`DispatchClass`].
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct PerDispatchClass<T> {
+    normal: T,
+    operational: T,
+    mandatory: T,
+}
+
+impl<T> PerDispatchClass<T> {
+    pub fn new(mut val: impl FnMut(DispatchClass) -> T) -> Self {
+        Self {
+            normal: val(DispatchClass::Normal),
+            operational: val(DispatchClass::Operational),
+            mandatory: val(DispatchClass::Mandatory),
+        }
+    }
+
+    pub fn get(&self, class: DispatchClass) -> &T {
+        match class {
+            DispatchClass::Normal => &self.normal,
+            DispatchClass::Operational => &self.operational,
+            DispatchClass::Mandatory => &self.mandatory,
+        }
+    }
+
+    pub fn get_mut(&mut self, class: DispatchClass) -> &mut T {
+        match class {
+            DispatchClass::Normal => &mut self.normal,
+            DispatchClass::Operational => &mut self.operational,
+            DispatchClass::Mandatory => &mut self.mandatory,
+        }
+    }
+}
+
+impl PerDispatchClass<Weight> {
+    /// The weight of all classes together.
+    pub fn total(&self) -> Weight {
+        DispatchClass::all()
+            .iter()
+            .fold(Weight::zero(), |total, class| total.saturating_add(*self.get(*class)))
+    }
+
+    pub fn add(&mut self, weight: Weight, class: DispatchClass) {
+        self.get_mut(class).saturating_accrue(weight);
+    }
+
+    /// Add `weight` to `class`, failing without change if the total would overflow.
+    #[allow(clippy::result_unit_err)]
+    pub fn checked_add(&mut self, weight: Weight, class: DispatchClass) -> Result<(), ()> {
+        self.total().checked_add(&weight).ok_or(())?;
+        let value = self.get_mut(class);
+        *value = value.checked_add(&weight).ok_or(())?;
+        Ok(())
+    }
+
+    pub fn sub(&mut self, weight: Weight, class: DispatchClass) {
+        self.get_mut(class).saturating_reduce(weight);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_give_dispatch_info() {
+        let weight = Weight::from_parts(10, 1);
+        assert_eq!(
+            annotated_dispatch_info(&weight, ()),
+            DispatchInfo { weight, class: DispatchClass::Normal, pays_fee: Pays::Yes }
+        );
+        assert_eq!(
+            annotated_dispatch_info(&(weight, DispatchClass::Operational), ()),
+            DispatchInfo { weight, class: DispatchClass::Operational, pays_fee: Pays::Yes }
+        );
+        assert_eq!(
+            annotated_dispatch_info(&(weight, DispatchClass::Mandatory, Pays::No), ()),
+            DispatchInfo { weight, class: DispatchClass::Mandatory, pays_fee: Pays::No }
+        );
+    }
+
+    #[test]
+    fn post_info_refunds_unused_weight() {
+        let info = DispatchInfo { weight: Weight::from_parts(100, 10), ..Default::default() };
+        let post_info: PostDispatchInfo = Some(Weight::from_parts(40, 10)).into();
+        assert_eq!(post_info.calc_actual_weight(&info), Weight::from_parts(40, 10));
+        assert_eq!(post_info.calc_unspent(&info), Weight::from_parts(60, 0));
+
+        // A call can not use more than it announced.
+        let post_info: PostDispatchInfo = Some(Weight::from_parts(200, 0)).into();
+        assert_eq!(post_info.calc_actual_weight(&info), Weight::from_parts(100, 0));
+
+        let result: DispatchResultWithPostInfo =
+            Err("failed".with_weight(Weight::from_parts(1, 1)));
+        assert_eq!(extract_actual_weight(&result, &info), Weight::from_parts(1, 1));
+        assert_eq!(extract_actual_weight(&Ok(().into()), &info), info.weight);
+    }
+
+    #[test]
+    fn pays_no_waives_fees() {
+        let info = DispatchInfo::default();
+        assert_eq!(extract_actual_pays_fee(&Ok(Pays::No.into()), &info), Pays::No);
+        assert_eq!(extract_actual_pays_fee(&Ok(().into()), &info), Pays::Yes);
+        let free = DispatchInfo { pays_fee: Pays::No, ..info };
+        assert_eq!(extract_actual_pays_fee(&Err(DispatchError::BadOrigin.into()), &free), Pays::No);
+    }
+
+    #[test]
+    fn dispatch_error_skips_messages() {
+        let error = DispatchError::Other("not allowed");
+        assert_eq!(error.encode(), vec![0]);
+        assert_eq!(DispatchError::decode(&mut &[0][..]), Ok(DispatchError::Other("")));
+    }
+
+    #[test]
+    fn module_errors_encode_their_pallet_and_error() {
+        struct First;
+        struct Second;
+        struct Far;
+        struct Missing;
+        struct Info;
+        impl PalletInfo for Info {
+            fn index<P: 'static>() -> Option<usize> {
+                use std::any::TypeId;
+                let pallets = [TypeId::of::<First>(), TypeId::of::<Second>(), TypeId::of::<Far>()];
+                let position = pallets.iter().position(|pallet| *pallet == TypeId::of::<P>())?;
+                Some([0, 1, 300][position])
+            }
+            fn name<P: 'static>() -> Option<&'static str> {
+                None
+            }
+        }
+
+        let error = DispatchError::Module(ModuleError::new::<Info, Second>(&3u8, "Third").unwrap());
+        assert_eq!(error.encode(), vec![3, 1, 3, 0, 0, 0]);
+        let decoded = DispatchError::decode(&mut &error.encode()[..]).unwrap();
+        let DispatchError::Module(ModuleError { message, .. }) = decoded else { panic!() };
+        assert_eq!(message, None);
+        assert_eq!(decoded, error);
+
+        // Pallets out of the runtime, or past the 256th, have no module errors.
+        assert_eq!(ModuleError::new::<Info, Missing>(&3u8, "Third"), None);
+        assert_eq!(ModuleError::new::<Info, Far>(&3u8, "Third"), None);
+    }
+
+    #[test]
+    fn token_and_arithmetic_errors_encode_their_reason() {
+        assert_eq!(DispatchError::from(TokenError::Frozen).encode(), vec![7, 5]);
+        assert_eq!(DispatchError::from(ArithmeticError::Overflow).encode(), vec![8, 1]);
+        assert_eq!(
+            DispatchError::decode(&mut &[7, 2][..]),
+            Ok(DispatchError::Token(TokenError::BelowMinimum))
+        );
+    }
+
+    #[test]
+    fn ensure_origins_work() {
+        let signed = || RawOrigin::Signed(1u32);
+        assert_eq!(ensure_signed(signed()), Ok(1));
+        assert_eq!(ensure_signed(RawOrigin::<u32>::Root), Err(BadOrigin));
+        assert_eq!(ensure_root(RawOrigin::<u32>::Root), Ok(()));
+        assert_eq!(ensure_root(signed()), Err(BadOrigin));
+        assert_eq!(ensure_none(RawOrigin::<u32>::from(None)), Ok(()));
+        assert_eq!(DispatchError::from(BadOrigin), DispatchError::BadOrigin);
+    }
+
+    #[test]
+    fn per_class_weight_accumulates() {
+        let mut consumed = PerDispatchClass::<Weight>::default();
+        consumed.add(Weight::from_parts(10, 0), DispatchClass::Normal);
+        consumed.add(Weight::from_parts(5, 1), DispatchClass::Mandatory);
+        assert_eq!(consumed.total(), Weight::from_parts(15, 1));
+
+        let max = Weight::from_parts(u64::MAX, 0);
+        assert_eq!(consumed.checked_add(max, DispatchClass::Operational), Err(()));
+        assert_eq!(*consumed.get(DispatchClass::Operational), Weight::zero());
+
+        consumed.sub(Weight::from_parts(20, 0), DispatchClass::Normal);
+        assert_eq!(*consumed.get(DispatchClass::Normal), Weight::zero());
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{
+    codec::{Decode, Encode},
+    generic::Header,
+    hashing::Hash as HashT,
+    scale_info::TypeInfo,
+};
+
+/// A block: its header and its extrinsics.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct Block<Header, Extrinsic> {
+    pub header: Header,
+    pub extrinsics: Vec<Extrinsic>,
+}
+
+impl<Number: Encode, Hash: Encode, Extrinsic> Block<Header<Number, Hash>, Extrinsic> {
+    /// The hash of the block, which is the hash of its header.
+    pub fn hash<Hashing: HashT<Output = Hash>>(&self) -> Hash {
+        self.header.hash::<Hashing>()
+    }
+}
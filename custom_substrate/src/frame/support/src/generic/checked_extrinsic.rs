@@ -0,0 +1,71 @@
+use crate::{
+    codec::{Decode, Encode},
+    dispatch::{DispatchInfo, DispatchResultWithPostInfo, GetDispatchInfo, PostDispatchInfo},
+    scale_info::TypeInfo,
+    storage::with_transaction,
+    traits::{Applyable, Dispatchable, SignedExtension},
+    transaction_validity::{TransactionValidity, TransactionValidityError},
+};
+
+/// An extrinsic whose signature was checked, so its signer is known.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct CheckedExtrinsic<AccountId, Call, Extra> {
+    /// The account which signed the extrinsic with its extensions, `None` if it is unsigned.
+    pub signed: Option<(AccountId, Extra)>,
+    /// The call to dispatch.
+    pub function: Call,
+}
+
+impl<AccountId, Call: GetDispatchInfo, Extra> GetDispatchInfo
+    for CheckedExtrinsic<AccountId, Call, Extra>
+{
+    fn get_dispatch_info(&self) -> DispatchInfo {
+        self.function.get_dispatch_info()
+    }
+}
+
+impl<AccountId, Call, Extra> Applyable for CheckedExtrinsic<AccountId, Call, Extra>
+where
+    AccountId: Clone,
+    Call: Dispatchable<PostInfo = PostDispatchInfo>,
+    Call::RuntimeOrigin: From<Option<AccountId>>,
+    Extra: SignedExtension<AccountId = AccountId, Call = Call>,
+{
+    type Call = Call;
+
+    fn validate(&self, info: &DispatchInfo, len: usize) -> TransactionValidity {
+        match &self.signed {
+            Some((who, extra)) => extra.validate(who, &self.function, info, len),
+            None => Extra::validate_unsigned(&self.function, info, len),
+        }
+    }
+
+    /// Dispatch the call from the signer, between the checks of the extensions.
+    fn apply(
+        self,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<DispatchResultWithPostInfo, TransactionValidityError> {
+        let (maybe_who, maybe_pre) = match self.signed {
+            Some((who, extra)) => {
+                let pre = extra.pre_dispatch(&who, &self.function, info, len)?;
+                (Some(who), Some(pre))
+            }
+            None => {
+                Extra::pre_dispatch_unsigned(&self.function, info, len)?;
+                (None, None)
+            }
+        };
+
+        // A failed call leaves nothing in the storage, unlike the checks of the extensions.
+        let origin = Call::RuntimeOrigin::from(maybe_who);
+        let res = with_transaction(|| self.function.dispatch(origin));
+        let post_info = match &res {
+            Ok(post_info) => *post_info,
+            Err(err) => err.post_info,
+        };
+        let dispatch_result = res.map(|_| ()).map_err(|err| err.error);
+        Extra::post_dispatch(maybe_pre, info, &post_info, len, &dispatch_result)?;
+        Ok(res)
+    }
+}
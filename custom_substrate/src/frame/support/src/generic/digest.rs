@@ -0,0 +1,40 @@
+use crate::{
+    codec::{Decode, Encode},
+    scale_info::TypeInfo,
+};
+
+/// The identifier of a consensus engine, e.g. `*b"aura"`.
+pub type ConsensusEngineId = [u8; 4];
+
+/// Logs of a block which are not extrinsics, e.g. the slot it was authored in and its seal.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Digest {
+    pub logs: Vec<DigestItem>,
+}
+
+impl Digest {
+    pub fn push(&mut self, item: DigestItem) {
+        self.logs.push(item);
+    }
+
+    pub fn logs(&self) -> &[DigestItem] {
+        &self.logs
+    }
+}
+
+/// A log of a [`Digest`].
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub enum DigestItem {
+    /// Anything else.
+    #[codec(index = 0)]
+    Other(Vec<u8>),
+    /// A message from the runtime to the consensus engine.
+    #[codec(index = 4)]
+    Consensus(ConsensusEngineId, Vec<u8>),
+    /// The seal of the block by its author, added once the block is built.
+    #[codec(index = 5)]
+    Seal(ConsensusEngineId, Vec<u8>),
+    /// A message from the block author to the runtime, added before the block is built.
+    #[codec(index = 6)]
+    PreRuntime(ConsensusEngineId, Vec<u8>),
+}
@@ -0,0 +1,83 @@
+use crate::{
+    codec::{Decode, Encode},
+    scale_info::TypeInfo,
+};
+
+/// The blocks a transaction is valid in.
+///
+/// A mortal transaction is valid for `period` blocks from the block it was made at, its birth,
+/// which is known from the current block number and its `phase`, i.e. the birth modulo the
+/// period. It names the hash of that block, so it can't be replayed on another fork or once it
+/// died.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Era {
+    /// Valid forever.
+    #[default]
+    Immortal,
+    /// Valid in the `period` blocks starting at the block whose number modulo `period` is
+    /// `phase`.
+    Mortal(u64, u64),
+}
+
+impl Era {
+    /// A transaction made at block `current`, valid for about `period` blocks.
+    ///
+    /// The period is rounded to a power of two between 4 and 65536, and the phase is quantized
+    /// so that it fits in 12 bits.
+    pub fn mortal(period: u64, current: u64) -> Self {
+        let period = period.checked_next_power_of_two().unwrap_or(1 << 16).clamp(4, 1 << 16);
+        let phase = current % period;
+        let quantize_factor = (period >> 12).max(1);
+        let quantized_phase = phase / quantize_factor * quantize_factor;
+        Era::Mortal(period, quantized_phase)
+    }
+
+    pub fn immortal() -> Self {
+        Era::Immortal
+    }
+
+    pub fn is_immortal(&self) -> bool {
+        matches!(self, Era::Immortal)
+    }
+
+    /// The block the transaction was made at, knowing the current block is `current`.
+    pub fn birth(self, current: u64) -> u64 {
+        match self {
+            Era::Immortal => 0,
+            Era::Mortal(period, phase) => (current.max(phase) - phase) / period * period + phase,
+        }
+    }
+
+    /// The first block the transaction is not valid in anymore.
+    pub fn death(self, current: u64) -> u64 {
+        match self {
+            Era::Immortal => u64::MAX,
+            Era::Mortal(period, _) => self.birth(current).saturating_add(period),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immortal_transactions_never_die() {
+        let era = Era::immortal();
+        assert!(era.is_immortal());
+        assert_eq!((era.birth(100), era.death(100)), (0, u64::MAX));
+    }
+
+    #[test]
+    fn mortal_transactions_live_for_their_period() {
+        let era = Era::mortal(64, 42);
+        assert_eq!(era, Era::Mortal(64, 42));
+        for current in 42..106 {
+            assert_eq!((era.birth(current), era.death(current)), (42, 106));
+        }
+        // The period is rounded up to a power of two.
+        assert_eq!(Era::mortal(5, 1), Era::Mortal(8, 1));
+        assert_eq!(Era::mortal(1, 1), Era::Mortal(4, 1));
+        assert_eq!(Era::mortal(1 << 20, 1), Era::Mortal(1 << 16, 0));
+    }
+}
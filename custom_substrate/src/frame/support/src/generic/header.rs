@@ -0,0 +1,59 @@
+use crate::{
+    codec::{Decode, Encode},
+    generic::Digest,
+    hashing::Hash as HashT,
+    scale_info::TypeInfo,
+};
+
+/// The header of a block, which commits to its parent, its extrinsics and the state after
+/// them.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Header<Number, Hash> {
+    /// The hash of the header of the parent block.
+    pub parent_hash: Hash,
+    pub number: Number,
+    /// The hash of the storage once the block is executed.
+    pub state_root: Hash,
+    /// The hash of the extrinsics of the block.
+    pub extrinsics_root: Hash,
+    /// What the block holds besides its extrinsics.
+    pub digest: Digest,
+}
+
+impl<Number, Hash: Default> Header<Number, Hash> {
+    /// The header a block producer starts from, whose roots are only known once the block is
+    /// finalized.
+    pub fn new_unfinalized(number: Number, parent_hash: Hash) -> Self {
+        Self {
+            parent_hash,
+            number,
+            state_root: Default::default(),
+            extrinsics_root: Default::default(),
+            digest: Default::default(),
+        }
+    }
+}
+
+impl<Number: Encode, Hash: Encode> Header<Number, Hash> {
+    /// The hash of the header, which identifies its block.
+    pub fn hash<Hashing: HashT<Output = Hash>>(&self) -> Hash {
+        Hashing::hash_of(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generic::DigestItem, hashing::BlakeTwo256};
+
+    #[test]
+    fn headers_are_hashed_with_their_digest() {
+        let header = Header::<u64, [u8; 32]>::new_unfinalized(1, [1; 32]);
+        assert_eq!(header.hash::<BlakeTwo256>(), BlakeTwo256::hash_of(&header));
+
+        let mut sealed = header.clone();
+        sealed.digest.push(DigestItem::Seal(*b"test", vec![1]));
+        assert_ne!(sealed.hash::<BlakeTwo256>(), header.hash::<BlakeTwo256>());
+        assert_eq!(Header::decode(&mut &sealed.encode()[..]), Ok(sealed));
+    }
+}
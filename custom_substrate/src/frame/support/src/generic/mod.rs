@@ -0,0 +1,16 @@
+//! Generic types of the chain, made concrete by every runtime with its own block number, hash,
+//! account id and call.
+
+mod block;
+mod checked_extrinsic;
+mod digest;
+mod era;
+mod header;
+mod unchecked_extrinsic;
+
+pub use block::Block;
+pub use checked_extrinsic::CheckedExtrinsic;
+pub use digest::{ConsensusEngineId, Digest, DigestItem};
+pub use era::Era;
+pub use header::Header;
+pub use unchecked_extrinsic::{SignedPayload, UncheckedExtrinsic, EXTRINSIC_FORMAT_VERSION};
@@ -0,0 +1,283 @@
+use super::CheckedExtrinsic;
+use crate::{
+    codec::{Decode, DecodeLimit, Encode, Error, Input, Output},
+    dispatch::{DispatchInfo, GetDispatchInfo},
+    hashing::blake2_256,
+    traits::{Checkable, IdentifyAccount, SignedExtension, StaticLookup, Verify},
+    transaction_validity::{InvalidTransaction, TransactionValidityError, UnknownTransaction},
+};
+
+/// The version of the extrinsic format, in the lower 7 bits of the version byte.
+pub const EXTRINSIC_FORMAT_VERSION: u8 = 4;
+
+/// The nesting depth the call of an extrinsic may have when decoded, e.g. through batches.
+pub const MAX_EXTRINSIC_DEPTH: u32 = 256;
+
+/// The bit of the version byte telling whether the extrinsic is signed.
+const SIGNED_BIT: u8 = 0b1000_0000;
+
+/// An extrinsic as it is sent over the network and put in blocks, whose signature is not
+/// checked yet.
+///
+/// It encodes as a byte vector, i.e. prefixed by its length, of the version byte, then the
+/// signer address, signature and extensions if it is signed, then the call.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UncheckedExtrinsic<Address, Call, Signature, Extra: SignedExtension> {
+    /// The signer, its signature of the payload and the extensions, `None` if it is unsigned.
+    pub signature: Option<(Address, Signature, Extra)>,
+    /// The call to dispatch.
+    pub function: Call,
+}
+
+impl<Address, Call, Signature, Extra: SignedExtension>
+    UncheckedExtrinsic<Address, Call, Signature, Extra>
+{
+    pub fn new_signed(function: Call, signed: Address, signature: Signature, extra: Extra) -> Self {
+        Self { signature: Some((signed, signature, extra)), function }
+    }
+
+    pub fn new_unsigned(function: Call) -> Self {
+        Self { signature: None, function }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signature.is_some()
+    }
+}
+
+impl<Address, Call, Signature, Extra> Encode for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+    Address: Encode,
+    Call: Encode,
+    Signature: Encode,
+    Extra: SignedExtension,
+{
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        let mut inner = Vec::new();
+        match &self.signature {
+            Some(signature) => {
+                inner.push(EXTRINSIC_FORMAT_VERSION | SIGNED_BIT);
+                signature.encode_to(&mut inner);
+            }
+            None => inner.push(EXTRINSIC_FORMAT_VERSION),
+        }
+        self.function.encode_to(&mut inner);
+        inner.encode_to(dest);
+    }
+}
+
+impl<Address, Call, Signature, Extra> Decode for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+    Address: Decode,
+    Call: Decode,
+    Signature: Decode,
+    Extra: SignedExtension,
+{
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let inner = Vec::<u8>::decode(input)?;
+        let inner = &mut &inner[..];
+
+        let version = inner.read_byte()?;
+        if version & !SIGNED_BIT != EXTRINSIC_FORMAT_VERSION {
+            return Err("Invalid transaction version".into())
+        }
+        let signature =
+            if version & SIGNED_BIT != 0 { Some(Decode::decode(inner)?) } else { None };
+        let function = Call::decode_all_with_depth_limit(MAX_EXTRINSIC_DEPTH, inner)?;
+        Ok(Self { signature, function })
+    }
+}
+
+impl<Address, Call: GetDispatchInfo, Signature, Extra: SignedExtension> GetDispatchInfo
+    for UncheckedExtrinsic<Address, Call, Signature, Extra>
+{
+    fn get_dispatch_info(&self) -> DispatchInfo {
+        self.function.get_dispatch_info()
+    }
+}
+
+impl<Address, AccountId, Call, Signature, Extra, Lookup> Checkable<Lookup>
+    for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+    Call: Encode,
+    Signature: Verify,
+    Signature::Signer: IdentifyAccount<AccountId = AccountId>,
+    Extra: SignedExtension<AccountId = AccountId>,
+    Lookup: StaticLookup<Source = Address, Target = AccountId>,
+{
+    type Checked = CheckedExtrinsic<AccountId, Call, Extra>;
+
+    fn check(self) -> Result<Self::Checked, TransactionValidityError> {
+        let Some((signed, signature, extra)) = self.signature else {
+            return Ok(CheckedExtrinsic { signed: None, function: self.function })
+        };
+
+        let signed = Lookup::lookup(signed).map_err(|_| UnknownTransaction::CannotLookup)?;
+        let payload = SignedPayload::new(self.function, extra)?;
+        if !payload.using_encoded(|payload| signature.verify(payload, &signed)) {
+            return Err(InvalidTransaction::BadProof.into())
+        }
+        let (function, extra) = payload.deconstruct();
+        Ok(CheckedExtrinsic { signed: Some((signed, extra)), function })
+    }
+}
+
+/// What the signer of an extrinsic signs: the call, the extensions and what they add.
+///
+/// Payloads over 256 bytes are hashed with `blake2_256` before being signed.
+pub struct SignedPayload<Call, Extra: SignedExtension>((Call, Extra, Extra::AdditionalSigned));
+
+impl<Call: Encode, Extra: SignedExtension> SignedPayload<Call, Extra> {
+    pub fn new(call: Call, extra: Extra) -> Result<Self, TransactionValidityError> {
+        let additional_signed = extra.additional_signed()?;
+        Ok(Self((call, extra, additional_signed)))
+    }
+
+    /// The payload with what the extensions add known beforehand, e.g. to sign out of the
+    /// runtime.
+    pub fn from_raw(call: Call, extra: Extra, additional_signed: Extra::AdditionalSigned) -> Self {
+        Self((call, extra, additional_signed))
+    }
+
+    /// The call and extensions of the payload.
+    pub fn deconstruct(self) -> (Call, Extra) {
+        let (call, extra, _) = self.0;
+        (call, extra)
+    }
+}
+
+impl<Call: Encode, Extra: SignedExtension> Encode for SignedPayload<Call, Extra> {
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        let encoded = self.0.encode();
+        if encoded.len() > 256 {
+            dest.write(&blake2_256(&encoded));
+        } else {
+            dest.write(&encoded);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        codec::Decode, testing::TestSignature, traits::IdentityLookup,
+        transaction_validity::TransactionValidityError,
+    };
+
+    /// Signs the number of the chain on top of itself.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    struct ChainId(u8);
+
+    impl SignedExtension for ChainId {
+        const IDENTIFIER: &'static str = "ChainId";
+
+        type AccountId = u64;
+        type Call = Vec<u8>;
+        type AdditionalSigned = u32;
+        type Pre = ();
+
+        fn additional_signed(&self) -> Result<u32, TransactionValidityError> {
+            Ok(42)
+        }
+
+        fn pre_dispatch(
+            self,
+            _who: &u64,
+            _call: &Vec<u8>,
+            _info: &DispatchInfo,
+            _len: usize,
+        ) -> Result<(), TransactionValidityError> {
+            Ok(())
+        }
+    }
+
+    type Extrinsic = UncheckedExtrinsic<u64, Vec<u8>, TestSignature<u64>, ChainId>;
+
+    fn signed(who: u64, call: Vec<u8>, extra: ChainId) -> Extrinsic {
+        let payload = SignedPayload::new(call.clone(), extra.clone()).unwrap().encode();
+        Extrinsic::new_signed(call, who, TestSignature(who, payload), extra)
+    }
+
+    fn check(
+        xt: Extrinsic,
+    ) -> Result<CheckedExtrinsic<u64, Vec<u8>, ChainId>, TransactionValidityError> {
+        <Extrinsic as Checkable<IdentityLookup<u64>>>::check(xt)
+    }
+
+    #[test]
+    fn extrinsics_encode_with_their_version() {
+        let unsigned = Extrinsic::new_unsigned(vec![1, 2]);
+        assert_eq!(unsigned.encode(), vec![4 << 2, 4, 2 << 2, 1, 2]);
+        assert_eq!(Extrinsic::decode(&mut &unsigned.encode()[..]), Ok(unsigned));
+
+        let signed = signed(1, vec![1, 2], ChainId(3));
+        let encoded = signed.encode();
+        assert_eq!(encoded[1], 4 | 0b1000_0000);
+        assert_eq!(Extrinsic::decode(&mut &encoded[..]), Ok(signed));
+
+        let other_version = [4 << 2, 5, 2 << 2, 1, 2];
+        assert!(Extrinsic::decode(&mut &other_version[..]).is_err());
+    }
+
+    /// A call which may wrap another, as a batch does.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    enum Nested {
+        Leaf,
+        Node(Box<Nested>),
+    }
+
+    #[test]
+    fn over_nested_calls_are_refused() {
+        type NestedExtrinsic = UncheckedExtrinsic<u64, Nested, TestSignature<u64>, ChainId>;
+        let nest = |levels| {
+            let mut call = Nested::Leaf;
+            for _ in 0..levels {
+                call = Nested::Node(Box::new(call));
+            }
+            NestedExtrinsic::new_unsigned(call).encode()
+        };
+
+        // Every level is the derived `Nested` plus the `Box` around it.
+        let xt = nest(100);
+        assert_eq!(NestedExtrinsic::decode(&mut &xt[..]).unwrap().encode(), xt);
+        let err = NestedExtrinsic::decode(&mut &nest(200)[..]).unwrap_err();
+        assert!(err.to_string().contains("Maximum recursion depth reached when decoding"));
+    }
+
+    #[test]
+    fn signatures_are_checked() {
+        assert_eq!(
+            check(signed(1, vec![1], ChainId(3))),
+            Ok(CheckedExtrinsic { signed: Some((1, ChainId(3))), function: vec![1] })
+        );
+
+        // Signed by another account, or for another call.
+        let mut xt = signed(1, vec![1], ChainId(3));
+        xt.signature.as_mut().unwrap().0 = 2;
+        assert_eq!(
+            check(xt),
+            Err(InvalidTransaction::BadProof.into())
+        );
+        let mut xt = signed(1, vec![1], ChainId(3));
+        xt.function = vec![2];
+        assert_eq!(
+            check(xt),
+            Err(InvalidTransaction::BadProof.into())
+        );
+
+        let unsigned = Extrinsic::new_unsigned(vec![1]);
+        assert_eq!(
+            check(unsigned),
+            Ok(CheckedExtrinsic { signed: None, function: vec![1] })
+        );
+    }
+
+    #[test]
+    fn long_payloads_are_hashed() {
+        let payload = SignedPayload::new(vec![0u8; 300], ChainId(3)).unwrap();
+        assert_eq!(payload.encode(), blake2_256(&(vec![0u8; 300], ChainId(3), 42u32).encode()));
+        let payload = SignedPayload::new(vec![0u8; 3], ChainId(3)).unwrap();
+        assert_eq!(payload.encode(), (vec![0u8; 3], ChainId(3), 42u32).encode());
+    }
+}
@@ -0,0 +1,140 @@
+//! Hashers turning the encoded key of a storage map into a storage key.
+
+use crate::{
+    codec::MaxEncodedLen,
+    metadata,
+    hashing::{blake2_128, blake2_256, twox_128, twox_64},
+};
+
+/// Hasher used to build the keys of storage maps.
+pub trait StorageHasher: 'static {
+    type Output: AsRef<[u8]>;
+
+    /// How the hasher is described in the metadata.
+    const METADATA: metadata::StorageHasher;
+
+    fn hash(x: &[u8]) -> Self::Output;
+
+    /// The maximum length of the output for a key of type `K`.
+    fn max_len<K: MaxEncodedLen>() -> usize;
+}
+
+/// A hasher that keeps the original key at the end of its output, so keys can be iterated.
+pub trait ReversibleStorageHasher: StorageHasher {
+    /// The encoded key, given the output of [`StorageHasher::hash`].
+    fn reverse(x: &[u8]) -> &[u8];
+}
+
+/// Use the encoded key as is. Only for keys an attacker can not choose.
+pub struct Identity;
+
+impl StorageHasher for Identity {
+    type Output = Vec<u8>;
+    const METADATA: metadata::StorageHasher = metadata::StorageHasher::Identity;
+
+    fn hash(x: &[u8]) -> Vec<u8> {
+        x.to_vec()
+    }
+
+    fn max_len<K: MaxEncodedLen>() -> usize {
+        K::max_encoded_len()
+    }
+}
+
+impl ReversibleStorageHasher for Identity {
+    fn reverse(x: &[u8]) -> &[u8] {
+        x
+    }
+}
+
+/// `twox_64` of the key followed by the key. Only for keys an attacker can not choose.
+pub struct Twox64Concat;
+
+impl StorageHasher for Twox64Concat {
+    type Output = Vec<u8>;
+    const METADATA: metadata::StorageHasher = metadata::StorageHasher::Twox64Concat;
+
+    fn hash(x: &[u8]) -> Vec<u8> {
+        twox_64(x).iter().chain(x).copied().collect()
+    }
+
+    fn max_len<K: MaxEncodedLen>() -> usize {
+        K::max_encoded_len().saturating_add(8)
+    }
+}
+
+impl ReversibleStorageHasher for Twox64Concat {
+    fn reverse(x: &[u8]) -> &[u8] {
+        &x[8..]
+    }
+}
+
+/// `blake2_128` of the key followed by the key. The safe default for maps.
+pub struct Blake2_128Concat;
+
+impl StorageHasher for Blake2_128Concat {
+    type Output = Vec<u8>;
+    const METADATA: metadata::StorageHasher = metadata::StorageHasher::Blake2_128Concat;
+
+    fn hash(x: &[u8]) -> Vec<u8> {
+        blake2_128(x).iter().chain(x).copied().collect()
+    }
+
+    fn max_len<K: MaxEncodedLen>() -> usize {
+        K::max_encoded_len().saturating_add(16)
+    }
+}
+
+impl ReversibleStorageHasher for Blake2_128Concat {
+    fn reverse(x: &[u8]) -> &[u8] {
+        &x[16..]
+    }
+}
+
+/// `blake2_128` of the key.
+pub struct Blake2_128;
+
+impl StorageHasher for Blake2_128 {
+    type Output = [u8; 16];
+    const METADATA: metadata::StorageHasher = metadata::StorageHasher::Blake2_128;
+
+    fn hash(x: &[u8]) -> [u8; 16] {
+        blake2_128(x)
+    }
+
+    fn max_len<K: MaxEncodedLen>() -> usize {
+        16
+    }
+}
+
+/// `blake2_256` of the key.
+pub struct Blake2_256;
+
+impl StorageHasher for Blake2_256 {
+    type Output = [u8; 32];
+    const METADATA: metadata::StorageHasher = metadata::StorageHasher::Blake2_256;
+
+    fn hash(x: &[u8]) -> [u8; 32] {
+        blake2_256(x)
+    }
+
+    fn max_len<K: MaxEncodedLen>() -> usize {
+        32
+    }
+}
+
+/// `twox_128` of the key.
+pub struct Twox128;
+
+impl StorageHasher for Twox128 {
+    type Output = [u8; 16];
+    const METADATA: metadata::StorageHasher = metadata::StorageHasher::Twox128;
+
+    fn hash(x: &[u8]) -> [u8; 16] {
+        twox_128(x)
+    }
+
+    fn max_len<K: MaxEncodedLen>() -> usize {
+        16
+    }
+}
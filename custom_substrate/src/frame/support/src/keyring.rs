@@ -0,0 +1,156 @@
+//! Well-known dev accounts, to sign extrinsics with in tests and on dev chains.
+//!
+//! The ed25519 key of each account is generated from the seed `blake2_256("//<Name>")`, e.g.
+//! `blake2_256("//Alice")`. Unlike Substrate's, the keys don't come from a secret phrase, so
+//! they are not the same.
+
+use crate::{
+    crypto::{ed25519, AccountId32, Pair},
+    hashing::blake2_256,
+    multi_signature::MultiSigner,
+};
+
+/// A dev account.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Keyring {
+    Alice,
+    Bob,
+    Charlie,
+    Dave,
+    Eve,
+    Ferdie,
+    One,
+    Two,
+}
+
+impl Keyring {
+    /// Every dev account, in order.
+    pub fn iter() -> impl Iterator<Item = Keyring> {
+        use Keyring::*;
+        [Alice, Bob, Charlie, Dave, Eve, Ferdie, One, Two].into_iter()
+    }
+
+    /// What the seed of the account is derived from, e.g. `//Alice`.
+    pub fn to_seed(self) -> String {
+        format!("//{self:?}")
+    }
+
+    pub fn pair(self) -> ed25519::Pair {
+        ed25519::Pair::from_seed(&blake2_256(self.to_seed().as_bytes()))
+    }
+
+    pub fn public(self) -> ed25519::Public {
+        self.pair().public()
+    }
+
+    pub fn to_account_id(self) -> AccountId32 {
+        self.public().into()
+    }
+
+    pub fn sign(self, msg: &[u8]) -> ed25519::Signature {
+        self.pair().sign(msg)
+    }
+
+    /// The dev account of `public`, if it is one.
+    pub fn from_public(public: &ed25519::Public) -> Option<Keyring> {
+        Self::iter().find(|keyring| keyring.public() == *public)
+    }
+
+    /// The dev account of `who`, if it is one.
+    pub fn from_account_id(who: &AccountId32) -> Option<Keyring> {
+        Self::iter().find(|keyring| keyring.to_account_id() == *who)
+    }
+}
+
+impl From<Keyring> for AccountId32 {
+    fn from(keyring: Keyring) -> Self {
+        keyring.to_account_id()
+    }
+}
+
+impl From<Keyring> for MultiSigner {
+    fn from(keyring: Keyring) -> Self {
+        keyring.public().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        codec::{Decode, Encode},
+        dispatch::DispatchInfo,
+        generic::{CheckedExtrinsic, SignedPayload, UncheckedExtrinsic},
+        multi_signature::MultiSignature,
+        traits::{Checkable, IdentityLookup, SignedExtension, Verify},
+        transaction_validity::{InvalidTransaction, TransactionValidityError},
+    };
+
+    #[test]
+    fn dev_accounts_are_deterministic_and_distinct() {
+        assert_eq!(Keyring::Alice.to_seed(), "//Alice");
+        assert_eq!(Keyring::Alice.public(), Keyring::Alice.pair().public());
+        let accounts: Vec<_> = Keyring::iter().map(Keyring::to_account_id).collect();
+        for (i, who) in accounts.iter().enumerate() {
+            assert!(!accounts[..i].contains(who));
+        }
+        assert_eq!(Keyring::from_account_id(&accounts[1]), Some(Keyring::Bob));
+        assert_eq!(Keyring::from_public(&ed25519::Public([0; 32])), None);
+
+        let signature = Keyring::Alice.sign(b"hello");
+        assert!(signature.verify(b"hello", &Keyring::Alice.public()));
+        assert!(!signature.verify(b"hello", &Keyring::Bob.public()));
+    }
+
+    /// Adds nothing to the call.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    struct NoExtra;
+
+    impl SignedExtension for NoExtra {
+        const IDENTIFIER: &'static str = "NoExtra";
+
+        type AccountId = AccountId32;
+        type Call = Vec<u8>;
+        type AdditionalSigned = ();
+        type Pre = ();
+
+        fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+            Ok(())
+        }
+
+        fn pre_dispatch(
+            self,
+            _who: &AccountId32,
+            _call: &Vec<u8>,
+            _info: &DispatchInfo,
+            _len: usize,
+        ) -> Result<(), TransactionValidityError> {
+            Ok(())
+        }
+    }
+
+    type Extrinsic = UncheckedExtrinsic<AccountId32, Vec<u8>, MultiSignature, NoExtra>;
+
+    #[test]
+    fn extrinsics_signed_by_dev_accounts_are_checked() {
+        let payload = SignedPayload::new(vec![1u8], NoExtra).unwrap().encode();
+        let signature = MultiSignature::from(Keyring::Alice.sign(&payload));
+        let xt = Extrinsic::new_signed(vec![1], Keyring::Alice.into(), signature, NoExtra);
+        let check = |xt: Extrinsic| <Extrinsic as Checkable<IdentityLookup<_>>>::check(xt);
+        assert_eq!(
+            check(xt.clone()),
+            Ok(CheckedExtrinsic {
+                signed: Some((Keyring::Alice.into(), NoExtra)),
+                function: vec![1],
+            })
+        );
+
+        // Bob didn't sign it, and Alice didn't sign another call.
+        let mut forged = xt.clone();
+        forged.signature.as_mut().unwrap().0 = Keyring::Bob.into();
+        assert_eq!(check(forged), Err(InvalidTransaction::BadProof.into()));
+        let mut tampered = xt;
+        tampered.function = vec![2];
+        assert_eq!(check(tampered), Err(InvalidTransaction::BadProof.into()));
+    }
+}
@@ -7,9 +7,37 @@ mod tests {
     }
 }
 
+// Lets the code generated by our own macros refer to `::frame_support` inside this crate too.
+extern crate self as frame_support;
+
+pub mod arithmetic;
+pub mod codec;
+pub mod crypto;
+pub mod dispatch;
+pub mod generic;
+pub mod hash;
+pub mod hashing;
+pub mod keyring;
+pub mod limits;
+pub mod metadata;
+pub mod multi_signature;
+pub mod multiaddress;
+pub mod scale_info;
+pub mod storage;
+pub mod testing;
+pub mod traits;
+pub mod transaction_validity;
+pub mod version;
+pub mod weights;
+
 pub use frame_support_procedural::{
     construct_runtime
 };
+pub use hash::{
+    Blake2_128, Blake2_128Concat, Blake2_256, Identity, ReversibleStorageHasher, StorageHasher,
+    Twox128, Twox64Concat,
+};
+pub use storage::{bounded_vec::BoundedVec, weak_bounded_vec::WeakBoundedVec};
 
 pub mod pallet_prelude {
 
@@ -19,5 +47,30 @@ pub mod pallet_prelude {
         hash::Hash,
         default::Default,
     };
-    pub use num::{Zero, CheckedAdd, CheckedSub};
+    pub use num::{Zero, One, CheckedAdd, CheckedSub, Saturating};
+    pub use crate::arithmetic::{
+        traits::{
+            AtLeast32Bit, AtLeast32BitUnsigned, BaseArithmetic, SaturatedConversion,
+            UniqueSaturatedFrom, UniqueSaturatedInto,
+        },
+        FixedI64, FixedPointNumber, FixedPointOperand, FixedU128, PerThing, Perbill, Percent,
+        Permill, Rounding,
+    };
+    pub use crate::codec::{Decode, Encode, MaxEncodedLen};
+    pub use crate::dispatch::{
+        DispatchClass, DispatchError, DispatchInfo, DispatchResult, DispatchResultWithPostInfo,
+        GetDispatchInfo, Parameter, Pays, PostDispatchInfo, WithPostDispatchInfo,
+    };
+    pub use crate::scale_info::TypeInfo;
+    pub use crate::storage::{
+        bounded_vec::BoundedVec,
+        types::{OptionQuery, StorageMap, StorageValue, ValueQuery},
+        weak_bounded_vec::WeakBoundedVec,
+    };
+    pub use crate::traits::{
+        ConstU32, ConstU64, Contains, EnsureOrigin, Get, GetDefault, Hooks, IsType, Member,
+        PartialStorageInfoTrait, StorageInfo, StorageInfoTrait, UnfilteredDispatchable,
+    };
+    pub use crate::weights::Weight;
+    pub use crate::{Blake2_128Concat, Identity, Twox64Concat};
 }
\ No newline at end of file
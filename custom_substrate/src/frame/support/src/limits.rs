@@ -0,0 +1,399 @@
+//! How much weight a block can hold, per [`DispatchClass`].
+//!
+//! Every extrinsic pays `base_extrinsic` of its class on top of its own weight. A class can be
+//! limited on its own (`max_total`) and may keep filling the block with `reserved` weight once
+//! the block is full, which is how operational calls still get in a block full of normal ones.
+//!
+//! The encoded length of the extrinsics of a block is limited per class too, by [`BlockLength`].
+
+use crate::{
+    codec::{Decode, Encode},
+    dispatch::{DispatchClass, DispatchInfo, PerDispatchClass},
+    scale_info::TypeInfo,
+    weights::{constants, Weight},
+};
+
+/// The error an extrinsic is refused with when the block has no room left for it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExhaustsResources;
+
+/// The limits of one [`DispatchClass`].
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct WeightsPerClass {
+    /// Paid by every extrinsic of the class on top of its own weight.
+    pub base_extrinsic: Weight,
+    /// The most a single extrinsic can weigh, `None` for no limit.
+    pub max_extrinsic: Option<Weight>,
+    /// The most all extrinsics of the class can weigh together, `None` for no limit.
+    pub max_total: Option<Weight>,
+    /// The most the class can weigh in total once the block is over `max_block`, `None` for
+    /// no limit.
+    pub reserved: Option<Weight>,
+}
+
+/// The weight limits of a block.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BlockWeights {
+    /// The weight of an empty block.
+    pub base_block: Weight,
+    /// The most a block can weigh.
+    pub max_block: Weight,
+    pub per_class: PerDispatchClass<WeightsPerClass>,
+}
+
+impl Default for BlockWeights {
+    fn default() -> Self {
+        Self::simple_max(Weight::from_parts(
+            constants::WEIGHT_REF_TIME_PER_SECOND * 2,
+            5 * constants::WEIGHT_PROOF_SIZE_PER_MB,
+        ))
+    }
+}
+
+/// The reason [`BlockWeightsBuilder::build`] refused the limits.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ValidationErrors {
+    pub errors: Vec<String>,
+}
+
+impl BlockWeights {
+    pub fn get(&self, class: DispatchClass) -> &WeightsPerClass {
+        self.per_class.get(class)
+    }
+
+    /// Limits where every class can use the whole `block_weight`.
+    pub fn simple_max(block_weight: Weight) -> Self {
+        Self::builder()
+            .base_block(Weight::zero())
+            .for_class(DispatchClass::all(), |weights| {
+                weights.base_extrinsic = Weight::zero();
+            })
+            .for_class(DispatchClass::non_mandatory(), |weights| {
+                weights.max_total = Some(block_weight);
+            })
+            .build()
+            .expect("we only specify `max_total` and leave base values as defaults; qed")
+    }
+
+    pub fn builder() -> BlockWeightsBuilder {
+        BlockWeightsBuilder {
+            weights: BlockWeights {
+                base_block: constants::BLOCK_EXECUTION_WEIGHT,
+                max_block: Weight::zero(),
+                per_class: PerDispatchClass::new(|class| {
+                    let initial =
+                        if class == DispatchClass::Mandatory { None } else { Some(Weight::zero()) };
+                    WeightsPerClass {
+                        base_extrinsic: constants::EXTRINSIC_BASE_WEIGHT,
+                        max_extrinsic: None,
+                        max_total: initial,
+                        reserved: initial,
+                    }
+                }),
+            },
+            init_cost: None,
+        }
+    }
+
+    /// Check the limits make sense: every class can fit at least one extrinsic in the block.
+    pub fn validate(self) -> Result<Self, ValidationErrors> {
+        let mut errors = Vec::new();
+        let mut error_assert = |cond: bool, msg: String| {
+            if !cond {
+                errors.push(msg);
+            }
+        };
+
+        let mut max_total = Weight::zero();
+        for class in DispatchClass::all() {
+            let weights = self.per_class.get(*class);
+            let class_max = weights.max_total.unwrap_or(self.max_block);
+            let max_extrinsic = weights.max_extrinsic.unwrap_or(Weight::zero());
+            max_total = max_total.max(class_max);
+
+            error_assert(
+                !self.base_block.any_gt(class_max),
+                format!(
+                    "[{:?}] {:?} (base_block) > {:?} (max_total)",
+                    class, self.base_block, class_max,
+                ),
+            );
+            error_assert(
+                !weights.base_extrinsic.any_gt(max_extrinsic) || weights.max_extrinsic.is_none(),
+                format!(
+                    "[{:?}] {:?} (base_extrinsic) > {:?} (max_extrinsic)",
+                    class, weights.base_extrinsic, max_extrinsic,
+                ),
+            );
+            error_assert(
+                !max_extrinsic.any_gt(class_max.saturating_sub(self.base_block)),
+                format!(
+                    "[{:?}] {:?} (max_extrinsic) > {:?} (max_total - base_block)",
+                    class, max_extrinsic, class_max.saturating_sub(self.base_block),
+                ),
+            );
+            error_assert(
+                !class_max.any_gt(self.max_block),
+                format!(
+                    "[{:?}] {:?} (max_total) > {:?} (max_block)",
+                    class, class_max, self.max_block,
+                ),
+            );
+            if let Some(reserved) = weights.reserved {
+                error_assert(
+                    weights.max_total.is_none() ||
+                        !weights.base_extrinsic.any_gt(class_max.saturating_add(reserved)),
+                    format!(
+                        "[{:?}] {:?} (base_extrinsic) > {:?} (max_total + reserved)",
+                        class, weights.base_extrinsic, class_max.saturating_add(reserved),
+                    ),
+                );
+            }
+        }
+        error_assert(
+            !max_total.any_gt(self.max_block),
+            format!("{:?} (max of max_total) > {:?} (max_block)", max_total, self.max_block),
+        );
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(ValidationErrors { errors })
+        }
+    }
+
+    /// Refuse an extrinsic heavier than the maximum of its class, whatever the block holds.
+    pub fn check_extrinsic_weight(&self, info: &DispatchInfo) -> Result<(), ExhaustsResources> {
+        match self.get(info.class).max_extrinsic {
+            Some(max) if info.weight.any_gt(max) => Err(ExhaustsResources),
+            _ => Ok(()),
+        }
+    }
+
+    /// The weight consumed by the block once the extrinsic described by `info` is added to
+    /// `consumed`, refusing it if that exceeds the limits of its class.
+    pub fn calculate_consumed_weight(
+        &self,
+        consumed: &PerDispatchClass<Weight>,
+        info: &DispatchInfo,
+    ) -> Result<PerDispatchClass<Weight>, ExhaustsResources> {
+        let limits = self.get(info.class);
+        let extrinsic_weight = info.weight.saturating_add(limits.base_extrinsic);
+        let mut all_weight = *consumed;
+
+        // An unlimited class saturates instead of failing.
+        if limits.max_total.is_none() && limits.reserved.is_none() {
+            all_weight.add(extrinsic_weight, info.class);
+        } else {
+            all_weight
+                .checked_add(extrinsic_weight, info.class)
+                .map_err(|_| ExhaustsResources)?;
+        }
+
+        let per_class = *all_weight.get(info.class);
+        if let Some(max) = limits.max_total {
+            if per_class.any_gt(max) {
+                return Err(ExhaustsResources)
+            }
+        }
+
+        // Past the block limit, only the reserved weight of the class is left.
+        if all_weight.total().any_gt(self.max_block) {
+            if let Some(reserved) = limits.reserved {
+                if per_class.any_gt(reserved) {
+                    return Err(ExhaustsResources)
+                }
+            }
+        }
+
+        Ok(all_weight)
+    }
+}
+
+/// Builds [`BlockWeights`], see [`BlockWeights::builder`].
+pub struct BlockWeightsBuilder {
+    weights: BlockWeights,
+    init_cost: Option<Weight>,
+}
+
+impl BlockWeightsBuilder {
+    pub fn base_block(mut self, base_block: Weight) -> Self {
+        self.weights.base_block = base_block;
+        self
+    }
+
+    /// The average weight of initializing a block, which the non mandatory extrinsics can
+    /// not use.
+    pub fn avg_block_initialization(mut self, init_cost: Weight) -> Self {
+        self.init_cost = Some(init_cost);
+        self
+    }
+
+    /// Change the limits of every class in `classes`.
+    pub fn for_class(
+        mut self,
+        classes: &[DispatchClass],
+        action: impl Fn(&mut WeightsPerClass),
+    ) -> Self {
+        for class in classes {
+            action(self.weights.per_class.get_mut(*class));
+        }
+        self
+    }
+
+    /// Complete the limits: `max_block` defaults to the largest `max_total`, and the
+    /// `max_extrinsic` of a class to what is left of its `max_total` after the block
+    /// initialization.
+    pub fn build(self) -> Result<BlockWeights, ValidationErrors> {
+        let Self { mut weights, init_cost } = self;
+
+        for class in DispatchClass::all() {
+            weights.max_block = match weights.per_class.get(*class).max_total {
+                Some(max) => max.max(weights.max_block),
+                _ => weights.max_block,
+            };
+        }
+        for class in DispatchClass::all() {
+            let per_class = weights.per_class.get_mut(*class);
+            if per_class.max_extrinsic.is_none() && init_cost.is_some() {
+                per_class.max_extrinsic = per_class
+                    .max_total
+                    .map(|x| x.saturating_sub(init_cost.expect("checked above; qed")))
+                    .map(|x| x.saturating_sub(per_class.base_extrinsic));
+            }
+        }
+
+        weights.validate()
+    }
+}
+
+/// The most the encoded extrinsics of a block can take, per [`DispatchClass`].
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BlockLength {
+    pub max: PerDispatchClass<u32>,
+}
+
+impl Default for BlockLength {
+    fn default() -> Self {
+        Self::max_with_normal_ratio(5 * 1024 * 1024, 75)
+    }
+}
+
+impl BlockLength {
+    /// Every class can use up to `max` bytes.
+    pub fn max(max: u32) -> Self {
+        Self { max: PerDispatchClass::new(|_| max) }
+    }
+
+    /// Normal extrinsics can only use `normal_percent` of the `max` bytes, the other classes
+    /// all of them.
+    pub fn max_with_normal_ratio(max: u32, normal_percent: u8) -> Self {
+        let normal = (u64::from(max) * u64::from(normal_percent.min(100)) / 100) as u32;
+        Self {
+            max: PerDispatchClass::new(|class| match class {
+                DispatchClass::Normal => normal,
+                _ => max,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatch::Pays;
+
+    fn info(ref_time: u64, class: DispatchClass) -> DispatchInfo {
+        DispatchInfo { weight: Weight::from_parts(ref_time, 0), class, pays_fee: Pays::Yes }
+    }
+
+    /// A block of 1000, 750 for normal extrinsics, while operational ones can use up to 300 even
+    /// in a full block.
+    fn block_weights() -> BlockWeights {
+        BlockWeights::builder()
+            .base_block(Weight::from_parts(10, 0))
+            .for_class(DispatchClass::all(), |weights| {
+                weights.base_extrinsic = Weight::from_parts(5, 0);
+            })
+            .for_class(&[DispatchClass::Normal], |weights| {
+                weights.max_total = Some(Weight::from_parts(750, u64::MAX));
+            })
+            .for_class(&[DispatchClass::Operational], |weights| {
+                weights.max_total = Some(Weight::from_parts(1000, u64::MAX));
+                weights.reserved = Some(Weight::from_parts(300, u64::MAX));
+            })
+            .avg_block_initialization(Weight::from_parts(50, 0))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn builder_completes_limits() {
+        let weights = block_weights();
+        assert_eq!(weights.max_block, Weight::from_parts(1000, u64::MAX));
+        assert_eq!(
+            weights.get(DispatchClass::Normal).max_extrinsic,
+            Some(Weight::from_parts(695, u64::MAX))
+        );
+        assert_eq!(weights.get(DispatchClass::Mandatory).max_extrinsic, None);
+    }
+
+    #[test]
+    fn invalid_limits_are_refused() {
+        let res = BlockWeights::builder()
+            .base_block(Weight::from_parts(100, 0))
+            .for_class(DispatchClass::non_mandatory(), |weights| {
+                weights.max_total = Some(Weight::from_parts(50, 0));
+            })
+            .build();
+        assert!(res.unwrap_err().errors[0].contains("(base_block) > "));
+    }
+
+    #[test]
+    fn overweight_extrinsics_are_refused() {
+        let weights = block_weights();
+        assert_eq!(weights.check_extrinsic_weight(&info(695, DispatchClass::Normal)), Ok(()));
+        assert_eq!(
+            weights.check_extrinsic_weight(&info(696, DispatchClass::Normal)),
+            Err(ExhaustsResources)
+        );
+        // Mandatory extrinsics are never refused.
+        let mandatory = info(u64::MAX, DispatchClass::Mandatory);
+        assert_eq!(weights.check_extrinsic_weight(&mandatory), Ok(()));
+    }
+
+    #[test]
+    fn full_blocks_refuse_extrinsics() {
+        let weights = block_weights();
+        let mut consumed = PerDispatchClass::default();
+        consumed.add(weights.base_block, DispatchClass::Mandatory);
+
+        // Normal extrinsics are limited to 750 in total, base weight included.
+        consumed = weights
+            .calculate_consumed_weight(&consumed, &info(700, DispatchClass::Normal))
+            .unwrap();
+        assert_eq!(*consumed.get(DispatchClass::Normal), Weight::from_parts(705, 0));
+        assert_eq!(
+            weights.calculate_consumed_weight(&consumed, &info(41, DispatchClass::Normal)),
+            Err(ExhaustsResources)
+        );
+
+        // Operational extrinsics fill the block, then only their reserved weight is left.
+        consumed = weights
+            .calculate_consumed_weight(&consumed, &info(280, DispatchClass::Operational))
+            .unwrap();
+        assert_eq!(consumed.total(), weights.max_block.set_proof_size(0));
+        consumed = weights
+            .calculate_consumed_weight(&consumed, &info(5, DispatchClass::Operational))
+            .unwrap();
+        assert!(consumed.total().any_gt(weights.max_block.set_proof_size(0)));
+        assert_eq!(
+            weights.calculate_consumed_weight(&consumed, &info(1, DispatchClass::Operational)),
+            Err(ExhaustsResources)
+        );
+
+        // Mandatory extrinsics always fit.
+        let mandatory = info(10_000, DispatchClass::Mandatory);
+        assert!(weights.calculate_consumed_weight(&consumed, &mandatory).is_ok());
+    }
+}
@@ -0,0 +1,266 @@
+//! Description of a runtime, so clients can build its calls and decode its storage and events.
+//!
+//! The runtime describes its pallets in the [`MetaForm`]; [`RuntimeMetadataPrefixed::new`]
+//! registers every type they refer to and gives the encodable [`PortableForm`].
+
+use crate::{
+    codec::Encode,
+    scale_info::{
+        Form, IntoPortable, MetaForm, MetaType, PortableForm, PortableRegistry, Registry,
+    },
+};
+
+/// The prefix of the encoded metadata, `meta` in little endian.
+pub const META_RESERVED: u32 = 0x6174_656d;
+
+/// The encoded form of the metadata, [`META_RESERVED`] followed by the metadata.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct RuntimeMetadataPrefixed(pub u32, pub RuntimeMetadata);
+
+impl RuntimeMetadataPrefixed {
+    pub fn new(pallets: Vec<PalletMetadata>) -> Self {
+        let mut registry = Registry::new();
+        let pallets = pallets.into_portable(&mut registry);
+        Self(META_RESERVED, RuntimeMetadata { types: registry.into(), pallets })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct RuntimeMetadata {
+    /// Every type the pallets refer to.
+    pub types: PortableRegistry,
+    pub pallets: Vec<PalletMetadata<PortableForm>>,
+}
+
+impl RuntimeMetadata {
+    pub fn pallet(&self, name: &str) -> Option<&PalletMetadata<PortableForm>> {
+        self.pallets.iter().find(|pallet| pallet.name == name)
+    }
+}
+
+impl<F: Form> PalletMetadata<F> {
+    pub fn constant(&self, name: &str) -> Option<&PalletConstantMetadata<F>> {
+        self.constants.iter().find(|constant| constant.name == name)
+    }
+}
+
+/// A pallet as declared in `construct_runtime!`.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct PalletMetadata<F: Form = MetaForm> {
+    pub name: &'static str,
+    pub storage: Option<PalletStorageMetadata<F>>,
+    pub calls: Option<PalletCallMetadata<F>>,
+    pub event: Option<PalletEventMetadata<F>>,
+    pub constants: Vec<PalletConstantMetadata<F>>,
+    /// The byte identifying the pallet in calls and events.
+    pub index: u8,
+}
+
+/// A constant of a pallet, e.g. a `Get` type of its configuration.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct PalletConstantMetadata<F: Form = MetaForm> {
+    pub name: &'static str,
+    pub ty: F::Type,
+    /// The encoded value.
+    pub value: Vec<u8>,
+    pub docs: Vec<&'static str>,
+}
+
+/// Implement `constants_metadata` for a pallet from the list of its constants, documented with
+/// their doc comments. `construct_runtime!` reads it for every pallet with the `Pallet` part.
+///
+/// ```
+/// use frame_support::pallet_prelude::*;
+///
+/// pub trait Config {
+///     type MaxGreetings: Get<u32>;
+/// }
+/// pub struct Pallet<T>(PhantomData<T>);
+///
+/// frame_support::impl_constant_metadata!(Pallet<T: Config> {
+///     /// How many greetings are kept.
+///     MaxGreetings: u32 = T::MaxGreetings::get(),
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_constant_metadata {
+    (
+        $pallet:ident<$t:ident: $bound:path> {
+            $( $( #[doc = $doc:literal] )* $name:ident: $ty:ty = $value:expr ),* $(,)?
+        }
+    ) => {
+        impl<$t: $bound> $pallet<$t> {
+            pub fn constants_metadata() -> Vec<$crate::metadata::PalletConstantMetadata> {
+                vec![ $(
+                    $crate::metadata::PalletConstantMetadata {
+                        name: stringify!($name),
+                        ty: $crate::scale_info::meta_type::<$ty>(),
+                        value: $crate::codec::Encode::encode(&{ let value: $ty = $value; value }),
+                        docs: vec![ $( $doc.trim() ),* ],
+                    }
+                ),* ]
+            }
+        }
+    };
+}
+
+/// The storage items of a pallet.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct PalletStorageMetadata<F: Form = MetaForm> {
+    /// The prefix of all the items, hashed to build their keys.
+    pub prefix: &'static str,
+    pub entries: Vec<StorageEntryMetadata<F>>,
+}
+
+impl<F: Form> PalletStorageMetadata<F> {
+    pub fn entry(&self, name: &str) -> Option<&StorageEntryMetadata<F>> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct StorageEntryMetadata<F: Form = MetaForm> {
+    pub name: &'static str,
+    pub modifier: StorageEntryModifier,
+    pub ty: StorageEntryType<F>,
+    /// The encoded value read when there is none in storage.
+    pub default: Vec<u8>,
+    pub docs: Vec<&'static str>,
+}
+
+/// What is read from an empty storage item.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode)]
+pub enum StorageEntryModifier {
+    /// Nothing, the item is queried as an `Option`.
+    Optional,
+    /// The default value.
+    Default,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub enum StorageEntryType<F: Form = MetaForm> {
+    Plain(F::Type),
+    Map {
+        /// One hasher per key.
+        hashers: Vec<StorageHasher>,
+        key: F::Type,
+        value: F::Type,
+    },
+}
+
+/// The hashers of storage map keys, see [`crate::hash`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode)]
+pub enum StorageHasher {
+    Blake2_128,
+    Blake2_256,
+    Blake2_128Concat,
+    Twox128,
+    Twox256,
+    Twox64Concat,
+    Identity,
+}
+
+/// The type of the `Call` enum of a pallet.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct PalletCallMetadata<F: Form = MetaForm> {
+    pub ty: F::Type,
+}
+
+/// The type of the `Event` enum of a pallet.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct PalletEventMetadata<F: Form = MetaForm> {
+    pub ty: F::Type,
+}
+
+impl IntoPortable for PalletMetadata {
+    type Output = PalletMetadata<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        PalletMetadata {
+            name: self.name,
+            storage: self.storage.into_portable(registry),
+            calls: self.calls.into_portable(registry),
+            event: self.event.into_portable(registry),
+            constants: self.constants.into_portable(registry),
+            index: self.index,
+        }
+    }
+}
+
+impl IntoPortable for PalletConstantMetadata {
+    type Output = PalletConstantMetadata<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        PalletConstantMetadata {
+            name: self.name,
+            ty: self.ty.into_portable(registry),
+            value: self.value,
+            docs: self.docs,
+        }
+    }
+}
+
+impl IntoPortable for PalletStorageMetadata {
+    type Output = PalletStorageMetadata<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        PalletStorageMetadata { prefix: self.prefix, entries: self.entries.into_portable(registry) }
+    }
+}
+
+impl IntoPortable for StorageEntryMetadata {
+    type Output = StorageEntryMetadata<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        StorageEntryMetadata {
+            name: self.name,
+            modifier: self.modifier,
+            ty: self.ty.into_portable(registry),
+            default: self.default,
+            docs: self.docs,
+        }
+    }
+}
+
+impl IntoPortable for StorageEntryType {
+    type Output = StorageEntryType<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        match self {
+            StorageEntryType::Plain(ty) => StorageEntryType::Plain(ty.into_portable(registry)),
+            StorageEntryType::Map { hashers, key, value } => StorageEntryType::Map {
+                hashers,
+                key: key.into_portable(registry),
+                value: value.into_portable(registry),
+            },
+        }
+    }
+}
+
+impl IntoPortable for PalletCallMetadata {
+    type Output = PalletCallMetadata<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        PalletCallMetadata { ty: self.ty.into_portable(registry) }
+    }
+}
+
+impl IntoPortable for PalletEventMetadata {
+    type Output = PalletEventMetadata<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        PalletEventMetadata { ty: self.ty.into_portable(registry) }
+    }
+}
+
+impl From<MetaType> for PalletCallMetadata {
+    fn from(ty: MetaType) -> Self {
+        Self { ty }
+    }
+}
+
+impl From<MetaType> for PalletEventMetadata {
+    fn from(ty: MetaType) -> Self {
+        Self { ty }
+    }
+}
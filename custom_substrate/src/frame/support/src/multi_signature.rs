@@ -0,0 +1,79 @@
+//! Signatures of any of the schemes the chain accepts, and the keys which make them.
+//!
+//! Only ed25519 is implemented; the indices of the variants are the ones sr25519 and ecdsa
+//! would sit next to.
+
+use crate::{
+    codec::{Decode, Encode, MaxEncodedLen},
+    crypto::{ed25519, AccountId32},
+    scale_info::TypeInfo,
+    traits::{IdentifyAccount, Verify},
+};
+
+/// A signature of one of the accepted schemes.
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Debug)]
+pub enum MultiSignature {
+    #[codec(index = 0)]
+    Ed25519(ed25519::Signature),
+}
+
+/// A public key of one of the accepted schemes, identifying an [`AccountId32`].
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Debug)]
+pub enum MultiSigner {
+    #[codec(index = 0)]
+    Ed25519(ed25519::Public),
+}
+
+impl Verify for MultiSignature {
+    type Signer = MultiSigner;
+
+    fn verify(&self, msg: &[u8], signer: &AccountId32) -> bool {
+        match self {
+            MultiSignature::Ed25519(signature) => {
+                signature.verify(msg, &ed25519::Public(signer.0))
+            }
+        }
+    }
+}
+
+impl IdentifyAccount for MultiSigner {
+    type AccountId = AccountId32;
+
+    fn into_account(self) -> AccountId32 {
+        match self {
+            MultiSigner::Ed25519(public) => public.into(),
+        }
+    }
+}
+
+impl From<ed25519::Signature> for MultiSignature {
+    fn from(signature: ed25519::Signature) -> Self {
+        MultiSignature::Ed25519(signature)
+    }
+}
+
+impl From<ed25519::Public> for MultiSigner {
+    fn from(public: ed25519::Public) -> Self {
+        MultiSigner::Ed25519(public)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Pair;
+
+    #[test]
+    fn signatures_verify_against_the_account_of_their_signer() {
+        let pair = ed25519::Pair::from_seed(&[1; 32]);
+        let account = MultiSigner::from(pair.public()).into_account();
+        assert_eq!(account, AccountId32(pair.public().0));
+
+        let signature = MultiSignature::from(pair.sign(b"hello"));
+        assert!(signature.verify(b"hello", &account));
+        assert!(!signature.verify(b"hello", &AccountId32([2; 32])));
+        // The scheme is the first byte.
+        assert_eq!(signature.encode()[0], 0);
+        assert_eq!(signature.encode().len(), MultiSignature::max_encoded_len());
+    }
+}
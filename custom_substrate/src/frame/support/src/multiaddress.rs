@@ -0,0 +1,69 @@
+//! An address naming an account in any of the ways a call can, e.g. by its short index.
+
+use crate::{
+    codec::{Decode, Encode},
+    scale_info::TypeInfo,
+};
+
+/// An account named by its id, its index or raw bytes.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MultiAddress<AccountId, AccountIndex> {
+    /// The account id itself.
+    #[codec(index = 0)]
+    Id(AccountId),
+    /// The short index of the account, resolved by the indices pallet.
+    #[codec(index = 1)]
+    Index(#[codec(compact)] AccountIndex),
+    /// Some raw bytes, for account ids the runtime does not know the type of.
+    #[codec(index = 2)]
+    Raw(Vec<u8>),
+    /// A 32 byte address.
+    #[codec(index = 3)]
+    Address32([u8; 32]),
+}
+
+impl<AccountId, AccountIndex> From<AccountId> for MultiAddress<AccountId, AccountIndex> {
+    fn from(a: AccountId) -> Self {
+        MultiAddress::Id(a)
+    }
+}
+
+impl<AccountId: Default, AccountIndex> Default for MultiAddress<AccountId, AccountIndex> {
+    fn default() -> Self {
+        MultiAddress::Id(AccountId::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Address = MultiAddress<u64, u32>;
+
+    #[test]
+    fn indices_are_compact() {
+        assert_eq!(Address::Id(1).encode(), vec![0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Address::Index(1).encode(), vec![1, 4]);
+        assert_eq!(Address::Raw(vec![7, 8]).encode(), vec![2, 8, 7, 8]);
+        assert_eq!(Address::Address32([9; 32]).encode()[..2], [3, 9]);
+    }
+
+    #[test]
+    fn addresses_round_trip() {
+        for address in [
+            Address::Id(u64::MAX),
+            Address::Index(u32::MAX),
+            Address::Raw(vec![1, 2, 3]),
+            Address::Address32([4; 32]),
+        ] {
+            assert_eq!(Address::decode(&mut &address.encode()[..]), Ok(address));
+        }
+        assert!(Address::decode(&mut &[4][..]).is_err());
+    }
+
+    #[test]
+    fn account_ids_convert_into_addresses() {
+        assert_eq!(Address::from(5), Address::Id(5));
+        assert_eq!(Address::default(), Address::Id(0));
+    }
+}
@@ -0,0 +1,206 @@
+//! [`TypeInfo`] of the types of the standard library and of the codec.
+
+use super::{
+    meta_type, Field, Path, Type, TypeDef, TypeDefPrimitive, TypeInfo, TypeParameter, Variant,
+};
+use crate::codec::Compact;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
+
+macro_rules! impl_primitive {
+    ( $( $t:ty => $primitive:ident ),* ) => { $(
+        impl TypeInfo for $t {
+            type Identity = Self;
+
+            fn type_info() -> Type {
+                Type::anonymous(TypeDef::Primitive(TypeDefPrimitive::$primitive))
+            }
+        }
+    )* };
+}
+
+impl_primitive!(
+    bool => Bool,
+    char => Char,
+    str => Str,
+    String => Str,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    i128 => I128
+);
+
+macro_rules! impl_tuple {
+    ( $( $name:ident )* ) => {
+        impl<$( $name: TypeInfo + 'static ),*> TypeInfo for ( $( $name, )* ) {
+            type Identity = Self;
+
+            fn type_info() -> Type {
+                Type::anonymous(TypeDef::Tuple(vec![ $( meta_type::<$name>() ),* ]))
+            }
+        }
+    };
+}
+
+impl_tuple!();
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+impl_tuple!(A B C D E);
+impl_tuple!(A B C D E F);
+impl_tuple!(A B C D E F G);
+impl_tuple!(A B C D E F G H);
+impl_tuple!(A B C D E F G H J);
+impl_tuple!(A B C D E F G H J K);
+
+impl<T: TypeInfo + 'static, const N: usize> TypeInfo for [T; N] {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::anonymous(TypeDef::Array(N as u32, meta_type::<T>()))
+    }
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for [T] {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::anonymous(TypeDef::Sequence(meta_type::<T>()))
+    }
+}
+
+/// Encoded like a slice.
+impl<T: TypeInfo + 'static> TypeInfo for Vec<T> {
+    type Identity = [T];
+
+    fn type_info() -> Type {
+        <[T]>::type_info()
+    }
+}
+
+impl<T: TypeInfo + ?Sized> TypeInfo for &T {
+    type Identity = T::Identity;
+
+    fn type_info() -> Type {
+        T::type_info()
+    }
+}
+
+impl<T: TypeInfo + ?Sized> TypeInfo for Box<T> {
+    type Identity = T::Identity;
+
+    fn type_info() -> Type {
+        T::type_info()
+    }
+}
+
+/// Encoded as nothing, like the unit tuple.
+impl<T: ?Sized> TypeInfo for PhantomData<T> {
+    type Identity = ();
+
+    fn type_info() -> Type {
+        <()>::type_info()
+    }
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Option<T> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type {
+            path: Path::prelude("Option"),
+            type_params: vec![TypeParameter { name: "T", ty: Some(meta_type::<T>()) }],
+            type_def: TypeDef::Variant(vec![
+                Variant { name: "None", fields: Vec::new(), index: 0, docs: Vec::new() },
+                Variant {
+                    name: "Some",
+                    fields: vec![unnamed_field::<T>("T")],
+                    index: 1,
+                    docs: Vec::new(),
+                },
+            ]),
+            docs: Vec::new(),
+        }
+    }
+}
+
+impl<T: TypeInfo + 'static, E: TypeInfo + 'static> TypeInfo for Result<T, E> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type {
+            path: Path::prelude("Result"),
+            type_params: vec![
+                TypeParameter { name: "T", ty: Some(meta_type::<T>()) },
+                TypeParameter { name: "E", ty: Some(meta_type::<E>()) },
+            ],
+            type_def: TypeDef::Variant(vec![
+                Variant {
+                    name: "Ok",
+                    fields: vec![unnamed_field::<T>("T")],
+                    index: 0,
+                    docs: Vec::new(),
+                },
+                Variant {
+                    name: "Err",
+                    fields: vec![unnamed_field::<E>("E")],
+                    index: 1,
+                    docs: Vec::new(),
+                },
+            ]),
+            docs: Vec::new(),
+        }
+    }
+}
+
+/// Encoded as a sequence of key value tuples.
+impl<K: TypeInfo + 'static, V: TypeInfo + 'static> TypeInfo for BTreeMap<K, V> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type {
+            path: Path::prelude("BTreeMap"),
+            type_params: vec![
+                TypeParameter { name: "K", ty: Some(meta_type::<K>()) },
+                TypeParameter { name: "V", ty: Some(meta_type::<V>()) },
+            ],
+            type_def: TypeDef::Composite(vec![unnamed_field::<[(K, V)]>("[(K, V)]")]),
+            docs: Vec::new(),
+        }
+    }
+}
+
+/// Encoded as a sequence of values.
+impl<T: TypeInfo + 'static> TypeInfo for BTreeSet<T> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type {
+            path: Path::prelude("BTreeSet"),
+            type_params: vec![TypeParameter { name: "T", ty: Some(meta_type::<T>()) }],
+            type_def: TypeDef::Composite(vec![unnamed_field::<[T]>("[T]")]),
+            docs: Vec::new(),
+        }
+    }
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Compact<T> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::anonymous(TypeDef::Compact(meta_type::<T>()))
+    }
+}
+
+fn unnamed_field<T: TypeInfo + ?Sized>(type_name: &'static str) -> Field {
+    Field { name: None, ty: meta_type::<T>(), type_name: Some(type_name), docs: Vec::new() }
+}
@@ -0,0 +1,376 @@
+//! Self-describing types, so clients can encode and decode values they don't know in advance.
+//!
+//! A type describes itself with [`TypeInfo`], referring to the types it is made of through
+//! [`MetaType`]. Registering a type into a [`Registry`] registers everything it refers to and
+//! replaces those references by numeric ids, giving the encodable [`PortableForm`] of the
+//! description. Every type is registered only once.
+
+mod impls;
+mod registry;
+
+pub use frame_support_procedural::TypeInfo;
+pub use registry::{IntoPortable, PortableRegistry, PortableType, Registry};
+
+use crate::codec::{Compact, Decode, Encode, Error, Input, Output};
+use std::{any::TypeId, cmp::Ordering, fmt, hash};
+
+/// A type able to describe its structure.
+pub trait TypeInfo {
+    /// The type this is described as, so e.g. `T`, `&T` and `Box<T>` share one registry entry.
+    type Identity: ?Sized + 'static;
+
+    fn type_info() -> Type;
+}
+
+/// A reference to a type implementing [`TypeInfo`].
+#[derive(Clone, Copy)]
+pub struct MetaType {
+    fn_type_info: fn() -> Type,
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl MetaType {
+    pub fn new<T: TypeInfo + ?Sized>() -> Self {
+        Self {
+            fn_type_info: T::type_info,
+            type_id: TypeId::of::<T::Identity>(),
+            type_name: std::any::type_name::<T::Identity>(),
+        }
+    }
+
+    pub fn type_info(&self) -> Type {
+        (self.fn_type_info)()
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+}
+
+impl PartialEq for MetaType {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl Eq for MetaType {}
+
+impl PartialOrd for MetaType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MetaType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.type_id.cmp(&other.type_id)
+    }
+}
+
+impl hash::Hash for MetaType {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.type_id.hash(state)
+    }
+}
+
+impl fmt::Debug for MetaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.type_name)
+    }
+}
+
+/// Shorthand for [`MetaType::new`].
+pub fn meta_type<T: TypeInfo + ?Sized>() -> MetaType {
+    MetaType::new::<T>()
+}
+
+/// How type descriptions refer to other types.
+pub trait Form {
+    type Type: Clone + PartialEq + Eq + fmt::Debug;
+}
+
+/// Types are referred to by [`MetaType`], the form types describe themselves in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MetaForm {}
+
+impl Form for MetaForm {
+    type Type = MetaType;
+}
+
+/// Types are referred to by their id in a [`Registry`], the form which gets encoded.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PortableForm {}
+
+impl Form for PortableForm {
+    type Type = UntrackedSymbol;
+}
+
+/// The id of a type in a [`Registry`], encoded as a compact integer.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct UntrackedSymbol {
+    id: u32,
+}
+
+impl UntrackedSymbol {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl From<u32> for UntrackedSymbol {
+    fn from(id: u32) -> Self {
+        Self { id }
+    }
+}
+
+impl Encode for UntrackedSymbol {
+    fn size_hint(&self) -> usize {
+        Compact(self.id).size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        Compact(self.id).encode_to(dest)
+    }
+}
+
+impl Decode for UntrackedSymbol {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        Ok(Self { id: <Compact<u32>>::decode(input)?.0 })
+    }
+}
+
+/// The name of a type with the path of the module declaring it, empty for built-in types.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Encode)]
+pub struct Path {
+    pub segments: Vec<&'static str>,
+}
+
+impl Path {
+    /// The path of `ident` declared in the module `module_path`, as given by `module_path!()`.
+    pub fn new(ident: &'static str, module_path: &'static str) -> Self {
+        let mut segments: Vec<_> = module_path.split("::").collect();
+        segments.push(ident);
+        Self { segments }
+    }
+
+    /// The path of a built-in type.
+    pub fn prelude(ident: &'static str) -> Self {
+        Self { segments: vec![ident] }
+    }
+
+    pub fn ident(&self) -> Option<&'static str> {
+        self.segments.last().copied()
+    }
+}
+
+/// The description of a type.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct Type<F: Form = MetaForm> {
+    pub path: Path,
+    /// The generic parameters of the type, with the types they are instantiated with.
+    pub type_params: Vec<TypeParameter<F>>,
+    pub type_def: TypeDef<F>,
+    pub docs: Vec<&'static str>,
+}
+
+impl Type {
+    /// A type without path, generic parameters or docs.
+    pub fn anonymous(type_def: TypeDef) -> Self {
+        Self { path: Path::default(), type_params: Vec::new(), type_def, docs: Vec::new() }
+    }
+}
+
+/// A generic parameter of a type. `ty` is `None` when the parameter doesn't describe itself.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct TypeParameter<F: Form = MetaForm> {
+    pub name: &'static str,
+    pub ty: Option<F::Type>,
+}
+
+/// The structure of a type, following its SCALE encoding.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub enum TypeDef<F: Form = MetaForm> {
+    /// A struct, its fields encoded one after the other.
+    Composite(Vec<Field<F>>),
+    /// An enum, the index of the variant followed by its fields.
+    Variant(Vec<Variant<F>>),
+    /// A sequence of values of the same type, prefixed by its compact length.
+    Sequence(F::Type),
+    /// A fixed number of values of the same type.
+    Array(u32, F::Type),
+    /// Values of different types, one after the other.
+    Tuple(Vec<F::Type>),
+    Primitive(TypeDefPrimitive),
+    /// An integer in the compact encoding.
+    Compact(F::Type),
+}
+
+/// A field of a struct or of an enum variant.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct Field<F: Form = MetaForm> {
+    /// `None` for tuple structs and variants.
+    pub name: Option<&'static str>,
+    pub ty: F::Type,
+    /// The type as written in the source, e.g. `T::Balances`.
+    pub type_name: Option<&'static str>,
+    pub docs: Vec<&'static str>,
+}
+
+/// A variant of an enum.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct Variant<F: Form = MetaForm> {
+    pub name: &'static str,
+    pub fields: Vec<Field<F>>,
+    /// The byte the variant is encoded with.
+    pub index: u8,
+    pub docs: Vec<&'static str>,
+}
+
+/// The built-in types everything is made of.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode)]
+pub enum TypeDefPrimitive {
+    Bool,
+    Char,
+    Str,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    I256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::Compact;
+    use std::marker::PhantomData;
+
+    /// A runtime type, which can not describe itself.
+    struct Runtime;
+
+    /// Some account.
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    struct Account<Balance> {
+        /// Free balance.
+        free: Balance,
+        #[codec(compact)]
+        nonce: u32,
+        #[codec(skip)]
+        cache: Vec<u8>,
+    }
+
+    #[derive(TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    #[allow(dead_code)]
+    enum Event<T> {
+        Transfer(u32, u32),
+        #[codec(index = 5)]
+        Marker { value: u64 },
+        #[codec(skip)]
+        Ignored(PhantomData<T>),
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    struct List {
+        next: Option<Box<List>>,
+    }
+
+    #[test]
+    fn derive_struct_works() {
+        let ty = Account::<u64>::type_info();
+        assert_eq!(ty.path.segments, vec!["frame_support", "scale_info", "tests", "Account"]);
+        assert_eq!(ty.docs, vec!["Some account."]);
+        assert_eq!(
+            ty.type_params,
+            vec![TypeParameter { name: "Balance", ty: Some(meta_type::<u64>()) }]
+        );
+        assert_eq!(
+            ty.type_def,
+            TypeDef::Composite(vec![
+                Field {
+                    name: Some("free"),
+                    ty: meta_type::<u64>(),
+                    type_name: Some("Balance"),
+                    docs: vec!["Free balance."],
+                },
+                Field {
+                    name: Some("nonce"),
+                    ty: meta_type::<Compact<u32>>(),
+                    type_name: Some("u32"),
+                    docs: vec![],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn derive_enum_works() {
+        let ty = Event::<Runtime>::type_info();
+        assert_eq!(ty.type_params, vec![TypeParameter { name: "T", ty: None }]);
+        let TypeDef::Variant(variants) = ty.type_def else { panic!("an enum is a variant type") };
+        let names: Vec<_> = variants.iter().map(|v| (v.name, v.index, v.fields.len())).collect();
+        assert_eq!(names, vec![("Transfer", 0, 2), ("Marker", 5, 1)]);
+    }
+
+    #[test]
+    fn registry_deduplicates_types() {
+        let mut registry = Registry::new();
+        let account = registry.register_type(&meta_type::<Account<u32>>());
+        assert_eq!(registry.register_type(&meta_type::<Account<u32>>()), account);
+        // `&T` and `Box<T>` are registered as `T`.
+        let id = registry.register_type(&meta_type::<u32>());
+        assert_eq!(registry.register_type(&meta_type::<&u32>()), id);
+        assert_eq!(registry.register_type(&meta_type::<Box<u32>>()), id);
+
+        let registry = PortableRegistry::from(registry);
+        let account = registry.resolve(account.id()).unwrap();
+        assert_eq!(account.type_params[0].ty, Some(id));
+        let TypeDef::Composite(fields) = &account.type_def else { panic!("a struct is composite") };
+        assert_eq!(fields[0].ty, id);
+        let compact = registry.resolve(fields[1].ty.id()).unwrap();
+        assert_eq!(compact.type_def, TypeDef::Compact(id));
+    }
+
+    #[test]
+    fn recursive_types_can_be_registered() {
+        let mut registry = Registry::new();
+        let list = registry.register_type(&meta_type::<List>());
+        let registry = PortableRegistry::from(registry);
+
+        let TypeDef::Composite(fields) = &registry.resolve(list.id()).unwrap().type_def else {
+            panic!("a struct is composite")
+        };
+        let option = registry.resolve(fields[0].ty.id()).unwrap();
+        assert_eq!(option.path, Path::prelude("Option"));
+        let TypeDef::Variant(variants) = &option.type_def else { panic!("an option is a variant") };
+        assert_eq!(variants[1].fields[0].ty, list);
+    }
+
+    #[test]
+    fn portable_types_encode() {
+        let mut registry = Registry::new();
+        registry.register_type(&meta_type::<(bool, Vec<u8>)>());
+        let registry = PortableRegistry::from(registry);
+        // The tuple refers to `bool` (id 1) and `Vec<u8>` (id 2), which refers to `u8` (id 3).
+        assert_eq!(
+            registry.encode(),
+            vec![
+                16, // 4 types
+                0, 0, 0, 4, 8, 4, 8, 0, // 0: (bool, Vec<u8>)
+                4, 0, 0, 5, 0, 0, // 1: bool
+                8, 0, 0, 2, 12, 0, // 2: Vec<u8>
+                12, 0, 0, 5, 3, 0, // 3: u8
+            ]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Registration of type descriptions into a deduplicated table.
+
+use super::{
+    Field, MetaForm, MetaType, PortableForm, Type, TypeDef, TypeParameter, UntrackedSymbol,
+    Variant,
+};
+use crate::codec::Encode;
+use std::{any::TypeId, collections::BTreeMap};
+
+/// Converts a description from the [`MetaForm`] to the [`PortableForm`], registering the types
+/// it refers to.
+pub trait IntoPortable {
+    type Output;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output;
+}
+
+/// Assigns an id to every registered type, in registration order.
+#[derive(Default, Debug)]
+pub struct Registry {
+    type_table: BTreeMap<TypeId, u32>,
+    types: Vec<Type<PortableForm>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `ty` and the types it refers to, returning its id.
+    pub fn register_type(&mut self, ty: &MetaType) -> UntrackedSymbol {
+        if let Some(id) = self.type_table.get(&ty.type_id()) {
+            return UntrackedSymbol::from(*id)
+        }
+
+        let id = u32::try_from(self.types.len()).expect("less than 2^32 types are registered");
+        self.type_table.insert(ty.type_id(), id);
+        // Reserve the id first, so recursive types can refer to themselves.
+        self.types.push(Type {
+            path: Default::default(),
+            type_params: Vec::new(),
+            type_def: TypeDef::Tuple(Vec::new()),
+            docs: Vec::new(),
+        });
+        let portable = ty.type_info().into_portable(self);
+        self.types[id as usize] = portable;
+        UntrackedSymbol::from(id)
+    }
+
+    pub fn register_types<'a>(
+        &mut self,
+        types: impl IntoIterator<Item = &'a MetaType>,
+    ) -> Vec<UntrackedSymbol> {
+        types.into_iter().map(|ty| self.register_type(ty)).collect()
+    }
+}
+
+/// A registered type together with its id.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct PortableType {
+    #[codec(compact)]
+    pub id: u32,
+    pub ty: Type<PortableForm>,
+}
+
+/// The frozen content of a [`Registry`], the part of the metadata describing types.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Encode)]
+pub struct PortableRegistry {
+    types: Vec<PortableType>,
+}
+
+impl PortableRegistry {
+    pub fn resolve(&self, id: u32) -> Option<&Type<PortableForm>> {
+        self.types.get(id as usize).map(|ty| &ty.ty)
+    }
+
+    pub fn types(&self) -> &[PortableType] {
+        &self.types
+    }
+}
+
+impl From<Registry> for PortableRegistry {
+    fn from(registry: Registry) -> Self {
+        let types = registry
+            .types
+            .into_iter()
+            .enumerate()
+            .map(|(id, ty)| PortableType { id: id as u32, ty })
+            .collect();
+        Self { types }
+    }
+}
+
+impl IntoPortable for MetaType {
+    type Output = UntrackedSymbol;
+
+    fn into_portable(self, registry: &mut Registry) -> UntrackedSymbol {
+        registry.register_type(&self)
+    }
+}
+
+impl<T: IntoPortable> IntoPortable for Vec<T> {
+    type Output = Vec<T::Output>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        self.into_iter().map(|item| item.into_portable(registry)).collect()
+    }
+}
+
+impl<T: IntoPortable> IntoPortable for Option<T> {
+    type Output = Option<T::Output>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        self.map(|item| item.into_portable(registry))
+    }
+}
+
+impl IntoPortable for Type<MetaForm> {
+    type Output = Type<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        Type {
+            path: self.path,
+            type_params: self.type_params.into_portable(registry),
+            type_def: self.type_def.into_portable(registry),
+            docs: self.docs,
+        }
+    }
+}
+
+impl IntoPortable for TypeParameter<MetaForm> {
+    type Output = TypeParameter<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        TypeParameter { name: self.name, ty: self.ty.into_portable(registry) }
+    }
+}
+
+impl IntoPortable for TypeDef<MetaForm> {
+    type Output = TypeDef<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        match self {
+            TypeDef::Composite(fields) => TypeDef::Composite(fields.into_portable(registry)),
+            TypeDef::Variant(variants) => TypeDef::Variant(variants.into_portable(registry)),
+            TypeDef::Sequence(ty) => TypeDef::Sequence(ty.into_portable(registry)),
+            TypeDef::Array(len, ty) => TypeDef::Array(len, ty.into_portable(registry)),
+            TypeDef::Tuple(types) => TypeDef::Tuple(types.into_portable(registry)),
+            TypeDef::Primitive(primitive) => TypeDef::Primitive(primitive),
+            TypeDef::Compact(ty) => TypeDef::Compact(ty.into_portable(registry)),
+        }
+    }
+}
+
+impl IntoPortable for Field<MetaForm> {
+    type Output = Field<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        Field {
+            name: self.name,
+            ty: self.ty.into_portable(registry),
+            type_name: self.type_name,
+            docs: self.docs,
+        }
+    }
+}
+
+impl IntoPortable for Variant<MetaForm> {
+    type Output = Variant<PortableForm>;
+
+    fn into_portable(self, registry: &mut Registry) -> Self::Output {
+        Variant {
+            name: self.name,
+            fields: self.fields.into_portable(registry),
+            index: self.index,
+            docs: self.docs,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! A `Vec` with a maximum length, so it can be kept in storage with a known worst case size.
+
+use crate::{
+    codec::{Compact, Decode, Encode, EncodeLike, Error, Input, MaxEncodedLen, Output},
+    scale_info::{meta_type, Field, Path, Type, TypeDef, TypeInfo, TypeParameter},
+    traits::Get,
+};
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+/// A `Vec` holding at most `S::get()` items.
+///
+/// Encodes like a `Vec`, decoding fails if the encoded vector is too long.
+pub struct BoundedVec<T, S>(Vec<T>, PhantomData<S>);
+
+impl<T, S: Get<u32>> BoundedVec<T, S> {
+    pub fn bound() -> usize {
+        S::get() as usize
+    }
+
+    /// Append `item`, giving it back if the vector is full.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.0.len() < Self::bound() {
+            self.0.push(item);
+            Ok(())
+        } else {
+            Err(item)
+        }
+    }
+
+    /// Insert `item` at `index`, giving it back if the vector is full.
+    ///
+    /// Panics if `index > len`.
+    pub fn try_insert(&mut self, index: usize, item: T) -> Result<(), T> {
+        if self.0.len() < Self::bound() {
+            self.0.insert(index, item);
+            Ok(())
+        } else {
+            Err(item)
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        self.0.remove(index)
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.0.retain(f)
+    }
+
+    /// Mutable access to the items. Only their values can change, not their number.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, S> Default for BoundedVec<T, S> {
+    fn default() -> Self {
+        Self(Vec::new(), PhantomData)
+    }
+}
+
+impl<T: Clone, S> Clone for BoundedVec<T, S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: PartialEq, S> PartialEq for BoundedVec<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq, S> Eq for BoundedVec<T, S> {}
+
+impl<T: fmt::Debug, S> fmt::Debug for BoundedVec<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoundedVec").field(&self.0).finish()
+    }
+}
+
+impl<T, S> Deref for BoundedVec<T, S> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T, S: Get<u32>> TryFrom<Vec<T>> for BoundedVec<T, S> {
+    type Error = Vec<T>;
+
+    fn try_from(v: Vec<T>) -> Result<Self, Vec<T>> {
+        if v.len() <= Self::bound() {
+            Ok(Self(v, PhantomData))
+        } else {
+            Err(v)
+        }
+    }
+}
+
+impl<T, S> From<BoundedVec<T, S>> for Vec<T> {
+    fn from(v: BoundedVec<T, S>) -> Vec<T> {
+        v.0
+    }
+}
+
+impl<T: Encode, S> Encode for BoundedVec<T, S> {
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.0.encode_to(dest)
+    }
+}
+
+impl<T: Encode, S> EncodeLike<Vec<T>> for BoundedVec<T, S> {}
+
+impl<T: Decode, S: Get<u32>> Decode for BoundedVec<T, S> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let len = <Compact<u32>>::decode(input)?.0;
+        if len > S::get() {
+            return Err("BoundedVec exceeds its limit".into())
+        }
+        let mut items = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            items.push(T::decode(input)?);
+        }
+        Ok(Self(items, PhantomData))
+    }
+}
+
+impl<T: MaxEncodedLen, S: Get<u32>> MaxEncodedLen for BoundedVec<T, S> {
+    fn max_encoded_len() -> usize {
+        Compact(S::get())
+            .encoded_size()
+            .saturating_add(Self::bound().saturating_mul(T::max_encoded_len()))
+    }
+}
+
+/// Described as a composite of the inner `Vec`, the bound is not part of the encoding.
+impl<T: TypeInfo + 'static, S: 'static> TypeInfo for BoundedVec<T, S> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type {
+            path: Path::new("BoundedVec", module_path!()),
+            type_params: vec![
+                TypeParameter { name: "T", ty: Some(meta_type::<T>()) },
+                TypeParameter { name: "S", ty: None },
+            ],
+            type_def: TypeDef::Composite(vec![Field {
+                name: None,
+                ty: meta_type::<Vec<T>>(),
+                type_name: Some("Vec<T>"),
+                docs: Vec::new(),
+            }]),
+            docs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ConstU32;
+
+    type Bounded = BoundedVec<u16, ConstU32<3>>;
+
+    #[test]
+    fn bound_is_enforced() {
+        let mut v = Bounded::try_from(vec![1, 2]).unwrap();
+        assert_eq!(v.try_push(3), Ok(()));
+        assert_eq!(v.try_push(4), Err(4));
+        assert_eq!(v.try_insert(0, 0), Err(0));
+        assert_eq!(*v, vec![1, 2, 3]);
+        assert_eq!(Bounded::try_from(vec![1, 2, 3, 4]), Err(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn codec_works() {
+        let v = Bounded::try_from(vec![1, 2, 3]).unwrap();
+        assert_eq!(v.encode(), vec![1u16, 2, 3].encode());
+        assert_eq!(Bounded::decode(&mut &v.encode()[..]), Ok(v));
+
+        let too_long = vec![1u16, 2, 3, 4].encode();
+        assert!(Bounded::decode(&mut &too_long[..]).is_err());
+
+        // Compact length plus three `u16`.
+        assert_eq!(Bounded::max_encoded_len(), 1 + 3 * 2);
+    }
+}
@@ -0,0 +1,403 @@
+//! The key-value storage pallets keep their state in, and typed accessors on top of it.
+//!
+//! Storage only exists inside [`TestExternalities::execute_with`], which installs a key-value
+//! map for the current thread. Pallets never own their state: two runs over the same
+//! externalities see the same storage, whatever pallet instances they create.
+//!
+//! Storage items are declared as type aliases of [`types::StorageValue`] or
+//! [`types::StorageMap`], with a prefix type made by [`storage_prefix!`](crate::storage_prefix).
+
+pub mod bounded_vec;
+pub mod types;
+pub mod weak_bounded_vec;
+
+use crate::{
+    codec::{Decode, DecodeAll, Encode},
+    hashing::{twox_128, Hash},
+};
+use std::{cell::RefCell, collections::BTreeMap, ops::Bound};
+
+/// The raw content of the storage.
+pub type Storage = BTreeMap<Vec<u8>, Vec<u8>>;
+
+thread_local! {
+    static EXTERNALITIES: RefCell<Option<Storage>> = const { RefCell::new(None) };
+}
+
+fn with_storage<R>(f: impl FnOnce(&mut Storage) -> R) -> R {
+    EXTERNALITIES.with(|ext| {
+        let mut ext = ext.borrow_mut();
+        let storage = ext
+            .as_mut()
+            .expect("storage can only be accessed inside `TestExternalities::execute_with`");
+        f(storage)
+    })
+}
+
+/// Owns a storage and makes it available to the code run by [`Self::execute_with`].
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct TestExternalities {
+    storage: Storage,
+}
+
+impl TestExternalities {
+    pub fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    /// Run `f` with `self` as the storage of the current thread.
+    ///
+    /// Calls can be nested, the storage of the outer call is back once the inner one returns.
+    pub fn execute_with<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        // Puts the storage back into `self` even if `f` panics.
+        struct Restore<'a> {
+            storage: &'a mut Storage,
+            previous: Option<Option<Storage>>,
+        }
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                let previous = self.previous.take().expect("only taken here; qed");
+                let storage = EXTERNALITIES.with(|ext| ext.replace(previous));
+                *self.storage = storage.unwrap_or_default();
+            }
+        }
+
+        let storage = std::mem::take(&mut self.storage);
+        let previous = EXTERNALITIES.with(|ext| ext.replace(Some(storage)));
+        let _restore = Restore { storage: &mut self.storage, previous: Some(previous) };
+        f()
+    }
+
+    pub fn storage(&self) -> &Storage {
+        &self.storage
+    }
+
+    pub fn into_storage(self) -> Storage {
+        self.storage
+    }
+}
+
+impl From<Storage> for TestExternalities {
+    fn from(storage: Storage) -> Self {
+        Self::new(storage)
+    }
+}
+
+/// The hash of the whole storage, which changes with any of its keys or values.
+pub fn root<H: Hash>() -> H::Output {
+    with_storage(|storage| H::hash_of(&*storage))
+}
+
+/// Run `f`, undoing every change it made to the storage if it fails.
+pub fn with_transaction<R, E>(f: impl FnOnce() -> Result<R, E>) -> Result<R, E> {
+    let snapshot = with_storage(|storage| storage.clone());
+    let res = f();
+    if res.is_err() {
+        with_storage(|storage| *storage = snapshot);
+    }
+    res
+}
+
+/// Access to storage by raw key, without any hashing.
+pub mod unhashed {
+    use super::*;
+
+    pub fn get_raw(key: &[u8]) -> Option<Vec<u8>> {
+        with_storage(|storage| storage.get(key).cloned())
+    }
+
+    pub fn put_raw(key: &[u8], value: &[u8]) {
+        with_storage(|storage| storage.insert(key.to_vec(), value.to_vec()));
+    }
+
+    /// The value at `key`, `None` if there is none or if it can't be decoded.
+    pub fn get<T: Decode>(key: &[u8]) -> Option<T> {
+        get_raw(key).and_then(|raw| T::decode_all(&mut &raw[..]).ok())
+    }
+
+    pub fn put<T: Encode + ?Sized>(key: &[u8], value: &T) {
+        value.using_encoded(|encoded| put_raw(key, encoded))
+    }
+
+    pub fn exists(key: &[u8]) -> bool {
+        with_storage(|storage| storage.contains_key(key))
+    }
+
+    pub fn kill(key: &[u8]) {
+        with_storage(|storage| storage.remove(key));
+    }
+
+    /// Remove every key starting with `prefix`, returning how many were removed.
+    pub fn clear_prefix(prefix: &[u8]) -> u32 {
+        with_storage(|storage| {
+            let keys: Vec<_> =
+                storage.keys().filter(|key| key.starts_with(prefix)).cloned().collect();
+            keys.iter().for_each(|key| {
+                storage.remove(key);
+            });
+            keys.len() as u32
+        })
+    }
+
+    /// The first key strictly after `key`, in lexicographic order.
+    pub fn next_key(key: &[u8]) -> Option<Vec<u8>> {
+        with_storage(|storage| {
+            storage
+                .range::<[u8], _>((Bound::Excluded(key), Bound::Unbounded))
+                .next()
+                .map(|(key, _)| key.clone())
+        })
+    }
+}
+
+/// The prefix of a storage item: the name of its pallet and its own name.
+pub trait StorageInstance {
+    fn pallet_prefix() -> &'static str;
+
+    const STORAGE_PREFIX: &'static str;
+
+    /// `twox_128(pallet_prefix) ++ twox_128(STORAGE_PREFIX)`, the start of every key of the item.
+    fn prefix_hash() -> [u8; 32] {
+        let mut res = [0u8; 32];
+        res[..16].copy_from_slice(&twox_128(Self::pallet_prefix().as_bytes()));
+        res[16..].copy_from_slice(&twox_128(Self::STORAGE_PREFIX.as_bytes()));
+        res
+    }
+}
+
+/// Iterates the keys under `prefix`, decoding every entry with `decode`.
+///
+/// Entries failing to decode are skipped. Storage can be modified while iterating.
+pub struct PrefixIterator<T> {
+    prefix: Vec<u8>,
+    previous_key: Vec<u8>,
+    /// Decodes an item from the key without its prefix and the raw value.
+    decode: fn(&[u8], &[u8]) -> Result<T, crate::codec::Error>,
+}
+
+impl<T> PrefixIterator<T> {
+    pub fn new(
+        prefix: Vec<u8>,
+        decode: fn(&[u8], &[u8]) -> Result<T, crate::codec::Error>,
+    ) -> Self {
+        Self { previous_key: prefix.clone(), prefix, decode }
+    }
+}
+
+impl<T> Iterator for PrefixIterator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            let key =
+                unhashed::next_key(&self.previous_key).filter(|k| k.starts_with(&self.prefix))?;
+            let value = unhashed::get_raw(&key);
+            self.previous_key = key;
+            let unprefixed = &self.previous_key[self.prefix.len()..];
+            if let Some(item) = value.and_then(|v| (self.decode)(unprefixed, &v).ok()) {
+                return Some(item)
+            }
+        }
+    }
+}
+
+/// Declare the prefix type of a storage item.
+///
+/// ```
+/// frame_support::storage_prefix!(pub AccountPrefix => "Balances", "Account");
+/// ```
+#[macro_export]
+macro_rules! storage_prefix {
+    ( $vis:vis $name:ident => $pallet:literal, $storage:literal ) => {
+        #[doc = concat!("Prefix of `", $pallet, "::", $storage, "`.")]
+        $vis struct $name;
+
+        impl $crate::storage::StorageInstance for $name {
+            fn pallet_prefix() -> &'static str {
+                $pallet
+            }
+
+            const STORAGE_PREFIX: &'static str = $storage;
+        }
+    };
+}
+
+/// Implement [`StorageInfoTrait`](crate::traits::StorageInfoTrait) for a pallet from the list
+/// of its storage items.
+///
+/// Every item must have bounded keys and values. A pallet with unbounded items must opt out
+/// with `#[without_storage_info]`, and then reports `None` for what it can not bound.
+///
+/// ```
+/// use frame_support::pallet_prelude::*;
+///
+/// pub trait Config {}
+/// pub struct Pallet<T>(PhantomData<T>);
+///
+/// frame_support::storage_prefix!(pub LogPrefix => "Logger", "Log");
+/// pub type Log = StorageValue<LogPrefix, Vec<u32>, ValueQuery>;
+///
+/// frame_support::impl_storage_info!(#[without_storage_info] Pallet<T: Config> { Log });
+/// ```
+///
+/// Without the opt-out the pallet is rejected:
+///
+/// ```compile_fail
+/// use frame_support::pallet_prelude::*;
+///
+/// pub trait Config {}
+/// pub struct Pallet<T>(PhantomData<T>);
+///
+/// frame_support::storage_prefix!(pub LogPrefix => "Logger", "Log");
+/// pub type Log = StorageValue<LogPrefix, Vec<u32>, ValueQuery>;
+///
+/// frame_support::impl_storage_info!(Pallet<T: Config> { Log });
+/// ```
+#[macro_export]
+macro_rules! impl_storage_info {
+    ( #[without_storage_info] $pallet:ident<$t:ident: $bound:path> { $( $item:ty ),* $(,)? } ) => {
+        impl<$t: $bound> $crate::traits::StorageInfoTrait for $pallet<$t> {
+            fn storage_info() -> Vec<$crate::traits::StorageInfo> {
+                let mut res = Vec::new();
+                $( res.extend(
+                    <$item as $crate::traits::PartialStorageInfoTrait>::partial_storage_info()
+                ); )*
+                res
+            }
+        }
+    };
+    ( $pallet:ident<$t:ident: $bound:path> { $( $item:ty ),* $(,)? } ) => {
+        impl<$t: $bound> $crate::traits::StorageInfoTrait for $pallet<$t> {
+            fn storage_info() -> Vec<$crate::traits::StorageInfo> {
+                let mut res = Vec::new();
+                $( res.extend(<$item as $crate::traits::StorageInfoTrait>::storage_info()); )*
+                res
+            }
+        }
+    };
+}
+
+/// Implement `storage_metadata` for a pallet from the list of its storage items, documented
+/// with their doc comments. `$prefix` is the pallet prefix the items are declared with.
+///
+/// ```
+/// use frame_support::pallet_prelude::*;
+///
+/// pub trait Config {}
+/// pub struct Pallet<T>(PhantomData<T>);
+///
+/// frame_support::storage_prefix!(pub LogPrefix => "Logger", "Log");
+/// pub type Log = StorageValue<LogPrefix, Vec<u32>, ValueQuery>;
+///
+/// frame_support::impl_storage_metadata!(Pallet<T: Config> => "Logger" {
+///     /// Every logged value.
+///     Log,
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_storage_metadata {
+    (
+        $pallet:ident<$t:ident: $bound:path> => $prefix:literal {
+            $( $( #[doc = $doc:literal] )* $item:ty ),* $(,)?
+        }
+    ) => {
+        impl<$t: $bound> $pallet<$t> {
+            pub fn storage_metadata() -> $crate::metadata::PalletStorageMetadata {
+                let mut entries = Vec::new();
+                $(
+                    <$item as $crate::storage::types::StorageEntryMetadataBuilder>::build_metadata(
+                        vec![ $( $doc.trim() ),* ],
+                        &mut entries,
+                    );
+                )*
+                $crate::metadata::PalletStorageMetadata { prefix: $prefix, entries }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_with_keeps_storage() {
+        let mut ext = TestExternalities::default();
+        ext.execute_with(|| unhashed::put(b"key", &7u32));
+        assert_eq!(ext.storage().get(&b"key"[..]), Some(&vec![7, 0, 0, 0]));
+        assert_eq!(ext.execute_with(|| unhashed::get::<u32>(b"key")), Some(7));
+
+        // Nested externalities don't see each other.
+        ext.execute_with(|| {
+            TestExternalities::default().execute_with(|| assert!(!unhashed::exists(b"key")));
+            assert!(unhashed::exists(b"key"));
+        });
+    }
+
+    #[test]
+    fn root_changes_with_the_storage() {
+        use crate::hashing::BlakeTwo256;
+
+        TestExternalities::default().execute_with(|| {
+            let empty = root::<BlakeTwo256>();
+            unhashed::put_raw(b"key", b"1");
+            let one = root::<BlakeTwo256>();
+            assert_ne!(one, empty);
+            unhashed::put_raw(b"key", b"2");
+            assert_ne!(root::<BlakeTwo256>(), one);
+            unhashed::kill(b"key");
+            assert_eq!(root::<BlakeTwo256>(), empty);
+        });
+    }
+
+    #[test]
+    fn failed_transactions_are_undone() {
+        TestExternalities::default().execute_with(|| {
+            let res: Result<(), ()> = with_transaction(|| {
+                unhashed::put_raw(b"key", b"1");
+                Err(())
+            });
+            assert_eq!(res, Err(()));
+            assert!(!unhashed::exists(b"key"));
+
+            let res: Result<(), ()> = with_transaction(|| {
+                unhashed::put_raw(b"key", b"1");
+                Ok(())
+            });
+            assert_eq!(res, Ok(()));
+            assert!(unhashed::exists(b"key"));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "storage can only be accessed inside")]
+    fn storage_outside_externalities_panics() {
+        unhashed::get_raw(b"key");
+    }
+
+    #[test]
+    fn prefix_operations_work() {
+        TestExternalities::default().execute_with(|| {
+            unhashed::put_raw(b"ab", b"1");
+            unhashed::put_raw(b"abc", b"2");
+            unhashed::put_raw(b"abd", b"3");
+            unhashed::put_raw(b"b", b"4");
+            assert_eq!(unhashed::next_key(b"a"), Some(b"ab".to_vec()));
+            assert_eq!(unhashed::next_key(b"ab"), Some(b"abc".to_vec()));
+            assert_eq!(unhashed::next_key(b"b"), None);
+
+            // The prefix itself is not an entry.
+            let values: Vec<_> =
+                PrefixIterator::new(b"ab".to_vec(), |key, value| Ok((key.to_vec(), value.to_vec())))
+                    .collect();
+            assert_eq!(
+                values,
+                vec![(b"c".to_vec(), b"2".to_vec()), (b"d".to_vec(), b"3".to_vec())]
+            );
+
+            assert_eq!(unhashed::clear_prefix(b"a"), 3);
+            assert!(unhashed::exists(b"b"));
+        });
+    }
+}
@@ -0,0 +1,306 @@
+//! A storage item mapping keys to values.
+
+use super::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder};
+use crate::{
+    codec::{Codec, DecodeAll, Encode, EncodeLike, MaxEncodedLen},
+    hash::{ReversibleStorageHasher, StorageHasher},
+    metadata::{StorageEntryMetadata, StorageEntryType},
+    scale_info::{meta_type, TypeInfo},
+    storage::{unhashed, PrefixIterator, StorageInstance},
+    traits::{Get, GetDefault, PartialStorageInfoTrait, StorageInfo, StorageInfoTrait},
+};
+use std::marker::PhantomData;
+
+/// A map whose values are stored at `prefix_hash ++ Hasher::hash(encoded_key)`.
+///
+/// `MaxValues` bounds the number of entries in the [`StorageInfo`] of the map, it is not
+/// enforced on insertion.
+pub struct StorageMap<Prefix, Hasher, Key, Value, QueryKind = OptionQuery, MaxValues = GetDefault>(
+    PhantomData<(Prefix, Hasher, Key, Value, QueryKind, MaxValues)>,
+);
+
+impl<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+    StorageMap<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+where
+    Prefix: StorageInstance,
+    Hasher: StorageHasher,
+    Key: Codec,
+    Value: Codec,
+    QueryKind: QueryKindTrait<Value>,
+    MaxValues: Get<Option<u32>>,
+{
+    pub fn hashed_key_for<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Vec<u8> {
+        let mut res = Prefix::prefix_hash().to_vec();
+        key.using_encoded(|encoded| res.extend_from_slice(Hasher::hash(encoded).as_ref()));
+        res
+    }
+
+    pub fn contains_key<KeyArg: EncodeLike<Key>>(key: KeyArg) -> bool {
+        unhashed::exists(&Self::hashed_key_for(key))
+    }
+
+    pub fn get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+        QueryKind::from_optional_value_to_query(Self::try_get(key).ok())
+    }
+
+    /// The value at `key`, `Err` if there is none whatever the query kind.
+    #[allow(clippy::result_unit_err)]
+    pub fn try_get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Result<Value, ()> {
+        unhashed::get(&Self::hashed_key_for(key)).ok_or(())
+    }
+
+    pub fn insert<KeyArg: EncodeLike<Key>, ValArg: EncodeLike<Value>>(key: KeyArg, val: ValArg) {
+        unhashed::put(&Self::hashed_key_for(key), &val)
+    }
+
+    /// Store `val` at `key`, or remove the entry if the query maps to no value.
+    pub fn set<KeyArg: EncodeLike<Key>>(key: KeyArg, val: QueryKind::Query) {
+        let key = Self::hashed_key_for(key);
+        match QueryKind::from_query_to_optional_value(val) {
+            Some(val) => unhashed::put(&key, &val),
+            None => unhashed::kill(&key),
+        }
+    }
+
+    pub fn remove<KeyArg: EncodeLike<Key>>(key: KeyArg) {
+        unhashed::kill(&Self::hashed_key_for(key))
+    }
+
+    pub fn take<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+        let key = Self::hashed_key_for(key);
+        let value = unhashed::get(&key);
+        unhashed::kill(&key);
+        QueryKind::from_optional_value_to_query(value)
+    }
+
+    pub fn mutate<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut QueryKind::Query) -> R>(
+        key: KeyArg,
+        f: F,
+    ) -> R {
+        Self::try_mutate(key, |v| Ok::<R, ()>(f(v))).expect("`Ok` is returned; qed")
+    }
+
+    /// Mutate the value at `key`, storing the result only if `f` returns `Ok`.
+    pub fn try_mutate<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+    where
+        KeyArg: EncodeLike<Key>,
+        F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+    {
+        let key = Self::hashed_key_for(key);
+        let mut val = QueryKind::from_optional_value_to_query(unhashed::get(&key));
+        let res = f(&mut val)?;
+        match QueryKind::from_query_to_optional_value(val) {
+            Some(val) => unhashed::put(&key, &val),
+            None => unhashed::kill(&key),
+        }
+        Ok(res)
+    }
+
+    /// Mutate the entry at `key`, seen as an `Option` whatever the query kind: leaving `None`
+    /// removes it.
+    pub fn mutate_exists<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut Option<Value>) -> R>(
+        key: KeyArg,
+        f: F,
+    ) -> R {
+        Self::try_mutate_exists(key, |v| Ok::<R, ()>(f(v))).expect("`Ok` is returned; qed")
+    }
+
+    pub fn try_mutate_exists<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+    where
+        KeyArg: EncodeLike<Key>,
+        F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+    {
+        let key = Self::hashed_key_for(key);
+        let mut val = unhashed::get(&key);
+        let res = f(&mut val)?;
+        match val {
+            Some(val) => unhashed::put(&key, &val),
+            None => unhashed::kill(&key),
+        }
+        Ok(res)
+    }
+
+    /// Remove every entry, returning how many were removed.
+    pub fn clear() -> u32 {
+        unhashed::clear_prefix(&Prefix::prefix_hash())
+    }
+
+    pub fn iter_values() -> PrefixIterator<Value> {
+        PrefixIterator::new(Prefix::prefix_hash().to_vec(), |_, mut value| {
+            Value::decode_all(&mut value)
+        })
+    }
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+    StorageMap<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+where
+    Prefix: StorageInstance,
+    Hasher: ReversibleStorageHasher,
+    Key: Codec,
+    Value: Codec,
+    QueryKind: QueryKindTrait<Value>,
+    MaxValues: Get<Option<u32>>,
+{
+    /// Iterate over all entries, in the order of their hashed keys.
+    pub fn iter() -> PrefixIterator<(Key, Value)> {
+        PrefixIterator::new(Prefix::prefix_hash().to_vec(), |key, mut value| {
+            let key = Key::decode_all(&mut Hasher::reverse(key))?;
+            Ok((key, Value::decode_all(&mut value)?))
+        })
+    }
+
+    pub fn iter_keys() -> PrefixIterator<Key> {
+        PrefixIterator::new(Prefix::prefix_hash().to_vec(), |key, _| {
+            Key::decode_all(&mut Hasher::reverse(key))
+        })
+    }
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, MaxValues> StorageEntryMetadataBuilder
+    for StorageMap<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+where
+    Prefix: StorageInstance,
+    Hasher: StorageHasher,
+    Key: Codec + TypeInfo + 'static,
+    Value: Codec + TypeInfo + 'static,
+    QueryKind: QueryKindTrait<Value>,
+    QueryKind::Query: Encode,
+    MaxValues: Get<Option<u32>>,
+{
+    fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+        entries.push(StorageEntryMetadata {
+            name: Prefix::STORAGE_PREFIX,
+            modifier: QueryKind::METADATA,
+            ty: StorageEntryType::Map {
+                hashers: vec![Hasher::METADATA],
+                key: meta_type::<Key>(),
+                value: meta_type::<Value>(),
+            },
+            default: QueryKind::from_optional_value_to_query(None).encode(),
+            docs,
+        });
+    }
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, MaxValues> StorageInfoTrait
+    for StorageMap<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+where
+    Prefix: StorageInstance,
+    Hasher: StorageHasher,
+    Key: Codec + MaxEncodedLen,
+    Value: Codec + MaxEncodedLen,
+    QueryKind: QueryKindTrait<Value>,
+    MaxValues: Get<Option<u32>>,
+{
+    fn storage_info() -> Vec<StorageInfo> {
+        let max_size = Hasher::max_len::<Key>().saturating_add(Value::max_encoded_len());
+        vec![StorageInfo {
+            pallet_name: Prefix::pallet_prefix().as_bytes().to_vec(),
+            storage_name: Prefix::STORAGE_PREFIX.as_bytes().to_vec(),
+            prefix: Prefix::prefix_hash().to_vec(),
+            max_values: MaxValues::get(),
+            max_size: Some(max_size.try_into().unwrap_or(u32::MAX)),
+        }]
+    }
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, MaxValues> PartialStorageInfoTrait
+    for StorageMap<Prefix, Hasher, Key, Value, QueryKind, MaxValues>
+where
+    Prefix: StorageInstance,
+    Hasher: StorageHasher,
+    Key: Codec,
+    Value: Codec,
+    QueryKind: QueryKindTrait<Value>,
+    MaxValues: Get<Option<u32>>,
+{
+    fn partial_storage_info() -> Vec<StorageInfo> {
+        vec![StorageInfo {
+            pallet_name: Prefix::pallet_prefix().as_bytes().to_vec(),
+            storage_name: Prefix::STORAGE_PREFIX.as_bytes().to_vec(),
+            prefix: Prefix::prefix_hash().to_vec(),
+            max_values: MaxValues::get(),
+            max_size: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hash::{Blake2_128Concat, Twox128},
+        storage::{types::ValueQuery, TestExternalities},
+        traits::ConstU32,
+    };
+
+    crate::storage_prefix!(BalancePrefix => "Test", "Balance");
+
+    type Balance = StorageMap<BalancePrefix, Blake2_128Concat, u32, u64, ValueQuery>;
+    type MaybeBalance = StorageMap<BalancePrefix, Blake2_128Concat, u32, u64>;
+
+    #[test]
+    fn map_works() {
+        TestExternalities::default().execute_with(|| {
+            assert!(!Balance::contains_key(1));
+            assert_eq!(Balance::get(1), 0);
+
+            Balance::insert(1, 10);
+            Balance::insert(2, 20);
+            assert_eq!(MaybeBalance::get(1), Some(10));
+            assert_eq!(MaybeBalance::get(3), None);
+
+            Balance::mutate(1, |b| *b += 5);
+            assert_eq!(Balance::get(1), 15);
+            assert_eq!(Balance::try_mutate(1, |b| { *b = 0; Err::<(), _>(()) }), Err(()));
+            assert_eq!(Balance::get(1), 15);
+
+            Balance::mutate_exists(2, |b| *b = None);
+            assert!(!Balance::contains_key(2));
+            MaybeBalance::mutate(3, |b| *b = Some(30));
+            assert_eq!(Balance::take(3), 30);
+            assert!(!Balance::contains_key(3));
+
+            Balance::remove(1);
+            assert_eq!(MaybeBalance::get(1), None);
+        });
+    }
+
+    #[test]
+    fn iteration_works() {
+        TestExternalities::default().execute_with(|| {
+            for i in 0..5u32 {
+                Balance::insert(i, u64::from(i) * 10);
+            }
+            let mut entries: Vec<_> = Balance::iter().collect();
+            entries.sort();
+            assert_eq!(entries, vec![(0, 0), (1, 10), (2, 20), (3, 30), (4, 40)]);
+
+            let mut keys: Vec<_> = Balance::iter_keys().collect();
+            keys.sort();
+            assert_eq!(keys, vec![0, 1, 2, 3, 4]);
+            assert_eq!(Balance::iter_values().sum::<u64>(), 100);
+
+            // Removing entries while iterating is fine.
+            for key in Balance::iter_keys() {
+                Balance::remove(key);
+            }
+            assert_eq!(Balance::clear(), 0);
+        });
+    }
+
+    #[test]
+    fn storage_info_works() {
+        type Bounded = StorageMap<BalancePrefix, Twox128, u32, u64, ValueQuery, ConstU32<100>>;
+
+        let info = Balance::storage_info();
+        assert_eq!(info[0].max_values, None);
+        assert_eq!(info[0].max_size, Some(16 + 4 + 8));
+
+        let info = Bounded::storage_info();
+        assert_eq!(info[0].max_values, Some(100));
+        assert_eq!(info[0].max_size, Some(16 + 8));
+        assert_eq!(info[0].max_total_size(), Some(2400));
+    }
+}
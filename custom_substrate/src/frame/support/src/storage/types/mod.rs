@@ -0,0 +1,61 @@
+//! Typed storage items, declared by pallets as type aliases.
+
+mod map;
+mod value;
+
+pub use map::StorageMap;
+pub use value::StorageValue;
+
+use crate::metadata::{StorageEntryMetadata, StorageEntryModifier};
+
+/// Describe a storage item in the metadata of its pallet.
+pub trait StorageEntryMetadataBuilder {
+    /// Push the description of the item, named after its storage prefix, to `entries`.
+    fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>);
+}
+
+/// How a storage item reports a missing value.
+pub trait QueryKindTrait<Value> {
+    /// What `get` returns.
+    type Query;
+
+    const METADATA: StorageEntryModifier;
+
+    fn from_optional_value_to_query(v: Option<Value>) -> Self::Query;
+
+    fn from_query_to_optional_value(v: Self::Query) -> Option<Value>;
+}
+
+/// A missing value is `None`, and setting `None` removes the value.
+pub struct OptionQuery;
+
+impl<Value> QueryKindTrait<Value> for OptionQuery {
+    type Query = Option<Value>;
+
+    const METADATA: StorageEntryModifier = StorageEntryModifier::Optional;
+
+    fn from_optional_value_to_query(v: Option<Value>) -> Self::Query {
+        v
+    }
+
+    fn from_query_to_optional_value(v: Self::Query) -> Option<Value> {
+        v
+    }
+}
+
+/// A missing value is the default value. Setting a value always stores it, even the default.
+pub struct ValueQuery;
+
+impl<Value: Default> QueryKindTrait<Value> for ValueQuery {
+    type Query = Value;
+
+    const METADATA: StorageEntryModifier = StorageEntryModifier::Default;
+
+    fn from_optional_value_to_query(v: Option<Value>) -> Self::Query {
+        v.unwrap_or_default()
+    }
+
+    fn from_query_to_optional_value(v: Self::Query) -> Option<Value> {
+        Some(v)
+    }
+}
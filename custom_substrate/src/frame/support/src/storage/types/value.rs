@@ -0,0 +1,188 @@
+//! A storage item holding a single value.
+
+use super::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder};
+use crate::{
+    codec::{Codec, Encode, EncodeLike, MaxEncodedLen},
+    metadata::{StorageEntryMetadata, StorageEntryType},
+    scale_info::{meta_type, TypeInfo},
+    storage::{unhashed, StorageInstance},
+    traits::{PartialStorageInfoTrait, StorageInfo, StorageInfoTrait},
+};
+use std::marker::PhantomData;
+
+/// A single value stored at `twox_128(pallet_prefix) ++ twox_128(STORAGE_PREFIX)`.
+pub struct StorageValue<Prefix, Value, QueryKind = OptionQuery>(
+    PhantomData<(Prefix, Value, QueryKind)>,
+);
+
+impl<Prefix, Value, QueryKind> StorageValue<Prefix, Value, QueryKind>
+where
+    Prefix: StorageInstance,
+    Value: Codec,
+    QueryKind: QueryKindTrait<Value>,
+{
+    pub fn hashed_key() -> [u8; 32] {
+        Prefix::prefix_hash()
+    }
+
+    pub fn exists() -> bool {
+        unhashed::exists(&Self::hashed_key())
+    }
+
+    pub fn get() -> QueryKind::Query {
+        QueryKind::from_optional_value_to_query(Self::try_get().ok())
+    }
+
+    /// The stored value, `Err` if there is none whatever the query kind.
+    #[allow(clippy::result_unit_err)]
+    pub fn try_get() -> Result<Value, ()> {
+        unhashed::get(&Self::hashed_key()).ok_or(())
+    }
+
+    pub fn put<Arg: EncodeLike<Value>>(val: Arg) {
+        unhashed::put(&Self::hashed_key(), &val)
+    }
+
+    /// Store `val`, or remove the value if the query maps to no value.
+    pub fn set(val: QueryKind::Query) {
+        match QueryKind::from_query_to_optional_value(val) {
+            Some(val) => Self::put(val),
+            None => Self::kill(),
+        }
+    }
+
+    pub fn kill() {
+        unhashed::kill(&Self::hashed_key())
+    }
+
+    pub fn take() -> QueryKind::Query {
+        let value = Self::get();
+        Self::kill();
+        value
+    }
+
+    pub fn mutate<R, F: FnOnce(&mut QueryKind::Query) -> R>(f: F) -> R {
+        Self::try_mutate(|v| Ok::<R, ()>(f(v))).expect("`Ok` is returned; qed")
+    }
+
+    /// Mutate the value, storing the result only if `f` returns `Ok`.
+    pub fn try_mutate<R, E, F: FnOnce(&mut QueryKind::Query) -> Result<R, E>>(
+        f: F,
+    ) -> Result<R, E> {
+        let mut val = Self::get();
+        let res = f(&mut val)?;
+        Self::set(val);
+        Ok(res)
+    }
+}
+
+impl<Prefix, Value, QueryKind> StorageEntryMetadataBuilder
+    for StorageValue<Prefix, Value, QueryKind>
+where
+    Prefix: StorageInstance,
+    Value: Codec + TypeInfo + 'static,
+    QueryKind: QueryKindTrait<Value>,
+    QueryKind::Query: Encode,
+{
+    fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+        entries.push(StorageEntryMetadata {
+            name: Prefix::STORAGE_PREFIX,
+            modifier: QueryKind::METADATA,
+            ty: StorageEntryType::Plain(meta_type::<Value>()),
+            default: QueryKind::from_optional_value_to_query(None).encode(),
+            docs,
+        });
+    }
+}
+
+impl<Prefix, Value, QueryKind> StorageInfoTrait for StorageValue<Prefix, Value, QueryKind>
+where
+    Prefix: StorageInstance,
+    Value: Codec + MaxEncodedLen,
+    QueryKind: QueryKindTrait<Value>,
+{
+    fn storage_info() -> Vec<StorageInfo> {
+        vec![StorageInfo {
+            pallet_name: Prefix::pallet_prefix().as_bytes().to_vec(),
+            storage_name: Prefix::STORAGE_PREFIX.as_bytes().to_vec(),
+            prefix: Self::hashed_key().to_vec(),
+            max_values: Some(1),
+            max_size: Some(Value::max_encoded_len().try_into().unwrap_or(u32::MAX)),
+        }]
+    }
+}
+
+impl<Prefix, Value, QueryKind> PartialStorageInfoTrait for StorageValue<Prefix, Value, QueryKind>
+where
+    Prefix: StorageInstance,
+    Value: Codec,
+    QueryKind: QueryKindTrait<Value>,
+{
+    fn partial_storage_info() -> Vec<StorageInfo> {
+        vec![StorageInfo {
+            pallet_name: Prefix::pallet_prefix().as_bytes().to_vec(),
+            storage_name: Prefix::STORAGE_PREFIX.as_bytes().to_vec(),
+            prefix: Self::hashed_key().to_vec(),
+            max_values: Some(1),
+            max_size: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{types::ValueQuery, TestExternalities};
+
+    crate::storage_prefix!(CounterPrefix => "Test", "Counter");
+
+    type Counter = StorageValue<CounterPrefix, u32, ValueQuery>;
+    type MaybeCounter = StorageValue<CounterPrefix, u32>;
+
+    #[test]
+    fn value_works() {
+        TestExternalities::default().execute_with(|| {
+            assert!(!Counter::exists());
+            assert_eq!(Counter::get(), 0);
+            assert_eq!(MaybeCounter::get(), None);
+
+            Counter::put(3);
+            assert_eq!(MaybeCounter::get(), Some(3));
+            Counter::mutate(|c| *c += 1);
+            assert_eq!(Counter::get(), 4);
+
+            assert_eq!(Counter::try_mutate(|c| { *c = 10; Err::<(), _>("nope") }), Err("nope"));
+            assert_eq!(Counter::get(), 4);
+
+            MaybeCounter::set(None);
+            assert!(!Counter::exists());
+            Counter::set(0);
+            assert!(Counter::exists());
+            assert_eq!(MaybeCounter::take(), Some(0));
+            assert!(!Counter::exists());
+        });
+    }
+
+    #[test]
+    fn undecodable_value_is_missing() {
+        TestExternalities::default().execute_with(|| {
+            unhashed::put_raw(&Counter::hashed_key(), &[1, 2]);
+            assert_eq!(MaybeCounter::get(), None);
+            assert_eq!(Counter::get(), 0);
+        });
+    }
+
+    #[test]
+    fn storage_info_works() {
+        assert_eq!(
+            Counter::storage_info(),
+            vec![StorageInfo {
+                pallet_name: b"Test".to_vec(),
+                storage_name: b"Counter".to_vec(),
+                prefix: Counter::hashed_key().to_vec(),
+                max_values: Some(1),
+                max_size: Some(4),
+            }]
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! A `Vec` with a length it should not exceed, but which it may when going over would lose data.
+
+use crate::{
+    codec::{Decode, Encode, EncodeLike, Error, Input, MaxEncodedLen, Output},
+    scale_info::{meta_type, Field, Path, Type, TypeDef, TypeInfo, TypeParameter},
+    storage::bounded_vec::BoundedVec,
+    traits::Get,
+};
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+/// A `Vec` which should hold at most `S::get()` items.
+///
+/// Unlike [`BoundedVec`], it can be forced over its bound, with a warning, e.g. so a lock set
+/// by a pallet is never silently dropped. Its `MaxEncodedLen` assumes the bound is kept.
+pub struct WeakBoundedVec<T, S>(Vec<T>, PhantomData<S>);
+
+impl<T, S: Get<u32>> WeakBoundedVec<T, S> {
+    pub fn bound() -> usize {
+        S::get() as usize
+    }
+
+    /// Wrap `v` even if it is too long, logging a warning naming `scope` if it is.
+    pub fn force_from(v: Vec<T>, scope: Option<&'static str>) -> Self {
+        if v.len() > Self::bound() {
+            log::warn!(
+                target: "runtime",
+                "length of a bounded vector in scope {} is not respected",
+                scope.unwrap_or("UNKNOWN"),
+            );
+        }
+        Self(v, PhantomData)
+    }
+
+    /// Append `item`, giving it back if the vector is full.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.0.len() < Self::bound() {
+            self.0.push(item);
+            Ok(())
+        } else {
+            Err(item)
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        self.0.remove(index)
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.0.retain(f)
+    }
+
+    /// Mutable access to the items. Only their values can change, not their number.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, S> Default for WeakBoundedVec<T, S> {
+    fn default() -> Self {
+        Self(Vec::new(), PhantomData)
+    }
+}
+
+impl<T: Clone, S> Clone for WeakBoundedVec<T, S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: PartialEq, S> PartialEq for WeakBoundedVec<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq, S> Eq for WeakBoundedVec<T, S> {}
+
+impl<T: fmt::Debug, S> fmt::Debug for WeakBoundedVec<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WeakBoundedVec").field(&self.0).finish()
+    }
+}
+
+impl<T, S> Deref for WeakBoundedVec<T, S> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T, S: Get<u32>> TryFrom<Vec<T>> for WeakBoundedVec<T, S> {
+    type Error = Vec<T>;
+
+    fn try_from(v: Vec<T>) -> Result<Self, Vec<T>> {
+        if v.len() <= Self::bound() {
+            Ok(Self(v, PhantomData))
+        } else {
+            Err(v)
+        }
+    }
+}
+
+impl<T, S> From<WeakBoundedVec<T, S>> for Vec<T> {
+    fn from(v: WeakBoundedVec<T, S>) -> Vec<T> {
+        v.0
+    }
+}
+
+impl<T: Encode, S> Encode for WeakBoundedVec<T, S> {
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.0.encode_to(dest)
+    }
+}
+
+impl<T: Encode, S> EncodeLike<Vec<T>> for WeakBoundedVec<T, S> {}
+
+/// Decoding keeps the items past the bound, as they were forced into storage.
+impl<T: Decode, S: Get<u32>> Decode for WeakBoundedVec<T, S> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        Ok(Self::force_from(Vec::decode(input)?, Some("decode")))
+    }
+}
+
+impl<T: MaxEncodedLen, S: Get<u32>> MaxEncodedLen for WeakBoundedVec<T, S> {
+    fn max_encoded_len() -> usize {
+        BoundedVec::<T, S>::max_encoded_len()
+    }
+}
+
+/// Described as a composite of the inner `Vec`, the bound is not part of the encoding.
+impl<T: TypeInfo + 'static, S: 'static> TypeInfo for WeakBoundedVec<T, S> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type {
+            path: Path::new("WeakBoundedVec", module_path!()),
+            type_params: vec![
+                TypeParameter { name: "T", ty: Some(meta_type::<T>()) },
+                TypeParameter { name: "S", ty: None },
+            ],
+            type_def: TypeDef::Composite(vec![Field {
+                name: None,
+                ty: meta_type::<Vec<T>>(),
+                type_name: Some("Vec<T>"),
+                docs: Vec::new(),
+            }]),
+            docs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ConstU32;
+
+    type WeakBounded = WeakBoundedVec<u16, ConstU32<3>>;
+
+    #[test]
+    fn bound_can_be_forced() {
+        let mut v = WeakBounded::try_from(vec![1, 2, 3]).unwrap();
+        assert_eq!(v.try_push(4), Err(4));
+        assert_eq!(WeakBounded::try_from(vec![1, 2, 3, 4]), Err(vec![1, 2, 3, 4]));
+
+        let v = WeakBounded::force_from(vec![1, 2, 3, 4], Some("test"));
+        assert_eq!(*v, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn codec_works() {
+        // What was forced over the bound decodes as it was.
+        let v = WeakBounded::force_from(vec![1, 2, 3, 4], None);
+        assert_eq!(v.encode(), vec![1u16, 2, 3, 4].encode());
+        assert_eq!(WeakBounded::decode(&mut &v.encode()[..]), Ok(v));
+
+        // Compact length plus three `u16`.
+        assert_eq!(WeakBounded::max_encoded_len(), 1 + 3 * 2);
+    }
+}
@@ -0,0 +1,32 @@
+//! Types standing in for the real ones in tests.
+
+use crate::{
+    codec::{Decode, Encode},
+    scale_info::TypeInfo,
+    traits::{IdentifyAccount, Verify},
+};
+
+/// The signer of a [`TestSignature`], which is its own account.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct TestSigner<AccountId>(pub AccountId);
+
+impl<AccountId> IdentifyAccount for TestSigner<AccountId> {
+    type AccountId = AccountId;
+
+    fn into_account(self) -> AccountId {
+        self.0
+    }
+}
+
+/// A signature which holds its signer and message in clear, valid if they are the ones it is
+/// checked against.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct TestSignature<AccountId>(pub AccountId, pub Vec<u8>);
+
+impl<AccountId: PartialEq> Verify for TestSignature<AccountId> {
+    type Signer = TestSigner<AccountId>;
+
+    fn verify(&self, msg: &[u8], signer: &AccountId) -> bool {
+        *signer == self.0 && msg == &self.1[..]
+    }
+}
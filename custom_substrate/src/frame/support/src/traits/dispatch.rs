@@ -0,0 +1,194 @@
+//! Traits dispatching calls and checking their origin.
+
+use super::SortedMembers;
+use crate::dispatch::{
+    BadOrigin, DispatchErrorWithPostInfo, DispatchResultWithPostInfo, RawOrigin,
+};
+use std::marker::PhantomData;
+
+/// A call which can be dispatched, checking it against the call filter of its origin.
+pub trait Dispatchable {
+    type RuntimeOrigin;
+    type Info;
+    type PostInfo;
+
+    fn dispatch(
+        self,
+        origin: Self::RuntimeOrigin,
+    ) -> Result<Self::PostInfo, DispatchErrorWithPostInfo<Self::PostInfo>>;
+}
+
+/// A call which can be dispatched without checking the call filter of its origin, e.g. by
+/// `sudo` or by a pallet which already checked the call.
+pub trait UnfilteredDispatchable {
+    type RuntimeOrigin;
+
+    fn dispatch_bypass_filter(self, origin: Self::RuntimeOrigin) -> DispatchResultWithPostInfo;
+}
+
+/// The methods of the `RuntimeOrigin` generated by `construct_runtime!`.
+pub trait OriginTrait: Sized {
+    type Call;
+    /// The caller of the origin, `OriginCaller` as generated by `construct_runtime!`.
+    type PalletsOrigin;
+    type AccountId;
+
+    /// Restrict the calls the origin can make to the ones `filter` accepts too.
+    fn add_filter(&mut self, filter: impl Fn(&Self::Call) -> bool + 'static);
+
+    /// Go back to the base call filter of the runtime.
+    fn reset_filter(&mut self);
+
+    /// Replace the caller by the one of `other`, keeping the filter.
+    fn set_caller_from(&mut self, other: impl Into<Self>);
+
+    /// Whether the origin can make `call`. Root can make any call.
+    fn filter_call(&self, call: &Self::Call) -> bool;
+
+    fn caller(&self) -> &Self::PalletsOrigin;
+
+    fn into_caller(self) -> Self::PalletsOrigin;
+
+    fn root() -> Self;
+
+    fn signed(by: Self::AccountId) -> Self;
+
+    fn none() -> Self;
+}
+
+/// Some way of checking the origin of a call, giving back `Success` when it is accepted.
+pub trait EnsureOrigin<OuterOrigin> {
+    type Success;
+
+    fn ensure_origin(o: OuterOrigin) -> Result<Self::Success, BadOrigin> {
+        Self::try_origin(o).map_err(|_| BadOrigin)
+    }
+
+    /// Check the origin, giving it back when it is refused.
+    fn try_origin(o: OuterOrigin) -> Result<Self::Success, OuterOrigin>;
+}
+
+/// Accepts root.
+pub struct EnsureRoot<AccountId>(PhantomData<AccountId>);
+
+impl<O, AccountId> EnsureOrigin<O> for EnsureRoot<AccountId>
+where
+    O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+{
+    type Success = ();
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Root => Ok(()),
+            r => Err(O::from(r)),
+        })
+    }
+}
+
+/// Accepts any signed origin, succeeding with the account.
+pub struct EnsureSigned<AccountId>(PhantomData<AccountId>);
+
+impl<O, AccountId> EnsureOrigin<O> for EnsureSigned<AccountId>
+where
+    O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+{
+    type Success = AccountId;
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Signed(who) => Ok(who),
+            r => Err(O::from(r)),
+        })
+    }
+}
+
+/// Accepts an origin signed by one of the `Who` members, succeeding with the account.
+pub struct EnsureSignedBy<Who, AccountId>(PhantomData<(Who, AccountId)>);
+
+impl<O, Who, AccountId> EnsureOrigin<O> for EnsureSignedBy<Who, AccountId>
+where
+    O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+    Who: SortedMembers<AccountId>,
+    AccountId: Ord + Clone,
+{
+    type Success = AccountId;
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Signed(ref who) if Who::contains(who) => Ok(who.clone()),
+            r => Err(O::from(r)),
+        })
+    }
+}
+
+/// Accepts the unsigned origin.
+pub struct EnsureNone<AccountId>(PhantomData<AccountId>);
+
+impl<O, AccountId> EnsureOrigin<O> for EnsureNone<AccountId>
+where
+    O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+{
+    type Success = ();
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::None => Ok(()),
+            r => Err(O::from(r)),
+        })
+    }
+}
+
+/// Accepts the origins accepted by either `L` or `R`, which must succeed with the same type.
+pub struct EitherOf<L, R>(PhantomData<(L, R)>);
+
+impl<O, L, R> EnsureOrigin<O> for EitherOf<L, R>
+where
+    L: EnsureOrigin<O>,
+    R: EnsureOrigin<O, Success = L::Success>,
+{
+    type Success = L::Success;
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        L::try_origin(o).or_else(R::try_origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Admins;
+
+    impl SortedMembers<u64> for Admins {
+        fn sorted_members() -> Vec<u64> {
+            vec![1, 3]
+        }
+    }
+
+    type Origin = RawOrigin<u64>;
+
+    #[test]
+    fn ensure_origins_work() {
+        assert_eq!(EnsureRoot::<u64>::try_origin(Origin::Root), Ok(()));
+        assert_eq!(EnsureRoot::<u64>::try_origin(Origin::None), Err(Origin::None));
+        assert_eq!(EnsureSigned::<u64>::try_origin(Origin::Signed(2)), Ok(2));
+        assert_eq!(EnsureSigned::<u64>::ensure_origin(Origin::Root), Err(BadOrigin));
+        assert_eq!(EnsureNone::<u64>::try_origin(Origin::None), Ok(()));
+    }
+
+    #[test]
+    fn ensure_signed_by_checks_members() {
+        type EnsureAdmin = EnsureSignedBy<Admins, u64>;
+        assert_eq!(EnsureAdmin::try_origin(Origin::Signed(3)), Ok(3));
+        assert_eq!(EnsureAdmin::try_origin(Origin::Signed(2)), Err(Origin::Signed(2)));
+        assert_eq!(EnsureAdmin::try_origin(Origin::Root), Err(Origin::Root));
+    }
+
+    #[test]
+    fn either_of_tries_both() {
+        type EnsureRootOrNone = EitherOf<EnsureRoot<u64>, EnsureNone<u64>>;
+        assert_eq!(EnsureRootOrNone::try_origin(Origin::Root), Ok(()));
+        assert_eq!(EnsureRootOrNone::try_origin(Origin::None), Ok(()));
+        assert_eq!(EnsureRootOrNone::try_origin(Origin::Signed(1)), Err(Origin::Signed(1)));
+    }
+}
@@ -0,0 +1,216 @@
+//! Traits checking and applying extrinsics.
+
+use crate::{
+    codec::{Codec, Encode},
+    dispatch::{DispatchInfo, DispatchResult, DispatchResultWithPostInfo, PostDispatchInfo},
+    transaction_validity::{TransactionValidity, TransactionValidityError, ValidTransaction},
+};
+use std::fmt::Debug;
+
+/// A signature which can be checked against the message it signs and its signer.
+pub trait Verify {
+    /// The key which made the signature.
+    type Signer: IdentifyAccount;
+
+    /// Whether this is a signature of `msg` by the key of the account `signer`.
+    fn verify(&self, msg: &[u8], signer: &<Self::Signer as IdentifyAccount>::AccountId) -> bool;
+}
+
+/// Something which identifies an account, e.g. a public key.
+pub trait IdentifyAccount {
+    type AccountId;
+
+    fn into_account(self) -> Self::AccountId;
+}
+
+/// Checks which come with a signed extrinsic, e.g. its nonce, and are run around its call.
+///
+/// The extension itself is part of the extrinsic and of the signed payload. What it adds to
+/// the payload without being part of the extrinsic, e.g. the genesis hash, is
+/// [`Self::AdditionalSigned`]: a signature made for another chain then fails to verify.
+pub trait SignedExtension: Codec + Clone + Eq + Debug {
+    /// The name of the extension, unique in the runtime.
+    const IDENTIFIER: &'static str;
+
+    type AccountId;
+    type Call;
+    /// What the extension adds to the signed payload.
+    type AdditionalSigned: Encode;
+    /// What `pre_dispatch` hands over to `post_dispatch`.
+    type Pre;
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError>;
+
+    /// Check the extrinsic signed by `who` without changing the state, e.g. before putting it
+    /// in the transaction pool, and tell how the pool should handle it.
+    fn validate(
+        &self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        Ok(ValidTransaction::default())
+    }
+
+    /// Check the extrinsic signed by `who` right before its call is dispatched, changing the
+    /// state if needed, e.g. to increase the nonce.
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError>;
+
+    /// [`Self::validate`] for an unsigned extrinsic.
+    fn validate_unsigned(
+        _call: &Self::Call,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        Ok(ValidTransaction::default())
+    }
+
+    /// [`Self::pre_dispatch`] for an unsigned extrinsic.
+    fn pre_dispatch_unsigned(
+        call: &Self::Call,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Self::validate_unsigned(call, info, len).map(|_| ())
+    }
+
+    /// Called once the call was dispatched, with what `pre_dispatch` returned if the extrinsic
+    /// is signed.
+    fn post_dispatch(
+        _pre: Option<Self::Pre>,
+        _info: &DispatchInfo,
+        _post_info: &PostDispatchInfo,
+        _len: usize,
+        _result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_for_tuples {
+    ( $( $name:ident )+ ) => {
+        impl<AccountId, Call, $( $name ),+> SignedExtension for ( $( $name, )+ )
+        where
+            $( $name: SignedExtension<AccountId = AccountId, Call = Call>, )+
+        {
+            const IDENTIFIER: &'static str = "";
+
+            type AccountId = AccountId;
+            type Call = Call;
+            type AdditionalSigned = ( $( $name::AdditionalSigned, )+ );
+            type Pre = ( $( $name::Pre, )+ );
+
+            #[allow(non_snake_case)]
+            fn additional_signed(
+                &self,
+            ) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+                let ( $( $name, )+ ) = self;
+                Ok(( $( $name.additional_signed()?, )+ ))
+            }
+
+            #[allow(non_snake_case)]
+            fn validate(
+                &self,
+                who: &AccountId,
+                call: &Call,
+                info: &DispatchInfo,
+                len: usize,
+            ) -> TransactionValidity {
+                let ( $( $name, )+ ) = self;
+                let valid = ValidTransaction::default();
+                $( let valid = valid.combine_with($name.validate(who, call, info, len)?); )+
+                Ok(valid)
+            }
+
+            #[allow(non_snake_case)]
+            fn pre_dispatch(
+                self,
+                who: &AccountId,
+                call: &Call,
+                info: &DispatchInfo,
+                len: usize,
+            ) -> Result<Self::Pre, TransactionValidityError> {
+                let ( $( $name, )+ ) = self;
+                Ok(( $( $name.pre_dispatch(who, call, info, len)?, )+ ))
+            }
+
+            fn validate_unsigned(
+                call: &Call,
+                info: &DispatchInfo,
+                len: usize,
+            ) -> TransactionValidity {
+                let valid = ValidTransaction::default();
+                $( let valid = valid.combine_with($name::validate_unsigned(call, info, len)?); )+
+                Ok(valid)
+            }
+
+            fn pre_dispatch_unsigned(
+                call: &Call,
+                info: &DispatchInfo,
+                len: usize,
+            ) -> Result<(), TransactionValidityError> {
+                $( $name::pre_dispatch_unsigned(call, info, len)?; )+
+                Ok(())
+            }
+
+            #[allow(non_snake_case)]
+            fn post_dispatch(
+                pre: Option<Self::Pre>,
+                info: &DispatchInfo,
+                post_info: &PostDispatchInfo,
+                len: usize,
+                result: &DispatchResult,
+            ) -> Result<(), TransactionValidityError> {
+                match pre {
+                    Some(( $( $name, )+ )) => {
+                        $( $name::post_dispatch(Some($name), info, post_info, len, result)?; )+
+                    }
+                    None => {
+                        $( $name::post_dispatch(None, info, post_info, len, result)?; )+
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_for_tuples!(A);
+impl_for_tuples!(A B);
+impl_for_tuples!(A B C);
+impl_for_tuples!(A B C D);
+impl_for_tuples!(A B C D E);
+impl_for_tuples!(A B C D E F);
+impl_for_tuples!(A B C D E F G);
+impl_for_tuples!(A B C D E F G H);
+
+/// An extrinsic whose signature can be checked, giving the extrinsic with its signer.
+///
+/// `Lookup` turns the address the extrinsic is signed by into an account id.
+pub trait Checkable<Lookup>: Sized {
+    type Checked;
+
+    fn check(self) -> Result<Self::Checked, TransactionValidityError>;
+}
+
+/// A checked extrinsic, which can be validated and applied.
+pub trait Applyable: Sized {
+    type Call;
+
+    /// Check the extrinsic, of encoded length `len`, without changing the state.
+    fn validate(&self, info: &DispatchInfo, len: usize) -> TransactionValidity;
+
+    /// Run the checks of the extrinsic and dispatch its call.
+    fn apply(
+        self,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<DispatchResultWithPostInfo, TransactionValidityError>;
+}
@@ -0,0 +1,71 @@
+//! Hooks which let pallets react to what happens in other pallets or in the block.
+
+use crate::weights::Weight;
+
+/// Called by the executive around the extrinsics of every block, in the order pallets are
+/// declared in the runtime.
+pub trait Hooks<BlockNumber> {
+    /// Called before the extrinsics of block `n`, returning the weight it used.
+    fn on_initialize(_n: BlockNumber) -> Weight {
+        Weight::zero()
+    }
+
+    /// Called after the extrinsics of block `n`.
+    fn on_finalize(_n: BlockNumber) {}
+}
+
+/// Called when an account is created, i.e. when something first allows it to exist.
+pub trait OnNewAccount<AccountId> {
+    fn on_new_account(who: &AccountId);
+}
+
+/// Called when an account is removed, i.e. when nothing allows it to exist anymore.
+pub trait OnKilledAccount<AccountId> {
+    fn on_killed_account(who: &AccountId);
+}
+
+macro_rules! impl_for_tuples {
+    ( $( $name:ident )* ) => {
+        impl<BlockNumber: Copy, $( $name: Hooks<BlockNumber> ),*> Hooks<BlockNumber>
+            for ( $( $name, )* )
+        {
+            #[allow(unused_variables)]
+            fn on_initialize(n: BlockNumber) -> Weight {
+                Weight::zero() $( .saturating_add($name::on_initialize(n)) )*
+            }
+
+            #[allow(unused_variables)]
+            fn on_finalize(n: BlockNumber) {
+                $( $name::on_finalize(n); )*
+            }
+        }
+
+        impl<AccountId, $( $name: OnNewAccount<AccountId> ),*> OnNewAccount<AccountId>
+            for ( $( $name, )* )
+        {
+            #[allow(unused_variables)]
+            fn on_new_account(who: &AccountId) {
+                $( $name::on_new_account(who); )*
+            }
+        }
+
+        impl<AccountId, $( $name: OnKilledAccount<AccountId> ),*> OnKilledAccount<AccountId>
+            for ( $( $name, )* )
+        {
+            #[allow(unused_variables)]
+            fn on_killed_account(who: &AccountId) {
+                $( $name::on_killed_account(who); )*
+            }
+        }
+    };
+}
+
+impl_for_tuples!();
+impl_for_tuples!(A);
+impl_for_tuples!(A B);
+impl_for_tuples!(A B C);
+impl_for_tuples!(A B C D);
+impl_for_tuples!(A B C D E);
+impl_for_tuples!(A B C D E F);
+impl_for_tuples!(A B C D E F G);
+impl_for_tuples!(A B C D E F G H);
@@ -0,0 +1,48 @@
+//! Traits converting the way a call names an account into the account.
+
+use crate::{
+    codec::{Decode, Encode},
+    dispatch::DispatchError,
+    scale_info::TypeInfo,
+};
+use std::{fmt::Debug, marker::PhantomData};
+
+/// The error of a lookup which found nothing.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LookupError;
+
+impl From<LookupError> for DispatchError {
+    fn from(_: LookupError) -> Self {
+        DispatchError::CannotLookup
+    }
+}
+
+/// Converts a `Source`, e.g. an address given as a call argument, into a `Target`, e.g. an
+/// account id, without reading storage.
+pub trait StaticLookup {
+    type Source: Encode + Decode + Clone + PartialEq + Eq + Debug + TypeInfo;
+    type Target;
+
+    fn lookup(s: Self::Source) -> Result<Self::Target, LookupError>;
+
+    /// The source naming `t`.
+    fn unlookup(t: Self::Target) -> Self::Source;
+}
+
+/// A lookup where the source is the target itself.
+pub struct IdentityLookup<T>(PhantomData<T>);
+
+impl<T: Encode + Decode + Clone + PartialEq + Eq + Debug + TypeInfo + 'static> StaticLookup
+    for IdentityLookup<T>
+{
+    type Source = T;
+    type Target = T;
+
+    fn lookup(s: T) -> Result<T, LookupError> {
+        Ok(s)
+    }
+
+    fn unlookup(t: T) -> T {
+        t
+    }
+}
@@ -0,0 +1,88 @@
+//! Traits describing a set of members, or a filter.
+
+use std::marker::PhantomData;
+
+/// A sorted list of members, e.g. the accounts allowed to make some call.
+pub trait SortedMembers<T: Ord> {
+    fn sorted_members() -> Vec<T>;
+
+    fn contains(t: &T) -> bool {
+        Self::sorted_members().binary_search(t).is_ok()
+    }
+
+    fn count() -> usize {
+        Self::sorted_members().len()
+    }
+}
+
+/// A filter: whether `T` is in some set.
+pub trait Contains<T> {
+    fn contains(t: &T) -> bool;
+}
+
+/// Contains everything.
+pub enum Everything {}
+
+impl<T> Contains<T> for Everything {
+    fn contains(_: &T) -> bool {
+        true
+    }
+}
+
+/// Contains nothing.
+pub enum Nothing {}
+
+impl<T> Contains<T> for Nothing {
+    fn contains(_: &T) -> bool {
+        false
+    }
+}
+
+/// Contains what both `A` and `B` contain.
+pub struct InsideBoth<A, B>(PhantomData<(A, B)>);
+
+impl<T, A: Contains<T>, B: Contains<T>> Contains<T> for InsideBoth<A, B> {
+    fn contains(t: &T) -> bool {
+        A::contains(t) && B::contains(t)
+    }
+}
+
+/// Contains what `These` contains, except what `Except` contains.
+pub struct TheseExcept<These, Except>(PhantomData<(These, Except)>);
+
+impl<T, These: Contains<T>, Except: Contains<T>> Contains<T> for TheseExcept<These, Except> {
+    fn contains(t: &T) -> bool {
+        These::contains(t) && !Except::contains(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Even;
+
+    impl Contains<u32> for Even {
+        fn contains(t: &u32) -> bool {
+            t.is_multiple_of(2)
+        }
+    }
+
+    struct Small;
+
+    impl Contains<u32> for Small {
+        fn contains(t: &u32) -> bool {
+            *t < 10
+        }
+    }
+
+    #[test]
+    fn filters_combine() {
+        assert!(Everything::contains(&1) && !Nothing::contains(&1));
+        assert!(InsideBoth::<Even, Small>::contains(&4));
+        assert!(!InsideBoth::<Even, Small>::contains(&12));
+        assert!(TheseExcept::<Even, Small>::contains(&12));
+        assert!(!TheseExcept::<Even, Small>::contains(&4));
+        assert!(!TheseExcept::<Everything, Everything>::contains(&4));
+    }
+}
@@ -0,0 +1,73 @@
+//! Small traits used all around the pallets.
+
+use std::fmt::Debug;
+
+/// What a type needs to be stored in pallet structures, e.g. as a configuration type.
+pub trait Member: Sized + Clone + Eq + Debug + 'static {}
+
+impl<T: Sized + Clone + Eq + Debug + 'static> Member for T {}
+
+/// `Self` is `T` under another name, e.g. the event type of a pallet configuration which must
+/// be the `RuntimeEvent` of `frame_system`.
+pub trait IsType<T>: Into<T> + From<T> {}
+
+impl<T> IsType<T> for T {}
+
+/// Where the pallets are in the runtime, `PalletInfo` as generated by `construct_runtime!`.
+pub trait PalletInfo {
+    /// The index of the pallet `P` in the runtime, `None` if it is not part of it.
+    fn index<P: 'static>() -> Option<usize>;
+    /// The name of the pallet `P` in the runtime, `None` if it is not part of it.
+    fn name<P: 'static>() -> Option<&'static str>;
+}
+
+/// A value known by a type, e.g. a constant of a pallet configuration.
+pub trait Get<T> {
+    fn get() -> T;
+}
+
+impl<T: Default> Get<T> for () {
+    fn get() -> T {
+        T::default()
+    }
+}
+
+/// Implements [`Get`] for any type by returning its default value.
+pub struct GetDefault;
+
+impl<T: Default> Get<T> for GetDefault {
+    fn get() -> T {
+        T::default()
+    }
+}
+
+macro_rules! impl_const_get {
+    ( $( $name:ident: $t:ty ),* ) => { $(
+        #[doc = concat!("Implements [`Get`] for the `", stringify!($t), "` constant `T`.")]
+        #[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+        pub struct $name<const T: $t>;
+
+        impl<const T: $t> Get<$t> for $name<T> {
+            fn get() -> $t {
+                T
+            }
+        }
+
+        impl<const T: $t> Get<Option<$t>> for $name<T> {
+            fn get() -> Option<$t> {
+                Some(T)
+            }
+        }
+    )* };
+}
+
+impl_const_get!(
+    ConstBool: bool,
+    ConstU8: u8,
+    ConstU16: u16,
+    ConstU32: u32,
+    ConstU64: u64,
+    ConstU128: u128,
+    ConstI32: i32,
+    ConstI64: i64
+);
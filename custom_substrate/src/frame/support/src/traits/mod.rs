@@ -0,0 +1,33 @@
+//! Traits shared between pallets and the runtime.
+
+mod dispatch;
+mod extrinsic;
+mod hooks;
+mod lookup;
+mod members;
+mod misc;
+mod storage;
+pub mod tokens;
+
+pub use dispatch::{
+    Dispatchable, EitherOf, EnsureNone, EnsureOrigin, EnsureRoot, EnsureSigned, EnsureSignedBy,
+    OriginTrait, UnfilteredDispatchable,
+};
+pub use extrinsic::{Applyable, Checkable, IdentifyAccount, SignedExtension, Verify};
+pub use hooks::{Hooks, OnKilledAccount, OnNewAccount};
+pub use lookup::{IdentityLookup, LookupError, StaticLookup};
+pub use members::{Contains, Everything, InsideBoth, Nothing, SortedMembers, TheseExcept};
+
+pub use misc::{
+    ConstBool, ConstI32, ConstI64, ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, Get,
+    GetDefault, IsType, Member, PalletInfo,
+};
+pub use storage::{PartialStorageInfoTrait, StorageInfo, StorageInfoTrait};
+pub use tokens::{
+    currency::{
+        Currency, LockIdentifier, LockableCurrency, NamedReservableCurrency, ReservableCurrency,
+    },
+    fungible,
+    imbalance::{Imbalance, OnUnbalanced, SameOrOther, SignedImbalance, TryDrop},
+    BalanceStatus, DepositConsequence, ExistenceRequirement, WithdrawConsequence, WithdrawReasons,
+};
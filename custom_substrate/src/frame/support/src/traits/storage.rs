@@ -0,0 +1,67 @@
+//! Information about the storage of pallets.
+
+/// Worst case description of a storage item, used to bound the size of storage proofs.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StorageInfo {
+    /// Prefix of the pallet the item belongs to.
+    pub pallet_name: Vec<u8>,
+    /// Name of the item in its pallet.
+    pub storage_name: Vec<u8>,
+    /// The prefix shared by every key of the item.
+    pub prefix: Vec<u8>,
+    /// Maximum number of values the item can hold, `None` if unbounded.
+    pub max_values: Option<u32>,
+    /// Maximum size of a key plus its value, `None` if unbounded.
+    pub max_size: Option<u32>,
+}
+
+impl StorageInfo {
+    /// The worst case number of bytes all values of this item take together, `None` when
+    /// either the number of values or their size is unbounded.
+    pub fn max_total_size(&self) -> Option<u64> {
+        Some(u64::from(self.max_values?) * u64::from(self.max_size?))
+    }
+}
+
+/// Storage information of a storage item or a pallet.
+///
+/// Only implemented by storage items whose keys and values have a [`MaxEncodedLen`], so a
+/// pallet can not provide it for an unbounded item.
+///
+/// [`MaxEncodedLen`]: crate::codec::MaxEncodedLen
+pub trait StorageInfoTrait {
+    fn storage_info() -> Vec<StorageInfo>;
+}
+
+/// Like [`StorageInfoTrait`] but also implemented by unbounded storage items, which then
+/// report `None` for the bounds they don't have.
+pub trait PartialStorageInfoTrait {
+    fn partial_storage_info() -> Vec<StorageInfo>;
+}
+
+macro_rules! impl_for_tuples {
+    ( $( $name:ident )* ) => {
+        impl<$( $name: StorageInfoTrait ),*> StorageInfoTrait for ( $( $name, )* ) {
+            #[allow(unused_mut)]
+            fn storage_info() -> Vec<StorageInfo> {
+                let mut res = Vec::new();
+                $( res.extend($name::storage_info()); )*
+                res
+            }
+        }
+    };
+}
+
+impl_for_tuples!();
+impl_for_tuples!(A);
+impl_for_tuples!(A B);
+impl_for_tuples!(A B C);
+impl_for_tuples!(A B C D);
+impl_for_tuples!(A B C D E);
+impl_for_tuples!(A B C D E F);
+impl_for_tuples!(A B C D E F G);
+impl_for_tuples!(A B C D E F G H);
+impl_for_tuples!(A B C D E F G H J);
+impl_for_tuples!(A B C D E F G H J K);
+impl_for_tuples!(A B C D E F G H J K L);
+impl_for_tuples!(A B C D E F G H J K L M);
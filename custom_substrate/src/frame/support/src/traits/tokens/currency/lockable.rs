@@ -0,0 +1,39 @@
+//! Traits of a currency whose funds can be locked.
+
+use super::Currency;
+use crate::traits::{tokens::WithdrawReasons, Get};
+
+/// The name of a lock, e.g. that of the pallet holding it.
+pub type LockIdentifier = [u8; 8];
+
+/// A currency where part of the free balance of an account can be locked, e.g. while it stakes
+/// or votes. Unlike reserved funds, locked funds stay free: locks overlap instead of adding up,
+/// and only keep the free balance from going below the largest of them.
+pub trait LockableCurrency<AccountId>: Currency<AccountId> {
+    /// The time a lock may be given for, e.g. a block number.
+    type Moment;
+
+    /// The maximum number of locks on an account.
+    type MaxLocks: Get<u32>;
+
+    /// Lock `amount` of the free balance of `who` against withdrawals for `reasons`, replacing
+    /// any lock named `id`. A lock of zero, or for no reasons, removes it.
+    fn set_lock(
+        id: LockIdentifier,
+        who: &AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons,
+    );
+
+    /// Extend the lock named `id` to at least `amount` and `reasons`, or set it if there is no
+    /// such lock.
+    fn extend_lock(
+        id: LockIdentifier,
+        who: &AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons,
+    );
+
+    /// Remove the lock named `id`.
+    fn remove_lock(id: LockIdentifier, who: &AccountId);
+}
@@ -0,0 +1,138 @@
+//! The traits of a currency, through which pallets move funds without knowing how the balances
+//! are kept.
+
+mod lockable;
+mod reservable;
+
+pub use lockable::{LockIdentifier, LockableCurrency};
+pub use reservable::{NamedReservableCurrency, ReservableCurrency};
+
+use super::{
+    imbalance::{Imbalance, SignedImbalance},
+    Balance, ExistenceRequirement, WithdrawReasons,
+};
+use crate::dispatch::{DispatchError, DispatchResult};
+
+/// A currency whose funds are created and destroyed through imbalances, so that its total
+/// issuance always is the sum of the balances.
+pub trait Currency<AccountId> {
+    /// The balance of an account.
+    type Balance: Balance;
+
+    /// Funds created but not deposited anywhere yet.
+    type PositiveImbalance: Imbalance<Self::Balance, Opposite = Self::NegativeImbalance>;
+
+    /// Funds destroyed but not withdrawn from anywhere yet.
+    type NegativeImbalance: Imbalance<Self::Balance, Opposite = Self::PositiveImbalance>;
+
+    /// The whole balance of `who`, free and reserved.
+    fn total_balance(who: &AccountId) -> Self::Balance;
+
+    /// Whether `value` could be slashed from `who`.
+    fn can_slash(who: &AccountId, value: Self::Balance) -> bool;
+
+    /// The sum of the balances of all accounts.
+    fn total_issuance() -> Self::Balance;
+
+    /// The balance below which an account is reaped.
+    fn minimum_balance() -> Self::Balance;
+
+    /// Take `amount` out of the total issuance, giving back the funds to deposit somewhere.
+    fn burn(amount: Self::Balance) -> Self::PositiveImbalance;
+
+    /// Add `amount` to the total issuance, giving back the funds to withdraw from somewhere.
+    fn issue(amount: Self::Balance) -> Self::NegativeImbalance;
+
+    /// Imbalances of `amount` going both ways, which cancel out.
+    fn pair(amount: Self::Balance) -> (Self::PositiveImbalance, Self::NegativeImbalance) {
+        (Self::burn(amount), Self::issue(amount))
+    }
+
+    /// The balance of `who` which is not reserved, and so can be spent unless locked.
+    fn free_balance(who: &AccountId) -> Self::Balance;
+
+    /// Whether the free balance of `who` may go down to `new_balance` for `reasons`, i.e.
+    /// whether no lock holds the funds for any of them.
+    fn ensure_can_withdraw(
+        who: &AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons,
+        new_balance: Self::Balance,
+    ) -> DispatchResult;
+
+    /// Move `value` of the free balance of `source` to `dest`, keeping `source` alive as told.
+    fn transfer(
+        source: &AccountId,
+        dest: &AccountId,
+        value: Self::Balance,
+        existence_requirement: ExistenceRequirement,
+    ) -> DispatchResult;
+
+    /// Take up to `value` from `who`, free balance first, returning the funds taken and the
+    /// part of `value` which could not be.
+    fn slash(who: &AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance);
+
+    /// Add `value` to the free balance of `who`, failing if the account does not exist.
+    fn deposit_into_existing(
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> Result<Self::PositiveImbalance, DispatchError>;
+
+    /// Deposit the funds of `value` into `who`, giving them back if the account does not exist.
+    fn resolve_into_existing(
+        who: &AccountId,
+        value: Self::NegativeImbalance,
+    ) -> Result<(), Self::NegativeImbalance> {
+        let amount = value.peek();
+        match Self::deposit_into_existing(who, amount) {
+            Ok(deposited) => {
+                drop(value.offset(deposited));
+                Ok(())
+            },
+            Err(_) => Err(value),
+        }
+    }
+
+    /// Add `value` to the free balance of `who`, creating the account if needed. Nothing is
+    /// deposited if that fails, e.g. if `value` is below the minimum balance.
+    fn deposit_creating(who: &AccountId, value: Self::Balance) -> Self::PositiveImbalance;
+
+    /// Deposit the funds of `value` into `who`, creating the account if needed.
+    fn resolve_creating(who: &AccountId, value: Self::NegativeImbalance) {
+        let amount = value.peek();
+        drop(value.offset(Self::deposit_creating(who, amount)));
+    }
+
+    /// Take `value` from the free balance of `who` for `reasons`, leaving the account alive as
+    /// told.
+    fn withdraw(
+        who: &AccountId,
+        value: Self::Balance,
+        reasons: WithdrawReasons,
+        liveness: ExistenceRequirement,
+    ) -> Result<Self::NegativeImbalance, DispatchError>;
+
+    /// Withdraw the funds of `value` from `who`, giving them back if that fails.
+    fn settle(
+        who: &AccountId,
+        value: Self::PositiveImbalance,
+        reasons: WithdrawReasons,
+        liveness: ExistenceRequirement,
+    ) -> Result<(), Self::PositiveImbalance> {
+        let amount = value.peek();
+        match Self::withdraw(who, amount, reasons, liveness) {
+            Ok(withdrawn) => {
+                drop(value.offset(withdrawn));
+                Ok(())
+            },
+            Err(_) => Err(value),
+        }
+    }
+
+    /// Set the free balance of `who` to `balance`, creating or reaping the account as needed,
+    /// and return the funds created or destroyed.
+    fn make_free_balance_be(
+        who: &AccountId,
+        balance: Self::Balance,
+    ) -> SignedImbalance<Self::Balance, Self::PositiveImbalance>;
+}
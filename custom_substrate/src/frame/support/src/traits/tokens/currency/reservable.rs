@@ -0,0 +1,131 @@
+//! Traits of a currency whose funds can be set aside.
+
+use super::Currency;
+use crate::{
+    codec::MaxEncodedLen,
+    dispatch::{DispatchError, DispatchResult, Parameter},
+    traits::{tokens::BalanceStatus, Member},
+};
+
+/// A currency where funds of an account can be reserved, e.g. as the deposit of something it
+/// holds, so that they can't be spent until unreserved.
+pub trait ReservableCurrency<AccountId>: Currency<AccountId> {
+    /// Whether `value` of the free balance of `who` can be reserved.
+    fn can_reserve(who: &AccountId, value: Self::Balance) -> bool;
+
+    /// Destroy up to `value` of the reserved balance of `who`, returning the funds taken and the
+    /// part of `value` which was not reserved.
+    fn slash_reserved(
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> (Self::NegativeImbalance, Self::Balance);
+
+    /// The amount of the balance of `who` which is reserved.
+    fn reserved_balance(who: &AccountId) -> Self::Balance;
+
+    /// Move `value` from the free to the reserved balance of `who`, failing if the free balance
+    /// is too low.
+    fn reserve(who: &AccountId, value: Self::Balance) -> DispatchResult;
+
+    /// Move up to `value` from the reserved to the free balance of `who`, returning the part of
+    /// `value` which was not reserved.
+    fn unreserve(who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+    /// Move up to `value` of the reserved balance of `slashed` to the balance of `beneficiary`,
+    /// free or reserved according to `status`, returning the part of `value` which was not
+    /// reserved.
+    fn repatriate_reserved(
+        slashed: &AccountId,
+        beneficiary: &AccountId,
+        value: Self::Balance,
+        status: BalanceStatus,
+    ) -> Result<Self::Balance, DispatchError>;
+}
+
+/// A currency where funds are reserved under a name, e.g. the pallet reserving them, so that
+/// each name only ever gives back what it reserved.
+pub trait NamedReservableCurrency<AccountId>: ReservableCurrency<AccountId> {
+    /// The name of a reserve.
+    type ReserveIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+
+    /// The amount of the balance of `who` reserved under `id`.
+    fn reserved_balance_named(id: &Self::ReserveIdentifier, who: &AccountId) -> Self::Balance;
+
+    /// Reserve `value` of the free balance of `who` under `id`.
+    fn reserve_named(
+        id: &Self::ReserveIdentifier,
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> DispatchResult;
+
+    /// Unreserve up to `value` reserved by `who` under `id`, returning the part of `value` which
+    /// was not reserved under it.
+    fn unreserve_named(
+        id: &Self::ReserveIdentifier,
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> Self::Balance;
+
+    /// Destroy up to `value` reserved by `who` under `id`, returning the funds taken and the
+    /// part of `value` which was not reserved under it.
+    fn slash_reserved_named(
+        id: &Self::ReserveIdentifier,
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> (Self::NegativeImbalance, Self::Balance);
+
+    /// Move up to `value` reserved by `slashed` under `id` to `beneficiary`, reserved under the
+    /// same name if `status` is reserved, returning the part of `value` which was not reserved
+    /// under it.
+    fn repatriate_reserved_named(
+        id: &Self::ReserveIdentifier,
+        slashed: &AccountId,
+        beneficiary: &AccountId,
+        value: Self::Balance,
+        status: BalanceStatus,
+    ) -> Result<Self::Balance, DispatchError>;
+
+    /// Reserve or unreserve under `id` so that exactly `value` is reserved under it.
+    fn ensure_reserved_named(
+        id: &Self::ReserveIdentifier,
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> DispatchResult {
+        let current = Self::reserved_balance_named(id, who);
+        if current > value {
+            Self::unreserve_named(id, who, current - value);
+            Ok(())
+        } else if value > current {
+            Self::reserve_named(id, who, value - current)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Unreserve everything reserved by `who` under `id`, returning the amount.
+    fn unreserve_all_named(id: &Self::ReserveIdentifier, who: &AccountId) -> Self::Balance {
+        let value = Self::reserved_balance_named(id, who);
+        Self::unreserve_named(id, who, value);
+        value
+    }
+
+    /// Destroy everything reserved by `who` under `id`.
+    fn slash_all_reserved_named(
+        id: &Self::ReserveIdentifier,
+        who: &AccountId,
+    ) -> Self::NegativeImbalance {
+        let value = Self::reserved_balance_named(id, who);
+        Self::slash_reserved_named(id, who, value).0
+    }
+
+    /// Move everything reserved by `slashed` under `id` to `beneficiary`.
+    fn repatriate_all_reserved_named(
+        id: &Self::ReserveIdentifier,
+        slashed: &AccountId,
+        beneficiary: &AccountId,
+        status: BalanceStatus,
+    ) -> DispatchResult {
+        let value = Self::reserved_balance_named(id, slashed);
+        Self::repatriate_reserved_named(id, slashed, beneficiary, value, status).map(|_| ())
+    }
+}
@@ -0,0 +1,37 @@
+//! Holding funds of a fungible asset, i.e. setting them aside in the account.
+
+use super::{Inspect, Transfer};
+use crate::dispatch::{DispatchError, DispatchResult};
+
+/// Reading the funds on hold.
+pub trait InspectHold<AccountId>: Inspect<AccountId> {
+    /// The amount of the balance of `who` which is on hold.
+    fn balance_on_hold(who: &AccountId) -> Self::Balance;
+
+    /// Whether `amount` of the balance of `who` could be put on hold.
+    fn can_hold(who: &AccountId, amount: Self::Balance) -> bool;
+}
+
+/// Putting funds on hold and taking them off.
+pub trait MutateHold<AccountId>: InspectHold<AccountId> + Transfer<AccountId> {
+    /// Put `amount` of the balance of `who` on hold, or fail without any change.
+    fn hold(who: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+    /// Take `amount` of the balance of `who` off hold, or as much as possible of it if
+    /// `best_effort`. Returns the amount released.
+    fn release(
+        who: &AccountId,
+        amount: Self::Balance,
+        best_effort: bool,
+    ) -> Result<Self::Balance, DispatchError>;
+
+    /// Move `amount` held by `source` to `dest`, or as much as possible of it if `best_effort`,
+    /// where it is on hold again if `on_hold`. Returns the amount moved.
+    fn transfer_held(
+        source: &AccountId,
+        dest: &AccountId,
+        amount: Self::Balance,
+        best_effort: bool,
+        on_hold: bool,
+    ) -> Result<Self::Balance, DispatchError>;
+}
@@ -0,0 +1,158 @@
+//! The traits of a single fungible asset, which tell what would become of a deposit or a
+//! withdrawal before it is made.
+//!
+//! Unlike a [`Currency`](super::currency::Currency), these move funds straight away rather than
+//! through imbalances: minting and burning change the total issuance at once.
+
+mod hold;
+
+pub use hold::{InspectHold, MutateHold};
+
+use super::{Balance, DepositConsequence, WithdrawConsequence};
+use crate::{
+    arithmetic::traits::AtLeast32BitUnsigned,
+    dispatch::{ArithmeticError, DispatchError, DispatchResult, TokenError},
+};
+use num::{CheckedAdd, Saturating, Zero};
+
+/// Reading the balances of a fungible asset.
+pub trait Inspect<AccountId> {
+    /// The balance of an account.
+    type Balance: Balance;
+
+    /// The sum of the balances of all accounts.
+    fn total_issuance() -> Self::Balance;
+
+    /// The balance below which an account is reaped.
+    fn minimum_balance() -> Self::Balance;
+
+    /// The whole balance of `who`.
+    fn balance(who: &AccountId) -> Self::Balance;
+
+    /// The part of the balance of `who` which can be withdrawn, leaving the account alive if
+    /// `keep_alive`.
+    fn reducible_balance(who: &AccountId, keep_alive: bool) -> Self::Balance;
+
+    /// What would become of depositing `amount` into `who`, minting it if `mint`.
+    fn can_deposit(who: &AccountId, amount: Self::Balance, mint: bool) -> DepositConsequence;
+
+    /// What would become of withdrawing `amount` from `who`.
+    fn can_withdraw(who: &AccountId, amount: Self::Balance) -> WithdrawConsequence<Self::Balance>;
+}
+
+/// Minting and burning a fungible asset.
+pub trait Mutate<AccountId>: Inspect<AccountId> {
+    /// Create `amount` in the balance of `who`, or fail without any change.
+    fn mint_into(who: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+    /// Destroy `amount` of the balance of `who`, or fail without any change. Returns the amount
+    /// destroyed, which is more than `amount` if the account was left below the minimum balance
+    /// and reaped.
+    fn burn_from(who: &AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError>;
+
+    /// Destroy as much as possible of `amount` of the balance of `who`, returning the amount
+    /// destroyed.
+    fn slash(who: &AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+        let actual = Self::reducible_balance(who, false).min(amount);
+        Self::burn_from(who, actual)
+    }
+}
+
+/// Transferring a fungible asset between accounts.
+pub trait Transfer<AccountId>: Inspect<AccountId> {
+    /// Move `amount` from `source` to `dest`, leaving `source` alive if `keep_alive`. Returns
+    /// the amount moved.
+    fn transfer(
+        source: &AccountId,
+        dest: &AccountId,
+        amount: Self::Balance,
+        keep_alive: bool,
+    ) -> Result<Self::Balance, DispatchError>;
+}
+
+/// Setting the balances of a fungible asset without keeping the total issuance, which the caller
+/// must then do, e.g. to implement [`Mutate`] on top.
+pub trait Unbalanced<AccountId>: Inspect<AccountId> {
+    /// Set the whole balance of `who` to `amount`.
+    fn set_balance(who: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+    /// Set the total issuance to `amount`.
+    fn set_total_issuance(amount: Self::Balance);
+
+    /// Take `amount` from the balance of `who`, reaping the account if it is left below the
+    /// minimum balance. Returns the amount taken, or fails without any change.
+    fn decrease_balance(
+        who: &AccountId,
+        amount: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        if Self::reducible_balance(who, false) < amount {
+            return Err(TokenError::NoFunds.into())
+        }
+        let (new_balance, amount) = reap_below_minimum::<Self::Balance>(
+            Self::balance(who) - amount,
+            amount,
+            Self::minimum_balance(),
+        );
+        Self::set_balance(who, new_balance)?;
+        Ok(amount)
+    }
+
+    /// Take as much as possible of `amount` from the balance of `who`, reaping the account if
+    /// it is left below the minimum balance. Returns the amount taken.
+    fn decrease_balance_at_most(who: &AccountId, amount: Self::Balance) -> Self::Balance {
+        let amount = amount.min(Self::reducible_balance(who, false));
+        let (new_balance, amount) = reap_below_minimum::<Self::Balance>(
+            Self::balance(who) - amount,
+            amount,
+            Self::minimum_balance(),
+        );
+        match Self::set_balance(who, new_balance) {
+            Ok(()) => amount,
+            Err(_) => Zero::zero(),
+        }
+    }
+
+    /// Add `amount` to the balance of `who`. Returns the amount added, or fails without any
+    /// change if the balance would overflow or stay below the minimum balance.
+    fn increase_balance(
+        who: &AccountId,
+        amount: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        let old_balance = Self::balance(who);
+        let new_balance = old_balance.checked_add(&amount).ok_or(ArithmeticError::Overflow)?;
+        if new_balance < Self::minimum_balance() {
+            return Err(TokenError::BelowMinimum.into())
+        }
+        if new_balance != old_balance {
+            Self::set_balance(who, new_balance)?;
+        }
+        Ok(amount)
+    }
+
+    /// Add as much as possible of `amount` to the balance of `who`. Returns the amount added.
+    fn increase_balance_at_most(who: &AccountId, amount: Self::Balance) -> Self::Balance {
+        let old_balance = Self::balance(who);
+        let new_balance = old_balance.saturating_add(amount);
+        if new_balance < Self::minimum_balance() || new_balance == old_balance {
+            return Zero::zero()
+        }
+        match Self::set_balance(who, new_balance) {
+            Ok(()) => new_balance - old_balance,
+            Err(_) => Zero::zero(),
+        }
+    }
+}
+
+/// The balance left and the amount taken by a decrease to `new_balance`: all of it is taken if
+/// it is below `minimum_balance`.
+fn reap_below_minimum<B: AtLeast32BitUnsigned>(
+    new_balance: B,
+    amount: B,
+    minimum_balance: B,
+) -> (B, B) {
+    if new_balance < minimum_balance {
+        (Zero::zero(), amount.saturating_add(new_balance))
+    } else {
+        (new_balance, amount)
+    }
+}
@@ -0,0 +1,284 @@
+//! Imbalances: funds created or destroyed which the total issuance does not account for yet.
+//!
+//! A currency hands out an imbalance instead of changing its total issuance straight away, e.g.
+//! a slash gives back what it took from the account. The imbalance may be merged with others,
+//! split, or offset against one going the other way, and only when it is dropped does the total
+//! issuance follow. Whatever is done with the funds, they are thus accounted for exactly once.
+
+/// A value which can be dropped if it is empty, and is otherwise given back.
+pub trait TryDrop: Sized {
+    /// Drop `self` if it is empty, otherwise give it back.
+    fn try_drop(self) -> Result<(), Self>;
+}
+
+impl TryDrop for () {
+    fn try_drop(self) -> Result<(), Self> {
+        Ok(())
+    }
+}
+
+/// One of two values going opposite ways, or none of them if they cancelled out.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SameOrOther<A, B> {
+    /// Nothing is left.
+    None,
+    /// What is left goes the same way.
+    Same(A),
+    /// What is left goes the other way.
+    Other(B),
+}
+
+impl<A, B> SameOrOther<A, B> {
+    /// The value going the same way, if that is what is left.
+    pub fn try_same(self) -> Result<A, Self> {
+        match self {
+            SameOrOther::Same(a) => Ok(a),
+            other => Err(other),
+        }
+    }
+
+    /// The value going the other way, if that is what is left.
+    pub fn try_other(self) -> Result<B, Self> {
+        match self {
+            SameOrOther::Other(b) => Ok(b),
+            other => Err(other),
+        }
+    }
+
+    /// Whether nothing is left.
+    pub fn try_none(self) -> Result<(), Self> {
+        match self {
+            SameOrOther::None => Ok(()),
+            other => Err(other),
+        }
+    }
+
+    /// The value going the same way, which is the default one if nothing is left, or else the
+    /// value going the other way.
+    pub fn same(self) -> Result<A, B>
+    where
+        A: Default,
+    {
+        match self {
+            SameOrOther::None => Ok(A::default()),
+            SameOrOther::Same(a) => Ok(a),
+            SameOrOther::Other(b) => Err(b),
+        }
+    }
+}
+
+/// An amount of `Balance` created or destroyed, which changes the total issuance of the currency
+/// when it is dropped.
+///
+/// The implementations must not change the total issuance anywhere else than when dropped: e.g.
+/// merging two imbalances gives one which changes it by their sum, once.
+pub trait Imbalance<Balance>: Sized + TryDrop + Default {
+    /// The imbalance going the other way.
+    type Opposite: Imbalance<Balance>;
+
+    /// An imbalance of nothing.
+    fn zero() -> Self;
+
+    /// Drop the imbalance if it is zero, otherwise give it back.
+    fn drop_zero(self) -> Result<(), Self>;
+
+    /// Split into an imbalance of up to `amount` and one of the rest.
+    fn split(self, amount: Balance) -> (Self, Self);
+
+    /// Merge with an imbalance going the same way.
+    fn merge(self, other: Self) -> Self;
+
+    /// Merge an imbalance going the same way into `self`.
+    fn subsume(&mut self, other: Self);
+
+    /// Cancel out against an imbalance going the other way, leaving whatever is left of the
+    /// larger one.
+    fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite>;
+
+    /// The amount of the imbalance.
+    fn peek(&self) -> Balance;
+
+    /// Merge with `other`, if any.
+    fn maybe_merge(self, other: Option<Self>) -> Self {
+        match other {
+            Some(other) => self.merge(other),
+            None => self,
+        }
+    }
+
+    /// Merge `self` into `other`.
+    fn merge_into(self, other: &mut Self) {
+        other.subsume(self)
+    }
+}
+
+/// An imbalance which may go either way, e.g. what is left of setting a balance to a value.
+pub enum SignedImbalance<Balance, PositiveImbalance: Imbalance<Balance>> {
+    /// Funds were created.
+    Positive(PositiveImbalance),
+    /// Funds were destroyed.
+    Negative(PositiveImbalance::Opposite),
+}
+
+impl<Balance, P, N> SignedImbalance<Balance, P>
+where
+    P: Imbalance<Balance, Opposite = N>,
+    N: Imbalance<Balance, Opposite = P>,
+{
+    /// An imbalance of nothing.
+    pub fn zero() -> Self {
+        SignedImbalance::Positive(P::zero())
+    }
+
+    /// Drop the imbalance if it is zero, otherwise give it back.
+    pub fn drop_zero(self) -> Result<(), Self> {
+        match self {
+            SignedImbalance::Positive(p) => p.drop_zero().map_err(SignedImbalance::Positive),
+            SignedImbalance::Negative(n) => n.drop_zero().map_err(SignedImbalance::Negative),
+        }
+    }
+
+    /// Merge with another signed imbalance, offsetting them if they go opposite ways.
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (SignedImbalance::Positive(one), SignedImbalance::Positive(other)) => {
+                SignedImbalance::Positive(one.merge(other))
+            },
+            (SignedImbalance::Negative(one), SignedImbalance::Negative(other)) => {
+                SignedImbalance::Negative(one.merge(other))
+            },
+            (SignedImbalance::Positive(one), SignedImbalance::Negative(other)) => {
+                match one.offset(other) {
+                    SameOrOther::Same(positive) => SignedImbalance::Positive(positive),
+                    SameOrOther::Other(negative) => SignedImbalance::Negative(negative),
+                    SameOrOther::None => SignedImbalance::zero(),
+                }
+            },
+            (SignedImbalance::Negative(one), SignedImbalance::Positive(other)) => {
+                match one.offset(other) {
+                    SameOrOther::Same(negative) => SignedImbalance::Negative(negative),
+                    SameOrOther::Other(positive) => SignedImbalance::Positive(positive),
+                    SameOrOther::None => SignedImbalance::zero(),
+                }
+            },
+        }
+    }
+}
+
+/// What becomes of an imbalance nobody else took, e.g. the fees of a transaction or the dust of
+/// a reaped account.
+pub trait OnUnbalanced<Imbalance: TryDrop> {
+    /// Handle `amount`, unless it is empty.
+    fn on_unbalanced(amount: Imbalance) {
+        amount.try_drop().unwrap_or_else(Self::on_nonzero_unbalanced)
+    }
+
+    /// Handle `amount`, which is not empty. By default it is dropped, so that the total
+    /// issuance follows.
+    fn on_nonzero_unbalanced(amount: Imbalance) {
+        drop(amount);
+    }
+}
+
+impl<Imbalance: TryDrop> OnUnbalanced<Imbalance> for () {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static HANDLED: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Default, PartialEq, Debug)]
+    struct Up(u64);
+
+    #[derive(Default, PartialEq, Debug)]
+    struct Down(u64);
+
+    macro_rules! impl_test_imbalance {
+        ($name:ident, $opposite:ident) => {
+            impl TryDrop for $name {
+                fn try_drop(self) -> Result<(), Self> {
+                    self.drop_zero()
+                }
+            }
+
+            impl Imbalance<u64> for $name {
+                type Opposite = $opposite;
+
+                fn zero() -> Self {
+                    $name(0)
+                }
+
+                fn drop_zero(self) -> Result<(), Self> {
+                    if self.0 == 0 { Ok(()) } else { Err(self) }
+                }
+
+                fn split(self, amount: u64) -> (Self, Self) {
+                    let first = self.0.min(amount);
+                    ($name(first), $name(self.0 - first))
+                }
+
+                fn merge(self, other: Self) -> Self {
+                    $name(self.0 + other.0)
+                }
+
+                fn subsume(&mut self, other: Self) {
+                    self.0 += other.0
+                }
+
+                fn offset(self, other: $opposite) -> SameOrOther<Self, $opposite> {
+                    match self.0.cmp(&other.0) {
+                        std::cmp::Ordering::Greater => SameOrOther::Same($name(self.0 - other.0)),
+                        std::cmp::Ordering::Less => SameOrOther::Other($opposite(other.0 - self.0)),
+                        std::cmp::Ordering::Equal => SameOrOther::None,
+                    }
+                }
+
+                fn peek(&self) -> u64 {
+                    self.0
+                }
+            }
+        };
+    }
+
+    impl_test_imbalance!(Up, Down);
+    impl_test_imbalance!(Down, Up);
+
+    struct Record;
+
+    impl OnUnbalanced<Up> for Record {
+        fn on_nonzero_unbalanced(amount: Up) {
+            HANDLED.with(|handled| handled.borrow_mut().push(amount.peek()));
+        }
+    }
+
+    #[test]
+    fn offset_leaves_the_larger_side() {
+        assert_eq!(Up(5).offset(Down(3)), SameOrOther::Same(Up(2)));
+        assert_eq!(Up(3).offset(Down(5)), SameOrOther::Other(Down(2)));
+        assert_eq!(Up(3).offset(Down(3)), SameOrOther::None);
+        assert_eq!(SameOrOther::<Up, Down>::None.same(), Ok(Up(0)));
+        assert_eq!(Up(3).offset(Down(5)).try_other(), Ok(Down(2)));
+    }
+
+    #[test]
+    fn signed_imbalances_merge_across_signs() {
+        let merged = SignedImbalance::Positive(Up(2)).merge(SignedImbalance::Negative(Down(7)));
+        assert!(matches!(merged, SignedImbalance::Negative(Down(5))));
+        let merged = merged.merge(SignedImbalance::Positive(Up(5)));
+        assert!(merged.drop_zero().is_ok());
+        assert!(SignedImbalance::<u64, Up>::Negative(Down(1)).drop_zero().is_err());
+    }
+
+    #[test]
+    fn handlers_only_see_nonzero_imbalances() {
+        Record::on_unbalanced(Up(0));
+        Record::on_unbalanced(Up(4));
+        <() as OnUnbalanced<Up>>::on_unbalanced(Up(9));
+        assert_eq!(HANDLED.with(|handled| handled.borrow().clone()), vec![4]);
+        assert_eq!(Up(4).maybe_merge(Some(Up(1))).split(2), (Up(2), Up(3)));
+    }
+}
@@ -0,0 +1,207 @@
+//! Traits for the balances of accounts, implemented by the balances pallet.
+
+pub mod currency;
+pub mod fungible;
+pub mod imbalance;
+
+use crate::{
+    arithmetic::{traits::AtLeast32BitUnsigned, FixedPointOperand},
+    codec::{Decode, Encode, MaxEncodedLen},
+    dispatch::{ArithmeticError, DispatchError, DispatchResult, Parameter, TokenError},
+    scale_info::TypeInfo,
+    traits::Member,
+};
+use std::ops::{BitOr, BitOrAssign};
+
+/// What the balance of a currency must be.
+pub trait Balance:
+    Parameter + Member + AtLeast32BitUnsigned + Default + MaxEncodedLen + FixedPointOperand
+{
+}
+
+impl<T> Balance for T where
+    T: Parameter + Member + AtLeast32BitUnsigned + Default + MaxEncodedLen + FixedPointOperand
+{
+}
+
+/// Whether funds moved to another account are free or reserved there.
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BalanceStatus {
+    Free,
+    Reserved,
+}
+
+/// Whether an account may be reaped by taking funds from it.
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExistenceRequirement {
+    /// The account must be left with at least the minimum balance.
+    KeepAlive,
+    /// The account may be reaped.
+    AllowDeath,
+}
+
+/// The reasons funds are withdrawn for, which locks may hold them against. A set of flags
+/// combined with `|`.
+#[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WithdrawReasons(u8);
+
+impl WithdrawReasons {
+    /// To pay for a transaction.
+    pub const TRANSACTION_PAYMENT: Self = WithdrawReasons(0b0000_0001);
+    /// To transfer the funds.
+    pub const TRANSFER: Self = WithdrawReasons(0b0000_0010);
+    /// To reserve the funds.
+    pub const RESERVE: Self = WithdrawReasons(0b0000_0100);
+    /// To pay a fee, other than for a transaction.
+    pub const FEE: Self = WithdrawReasons(0b0000_1000);
+    /// To pay a tip to the block author.
+    pub const TIP: Self = WithdrawReasons(0b0001_0000);
+
+    /// No reason at all.
+    pub const fn empty() -> Self {
+        WithdrawReasons(0)
+    }
+
+    /// All the reasons.
+    pub const fn all() -> Self {
+        WithdrawReasons(0b0001_1111)
+    }
+
+    /// All the reasons but `one`.
+    pub const fn except(one: Self) -> Self {
+        WithdrawReasons(Self::all().0 & !one.0)
+    }
+
+    /// Whether there is no reason.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all of `other` are among the reasons.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any of `other` is among the reasons.
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for WithdrawReasons {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        WithdrawReasons(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for WithdrawReasons {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0
+    }
+}
+
+/// What would become of a deposit into an account.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DepositConsequence {
+    /// The account would be left with less than the minimum balance, so it could not exist.
+    BelowMinimum,
+    /// The account could not be created.
+    CannotCreate,
+    /// The asset is not known.
+    UnknownAsset,
+    /// The balance or the total issuance would overflow.
+    Overflow,
+    /// The deposit would succeed.
+    Success,
+}
+
+impl DepositConsequence {
+    /// Succeed if the deposit would, or fail with why it would not.
+    pub fn into_result(self) -> DispatchResult {
+        match self {
+            DepositConsequence::BelowMinimum => Err(TokenError::BelowMinimum.into()),
+            DepositConsequence::CannotCreate => Err(TokenError::CannotCreate.into()),
+            DepositConsequence::UnknownAsset => Err(TokenError::UnknownAsset.into()),
+            DepositConsequence::Overflow => Err(ArithmeticError::Overflow.into()),
+            DepositConsequence::Success => Ok(()),
+        }
+    }
+}
+
+/// What would become of a withdrawal from an account.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WithdrawConsequence<Balance> {
+    /// The account does not have the funds.
+    NoFunds,
+    /// The account would be reaped, which is not allowed.
+    WouldDie,
+    /// The asset is not known.
+    UnknownAsset,
+    /// The total issuance would underflow.
+    Underflow,
+    /// The total issuance would overflow.
+    Overflow,
+    /// The funds are locked.
+    Frozen,
+    /// The withdrawal would succeed, but leave the account below the minimum balance, so that
+    /// it is reaped and the given rest of its balance is lost too.
+    ReducedToZero(Balance),
+    /// The withdrawal would succeed.
+    Success,
+}
+
+impl<Balance: Default> WithdrawConsequence<Balance> {
+    /// The amount lost on top of the withdrawal if it would succeed, or why it would not.
+    pub fn into_result(self) -> Result<Balance, DispatchError> {
+        match self {
+            WithdrawConsequence::NoFunds => Err(TokenError::NoFunds.into()),
+            WithdrawConsequence::WouldDie => Err(TokenError::WouldDie.into()),
+            WithdrawConsequence::UnknownAsset => Err(TokenError::UnknownAsset.into()),
+            WithdrawConsequence::Underflow => Err(ArithmeticError::Underflow.into()),
+            WithdrawConsequence::Overflow => Err(ArithmeticError::Overflow.into()),
+            WithdrawConsequence::Frozen => Err(TokenError::Frozen.into()),
+            WithdrawConsequence::ReducedToZero(rest) => Ok(rest),
+            WithdrawConsequence::Success => Ok(Balance::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn withdraw_reasons_combine() {
+        let fees = WithdrawReasons::TRANSACTION_PAYMENT | WithdrawReasons::TIP;
+        assert!(fees.contains(WithdrawReasons::TIP));
+        assert!(!fees.contains(WithdrawReasons::TIP | WithdrawReasons::TRANSFER));
+        assert!(fees.intersects(WithdrawReasons::TIP | WithdrawReasons::TRANSFER));
+        assert!(!WithdrawReasons::except(WithdrawReasons::TRANSFER).intersects(
+            WithdrawReasons::TRANSFER
+        ));
+        assert!(WithdrawReasons::all().contains(fees));
+        assert!(WithdrawReasons::empty().is_empty());
+        assert_eq!(WithdrawReasons::TRANSFER.encode(), vec![2]);
+    }
+
+    #[test]
+    fn consequences_turn_into_errors() {
+        assert_eq!(DepositConsequence::Success.into_result(), Ok(()));
+        assert_eq!(
+            DepositConsequence::BelowMinimum.into_result(),
+            Err(DispatchError::Token(TokenError::BelowMinimum))
+        );
+        assert_eq!(
+            DepositConsequence::Overflow.into_result(),
+            Err(DispatchError::Arithmetic(ArithmeticError::Overflow))
+        );
+        assert_eq!(WithdrawConsequence::<u64>::Success.into_result(), Ok(0));
+        assert_eq!(WithdrawConsequence::ReducedToZero(3u64).into_result(), Ok(3));
+        assert_eq!(
+            WithdrawConsequence::<u64>::Frozen.into_result(),
+            Err(DispatchError::Token(TokenError::Frozen))
+        );
+    }
+}
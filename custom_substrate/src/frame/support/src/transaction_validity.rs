@@ -0,0 +1,142 @@
+//! Whether a transaction can be included in a block, and how.
+
+use crate::{
+    codec::{Decode, Encode},
+    scale_info::TypeInfo,
+};
+
+/// A transaction which is invalid, whatever the state it is applied on.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InvalidTransaction {
+    /// The call of the transaction is not expected.
+    Call,
+    /// The sender can't pay the fees of the transaction.
+    Payment,
+    /// The transaction is not valid yet, e.g. its nonce is ahead.
+    Future,
+    /// The transaction is outdated, e.g. its nonce was used.
+    Stale,
+    /// The signature of the transaction is wrong.
+    BadProof,
+    /// The block the transaction was made for is too old or unknown.
+    AncientBirthBlock,
+    /// The transaction doesn't fit in the block anymore.
+    ExhaustsResources,
+    /// A mandatory call failed, which makes the whole block invalid.
+    BadMandatory,
+    /// A mandatory call was sent as a transaction.
+    MandatoryValidation,
+    /// An error of some part of the runtime.
+    Custom(u8),
+}
+
+/// A transaction whose validity could not be decided.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UnknownTransaction {
+    /// The state the transaction needs could not be read.
+    CannotLookup,
+    /// No part of the runtime knows how to validate the transaction.
+    NoUnsignedValidator,
+    /// An error of some part of the runtime.
+    Custom(u8),
+}
+
+/// Why a transaction was refused.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransactionValidityError {
+    Invalid(InvalidTransaction),
+    Unknown(UnknownTransaction),
+}
+
+impl From<InvalidTransaction> for TransactionValidityError {
+    fn from(err: InvalidTransaction) -> Self {
+        TransactionValidityError::Invalid(err)
+    }
+}
+
+impl From<UnknownTransaction> for TransactionValidityError {
+    fn from(err: UnknownTransaction) -> Self {
+        TransactionValidityError::Unknown(err)
+    }
+}
+
+/// The priority of a transaction: the higher, the sooner it is included.
+pub type TransactionPriority = u64;
+
+/// The number of blocks a transaction stays valid for.
+pub type TransactionLongevity = u64;
+
+/// A tag a transaction provides or requires, e.g. the nonce of its signer.
+pub type TransactionTag = Vec<u8>;
+
+/// The result of validating a transaction outside of a block.
+pub type TransactionValidity = Result<ValidTransaction, TransactionValidityError>;
+
+/// How a valid transaction should be handled by the transaction pool.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+pub struct ValidTransaction {
+    /// Transactions of higher priority are included first.
+    pub priority: TransactionPriority,
+    /// The tags which other transactions must provide before this one can be included.
+    pub requires: Vec<TransactionTag>,
+    /// The tags this transaction provides once included. Two transactions providing the same
+    /// tag can't both be included.
+    pub provides: Vec<TransactionTag>,
+    /// The number of blocks the transaction stays valid for, from the next block.
+    pub longevity: TransactionLongevity,
+    /// Whether the transaction is gossiped to other nodes.
+    pub propagate: bool,
+}
+
+impl Default for ValidTransaction {
+    fn default() -> Self {
+        ValidTransaction {
+            priority: 0,
+            requires: vec![],
+            provides: vec![],
+            longevity: TransactionLongevity::MAX,
+            propagate: true,
+        }
+    }
+}
+
+impl ValidTransaction {
+    /// Combine two validities, e.g. of two signed extensions: priorities add up, tags are
+    /// merged, and the shortest longevity is kept.
+    pub fn combine_with(mut self, mut other: ValidTransaction) -> Self {
+        ValidTransaction {
+            priority: self.priority.saturating_add(other.priority),
+            requires: {
+                self.requires.append(&mut other.requires);
+                self.requires
+            },
+            provides: {
+                self.provides.append(&mut other.provides);
+                self.provides
+            },
+            longevity: self.longevity.min(other.longevity),
+            propagate: self.propagate && other.propagate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validities_are_combined() {
+        let nonce = ValidTransaction {
+            requires: vec![b"nonce 0".to_vec()],
+            provides: vec![b"nonce 1".to_vec()],
+            ..Default::default()
+        };
+        let mortal = ValidTransaction { priority: 5, longevity: 10, ..Default::default() };
+        let tip = ValidTransaction { priority: 2, propagate: false, ..Default::default() };
+
+        assert_eq!(
+            nonce.clone().combine_with(mortal).combine_with(tip),
+            ValidTransaction { priority: 7, longevity: 10, propagate: false, ..nonce }
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! The version of a runtime, which tells whether a node can run it and which transactions it
+//! accepts.
+
+use crate::{
+    codec::{Decode, Encode},
+    scale_info::TypeInfo,
+};
+
+/// The version of a runtime.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Default, Debug)]
+pub struct RuntimeVersion {
+    /// The name of the chain the runtime is for.
+    pub spec_name: String,
+    /// The name of the implementation of the runtime.
+    pub impl_name: String,
+    /// The version of the specification, increased whenever the runtime behaves differently.
+    pub spec_version: u32,
+    /// The version of the implementation, which doesn't change how the runtime behaves.
+    pub impl_version: u32,
+    /// The version of the format of the extrinsics, increased whenever signed transactions
+    /// must be made again.
+    pub transaction_version: u32,
+}
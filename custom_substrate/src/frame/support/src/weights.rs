@@ -0,0 +1,269 @@
+//! The cost of executing code, measured in time and in the size of the storage proof.
+
+use crate::{
+    codec::{Decode, Encode, MaxEncodedLen},
+    scale_info::TypeInfo,
+};
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// The cost of an operation in two dimensions.
+///
+/// Weights are only partially ordered: one can be larger in time and smaller in proof size than
+/// another. Comparisons say whether `any` or `all` of the dimensions satisfy them.
+#[derive(
+    Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug,
+)]
+pub struct Weight {
+    /// The time it takes to execute, in picoseconds on reference hardware.
+    #[codec(compact)]
+    ref_time: u64,
+    /// The size of the storage proof needed to execute, in bytes.
+    #[codec(compact)]
+    proof_size: u64,
+}
+
+impl Weight {
+    pub const MAX: Self = Self { ref_time: u64::MAX, proof_size: u64::MAX };
+
+    pub const fn from_parts(ref_time: u64, proof_size: u64) -> Self {
+        Self { ref_time, proof_size }
+    }
+
+    pub const fn zero() -> Self {
+        Self { ref_time: 0, proof_size: 0 }
+    }
+
+    pub const fn ref_time(&self) -> u64 {
+        self.ref_time
+    }
+
+    pub const fn proof_size(&self) -> u64 {
+        self.proof_size
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.ref_time == 0 && self.proof_size == 0
+    }
+
+    pub const fn set_ref_time(mut self, ref_time: u64) -> Self {
+        self.ref_time = ref_time;
+        self
+    }
+
+    pub const fn set_proof_size(mut self, proof_size: u64) -> Self {
+        self.proof_size = proof_size;
+        self
+    }
+
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            ref_time: self.ref_time.saturating_add(rhs.ref_time),
+            proof_size: self.proof_size.saturating_add(rhs.proof_size),
+        }
+    }
+
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self {
+            ref_time: self.ref_time.saturating_sub(rhs.ref_time),
+            proof_size: self.proof_size.saturating_sub(rhs.proof_size),
+        }
+    }
+
+    pub const fn saturating_mul(self, scalar: u64) -> Self {
+        Self {
+            ref_time: self.ref_time.saturating_mul(scalar),
+            proof_size: self.proof_size.saturating_mul(scalar),
+        }
+    }
+
+    pub fn saturating_accrue(&mut self, amount: Self) {
+        *self = self.saturating_add(amount);
+    }
+
+    pub fn saturating_reduce(&mut self, amount: Self) {
+        *self = self.saturating_sub(amount);
+    }
+
+    /// `None` if either dimension overflows.
+    pub const fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let Some(ref_time) = self.ref_time.checked_add(rhs.ref_time) else { return None };
+        let Some(proof_size) = self.proof_size.checked_add(rhs.proof_size) else { return None };
+        Some(Self { ref_time, proof_size })
+    }
+
+    /// `None` if either dimension underflows.
+    pub const fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        let Some(ref_time) = self.ref_time.checked_sub(rhs.ref_time) else { return None };
+        let Some(proof_size) = self.proof_size.checked_sub(rhs.proof_size) else { return None };
+        Some(Self { ref_time, proof_size })
+    }
+
+    /// The largest value of each dimension.
+    pub const fn max(&self, other: Self) -> Self {
+        Self {
+            ref_time: if self.ref_time > other.ref_time { self.ref_time } else { other.ref_time },
+            proof_size: if self.proof_size > other.proof_size {
+                self.proof_size
+            } else {
+                other.proof_size
+            },
+        }
+    }
+
+    /// The smallest value of each dimension.
+    pub const fn min(&self, other: Self) -> Self {
+        Self {
+            ref_time: if self.ref_time < other.ref_time { self.ref_time } else { other.ref_time },
+            proof_size: if self.proof_size < other.proof_size {
+                self.proof_size
+            } else {
+                other.proof_size
+            },
+        }
+    }
+
+    /// Whether any dimension of `self` is greater than the one of `other`.
+    pub const fn any_gt(self, other: Self) -> bool {
+        self.ref_time > other.ref_time || self.proof_size > other.proof_size
+    }
+
+    /// Whether all dimensions of `self` are greater than the ones of `other`.
+    pub const fn all_gt(self, other: Self) -> bool {
+        self.ref_time > other.ref_time && self.proof_size > other.proof_size
+    }
+
+    pub const fn any_lt(self, other: Self) -> bool {
+        self.ref_time < other.ref_time || self.proof_size < other.proof_size
+    }
+
+    pub const fn all_lte(self, other: Self) -> bool {
+        self.ref_time <= other.ref_time && self.proof_size <= other.proof_size
+    }
+}
+
+impl Add for Weight {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            ref_time: self.ref_time + rhs.ref_time,
+            proof_size: self.proof_size + rhs.proof_size,
+        }
+    }
+}
+
+impl Sub for Weight {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            ref_time: self.ref_time - rhs.ref_time,
+            proof_size: self.proof_size - rhs.proof_size,
+        }
+    }
+}
+
+impl Mul<u64> for Weight {
+    type Output = Self;
+
+    fn mul(self, scalar: u64) -> Self {
+        Self { ref_time: self.ref_time * scalar, proof_size: self.proof_size * scalar }
+    }
+}
+
+impl AddAssign for Weight {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Weight {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// The weight of reading and writing one storage item.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct RuntimeDbWeight {
+    pub read: u64,
+    pub write: u64,
+}
+
+impl RuntimeDbWeight {
+    pub fn reads(self, r: u64) -> Weight {
+        Weight::from_parts(self.read.saturating_mul(r), 0)
+    }
+
+    pub fn writes(self, w: u64) -> Weight {
+        Weight::from_parts(self.write.saturating_mul(w), 0)
+    }
+
+    pub fn reads_writes(self, r: u64, w: u64) -> Weight {
+        self.reads(r).saturating_add(self.writes(w))
+    }
+}
+
+pub mod constants {
+    use super::{RuntimeDbWeight, Weight};
+    use crate::traits::Get;
+
+    pub const WEIGHT_REF_TIME_PER_SECOND: u64 = 1_000_000_000_000;
+    pub const WEIGHT_REF_TIME_PER_MILLIS: u64 = 1_000_000_000;
+    pub const WEIGHT_REF_TIME_PER_MICROS: u64 = 1_000_000;
+    pub const WEIGHT_REF_TIME_PER_NANOS: u64 = 1_000;
+    pub const WEIGHT_PROOF_SIZE_PER_MB: u64 = 1024 * 1024;
+    pub const WEIGHT_PROOF_SIZE_PER_KB: u64 = 1024;
+
+    /// The weight of an extrinsic doing nothing, on top of which the weight of calls is added.
+    pub const EXTRINSIC_BASE_WEIGHT: Weight =
+        Weight::from_parts(WEIGHT_REF_TIME_PER_NANOS * 100_000, 0);
+
+    /// The weight of initializing and finalizing an empty block.
+    pub const BLOCK_EXECUTION_WEIGHT: Weight =
+        Weight::from_parts(WEIGHT_REF_TIME_PER_NANOS * 5_000_000, 0);
+
+    /// Storage access costs of a RocksDB backed node.
+    pub struct RocksDbWeight;
+
+    impl Get<RuntimeDbWeight> for RocksDbWeight {
+        fn get() -> RuntimeDbWeight {
+            RuntimeDbWeight {
+                read: 25_000 * WEIGHT_REF_TIME_PER_NANOS,
+                write: 100_000 * WEIGHT_REF_TIME_PER_NANOS,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparisons_are_per_dimension() {
+        let a = Weight::from_parts(10, 1);
+        let b = Weight::from_parts(5, 2);
+        assert!(a.any_gt(b) && b.any_gt(a));
+        assert!(!a.all_gt(b) && !a.all_lte(b));
+        assert_eq!(a.max(b), Weight::from_parts(10, 2));
+        assert_eq!(a.min(b), Weight::from_parts(5, 1));
+    }
+
+    #[test]
+    fn arithmetic_works() {
+        let a = Weight::from_parts(10, 1);
+        assert_eq!(a.saturating_sub(Weight::from_parts(20, 0)), Weight::from_parts(0, 1));
+        assert_eq!(Weight::MAX.saturating_add(a), Weight::MAX);
+        assert_eq!(a.checked_sub(&Weight::from_parts(0, 2)), None);
+        assert_eq!(a.checked_add(&a), Some(a * 2));
+        let db_weight = RuntimeDbWeight { read: 2, write: 3 };
+        assert_eq!(db_weight.reads_writes(2, 1), Weight::from_parts(7, 0));
+    }
+
+    #[test]
+    fn weight_encodes_compact() {
+        assert_eq!(Weight::from_parts(1, 64).encode(), vec![4, 1, 1]);
+        assert_eq!(Weight::max_encoded_len(), 18);
+    }
+}
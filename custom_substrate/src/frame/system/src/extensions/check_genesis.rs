@@ -0,0 +1,44 @@
+use crate::{Config, Pallet};
+use frame_support::{
+    pallet_prelude::*, traits::SignedExtension, transaction_validity::TransactionValidityError,
+};
+
+/// Signs the genesis hash, so the transaction is only valid on this chain.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckGenesis<T: Config>(PhantomData<T>);
+
+impl<T: Config> CheckGenesis<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config> Default for CheckGenesis<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config> SignedExtension for CheckGenesis<T> {
+    const IDENTIFIER: &'static str = "CheckGenesis";
+
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = T::Hash;
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<T::Hash, TransactionValidityError> {
+        Ok(Pallet::<T>::block_hash(T::BlockNumber::zero()))
+    }
+
+    fn pre_dispatch(
+        self,
+        _who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+}
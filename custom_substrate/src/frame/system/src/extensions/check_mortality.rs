@@ -0,0 +1,112 @@
+use crate::{BlockHash, Config, Pallet};
+use frame_support::{
+    generic::Era,
+    pallet_prelude::*,
+    traits::SignedExtension,
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+
+/// Limits the blocks the transaction is valid in to its [`Era`], signing the hash of the block
+/// it was made at so it is only valid on the fork of that block.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckMortality<T: Config>(pub Era, PhantomData<T>);
+
+impl<T: Config> CheckMortality<T> {
+    pub fn from(era: Era) -> Self {
+        Self(era, PhantomData)
+    }
+}
+
+impl<T: Config> SignedExtension for CheckMortality<T> {
+    const IDENTIFIER: &'static str = "CheckMortality";
+
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = T::Hash;
+    type Pre = ();
+
+    /// The hash of the birth block, which must be known, i.e. not in the future nor pruned.
+    fn additional_signed(&self) -> Result<T::Hash, TransactionValidityError> {
+        let current = Pallet::<T>::block_number().into();
+        let birth = T::BlockNumber::try_from(self.0.birth(current))
+            .map_err(|_| InvalidTransaction::AncientBirthBlock)?;
+        if !BlockHash::<T>::contains_key(birth) {
+            return Err(InvalidTransaction::AncientBirthBlock.into())
+        }
+        Ok(Pallet::<T>::block_hash(birth))
+    }
+
+    /// The transaction stays valid until the end of its era.
+    fn validate(
+        &self,
+        _who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        let current = Pallet::<T>::block_number().into();
+        Ok(ValidTransaction {
+            longevity: self.0.death(current).saturating_sub(current),
+            ..Default::default()
+        })
+    }
+
+    fn pre_dispatch(
+        self,
+        _who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::*, CheckGenesis, CheckSpecVersion};
+    use frame_support::storage::TestExternalities;
+
+    #[test]
+    fn mortal_transactions_sign_their_birth_block() {
+        TestExternalities::default().execute_with(|| {
+            for n in 0..10 {
+                BlockHash::<Test>::insert(n, [n as u8; 32]);
+            }
+            System::set_block_number(10);
+
+            let mortal = CheckMortality::<Test>::from(Era::mortal(4, 9));
+            assert_eq!(mortal.additional_signed(), Ok([9; 32]));
+            let immortal = CheckMortality::<Test>::from(Era::immortal());
+            assert_eq!(immortal.additional_signed(), Ok([0; 32]));
+
+            let info = DispatchInfo::default();
+            let remark = RuntimeCall::System(crate::Call::remark { remark: vec![] });
+            let longevity = |ext: &CheckMortality<Test>| {
+                ext.validate(&1, &remark, &info, 0).unwrap().longevity
+            };
+            assert_eq!(longevity(&mortal), 3);
+            assert_eq!(longevity(&immortal), u64::MAX - 10);
+
+            // Later, the transaction is taken for one made at block 17, which is not known.
+            System::set_block_number(20);
+            assert_eq!(
+                mortal.additional_signed(),
+                Err(InvalidTransaction::AncientBirthBlock.into())
+            );
+        });
+    }
+
+    #[test]
+    fn the_chain_and_runtime_are_signed() {
+        TestExternalities::default().execute_with(|| {
+            BlockHash::<Test>::insert(0, [1; 32]);
+            assert_eq!(CheckGenesis::<Test>::new().additional_signed(), Ok([1; 32]));
+            assert_eq!(CheckSpecVersion::<Test>::new().additional_signed(), Ok(SPEC_VERSION));
+        });
+    }
+}
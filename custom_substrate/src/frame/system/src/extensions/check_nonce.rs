@@ -0,0 +1,119 @@
+use crate::{Account, Config};
+use frame_support::{
+    pallet_prelude::*,
+    traits::SignedExtension,
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+
+/// Checks the nonce of the transaction is the one of its signer, and increases it, so a
+/// transaction can't be applied twice.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckNonce<T: Config>(pub T::Index);
+
+impl<T: Config> CheckNonce<T> {
+    pub fn from(nonce: T::Index) -> Self {
+        Self(nonce)
+    }
+}
+
+impl<T: Config> SignedExtension for CheckNonce<T> {
+    const IDENTIFIER: &'static str = "CheckNonce";
+
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    /// Refuse nonces which were already used. A nonce ahead of the signer requires the
+    /// transaction of the nonce before it, so transactions of an account are included in order.
+    fn validate(
+        &self,
+        who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        let nonce = Account::<T>::get(who).nonce;
+        if self.0 < nonce {
+            return Err(InvalidTransaction::Stale.into())
+        }
+        let requires = if self.0 > nonce {
+            vec![(who, self.0.saturating_sub(T::Index::one())).encode()]
+        } else {
+            vec![]
+        };
+        Ok(ValidTransaction {
+            requires,
+            provides: vec![(who, self.0).encode()],
+            ..Default::default()
+        })
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        let mut account = Account::<T>::get(who);
+        if self.0 != account.nonce {
+            let err = if self.0 < account.nonce {
+                InvalidTransaction::Stale
+            } else {
+                InvalidTransaction::Future
+            };
+            return Err(err.into())
+        }
+        account.nonce = account.nonce.saturating_add(T::Index::one());
+        Account::<T>::insert(who, account);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::*;
+    use frame_support::storage::TestExternalities;
+
+    fn remark() -> RuntimeCall {
+        RuntimeCall::System(crate::Call::remark { remark: vec![] })
+    }
+
+    #[test]
+    fn nonces_are_used_in_order() {
+        TestExternalities::default().execute_with(|| {
+            let info = DispatchInfo::default();
+            System::inc_account_nonce(&1);
+
+            assert_eq!(
+                CheckNonce::<Test>(0).validate(&1, &remark(), &info, 0),
+                Err(InvalidTransaction::Stale.into())
+            );
+            let valid = CheckNonce::<Test>(1).validate(&1, &remark(), &info, 0).unwrap();
+            assert_eq!((valid.requires, valid.provides), (vec![], vec![(1u64, 1u64).encode()]));
+            // Future nonces are valid, but wait for the transaction of the previous nonce.
+            let valid = CheckNonce::<Test>(2).validate(&1, &remark(), &info, 0).unwrap();
+            assert_eq!(valid.requires, vec![(1u64, 1u64).encode()]);
+            assert_eq!(
+                CheckNonce::<Test>(2).pre_dispatch(&1, &remark(), &info, 0),
+                Err(InvalidTransaction::Future.into())
+            );
+
+            assert_eq!(CheckNonce::<Test>(1).pre_dispatch(&1, &remark(), &info, 0), Ok(()));
+            assert_eq!(System::account_nonce(&1), 2);
+            assert_eq!(
+                CheckNonce::<Test>(1).pre_dispatch(&1, &remark(), &info, 0),
+                Err(InvalidTransaction::Stale.into())
+            );
+        });
+    }
+}
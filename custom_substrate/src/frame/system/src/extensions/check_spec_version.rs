@@ -0,0 +1,45 @@
+use crate::Config;
+use frame_support::{
+    pallet_prelude::*, traits::SignedExtension, transaction_validity::TransactionValidityError,
+};
+
+/// Signs the spec version of the runtime, so the transaction is not valid anymore once the
+/// runtime behaves differently.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckSpecVersion<T: Config>(PhantomData<T>);
+
+impl<T: Config> CheckSpecVersion<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config> Default for CheckSpecVersion<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config> SignedExtension for CheckSpecVersion<T> {
+    const IDENTIFIER: &'static str = "CheckSpecVersion";
+
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = u32;
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<u32, TransactionValidityError> {
+        Ok(T::Version::get().spec_version)
+    }
+
+    fn pre_dispatch(
+        self,
+        _who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfo,
+        _len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{AllExtrinsicsLen, BlockWeight, Config};
+use frame_support::{
+    dispatch::PerDispatchClass,
+    pallet_prelude::*,
+    traits::SignedExtension,
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+
+/// Checks the transaction fits in the block, by weight and by length, and accounts for it.
+///
+/// Unsigned transactions are checked too. Once the call is dispatched, the weight it didn't
+/// use is given back to the block.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckWeight<T: Config>(PhantomData<T>);
+
+impl<T: Config> CheckWeight<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// Refuse an extrinsic heavier than the maximum of its class.
+    fn check_extrinsic_weight(info: &DispatchInfo) -> Result<(), TransactionValidityError> {
+        T::BlockWeights::get()
+            .check_extrinsic_weight(info)
+            .map_err(|_| InvalidTransaction::ExhaustsResources.into())
+    }
+
+    /// The length of the block with the extrinsic, if it fits.
+    fn check_block_length(
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<u32, TransactionValidityError> {
+        let max = *T::BlockLength::get().max.get(info.class);
+        let added = AllExtrinsicsLen::get().unwrap_or_default().saturating_add(len as u32);
+        if added > max {
+            return Err(InvalidTransaction::ExhaustsResources.into())
+        }
+        Ok(added)
+    }
+
+    /// The weight of the block with the extrinsic, if it fits.
+    fn check_block_weight(
+        info: &DispatchInfo,
+    ) -> Result<PerDispatchClass<Weight>, TransactionValidityError> {
+        T::BlockWeights::get()
+            .calculate_consumed_weight(&BlockWeight::get(), info)
+            .map_err(|_| InvalidTransaction::ExhaustsResources.into())
+    }
+
+    /// Check the extrinsic fits in the block and add it to the weight and length of the block.
+    pub fn do_pre_dispatch(
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Self::check_extrinsic_weight(info)?;
+        let length = Self::check_block_length(info, len)?;
+        let weight = Self::check_block_weight(info)?;
+        AllExtrinsicsLen::put(length);
+        BlockWeight::put(weight);
+        Ok(())
+    }
+
+    /// Check the extrinsic could fit in a block, whatever the current block holds.
+    pub fn do_validate(info: &DispatchInfo, len: usize) -> Result<(), TransactionValidityError> {
+        Self::check_extrinsic_weight(info)?;
+        let max = *T::BlockLength::get().max.get(info.class);
+        if len > max as usize {
+            return Err(InvalidTransaction::ExhaustsResources.into())
+        }
+        Ok(())
+    }
+}
+
+impl<T: Config> Default for CheckWeight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config> SignedExtension for CheckWeight<T> {
+    const IDENTIFIER: &'static str = "CheckWeight";
+
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        _who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> TransactionValidity {
+        Self::do_validate(info, len)?;
+        Ok(ValidTransaction::default())
+    }
+
+    fn pre_dispatch(
+        self,
+        _who: &T::AccountId,
+        _call: &T::RuntimeCall,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Self::do_pre_dispatch(info, len)
+    }
+
+    fn validate_unsigned(
+        _call: &T::RuntimeCall,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> TransactionValidity {
+        Self::do_validate(info, len)?;
+        Ok(ValidTransaction::default())
+    }
+
+    fn pre_dispatch_unsigned(
+        _call: &T::RuntimeCall,
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        Self::do_pre_dispatch(info, len)
+    }
+
+    /// Give back the weight the call didn't use.
+    fn post_dispatch(
+        _pre: Option<()>,
+        info: &DispatchInfo,
+        post_info: &PostDispatchInfo,
+        _len: usize,
+        _result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        let unspent = post_info.calc_unspent(info);
+        BlockWeight::mutate(|weight| weight.sub(unspent, info.class));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::*;
+    use frame_support::storage::TestExternalities;
+
+    fn info(ref_time: u64, class: DispatchClass) -> DispatchInfo {
+        DispatchInfo { weight: Weight::from_parts(ref_time, 0), class, ..Default::default() }
+    }
+
+    #[test]
+    fn extrinsics_fill_the_block_by_weight() {
+        TestExternalities::default().execute_with(|| {
+            let half = info(MAX_BLOCK_REF_TIME / 2, DispatchClass::Normal);
+            assert_eq!(CheckWeight::<Test>::do_pre_dispatch(&half, 0), Ok(()));
+            assert_eq!(CheckWeight::<Test>::do_pre_dispatch(&half, 0), Ok(()));
+            assert_eq!(
+                CheckWeight::<Test>::do_pre_dispatch(&info(1, DispatchClass::Normal), 0),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+            // Validating doesn't depend on what the block holds.
+            assert_eq!(CheckWeight::<Test>::do_validate(&half, 0), Ok(()));
+            // Mandatory extrinsics always get in.
+            let mandatory = info(1, DispatchClass::Mandatory);
+            assert_eq!(CheckWeight::<Test>::do_pre_dispatch(&mandatory, 0), Ok(()));
+        });
+    }
+
+    #[test]
+    fn extrinsics_fill_the_block_by_length() {
+        TestExternalities::default().execute_with(|| {
+            let normal = info(0, DispatchClass::Normal);
+            let normal_max = (MAX_BLOCK_LENGTH * 3 / 4) as usize;
+            assert_eq!(
+                CheckWeight::<Test>::do_validate(&normal, normal_max + 1),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+            assert_eq!(CheckWeight::<Test>::do_pre_dispatch(&normal, normal_max), Ok(()));
+            assert_eq!(
+                CheckWeight::<Test>::do_pre_dispatch(&normal, 1),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+            // Operational extrinsics can use the rest.
+            let operational = info(0, DispatchClass::Operational);
+            assert_eq!(CheckWeight::<Test>::do_pre_dispatch(&operational, 1), Ok(()));
+            assert_eq!(AllExtrinsicsLen::get(), Some(MAX_BLOCK_LENGTH * 3 / 4 + 1));
+        });
+    }
+
+    #[test]
+    fn unused_weight_is_given_back() {
+        TestExternalities::default().execute_with(|| {
+            let info = info(100, DispatchClass::Normal);
+            let post_info = PostDispatchInfo::from(Some(Weight::from_parts(30, 0)));
+            CheckWeight::<Test>(PhantomData).pre_dispatch(&1, &remark(), &info, 0).unwrap();
+            CheckWeight::<Test>::post_dispatch(Some(()), &info, &post_info, 0, &Ok(())).unwrap();
+            let weight = *System::block_weight().get(DispatchClass::Normal);
+            assert_eq!(weight, Weight::from_parts(30, 0));
+        });
+    }
+
+    fn remark() -> RuntimeCall {
+        RuntimeCall::System(crate::Call::remark { remark: vec![] })
+    }
+}
@@ -0,0 +1,8 @@
+//! The signed extensions of the system: what every signed transaction must check so it can't
+//! be replayed, run on the wrong chain or overfill the block.
+
+pub mod check_genesis;
+pub mod check_mortality;
+pub mod check_nonce;
+pub mod check_spec_version;
+pub mod check_weight;
@@ -0,0 +1,906 @@
+//! The system pallet: what every runtime needs, i.e. the block number and hashes, the accounts
+//! with their nonce, the events and the origin of calls.
+
+pub use frame_support::{
+    dispatch::{ensure_none, ensure_root, ensure_signed, RawOrigin},
+    traits::{EnsureNone, EnsureRoot, EnsureSigned, EnsureSignedBy},
+};
+pub use extensions::{
+    check_genesis::CheckGenesis, check_mortality::CheckMortality, check_nonce::CheckNonce,
+    check_spec_version::CheckSpecVersion, check_weight::CheckWeight,
+};
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod extensions;
+#[cfg(test)]
+mod mock;
+pub mod weights;
+
+pub mod pallet {
+
+    use frame_support::{
+        dispatch::{
+            annotated_dispatch_info, ensure_signed, extract_actual_pays_fee,
+            extract_actual_weight, PerDispatchClass, RawOrigin,
+        },
+        generic::{self, Header},
+        hashing::Hash as HashT,
+        limits::{BlockLength, BlockWeights},
+        pallet_prelude::*,
+        traits::{
+            Dispatchable, OnKilledAccount, OnNewAccount, OriginTrait, PalletInfo, StaticLookup,
+        },
+        version::RuntimeVersion,
+    };
+    use crate::weights::WeightInfo;
+    use std::{convert::Infallible, fmt::Debug};
+
+    pub trait Config: Sized + Clone + Eq + Debug + 'static {
+        /// The calls any origin but root can make, e.g. `Everything`.
+        type BaseCallFilter: Contains<Self::RuntimeCall>;
+        /// The origin of calls, `RuntimeOrigin` as generated by `construct_runtime!`.
+        type RuntimeOrigin: Into<Result<RawOrigin<Self::AccountId>, Self::RuntimeOrigin>>
+            + From<RawOrigin<Self::AccountId>>
+            + Clone
+            + OriginTrait<Call = Self::RuntimeCall, AccountId = Self::AccountId>;
+        /// Every call of the runtime, `RuntimeCall` as generated by `construct_runtime!`.
+        type RuntimeCall: Parameter
+            + GetDispatchInfo
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>;
+        /// The nonce of an account, counting its transactions.
+        type Index: Parameter + Member + MaxEncodedLen + Copy + Default + Ord + Zero + One
+            + CheckedAdd + Saturating;
+        /// The number of a block.
+        type BlockNumber: Parameter + Member + MaxEncodedLen + Copy + Default + Ord + Hash
+            + Zero + One + CheckedAdd + CheckedSub + Saturating + From<u32> + Into<u64>
+            + TryFrom<u64>;
+        /// The output of `Hashing`, e.g. a block hash.
+        type Hash: Parameter + Member + MaxEncodedLen + Copy + Default + Ord + Hash + AsRef<[u8]>
+            + AsMut<[u8]>;
+        /// The hash function of blocks and extrinsics.
+        type Hashing: HashT<Output = Self::Hash>;
+        /// The identifier of an account.
+        type AccountId: Parameter + Member + MaxEncodedLen + Ord + Hash;
+        /// Converts the way calls name accounts into account ids, e.g. `IdentityLookup`.
+        type Lookup: StaticLookup<Target = Self::AccountId>;
+        /// Every event of the runtime.
+        type RuntimeEvent: Parameter + Member + From<Event<Self>>;
+        /// What pallets store for every account along with its nonce, e.g. its balance.
+        type AccountData: Parameter + Member + MaxEncodedLen + Default;
+        /// Called when an account is created.
+        type OnNewAccount: OnNewAccount<Self::AccountId>;
+        /// Called when an account is removed.
+        type OnKilledAccount: OnKilledAccount<Self::AccountId>;
+        /// The weight limits of a block.
+        type BlockWeights: Get<BlockWeights>;
+        /// The length limits of a block.
+        type BlockLength: Get<BlockLength>;
+        /// The version of the runtime.
+        type Version: Get<RuntimeVersion>;
+        /// Where the pallets are in the runtime, `PalletInfo` as generated by
+        /// `construct_runtime!`.
+        type PalletInfo: PalletInfo;
+        /// The prefix of the SS58 addresses of the chain, e.g. 42 for any Substrate chain.
+        type SS58Prefix: Get<u16>;
+        type SystemWeightInfo: WeightInfo;
+    }
+
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    pub enum Event<T: Config> {
+        /// An extrinsic was applied, with what it actually cost.
+        ExtrinsicSuccess { dispatch_info: DispatchInfo },
+        /// The call of an extrinsic failed, with what it actually cost.
+        ExtrinsicFailed { dispatch_error: DispatchError, dispatch_info: DispatchInfo },
+        /// An account was created.
+        NewAccount { account: T::AccountId },
+        /// An account was removed.
+        KilledAccount { account: T::AccountId },
+    }
+
+    /// The dispatchable functions of the pallet, with their arguments.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[scale_info(skip_type_params(T))]
+    #[allow(non_camel_case_types)]
+    pub enum Call<T: Config> {
+        /// Do nothing but put `remark` in the block.
+        #[codec(index = 0)]
+        remark { remark: Vec<u8> },
+        #[doc(hidden)]
+        #[codec(skip)]
+        __Ignore(PhantomData<T>, Infallible),
+    }
+
+    impl<T: Config> GetDispatchInfo for Call<T> {
+        fn get_dispatch_info(&self) -> DispatchInfo {
+            match self {
+                Call::remark { remark } => annotated_dispatch_info(
+                    &T::SystemWeightInfo::remark(remark.len() as u32),
+                    (remark,),
+                ),
+                Call::__Ignore(_, never) => match *never {},
+            }
+        }
+    }
+
+    impl<T: Config> UnfilteredDispatchable for Call<T> {
+        type RuntimeOrigin = T::RuntimeOrigin;
+
+        fn dispatch_bypass_filter(self, origin: Self::RuntimeOrigin) -> DispatchResultWithPostInfo {
+            match self {
+                Call::remark { remark } => {
+                    Pallet::<T>::remark(origin, remark)?;
+                }
+                Call::__Ignore(_, never) => match never {},
+            }
+            Ok(().into())
+        }
+    }
+
+    pub struct Pallet<T: Config>(PhantomData<T>);
+
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+    /// The origin of calls made by root, by a signed account or by nobody.
+    pub type Origin<T> = RawOrigin<<T as Config>::AccountId>;
+
+    /// How many references there are to an account.
+    pub type RefCount = u32;
+
+    /// The position of an event in the events of the block.
+    pub type EventIndex = u32;
+
+    /// The information the system keeps for an account.
+    #[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, Default, Debug)]
+    pub struct AccountInfo<Index, AccountData> {
+        /// The number of transactions the account sent.
+        pub nonce: Index,
+        /// The number of other modules which depend on the account existing.
+        pub consumers: RefCount,
+        /// The number of other modules which allow the account to exist.
+        pub providers: RefCount,
+        /// The number of modules which allow the account to exist on their own, e.g. without
+        /// an existential deposit.
+        pub sufficients: RefCount,
+        pub data: AccountData,
+    }
+
+    /// What adding a reference did to an account.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum IncRefStatus {
+        /// The account was created.
+        Created,
+        /// The account already existed.
+        Existed,
+    }
+
+    /// What removing a reference did to an account.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum DecRefStatus {
+        /// The account was removed.
+        Reaped,
+        /// The account still exists.
+        Exists,
+    }
+
+    /// When an event happened in the block.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Default, Debug)]
+    pub enum Phase {
+        /// While applying the extrinsic at this index.
+        ApplyExtrinsic(u32),
+        /// While finalizing the block.
+        Finalization,
+        /// While initializing the block.
+        #[default]
+        Initialization,
+    }
+
+    /// An event, with when it happened and the topics it can be looked up by.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    pub struct EventRecord<E, T> {
+        pub phase: Phase,
+        pub event: E,
+        pub topics: Vec<T>,
+    }
+
+    frame_support::storage_prefix!(pub AccountPrefix => "System", "Account");
+    frame_support::storage_prefix!(pub NumberPrefix => "System", "Number");
+    frame_support::storage_prefix!(pub ParentHashPrefix => "System", "ParentHash");
+    frame_support::storage_prefix!(pub BlockHashPrefix => "System", "BlockHash");
+    frame_support::storage_prefix!(pub DigestPrefix => "System", "Digest");
+    frame_support::storage_prefix!(pub ExecutionPhasePrefix => "System", "ExecutionPhase");
+    frame_support::storage_prefix!(pub EventsPrefix => "System", "Events");
+    frame_support::storage_prefix!(pub EventCountPrefix => "System", "EventCount");
+    frame_support::storage_prefix!(pub EventTopicsPrefix => "System", "EventTopics");
+    frame_support::storage_prefix!(pub BlockWeightPrefix => "System", "BlockWeight");
+    frame_support::storage_prefix!(pub AllExtrinsicsLenPrefix => "System", "AllExtrinsicsLen");
+    frame_support::storage_prefix!(pub ExtrinsicCountPrefix => "System", "ExtrinsicCount");
+    frame_support::storage_prefix!(pub ExtrinsicDataPrefix => "System", "ExtrinsicData");
+
+    /// The information of every account.
+    pub type Account<T> = StorageMap<
+        AccountPrefix,
+        Blake2_128Concat,
+        <T as Config>::AccountId,
+        AccountInfo<<T as Config>::Index, <T as Config>::AccountData>,
+        ValueQuery,
+    >;
+
+    /// The number of the current block.
+    pub type Number<T> = StorageValue<NumberPrefix, <T as Config>::BlockNumber, ValueQuery>;
+
+    /// The hash of the previous block.
+    pub type ParentHash<T> = StorageValue<ParentHashPrefix, <T as Config>::Hash, ValueQuery>;
+
+    /// The hash of every block, by number.
+    pub type BlockHash<T> = StorageMap<
+        BlockHashPrefix,
+        Twox64Concat,
+        <T as Config>::BlockNumber,
+        <T as Config>::Hash,
+        ValueQuery,
+    >;
+
+    /// The digest of the current block.
+    pub type Digest = StorageValue<DigestPrefix, generic::Digest, ValueQuery>;
+
+    /// The phase of the execution of the current block.
+    pub type ExecutionPhase = StorageValue<ExecutionPhasePrefix, Phase>;
+
+    /// The events of the current block.
+    pub type Events<T> = StorageValue<
+        EventsPrefix,
+        Vec<EventRecord<<T as Config>::RuntimeEvent, <T as Config>::Hash>>,
+        ValueQuery,
+    >;
+
+    /// The number of events of the current block.
+    pub type EventCount = StorageValue<EventCountPrefix, EventIndex, ValueQuery>;
+
+    /// The blocks and indices of the events of every topic.
+    pub type EventTopics<T> = StorageMap<
+        EventTopicsPrefix,
+        Blake2_128Concat,
+        <T as Config>::Hash,
+        Vec<(<T as Config>::BlockNumber, EventIndex)>,
+        ValueQuery,
+    >;
+
+    /// The weight of the block so far, per class.
+    pub type BlockWeight = StorageValue<BlockWeightPrefix, PerDispatchClass<Weight>, ValueQuery>;
+
+    /// The encoded length of the extrinsics of the block so far.
+    pub type AllExtrinsicsLen = StorageValue<AllExtrinsicsLenPrefix, u32>;
+
+    /// The number of extrinsics of the block, once they are all applied.
+    pub type ExtrinsicCount = StorageValue<ExtrinsicCountPrefix, u32>;
+
+    /// The encoded extrinsics of the block, by index.
+    pub type ExtrinsicData =
+        StorageMap<ExtrinsicDataPrefix, Twox64Concat, u32, Vec<u8>, ValueQuery>;
+
+    frame_support::impl_storage_info!(#[without_storage_info] Pallet<T: Config> {
+        Account<T>,
+        Number<T>,
+        ParentHash<T>,
+        BlockHash<T>,
+        Digest,
+        ExecutionPhase,
+        Events<T>,
+        EventCount,
+        EventTopics<T>,
+        BlockWeight,
+        AllExtrinsicsLen,
+        ExtrinsicCount,
+        ExtrinsicData,
+    });
+
+    frame_support::impl_constant_metadata!(Pallet<T: Config> {
+        /// The version of the runtime.
+        Version: RuntimeVersion = T::Version::get(),
+        /// The prefix of the SS58 addresses of the chain.
+        SS58Prefix: u16 = T::SS58Prefix::get(),
+    });
+
+    frame_support::impl_storage_metadata!(Pallet<T: Config> => "System" {
+        /// The information of every account.
+        Account<T>,
+        /// The number of the current block.
+        Number<T>,
+        /// The hash of the previous block.
+        ParentHash<T>,
+        /// The hash of every block, by number.
+        BlockHash<T>,
+        /// The digest of the current block.
+        Digest,
+        /// The phase of the execution of the current block.
+        ExecutionPhase,
+        /// The events of the current block.
+        Events<T>,
+        /// The number of events of the current block.
+        EventCount,
+        /// The blocks and indices of the events of every topic.
+        EventTopics<T>,
+        BlockWeight,
+        AllExtrinsicsLen,
+        ExtrinsicCount,
+        ExtrinsicData,
+    });
+
+    impl<T: Config> Pallet<T> {
+        pub fn block_number() -> T::BlockNumber {
+            Number::<T>::get()
+        }
+
+        pub fn set_block_number(n: T::BlockNumber) {
+            Number::<T>::put(n);
+        }
+
+        pub fn parent_hash() -> T::Hash {
+            ParentHash::<T>::get()
+        }
+
+        /// The hash of block `n`, or the default hash if it is unknown.
+        pub fn block_hash(n: T::BlockNumber) -> T::Hash {
+            BlockHash::<T>::get(n)
+        }
+
+        pub fn account(who: &T::AccountId) -> AccountInfo<T::Index, T::AccountData> {
+            Account::<T>::get(who)
+        }
+
+        pub fn account_nonce(who: &T::AccountId) -> T::Index {
+            Account::<T>::get(who).nonce
+        }
+
+        pub fn inc_account_nonce(who: &T::AccountId) {
+            Account::<T>::mutate(who, |a| a.nonce = a.nonce.saturating_add(T::Index::one()));
+        }
+
+        /// Whether anything allows the account to exist.
+        pub fn account_exists(who: &T::AccountId) -> bool {
+            let account = Account::<T>::get(who);
+            account.providers > 0 || account.sufficients > 0
+        }
+
+        pub fn providers(who: &T::AccountId) -> RefCount {
+            Account::<T>::get(who).providers
+        }
+
+        pub fn consumers(who: &T::AccountId) -> RefCount {
+            Account::<T>::get(who).consumers
+        }
+
+        pub fn sufficients(who: &T::AccountId) -> RefCount {
+            Account::<T>::get(who).sufficients
+        }
+
+        /// Whether a provider can be removed, i.e. it is not the last one while other modules
+        /// depend on the account.
+        pub fn can_dec_provider(who: &T::AccountId) -> bool {
+            let account = Account::<T>::get(who);
+            account.consumers == 0 || account.providers > 1
+        }
+
+        /// Whether a module can depend on the account, i.e. something provides it.
+        pub fn can_inc_consumer(who: &T::AccountId) -> bool {
+            Account::<T>::get(who).providers > 0
+        }
+
+        /// Allow the account to exist, creating it if nothing did before.
+        pub fn inc_providers(who: &T::AccountId) -> IncRefStatus {
+            let mut account = Account::<T>::get(who);
+            let created = account.providers == 0 && account.sufficients == 0;
+            account.providers = account.providers.saturating_add(1);
+            Account::<T>::insert(who, account);
+            if created {
+                Self::on_created_account(who);
+                IncRefStatus::Created
+            } else {
+                IncRefStatus::Existed
+            }
+        }
+
+        /// Remove a provider of the account, which is removed with the last one unless it is
+        /// sufficient on its own. The last provider can't be removed while other modules depend
+        /// on the account.
+        pub fn dec_providers(who: &T::AccountId) -> Result<DecRefStatus, DispatchError> {
+            let mut account = Account::<T>::get(who);
+            if account.providers == 0 {
+                // Nothing to remove, the account only exists if it is sufficient.
+                return Ok(DecRefStatus::Exists)
+            }
+            if account.providers == 1 && account.consumers > 0 {
+                return Err(DispatchError::ConsumerRemaining)
+            }
+            account.providers -= 1;
+            if account.providers == 0 && account.sufficients == 0 {
+                Self::kill_account(who);
+                return Ok(DecRefStatus::Reaped)
+            }
+            Account::<T>::insert(who, account);
+            Ok(DecRefStatus::Exists)
+        }
+
+        /// Allow the account to exist on its own, creating it if nothing did before.
+        pub fn inc_sufficients(who: &T::AccountId) -> IncRefStatus {
+            let mut account = Account::<T>::get(who);
+            let created = account.providers == 0 && account.sufficients == 0;
+            account.sufficients = account.sufficients.saturating_add(1);
+            Account::<T>::insert(who, account);
+            if created {
+                Self::on_created_account(who);
+                IncRefStatus::Created
+            } else {
+                IncRefStatus::Existed
+            }
+        }
+
+        /// Remove a sufficient reference, removing the account if nothing provides it.
+        pub fn dec_sufficients(who: &T::AccountId) -> DecRefStatus {
+            let mut account = Account::<T>::get(who);
+            if account.sufficients == 0 {
+                return DecRefStatus::Exists
+            }
+            account.sufficients -= 1;
+            if account.providers == 0 && account.sufficients == 0 {
+                Self::kill_account(who);
+                return DecRefStatus::Reaped
+            }
+            Account::<T>::insert(who, account);
+            DecRefStatus::Exists
+        }
+
+        /// Depend on the account, which must be provided.
+        pub fn inc_consumers(who: &T::AccountId) -> DispatchResult {
+            let mut account = Account::<T>::get(who);
+            if account.providers == 0 {
+                return Err(DispatchError::NoProviders)
+            }
+            account.consumers = account.consumers.saturating_add(1);
+            Account::<T>::insert(who, account);
+            Ok(())
+        }
+
+        /// Stop depending on the account.
+        pub fn dec_consumers(who: &T::AccountId) {
+            Account::<T>::mutate(who, |a| a.consumers = a.consumers.saturating_sub(1));
+        }
+
+        fn on_created_account(who: &T::AccountId) {
+            T::OnNewAccount::on_new_account(who);
+            Self::deposit_event(Event::NewAccount { account: who.clone() });
+        }
+
+        fn kill_account(who: &T::AccountId) {
+            Account::<T>::remove(who);
+            T::OnKilledAccount::on_killed_account(who);
+            Self::deposit_event(Event::KilledAccount { account: who.clone() });
+        }
+
+        /// Do nothing, the remark is only kept in the block.
+        pub fn remark(origin: T::RuntimeOrigin, _remark: Vec<u8>) -> DispatchResult {
+            ensure_signed(origin)?;
+            Ok(())
+        }
+
+        /// Start the execution of block `number`, child of `parent_hash`, with the logs of
+        /// `digest` and without the events of the previous block.
+        pub fn initialize(
+            number: &T::BlockNumber,
+            parent_hash: &T::Hash,
+            digest: &generic::Digest,
+        ) {
+            ExecutionPhase::put(Phase::Initialization);
+            Number::<T>::put(number);
+            ParentHash::<T>::put(parent_hash);
+            Digest::put(digest);
+            BlockHash::<T>::insert(number.saturating_sub(One::one()), parent_hash);
+            Self::reset_events();
+        }
+
+        /// The index of the extrinsic being applied, if extrinsics are being applied.
+        pub fn extrinsic_index() -> Option<u32> {
+            match ExecutionPhase::get()? {
+                Phase::ApplyExtrinsic(index) => Some(index),
+                _ => None,
+            }
+        }
+
+        /// The weight of the block so far, per class.
+        pub fn block_weight() -> PerDispatchClass<Weight> {
+            BlockWeight::get()
+        }
+
+        /// Add `weight` to the weight of the block, even if it doesn't fit anymore, e.g. for
+        /// the hooks of the pallets.
+        pub fn register_extra_weight_unchecked(weight: Weight, class: DispatchClass) {
+            BlockWeight::mutate(|current| current.add(weight, class));
+        }
+
+        /// The hooks of the block ran, so its extrinsics come next.
+        pub fn note_finished_initialize() {
+            ExecutionPhase::put(Phase::ApplyExtrinsic(0));
+        }
+
+        /// Keep the encoded extrinsic being applied, committed to by the extrinsics root.
+        pub fn note_extrinsic(encoded: Vec<u8>) {
+            ExtrinsicData::insert(Self::extrinsic_index().unwrap_or_default(), encoded);
+        }
+
+        /// Record what the extrinsic being applied cost, then move to the next one.
+        pub fn note_applied_extrinsic(result: &DispatchResultWithPostInfo, mut info: DispatchInfo) {
+            info.weight = extract_actual_weight(result, &info);
+            info.pays_fee = extract_actual_pays_fee(result, &info);
+            Self::deposit_event(match result {
+                Ok(_) => Event::ExtrinsicSuccess { dispatch_info: info },
+                Err(err) => {
+                    Event::ExtrinsicFailed { dispatch_error: err.error, dispatch_info: info }
+                }
+            });
+
+            let next = Self::extrinsic_index().unwrap_or_default() + 1;
+            ExecutionPhase::put(Phase::ApplyExtrinsic(next));
+        }
+
+        /// All extrinsics of the block were applied, so it is being finalized.
+        pub fn note_finished_extrinsics() {
+            ExtrinsicCount::put(Self::extrinsic_index().unwrap_or_default());
+            ExecutionPhase::put(Phase::Finalization);
+        }
+
+        /// End the block, removing what only matters while it executes, and give its header.
+        pub fn finalize() -> Header<T::BlockNumber, T::Hash> {
+            ExecutionPhase::kill();
+            BlockWeight::kill();
+            let digest = Digest::take();
+            AllExtrinsicsLen::kill();
+            let count = ExtrinsicCount::take().unwrap_or_default();
+            let extrinsics: Vec<_> = (0..count).map(ExtrinsicData::take).collect();
+
+            Header {
+                parent_hash: Self::parent_hash(),
+                number: Self::block_number(),
+                state_root: frame_support::storage::root::<T::Hashing>(),
+                extrinsics_root: T::Hashing::hash_of(&extrinsics),
+                digest,
+            }
+        }
+
+        /// Add `item` to the digest of the block.
+        pub fn deposit_log(item: generic::DigestItem) {
+            Digest::mutate(|digest| digest.push(item));
+        }
+
+        /// Remove the events of the block, with their topics.
+        pub fn reset_events() {
+            Events::<T>::kill();
+            EventCount::kill();
+            EventTopics::<T>::clear();
+        }
+
+        /// Record `event`, in the current phase of the block.
+        pub fn deposit_event(event: impl Into<T::RuntimeEvent>) {
+            Self::deposit_event_indexed(&[], event.into());
+        }
+
+        /// Record `event`, which can be looked up by any of `topics` with
+        /// [`Self::event_topics`].
+        pub fn deposit_event_indexed(topics: &[T::Hash], event: T::RuntimeEvent) {
+            // The genesis block has no events.
+            let block_number = Self::block_number();
+            if block_number.is_zero() {
+                return
+            }
+
+            let index = EventCount::get();
+            // No more events fit in the block.
+            let Some(count) = index.checked_add(1) else { return };
+            EventCount::put(count);
+
+            let phase = ExecutionPhase::get().unwrap_or_default();
+            let record = EventRecord { phase, event, topics: topics.to_vec() };
+            Events::<T>::mutate(|events| events.push(record));
+            for topic in topics {
+                EventTopics::<T>::mutate(topic, |indices| indices.push((block_number, index)));
+            }
+        }
+
+        /// The events of the block, with their phase and topics.
+        pub fn events() -> Vec<EventRecord<T::RuntimeEvent, T::Hash>> {
+            Events::<T>::get()
+        }
+
+        pub fn event_count() -> EventIndex {
+            EventCount::get()
+        }
+
+        /// The block numbers and indices of the events with `topic`.
+        pub fn event_topics(topic: &T::Hash) -> Vec<(T::BlockNumber, EventIndex)> {
+            EventTopics::<T>::get(topic)
+        }
+
+        /// Panic unless `event` was deposited in the block.
+        pub fn assert_has_event(event: T::RuntimeEvent) {
+            let events = Self::events();
+            assert!(
+                events.iter().any(|record| record.event == event),
+                "expected event {:?} not found in events {:?}",
+                event,
+                events,
+            );
+        }
+
+        /// Panic unless `event` is the last event deposited in the block.
+        pub fn assert_last_event(event: T::RuntimeEvent) {
+            let last = Self::events().pop().map(|record| record.event);
+            assert_eq!(last, Some(event), "expected event is not the last event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::*;
+    use frame_support::{
+        generic::DigestItem,
+        hashing::{BlakeTwo256, Hash},
+        pallet_prelude::*,
+        storage::TestExternalities,
+        traits::Dispatchable,
+    };
+
+    fn new_account(account: u64) -> RuntimeEvent {
+        Event::NewAccount { account }.into()
+    }
+
+    #[test]
+    fn nonces_are_kept_per_account() {
+        TestExternalities::default().execute_with(|| {
+            System::inc_account_nonce(&1);
+            System::inc_account_nonce(&1);
+            assert_eq!(System::account_nonce(&1), 2);
+            assert_eq!(System::account_nonce(&2), 0);
+            assert_eq!(System::account(&1), AccountInfo { nonce: 2, ..Default::default() });
+        });
+    }
+
+    #[test]
+    fn providers_create_and_remove_accounts() {
+        TestExternalities::default().execute_with(|| {
+            assert!(!System::account_exists(&1));
+            assert_eq!(System::inc_providers(&1), IncRefStatus::Created);
+            assert_eq!(System::inc_providers(&1), IncRefStatus::Existed);
+            System::inc_account_nonce(&1);
+            assert_eq!((System::account_exists(&1), System::providers(&1)), (true, 2));
+
+            assert_eq!(System::dec_providers(&1), Ok(DecRefStatus::Exists));
+            assert_eq!(System::dec_providers(&1), Ok(DecRefStatus::Reaped));
+            assert!(!Account::<Test>::contains_key(1));
+            // Removing a provider of a missing account does nothing.
+            assert_eq!(System::dec_providers(&1), Ok(DecRefStatus::Exists));
+
+            assert_eq!(NEW.with(|new| new.take()), vec![1]);
+            assert_eq!(KILLED.with(|killed| killed.take()), vec![1]);
+        });
+    }
+
+    #[test]
+    fn consumers_keep_the_last_provider() {
+        TestExternalities::default().execute_with(|| {
+            assert_eq!(System::inc_consumers(&1), Err(DispatchError::NoProviders));
+            assert!(!System::can_inc_consumer(&1));
+
+            System::inc_providers(&1);
+            assert_eq!(System::inc_consumers(&1), Ok(()));
+            assert!(!System::can_dec_provider(&1));
+            assert_eq!(System::dec_providers(&1), Err(DispatchError::ConsumerRemaining));
+            assert_eq!(System::providers(&1), 1);
+
+            // Another provider can go.
+            System::inc_providers(&1);
+            assert!(System::can_dec_provider(&1));
+            assert_eq!(System::dec_providers(&1), Ok(DecRefStatus::Exists));
+
+            System::dec_consumers(&1);
+            assert_eq!(System::consumers(&1), 0);
+            assert_eq!(System::dec_providers(&1), Ok(DecRefStatus::Reaped));
+            assert_eq!(KILLED.with(|killed| killed.take()), vec![1]);
+        });
+    }
+
+    #[test]
+    fn sufficients_keep_accounts_on_their_own() {
+        TestExternalities::default().execute_with(|| {
+            assert_eq!(System::inc_sufficients(&1), IncRefStatus::Created);
+            assert_eq!(System::inc_providers(&1), IncRefStatus::Existed);
+            assert_eq!(System::dec_providers(&1), Ok(DecRefStatus::Exists));
+            assert!(System::account_exists(&1));
+            assert_eq!(System::dec_sufficients(&1), DecRefStatus::Reaped);
+            assert!(!System::account_exists(&1));
+
+            assert_eq!(NEW.with(|new| new.take()), vec![1]);
+            assert_eq!(KILLED.with(|killed| killed.take()), vec![1]);
+        });
+    }
+
+    #[test]
+    fn block_number_and_hashes_are_stored() {
+        TestExternalities::default().execute_with(|| {
+            assert_eq!(System::block_number(), 0);
+            System::set_block_number(2);
+            assert_eq!(System::block_number(), 2);
+            assert_eq!(System::block_hash(1), [0; 32]);
+            BlockHash::<Test>::insert(1, [1; 32]);
+            assert_eq!(System::block_hash(1), [1; 32]);
+        });
+    }
+
+    #[test]
+    fn events_are_recorded_with_topics() {
+        TestExternalities::default().execute_with(|| {
+            // Nothing is recorded at genesis.
+            System::deposit_event(new_account(1));
+            assert!(System::events().is_empty());
+
+            System::initialize(&1, &[1; 32], &Default::default());
+            System::deposit_event(new_account(1));
+            ExecutionPhase::put(Phase::ApplyExtrinsic(0));
+            System::deposit_event_indexed(&[[7; 32]], new_account(2));
+            assert_eq!(
+                System::events(),
+                vec![
+                    EventRecord {
+                        phase: Phase::Initialization,
+                        event: new_account(1),
+                        topics: vec![],
+                    },
+                    EventRecord {
+                        phase: Phase::ApplyExtrinsic(0),
+                        event: new_account(2),
+                        topics: vec![[7; 32]],
+                    },
+                ]
+            );
+            assert_eq!(System::event_count(), 2);
+            assert_eq!(System::event_topics(&[7; 32]), vec![(1, 1)]);
+            System::assert_has_event(new_account(1));
+            System::assert_last_event(new_account(2));
+
+            // The next block starts without them.
+            System::initialize(&2, &[2; 32], &Default::default());
+            assert!(System::events().is_empty());
+            assert!(System::event_topics(&[7; 32]).is_empty());
+            assert_eq!((System::parent_hash(), System::block_hash(1)), ([2; 32], [2; 32]));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "expected event is not the last event")]
+    fn assert_last_event_fails_on_other_events() {
+        TestExternalities::default().execute_with(|| {
+            System::set_block_number(1);
+            System::deposit_event(new_account(1));
+            System::assert_last_event(new_account(2));
+        });
+    }
+
+    #[test]
+    fn applied_extrinsics_are_recorded() {
+        TestExternalities::default().execute_with(|| {
+            System::initialize(&1, &[1; 32], &Default::default());
+            System::note_finished_initialize();
+
+            System::note_extrinsic(vec![1]);
+            let info = DispatchInfo { weight: Weight::from_parts(10, 0), ..Default::default() };
+            let post_info = PostDispatchInfo::from(Some(Weight::from_parts(4, 0)));
+            System::note_applied_extrinsic(&Ok(post_info), info);
+            System::note_extrinsic(vec![2]);
+            System::note_applied_extrinsic(&Err(DispatchError::BadOrigin.into()), info);
+            assert_eq!(System::extrinsic_index(), Some(2));
+
+            let events: Vec<_> = System::events().into_iter().map(|r| (r.phase, r.event)).collect();
+            let actual_info = DispatchInfo { weight: Weight::from_parts(4, 0), ..info };
+            assert_eq!(
+                events,
+                vec![
+                    (
+                        Phase::ApplyExtrinsic(0),
+                        Event::ExtrinsicSuccess { dispatch_info: actual_info }.into(),
+                    ),
+                    (
+                        Phase::ApplyExtrinsic(1),
+                        Event::ExtrinsicFailed {
+                            dispatch_error: DispatchError::BadOrigin,
+                            dispatch_info: info,
+                        }
+                        .into(),
+                    ),
+                ]
+            );
+
+            System::note_finished_extrinsics();
+            let header = System::finalize();
+            assert_eq!((header.number, header.parent_hash), (1, [1; 32]));
+            assert_eq!(header.extrinsics_root, BlakeTwo256::hash_of(&vec![vec![1u8], vec![2]]));
+            // The state root is taken without the data of the extrinsics.
+            assert_eq!(ExtrinsicData::iter().count(), 0);
+            assert_eq!(header.state_root, frame_support::storage::root::<BlakeTwo256>());
+        });
+    }
+
+    #[test]
+    fn logs_are_kept_in_the_header() {
+        TestExternalities::default().execute_with(|| {
+            let mut digest = frame_support::generic::Digest::default();
+            digest.push(DigestItem::PreRuntime(*b"test", vec![1]));
+            System::initialize(&1, &[1; 32], &digest);
+            System::deposit_log(DigestItem::Other(vec![2]));
+
+            digest.push(DigestItem::Other(vec![2]));
+            assert_eq!(System::finalize().digest, digest);
+            assert!(!Digest::exists());
+        });
+    }
+
+    #[test]
+    fn signed_origins_make_remarks() {
+        TestExternalities::default().execute_with(|| {
+            let call = RuntimeCall::System(Call::remark { remark: vec![0; 10] });
+            assert_eq!(call.get_dispatch_info().weight, <() as WeightInfo>::remark(10));
+            assert_eq!(call.clone().dispatch(RuntimeOrigin::signed(1)), Ok(().into()));
+            assert_eq!(call.dispatch(RuntimeOrigin::none()), Err(DispatchError::BadOrigin.into()));
+        });
+    }
+
+    #[test]
+    fn accounts_are_announced() {
+        TestExternalities::default().execute_with(|| {
+            System::set_block_number(1);
+            System::inc_providers(&1);
+            System::dec_providers(&1).unwrap();
+            assert_eq!(
+                System::events().into_iter().map(|r| r.event).collect::<Vec<_>>(),
+                vec![new_account(1), Event::KilledAccount { account: 1 }.into()]
+            );
+        });
+    }
+
+    #[test]
+    fn storage_is_described() {
+        let storage = System::storage_metadata();
+        let names: Vec<_> = storage.entries.iter().map(|e| e.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Account",
+                "Number",
+                "ParentHash",
+                "BlockHash",
+                "Digest",
+                "ExecutionPhase",
+                "Events",
+                "EventCount",
+                "EventTopics",
+                "BlockWeight",
+                "AllExtrinsicsLen",
+                "ExtrinsicCount",
+                "ExtrinsicData",
+            ]
+        );
+        // Events are not bounded.
+        assert!(System::storage_info().iter().all(|i| i.max_size.is_none()));
+    }
+
+    #[test]
+    fn constants_are_described() {
+        let constants = System::constants_metadata();
+        let names: Vec<_> = constants.iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["Version", "SS58Prefix"]);
+        assert_eq!(constants[0].value, TestVersion::get().encode());
+        assert_eq!(constants[1].value, vec![0, 0]);
+        assert_eq!(constants[1].docs, vec!["The prefix of the SS58 addresses of the chain."]);
+    }
+}
@@ -0,0 +1,94 @@
+//! A runtime with only the system pallet, to test it.
+
+use crate as frame_system;
+use frame_support::{
+    hashing::BlakeTwo256,
+    limits::{BlockLength, BlockWeights},
+    traits::{Everything, Get, IdentityLookup, OnKilledAccount, OnNewAccount},
+    version::RuntimeVersion,
+    weights::Weight,
+};
+use std::cell::RefCell;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system::{Pallet, Call, Storage, Origin<T>, Event<T>},
+    }
+);
+
+pub const MAX_BLOCK_REF_TIME: u64 = 1_000_000_000;
+pub const MAX_BLOCK_LENGTH: u32 = 1024;
+pub const SPEC_VERSION: u32 = 7;
+
+pub struct TestBlockWeights;
+
+impl Get<BlockWeights> for TestBlockWeights {
+    fn get() -> BlockWeights {
+        BlockWeights::simple_max(Weight::from_parts(MAX_BLOCK_REF_TIME, u64::MAX))
+    }
+}
+
+pub struct TestBlockLength;
+
+impl Get<BlockLength> for TestBlockLength {
+    fn get() -> BlockLength {
+        BlockLength::max_with_normal_ratio(MAX_BLOCK_LENGTH, 75)
+    }
+}
+
+pub struct TestVersion;
+
+impl Get<RuntimeVersion> for TestVersion {
+    fn get() -> RuntimeVersion {
+        RuntimeVersion {
+            spec_name: "test".into(),
+            spec_version: SPEC_VERSION,
+            ..Default::default()
+        }
+    }
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = [u8; 32];
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<u64>;
+    type RuntimeEvent = RuntimeEvent;
+    type AccountData = u128;
+    type OnNewAccount = RecordNew;
+    type OnKilledAccount = RecordKilled;
+    type BlockWeights = TestBlockWeights;
+    type BlockLength = TestBlockLength;
+    type Version = TestVersion;
+    type PalletInfo = PalletInfo;
+    type SS58Prefix = ();
+    type SystemWeightInfo = ();
+}
+
+thread_local! {
+    pub static NEW: RefCell<Vec<u64>> = Default::default();
+    pub static KILLED: RefCell<Vec<u64>> = Default::default();
+}
+
+/// Records the accounts created, in [`NEW`].
+pub struct RecordNew;
+
+impl OnNewAccount<u64> for RecordNew {
+    fn on_new_account(who: &u64) {
+        NEW.with(|new| new.borrow_mut().push(*who));
+    }
+}
+
+/// Records the accounts removed, in [`KILLED`].
+pub struct RecordKilled;
+
+impl OnKilledAccount<u64> for RecordKilled {
+    fn on_killed_account(who: &u64) {
+        KILLED.with(|killed| killed.borrow_mut().push(*who));
+    }
+}
@@ -0,0 +1,14 @@
+//! The weights of the system dispatchables.
+
+use frame_support::weights::Weight;
+
+pub trait WeightInfo {
+    fn remark(b: u32) -> Weight;
+}
+
+/// Estimated from the length of the remark, until the dispatchables are benchmarked.
+impl WeightInfo for () {
+    fn remark(b: u32) -> Weight {
+        Weight::from_parts(2_000_000, 0).saturating_add(Weight::from_parts(400, 0) * u64::from(b))
+    }
+}
@@ -1,3 +1,11 @@
+//! The node: what runs the runtime, out of it.
+
+pub mod block_builder;
+pub mod block_tree;
+pub mod node;
+pub mod runtime_api;
+pub mod transaction_pool;
+
 #[cfg(test)]
 mod tests {
     #[test]
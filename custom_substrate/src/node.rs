@@ -0,0 +1,183 @@
+//! A node authoring blocks from its transaction pool, and importing the blocks of others.
+//!
+//! With [`Sealing::Instant`], every transaction submitted is sealed in a block of its own, which
+//! is finalized right away. With [`Sealing::Manual`], blocks are sealed on request by
+//! [`Node::seal_block`], finalized then or later by [`Node::finalize_block`].
+
+use crate::{
+    block_builder::BlockBuilder,
+    block_tree::{self, BlockTree},
+    runtime_api::{BlockOf, RuntimeApi},
+    transaction_pool::{self, ChainApi, TransactionPool, TxHash},
+};
+use frame_support::{
+    generic::Digest,
+    storage::{Storage, TestExternalities},
+    transaction_validity::TransactionValidity,
+};
+
+/// When blocks are sealed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sealing {
+    /// A block, finalized, for every transaction submitted.
+    Instant,
+    /// A block on each call to [`Node::seal_block`].
+    Manual,
+}
+
+/// Why the node could not do what it was asked.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// The transaction pool refused the transaction.
+    Pool(transaction_pool::Error),
+    /// No transaction is ready to be included, and empty blocks were not asked for.
+    EmptyTransactionPool,
+    /// The block could not be imported or finalized.
+    Block(block_tree::Error),
+}
+
+impl From<transaction_pool::Error> for Error {
+    fn from(err: transaction_pool::Error) -> Self {
+        Error::Pool(err)
+    }
+}
+
+impl From<block_tree::Error> for Error {
+    fn from(err: block_tree::Error) -> Self {
+        Error::Block(err)
+    }
+}
+
+/// A node of the runtime `R`, authoring blocks and importing the blocks of others.
+pub struct Node<R: RuntimeApi> {
+    sealing: Sealing,
+    pool: TransactionPool<R::Extrinsic>,
+    tree: BlockTree<R>,
+}
+
+/// Validates transactions against the state after the best block, for the pool.
+struct BestState<'a, R: RuntimeApi> {
+    tree: &'a BlockTree<R>,
+}
+
+impl<R: RuntimeApi> ChainApi for BestState<'_, R> {
+    type Extrinsic = R::Extrinsic;
+
+    fn validate_transaction(&self, xt: &R::Extrinsic) -> TransactionValidity {
+        // Validating starts the next block: do it on a copy of the state.
+        TestExternalities::new(self.tree.best_state().clone())
+            .execute_with(|| R::validate_transaction(xt.clone(), self.tree.best_hash()))
+    }
+
+    fn block_number(&self) -> u64 {
+        self.tree.best_block().header.number.into()
+    }
+}
+
+impl<R: RuntimeApi> Node<R> {
+    /// Start a chain whose genesis block has the state `genesis`.
+    pub fn new(genesis: Storage, sealing: Sealing) -> Self {
+        Node { sealing, pool: TransactionPool::default(), tree: BlockTree::new(genesis) }
+    }
+
+    pub fn pool(&self) -> &TransactionPool<R::Extrinsic> {
+        &self.pool
+    }
+
+    /// The blocks the node knows of, forks included.
+    pub fn tree(&self) -> &BlockTree<R> {
+        &self.tree
+    }
+
+    pub fn genesis_hash(&self) -> R::Hash {
+        self.tree.genesis_hash()
+    }
+
+    pub fn best_hash(&self) -> R::Hash {
+        self.tree.best_hash()
+    }
+
+    pub fn best_block(&self) -> &BlockOf<R> {
+        self.tree.best_block()
+    }
+
+    pub fn finalized_hash(&self) -> R::Hash {
+        self.tree.finalized_hash()
+    }
+
+    /// The block of `hash`, on any fork.
+    pub fn block(&self, hash: &R::Hash) -> Option<&BlockOf<R>> {
+        self.tree.block(hash)
+    }
+
+    /// Run `f` on a copy of the state after the best block, e.g. to read storage.
+    pub fn execute_with<T>(&self, f: impl FnOnce() -> T) -> T {
+        TestExternalities::new(self.tree.best_state().clone()).execute_with(f)
+    }
+
+    /// Add `xt` to the transaction pool, and seal it with [`Sealing::Instant`].
+    pub fn submit(&mut self, xt: R::Extrinsic) -> Result<TxHash, Error> {
+        let hash = self.pool.submit(&BestState { tree: &self.tree }, xt)?;
+        if self.sealing == Sealing::Instant {
+            // The transaction may wait for another one.
+            match self.seal_block(false, true) {
+                Ok(_) | Err(Error::EmptyTransactionPool) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Seal a block of the ready transactions of the pool on top of the best block, and
+    /// finalize it if `finalize`. Gives the hash of the block.
+    ///
+    /// Without ready transactions, the block is only sealed if `create_empty`.
+    pub fn seal_block(&mut self, create_empty: bool, finalize: bool) -> Result<R::Hash, Error> {
+        if !create_empty && self.pool.status().ready == 0 {
+            return Err(Error::EmptyTransactionPool)
+        }
+
+        let parent = &self.tree.best_block().header;
+        let state = self.tree.best_state().clone();
+        let mut builder = BlockBuilder::<R>::new(parent, state, Digest::default());
+        let invalid = builder.apply_from_pool(&self.pool);
+        let built = builder.build();
+
+        let hash = R::hash_header(&built.block.header);
+        self.tree.import_executed(built.block, built.state)?;
+        if finalize {
+            self.tree.finalize(&hash)?;
+        }
+
+        // The included transactions are now stale.
+        self.pool.remove(&invalid);
+        self.pool.maintain(&BestState { tree: &self.tree });
+        Ok(hash)
+    }
+
+    /// Import `block`, e.g. sealed by another node. Gives the hash of the block.
+    ///
+    /// When the block makes another fork the best chain, the extrinsics of the blocks which left
+    /// the best chain go back to the pool, unless the new best chain includes them too.
+    pub fn import_block(&mut self, block: BlockOf<R>) -> Result<R::Hash, Error> {
+        let hash = R::hash_header(&block.header);
+        let outcome = self.tree.import(block)?;
+        if !outcome.is_new_best {
+            return Ok(hash)
+        }
+        let retracted = outcome.retracted.iter().rev().flat_map(|hash| {
+            self.tree.block(hash).expect("retracted blocks are in the tree").extrinsics.clone()
+        });
+        for xt in retracted.collect::<Vec<_>>() {
+            // Included again, or no longer valid.
+            let _ = self.pool.submit(&BestState { tree: &self.tree }, xt);
+        }
+        self.pool.maintain(&BestState { tree: &self.tree });
+        Ok(hash)
+    }
+
+    /// Finalize the block of `hash` and the blocks before it, pruning the other forks.
+    pub fn finalize_block(&mut self, hash: &R::Hash) -> Result<(), Error> {
+        Ok(self.tree.finalize(hash)?)
+    }
+}
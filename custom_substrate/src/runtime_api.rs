@@ -0,0 +1,87 @@
+//! What the node calls into the runtime for.
+
+use frame_executive::{CheckedOf, Executive};
+use frame_support::{
+    codec::Encode,
+    dispatch::{ApplyExtrinsicResult, GetDispatchInfo},
+    generic::{Block, Header},
+    hashing::Hash,
+    pallet_prelude::{One, Saturating, Zero},
+    storage,
+    traits::{Applyable, Checkable, Hooks},
+    transaction_validity::TransactionValidity,
+};
+use std::fmt::Debug;
+
+/// The header of the blocks of the runtime `R`.
+pub type HeaderOf<R> = Header<<R as RuntimeApi>::BlockNumber, <R as RuntimeApi>::Hash>;
+
+/// The blocks of the runtime `R`.
+pub type BlockOf<R> = Block<HeaderOf<R>, <R as RuntimeApi>::Extrinsic>;
+
+/// A runtime, as the node sees it: functions run against the state of a block, inside
+/// `TestExternalities::execute_with`.
+pub trait RuntimeApi {
+    type BlockNumber: Copy + Ord + Debug + Zero + One + Saturating + Into<u64>;
+    type Hash: Copy + Ord + Default + Debug + Encode;
+    type Extrinsic: Encode + Clone;
+
+    /// The hash of the block of `header`, which identifies the block.
+    fn hash_header(header: &HeaderOf<Self>) -> Self::Hash;
+
+    /// The header of the genesis block, whose state is the current one.
+    fn genesis_header() -> HeaderOf<Self>;
+
+    fn initialize_block(header: &HeaderOf<Self>);
+
+    fn apply_extrinsic(xt: Self::Extrinsic) -> ApplyExtrinsicResult;
+
+    fn finalize_block() -> HeaderOf<Self>;
+
+    /// Validate `xt` for the block following the one of `block_hash`, whose state is the
+    /// current one.
+    fn validate_transaction(xt: Self::Extrinsic, block_hash: Self::Hash) -> TransactionValidity;
+}
+
+impl<System, Extrinsic, AllPalletsWithSystem> RuntimeApi
+    for Executive<System, Extrinsic, AllPalletsWithSystem>
+where
+    System: frame_system::Config,
+    Extrinsic: Checkable<System::Lookup> + Encode + Clone,
+    CheckedOf<Extrinsic, System>: Applyable + GetDispatchInfo,
+    AllPalletsWithSystem: Hooks<System::BlockNumber>,
+{
+    type BlockNumber = System::BlockNumber;
+    type Hash = System::Hash;
+    type Extrinsic = Extrinsic;
+
+    fn hash_header(header: &HeaderOf<Self>) -> System::Hash {
+        header.hash::<System::Hashing>()
+    }
+
+    fn genesis_header() -> HeaderOf<Self> {
+        Header {
+            parent_hash: Default::default(),
+            number: Zero::zero(),
+            state_root: storage::root::<System::Hashing>(),
+            extrinsics_root: System::Hashing::hash_of(&Vec::<Vec<u8>>::new()),
+            digest: Default::default(),
+        }
+    }
+
+    fn initialize_block(header: &HeaderOf<Self>) {
+        Self::initialize_block(header)
+    }
+
+    fn apply_extrinsic(xt: Extrinsic) -> ApplyExtrinsicResult {
+        Self::apply_extrinsic(xt)
+    }
+
+    fn finalize_block() -> HeaderOf<Self> {
+        Self::finalize_block()
+    }
+
+    fn validate_transaction(xt: Extrinsic, block_hash: System::Hash) -> TransactionValidity {
+        Self::validate_transaction(xt, block_hash)
+    }
+}
@@ -1,41 +1,302 @@
 
-use pallet_hello;
-use pallet_balances;
 use frame_support::{
-    construct_runtime
+    construct_runtime, generic,
+    hashing::BlakeTwo256,
+    testing::TestSignature,
+    traits::{ConstU16, ConstU32, Everything, IdentityLookup},
 };
-// Aggregate all the pallet event types
-pub struct Event {}
 
-impl<T: pallet_hello::Config> From<pallet_hello::Event<T>> for Event {
-    fn from(_: pallet_hello::Event<T>) -> Self {
-        Self {}
-    }
-}
-
-impl<T: pallet_balances::Config> From<pallet_balances::Event<T>> for Event {
-    fn from(_: pallet_balances::Event<T>) -> Self {
-        Self {}
-    }
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u32;
+    type BlockNumber = u32;
+    type Hash = [u8; 32];
+    type Hashing = BlakeTwo256;
+    type AccountId = u32;
+    type Lookup = IdentityLookup<u32>;
+    type RuntimeEvent = RuntimeEvent;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type SS58Prefix = ConstU16<42>;
+    type SystemWeightInfo = ();
 }
 
 impl pallet_hello::Config for Runtime {
-    type Event = Event;
+    type RuntimeEvent = RuntimeEvent;
 }
 
 impl pallet_balances::Config for Runtime {
-    type Event = Event;
+    type RuntimeEvent = RuntimeEvent;
     type Balances = u32;
-    type AccountId = u32;
+    type MaxLocks = ConstU32<50>;
+    type ReserveIdentifier = [u8; 8];
+    type MaxReserves = ConstU32<50>;
+    type ExistentialDeposit = ConstU32<10>;
+    type DustRemoval = ();
+    type WeightInfo = ();
 }
 
 // Our goal is make "construct_runtime" macros like real-Substrate code
 construct_runtime!(
     pub enum Runtime 
     {
-        Hello: pallet_hello
-        Balances: pallet_balances
+        System: frame_system::{Pallet, Call, Storage, Origin<T>, Event<T>},
+        Hello: pallet_hello::{Pallet, Origin, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
     }
 );
 
-pub enum Runtime {}
\ No newline at end of file
+/// The checks run around each signed extrinsic.
+pub type SignedExtra = (
+    frame_system::CheckSpecVersion<Runtime>,
+    frame_system::CheckGenesis<Runtime>,
+    frame_system::CheckMortality<Runtime>,
+    frame_system::CheckNonce<Runtime>,
+    frame_system::CheckWeight<Runtime>,
+);
+
+/// The extrinsics of the blocks, as they are sent to the runtime.
+pub type UncheckedExtrinsic =
+    generic::UncheckedExtrinsic<u32, RuntimeCall, TestSignature<u32>, SignedExtra>;
+
+/// Runs the blocks of the runtime.
+pub type Executive = frame_executive::Executive<Runtime, UncheckedExtrinsic, AllPalletsWithSystem>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{
+        codec::{Decode, Encode},
+        crypto::{AccountId32, Ss58AddressFormat, Ss58Codec},
+        dispatch::{BadOrigin, DispatchError, GetDispatchInfo, RawOrigin},
+        metadata::{StorageEntryType, META_RESERVED},
+        scale_info::{PortableForm, Type, TypeDef, TypeDefPrimitive},
+        traits::{
+            Dispatchable, EitherOf, EnsureOrigin, EnsureRoot, OriginTrait, StorageInfoTrait,
+            UnfilteredDispatchable,
+        },
+    };
+    use frame_support::generic::{Block, Era, Header, SignedPayload};
+    use pallet_hello::EnsureGreeters;
+
+    #[test]
+    fn storage_info_bounds_proof_size() {
+        let info = AllPalletsWithSystem::storage_info();
+        let names: Vec<_> =
+            info.iter().map(|i| (i.pallet_name.clone(), i.storage_name.clone())).collect();
+        assert_eq!(names.last(), Some(&(b"Balances".to_vec(), b"TotalIssuance".to_vec())));
+
+        // The events of the system are not bounded.
+        let events = info.iter().find(|i| i.storage_name == b"Events".to_vec()).unwrap();
+        assert_eq!(events.max_size, None);
+
+        // Reading one account proves at most its balances, its locks and its named reserves, each
+        // after its hashed key, and the total issuance next to them.
+        let max_proof_size: u32 =
+            info.iter().filter(|i| i.pallet_name == b"Balances").filter_map(|i| i.max_size).sum();
+        let locks = 16 + 4 + 1 + 50 * (8 + 4 + 1);
+        let reserves = 16 + 4 + 1 + 50 * (8 + 4);
+        assert_eq!(max_proof_size, (16 + 4 + 3 * 4) + locks + reserves + 4);
+    }
+
+    #[test]
+    fn metadata_describes_balances() {
+        let metadata = Runtime::metadata();
+        assert_eq!(metadata.0, META_RESERVED);
+        assert_eq!(&metadata.encode()[..4], b"meta");
+
+        let metadata = metadata.1;
+        let resolve = |id: u32| -> &Type<PortableForm> { metadata.types.resolve(id).unwrap() };
+        let u32_type = TypeDef::Primitive(TypeDefPrimitive::U32);
+
+        let hello = metadata.pallet("Hello").unwrap();
+        assert_eq!((hello.index, hello.storage.is_none(), hello.calls.is_none()), (1, true, true));
+
+        let balances = metadata.pallet("Balances").unwrap();
+        assert_eq!(balances.index, 2);
+
+        let event = resolve(balances.event.as_ref().unwrap().ty.id());
+        assert_eq!(event.path.segments, vec!["pallet_balances", "pallet", "Event"]);
+        let TypeDef::Variant(variants) = &event.type_def else { panic!("events are enums") };
+        assert_eq!(variants[0].name, "BalanceSet");
+        let fields: Vec<_> = variants[0]
+            .fields
+            .iter()
+            .map(|f| (f.name.unwrap(), f.type_name.unwrap(), &resolve(f.ty.id()).type_def))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("who", "T::AccountId", &u32_type),
+                ("free", "T::Balances", &u32_type),
+                ("reserved", "T::Balances", &u32_type),
+            ]
+        );
+
+        let call = resolve(balances.calls.as_ref().unwrap().ty.id());
+        let TypeDef::Variant(variants) = &call.type_def else { panic!("calls are enums") };
+        let names: Vec<_> = variants.iter().map(|v| v.name).collect();
+        assert_eq!(
+            names,
+            vec!["transfer", "set_balance", "force_transfer", "transfer_keep_alive", "transfer_all"]
+        );
+        let args: Vec<_> = variants[1].fields.iter().map(|f| f.name.unwrap()).collect();
+        assert_eq!(args, vec!["who", "new_free", "new_reserved"]);
+
+        let account = balances.storage.as_ref().unwrap().entry("Account").unwrap();
+        let StorageEntryType::Map { key, value, .. } = &account.ty else { panic!("a map") };
+        assert_eq!(resolve(key.id()).type_def, u32_type);
+        let TypeDef::Composite(fields) = &resolve(value.id()).type_def else { panic!("a struct") };
+        let names: Vec<_> = fields.iter().map(|f| f.name.unwrap()).collect();
+        assert_eq!(names, vec!["free", "reserved", "frozen"]);
+        assert!(fields.iter().all(|f| resolve(f.ty.id()).type_def == u32_type));
+    }
+
+    #[test]
+    fn metadata_tells_the_address_format() {
+        let metadata = Runtime::metadata().1;
+        let system = metadata.pallet("System").unwrap();
+        let prefix = system.constant("SS58Prefix").unwrap();
+        assert_eq!(prefix.value, 42u16.encode());
+        let ty = &metadata.types.resolve(prefix.ty.id()).unwrap().type_def;
+        assert_eq!(ty, &TypeDef::Primitive(TypeDefPrimitive::U16));
+        let max_locks = metadata.pallet("Balances").unwrap().constant("MaxLocks").unwrap();
+        assert_eq!(max_locks.value, 50u32.encode());
+
+        let account = AccountId32([0; 32]);
+        let format = Ss58AddressFormat::custom(u16::decode(&mut &prefix.value[..]).unwrap());
+        assert_eq!(
+            account.to_ss58check_with_version(format),
+            "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM"
+        );
+    }
+
+    #[test]
+    fn origins_convert_to_their_pallet() {
+        let origin = RuntimeOrigin::signed(1);
+        assert_eq!(origin.caller(), &OriginCaller::system(RawOrigin::Signed(1)));
+        assert_eq!(RuntimeOrigin::from(Some(1)).caller(), origin.caller());
+        assert_eq!(RuntimeOrigin::from(None).caller(), RuntimeOrigin::none().caller());
+        assert_eq!(frame_system::ensure_signed(origin), Ok(1));
+
+        let greeters = RuntimeOrigin::from(pallet_hello::Origin::Greeters);
+        assert_eq!(greeters.caller(), &OriginCaller::Hello(pallet_hello::Origin::Greeters));
+        assert_eq!(frame_system::ensure_root(greeters.clone()), Err(BadOrigin));
+
+        type EnsureRootOrGreeters = EitherOf<EnsureRoot<u32>, EnsureGreeters>;
+        assert!(EnsureRootOrGreeters::try_origin(greeters).is_ok());
+        assert!(EnsureRootOrGreeters::try_origin(RuntimeOrigin::root()).is_ok());
+        assert!(EnsureRootOrGreeters::try_origin(RuntimeOrigin::signed(1)).is_err());
+
+        // Origins encode with the index of their pallet.
+        assert_eq!(OriginCaller::Hello(pallet_hello::Origin::Greeters).encode(), vec![1, 0]);
+        assert_eq!(RuntimeOrigin::root().into_caller().encode(), vec![0, 0]);
+    }
+
+    #[test]
+    fn only_root_sets_balances() {
+        frame_support::storage::TestExternalities::default().execute_with(|| {
+            assert_eq!(
+                Balances::set_balance(RuntimeOrigin::signed(1), 1, 100, 0),
+                Err(DispatchError::BadOrigin)
+            );
+            System::set_block_number(1);
+            assert_eq!(Balances::set_balance(RuntimeOrigin::root(), 1, 100, 0), Ok(()));
+            assert_eq!(Balances::get_balances(1), 100);
+
+            let event = RuntimeEvent::from(pallet_balances::Event::BalanceSet {
+                who: 1,
+                free: 100,
+                reserved: 0,
+            });
+            System::assert_last_event(event.clone());
+            // Events encode with the index of their pallet.
+            assert_eq!(event.encode(), vec![2, 0, 1, 0, 0, 0, 100, 0, 0, 0, 0, 0, 0, 0]);
+        });
+    }
+
+    #[test]
+    fn filtered_origins_can_not_dispatch() {
+        frame_support::storage::TestExternalities::default().execute_with(|| {
+            let call = RuntimeCall::from(pallet_balances::Call::set_balance {
+                who: 1,
+                new_free: 100,
+                new_reserved: 0,
+            });
+            let block_balances = |call: &RuntimeCall| !matches!(call, RuntimeCall::Balances(_));
+
+            let mut origin = RuntimeOrigin::none();
+            assert!(origin.filter_call(&call));
+            origin.add_filter(block_balances);
+            assert!(!origin.filter_call(&call));
+            assert_eq!(
+                call.clone().dispatch(origin.clone()),
+                Err(DispatchError::CallFiltered.into())
+            );
+            // Bypassing the filter reaches the origin check of the pallet.
+            assert_eq!(
+                call.clone().dispatch_bypass_filter(origin.clone()),
+                Err(DispatchError::BadOrigin.into())
+            );
+            origin.reset_filter();
+            assert!(origin.filter_call(&call));
+
+            // Root can make any call.
+            let mut root = RuntimeOrigin::root();
+            root.add_filter(block_balances);
+            assert_eq!(call.dispatch(root), Ok(().into()));
+            assert_eq!(Balances::get_balances(1), 100);
+        });
+    }
+
+    fn sign(who: u32, nonce: u32, call: RuntimeCall) -> UncheckedExtrinsic {
+        let extra = (
+            frame_system::CheckSpecVersion::new(),
+            frame_system::CheckGenesis::new(),
+            frame_system::CheckMortality::from(Era::immortal()),
+            frame_system::CheckNonce::from(nonce),
+            frame_system::CheckWeight::new(),
+        );
+        let payload = SignedPayload::new(call.clone(), extra.clone()).unwrap();
+        UncheckedExtrinsic::new_signed(call, who, TestSignature(who, payload.encode()), extra)
+    }
+
+    #[test]
+    fn blocks_are_produced_and_executed() {
+        let set_balance = RuntimeCall::Balances(pallet_balances::Call::set_balance {
+            who: 1,
+            new_free: 100,
+            new_reserved: 0,
+        });
+
+        let (header, extrinsics) =
+            frame_support::storage::TestExternalities::default().execute_with(|| {
+                Executive::initialize_block(&Header::new_unfinalized(1, [0; 32]));
+                let extrinsics = vec![
+                    sign(1, 0, RuntimeCall::System(frame_system::Call::remark { remark: vec![1] })),
+                    sign(1, 1, set_balance.clone()),
+                ];
+                for xt in extrinsics.clone() {
+                    assert!(Executive::apply_extrinsic(xt).is_ok());
+                }
+                // Only root sets balances.
+                System::assert_last_event(RuntimeEvent::System(
+                    frame_system::Event::ExtrinsicFailed {
+                        dispatch_error: DispatchError::BadOrigin,
+                        dispatch_info: set_balance.get_dispatch_info(),
+                    },
+                ));
+                (Executive::finalize_block(), extrinsics)
+            });
+
+        frame_support::storage::TestExternalities::default()
+            .execute_with(|| Executive::execute_block(Block { header, extrinsics }));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,543 @@
+//! The pool of the transactions waiting to be included in a block.
+//!
+//! A transaction is validated against the latest state of the chain when it is submitted, and
+//! again after each new block by [`TransactionPool::maintain`]. Its validity tells the tags it
+//! provides and requires, e.g. the nonce of its signer and the nonce before it. A transaction
+//! whose required tags are all provided by ready transactions is ready to be included, others wait
+//! in the future queue until they are. [`TransactionPool::ready`] gives the ready transactions
+//! ordered by priority, each after the transactions it requires.
+
+use frame_support::{
+    codec::Encode,
+    hashing::{BlakeTwo256, Hash},
+    transaction_validity::{
+        TransactionPriority, TransactionTag, TransactionValidity, TransactionValidityError,
+    },
+};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+};
+
+/// The hash of a transaction, which identifies it in the pool.
+pub type TxHash = [u8; 32];
+
+/// What the pool needs to know of the chain.
+pub trait ChainApi {
+    type Extrinsic: Encode + Clone;
+
+    /// Validate `xt` against the latest state, for the next block.
+    fn validate_transaction(&self, xt: &Self::Extrinsic) -> TransactionValidity;
+
+    /// The number of the latest block.
+    fn block_number(&self) -> u64;
+}
+
+/// The most transactions a queue of the pool holds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Limit {
+    pub count: usize,
+    pub total_bytes: usize,
+}
+
+impl Limit {
+    fn is_exceeded(&self, count: usize, total_bytes: usize) -> bool {
+        count > self.count || total_bytes > self.total_bytes
+    }
+}
+
+/// The limits of the ready and future queues.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Options {
+    pub ready: Limit,
+    pub future: Limit,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            ready: Limit { count: 8192, total_bytes: 20 * 1024 * 1024 },
+            future: Limit { count: 512, total_bytes: 1024 * 1024 },
+        }
+    }
+}
+
+/// Why a transaction was not added to the pool.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// The runtime refused the transaction.
+    Invalid(TransactionValidityError),
+    /// The transaction is already in the pool.
+    AlreadyImported,
+    /// The transaction provides no tag, so nothing prevents it from being included twice.
+    NoTagsProvided,
+    /// A ready transaction provides the same tag, with a priority at least as high.
+    TooLowPriority { old: TransactionPriority, new: TransactionPriority },
+    /// The pool is full of transactions of higher priority.
+    ImmediatelyDropped,
+}
+
+/// A transaction of the pool, with its validity.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Transaction<Extrinsic> {
+    pub data: Extrinsic,
+    pub hash: TxHash,
+    /// The length of the encoded transaction.
+    pub bytes: usize,
+    pub priority: TransactionPriority,
+    pub requires: Vec<TransactionTag>,
+    pub provides: Vec<TransactionTag>,
+    /// The last block the transaction can be included in.
+    pub valid_till: u64,
+    pub propagate: bool,
+    /// The order transactions were submitted in.
+    insertion_id: u64,
+}
+
+impl<Extrinsic> Transaction<Extrinsic> {
+    /// The order transactions are evicted in: the lowest priority, then the latest submitted.
+    fn eviction_key(&self) -> (TransactionPriority, Reverse<u64>) {
+        (self.priority, Reverse(self.insertion_id))
+    }
+}
+
+/// The number and size of the transactions in each queue.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PoolStatus {
+    pub ready: usize,
+    pub ready_bytes: usize,
+    pub future: usize,
+    pub future_bytes: usize,
+}
+
+/// The transactions waiting to be included in a block, see the [module docs](self).
+pub struct TransactionPool<Extrinsic> {
+    options: Options,
+    ready: BTreeMap<TxHash, Transaction<Extrinsic>>,
+    future: BTreeMap<TxHash, Transaction<Extrinsic>>,
+    /// The tags provided by the ready transactions, with the transaction providing each.
+    provided: BTreeMap<TransactionTag, TxHash>,
+    next_insertion_id: u64,
+}
+
+impl<Extrinsic: Encode + Clone> TransactionPool<Extrinsic> {
+    pub fn new(options: Options) -> Self {
+        TransactionPool {
+            options,
+            ready: BTreeMap::new(),
+            future: BTreeMap::new(),
+            provided: BTreeMap::new(),
+            next_insertion_id: 0,
+        }
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        PoolStatus {
+            ready: self.ready.len(),
+            ready_bytes: self.ready.values().map(|tx| tx.bytes).sum(),
+            future: self.future.len(),
+            future_bytes: self.future.values().map(|tx| tx.bytes).sum(),
+        }
+    }
+
+    /// The transaction of `hash`, ready or not.
+    pub fn get(&self, hash: &TxHash) -> Option<&Transaction<Extrinsic>> {
+        self.ready.get(hash).or_else(|| self.future.get(hash))
+    }
+
+    /// Validate `xt` against the latest state of `api` and add it to the pool.
+    pub fn submit<Api: ChainApi<Extrinsic = Extrinsic>>(
+        &mut self,
+        api: &Api,
+        xt: Extrinsic,
+    ) -> Result<TxHash, Error> {
+        let hash = BlakeTwo256::hash_of(&xt);
+        if self.get(&hash).is_some() {
+            return Err(Error::AlreadyImported)
+        }
+        let valid = api.validate_transaction(&xt).map_err(Error::Invalid)?;
+        if valid.provides.is_empty() {
+            return Err(Error::NoTagsProvided)
+        }
+
+        let tx = Transaction {
+            bytes: xt.encode().len(),
+            data: xt,
+            hash,
+            priority: valid.priority,
+            requires: valid.requires,
+            provides: valid.provides,
+            valid_till: api.block_number().saturating_add(valid.longevity),
+            propagate: valid.propagate,
+            insertion_id: self.next_insertion_id,
+        };
+        self.next_insertion_id += 1;
+        self.import(tx)?;
+
+        self.enforce_limits();
+        if self.get(&hash).is_none() {
+            return Err(Error::ImmediatelyDropped)
+        }
+        Ok(hash)
+    }
+
+    /// The ready transactions, by priority, each after the transactions providing what it
+    /// requires.
+    pub fn ready(&self) -> Vec<&Transaction<Extrinsic>> {
+        // The number of tags each transaction still waits for, and the transactions which wait
+        // for each tag.
+        let mut waiting = BTreeMap::new();
+        let mut requirers = BTreeMap::<&TransactionTag, Vec<TxHash>>::new();
+        let mut unlocked = BinaryHeap::new();
+        for tx in self.ready.values() {
+            let requires: BTreeSet<_> = tx.requires.iter().collect();
+            if requires.is_empty() {
+                unlocked.push((tx.priority, Reverse(tx.insertion_id), tx.hash));
+            }
+            waiting.insert(tx.hash, requires.len());
+            for tag in requires {
+                requirers.entry(tag).or_default().push(tx.hash);
+            }
+        }
+
+        let mut best = Vec::with_capacity(self.ready.len());
+        while let Some((_, _, hash)) = unlocked.pop() {
+            let tx = &self.ready[&hash];
+            best.push(tx);
+            for requirer in tx.provides.iter().flat_map(|tag| requirers.remove(tag)).flatten() {
+                let count = waiting.get_mut(&requirer).expect("ready transactions are counted");
+                *count -= 1;
+                if *count == 0 {
+                    let tx = &self.ready[&requirer];
+                    unlocked.push((tx.priority, Reverse(tx.insertion_id), tx.hash));
+                }
+            }
+        }
+        best
+    }
+
+    /// Remove the transactions of `hashes`, e.g. which were found invalid when building a
+    /// block. Ready transactions requiring them go back to the future queue.
+    pub fn remove(&mut self, hashes: &[TxHash]) {
+        for hash in hashes {
+            if self.remove_ready(hash).is_none() {
+                self.future.remove(hash);
+            }
+        }
+        self.reorganize();
+    }
+
+    /// Revalidate all transactions against the latest state of `api`, once a new block was
+    /// imported, and drop the ones which are not valid anymore, e.g. which were included in the
+    /// block or outlived their longevity. Gives the hashes of the dropped transactions.
+    pub fn maintain<Api: ChainApi<Extrinsic = Extrinsic>>(&mut self, api: &Api) -> Vec<TxHash> {
+        let number = api.block_number();
+        let mut txs: Vec<_> = std::mem::take(&mut self.ready)
+            .into_values()
+            .chain(std::mem::take(&mut self.future).into_values())
+            .collect();
+        txs.sort_by_key(|tx| tx.insertion_id);
+        self.provided.clear();
+
+        let hashes: Vec<_> = txs.iter().map(|tx| tx.hash).collect();
+        for tx in txs {
+            if tx.valid_till <= number {
+                continue
+            }
+            let valid = match api.validate_transaction(&tx.data) {
+                Ok(valid) if !valid.provides.is_empty() => valid,
+                _ => continue,
+            };
+            let _ = self.import(Transaction {
+                priority: valid.priority,
+                requires: valid.requires,
+                provides: valid.provides,
+                valid_till: tx.valid_till.min(number.saturating_add(valid.longevity)),
+                propagate: valid.propagate,
+                ..tx
+            });
+        }
+        self.enforce_limits();
+        hashes.into_iter().filter(|hash| self.get(hash).is_none()).collect()
+    }
+
+    /// Add `tx` to the ready queue if all it requires is provided, else to the future queue.
+    fn import(&mut self, tx: Transaction<Extrinsic>) -> Result<(), Error> {
+        if tx.requires.iter().all(|tag| self.provided.contains_key(tag)) {
+            self.import_ready(tx)?;
+            self.reorganize();
+        } else {
+            self.future.insert(tx.hash, tx);
+        }
+        Ok(())
+    }
+
+    /// Add `tx` to the ready queue, in place of the ready transactions providing the same tags
+    /// if it has a higher priority than all of them.
+    fn import_ready(&mut self, tx: Transaction<Extrinsic>) -> Result<(), Error> {
+        let replaced: BTreeSet<_> =
+            tx.provides.iter().filter_map(|tag| self.provided.get(tag)).copied().collect();
+        if let Some(old) = replaced.iter().map(|hash| self.ready[hash].priority).max() {
+            if old >= tx.priority {
+                return Err(Error::TooLowPriority { old, new: tx.priority })
+            }
+        }
+        for hash in &replaced {
+            self.remove_ready(hash);
+        }
+        for tag in &tx.provides {
+            self.provided.insert(tag.clone(), tx.hash);
+        }
+        self.ready.insert(tx.hash, tx);
+        Ok(())
+    }
+
+    /// Remove the ready transaction of `hash`, with the tags it provides.
+    fn remove_ready(&mut self, hash: &TxHash) -> Option<Transaction<Extrinsic>> {
+        let tx = self.ready.remove(hash)?;
+        for tag in &tx.provides {
+            if self.provided.get(tag) == Some(hash) {
+                self.provided.remove(tag);
+            }
+        }
+        Some(tx)
+    }
+
+    /// The ready transactions which require a tag no ready transaction provides anymore.
+    fn unsatisfied(&self) -> Vec<TxHash> {
+        self.ready
+            .values()
+            .filter(|tx| tx.requires.iter().any(|tag| !self.provided.contains_key(tag)))
+            .map(|tx| tx.hash)
+            .collect()
+    }
+
+    /// Move back to the future queue the ready transactions which lost what they require, and
+    /// move to the ready queue the future transactions which got it.
+    fn reorganize(&mut self) {
+        loop {
+            let mut unsatisfied = self.unsatisfied();
+            while !unsatisfied.is_empty() {
+                for hash in unsatisfied {
+                    let tx = self.remove_ready(&hash).expect("unsatisfied transactions are ready");
+                    self.future.insert(hash, tx);
+                }
+                unsatisfied = self.unsatisfied();
+            }
+
+            let satisfied: Vec<_> = self
+                .future
+                .values()
+                .filter(|tx| tx.requires.iter().all(|tag| self.provided.contains_key(tag)))
+                .map(|tx| tx.hash)
+                .collect();
+            if satisfied.is_empty() {
+                return
+            }
+            for hash in satisfied {
+                let tx = self.future.remove(&hash).expect("satisfied transactions are in future");
+                // A transaction of the same tags but a higher priority is already ready.
+                let _ = self.import_ready(tx);
+            }
+        }
+    }
+
+    /// Evict the transactions of lowest priority until both queues are within their limits.
+    ///
+    /// The ready transactions which require an evicted one are evicted with it, as they can't
+    /// be included without it.
+    fn enforce_limits(&mut self) {
+        while self.options.ready.is_exceeded(self.ready.len(), self.status().ready_bytes) {
+            let worst = self.ready.values().min_by_key(|tx| tx.eviction_key()).map(|tx| tx.hash);
+            let mut evicted = worst.into_iter().collect::<Vec<_>>();
+            while !evicted.is_empty() {
+                for hash in &evicted {
+                    self.remove_ready(hash);
+                }
+                evicted = self.unsatisfied();
+            }
+        }
+        while self.options.future.is_exceeded(self.future.len(), self.status().future_bytes) {
+            let worst = self.future.values().min_by_key(|tx| tx.eviction_key()).map(|tx| tx.hash);
+            if let Some(hash) = worst {
+                self.future.remove(&hash);
+            }
+        }
+    }
+}
+
+impl<Extrinsic: Encode + Clone> Default for TransactionPool<Extrinsic> {
+    fn default() -> Self {
+        Self::new(Options::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::transaction_validity::{InvalidTransaction, ValidTransaction};
+
+    /// A transaction of `sender`, valid if its nonce is not used yet.
+    #[derive(Encode, Clone, PartialEq, Eq, Debug)]
+    struct Tx {
+        sender: u8,
+        nonce: u64,
+        priority: u64,
+        longevity: u64,
+    }
+
+    fn tx(sender: u8, nonce: u64, priority: u64) -> Tx {
+        Tx { sender, nonce, priority, longevity: 64 }
+    }
+
+    #[derive(Default)]
+    struct TestApi {
+        nonces: BTreeMap<u8, u64>,
+        block_number: u64,
+    }
+
+    impl ChainApi for TestApi {
+        type Extrinsic = Tx;
+
+        fn validate_transaction(&self, tx: &Tx) -> TransactionValidity {
+            let nonce = self.nonces.get(&tx.sender).copied().unwrap_or_default();
+            if tx.nonce < nonce {
+                return Err(InvalidTransaction::Stale.into())
+            }
+            let requires =
+                if tx.nonce > nonce { vec![(tx.sender, tx.nonce - 1).encode()] } else { vec![] };
+            Ok(ValidTransaction {
+                priority: tx.priority,
+                requires,
+                provides: vec![(tx.sender, tx.nonce).encode()],
+                longevity: tx.longevity,
+                propagate: true,
+            })
+        }
+
+        fn block_number(&self) -> u64 {
+            self.block_number
+        }
+    }
+
+    /// The sender and nonce of the ready transactions, in the order they are handed over.
+    fn ready(pool: &TransactionPool<Tx>) -> Vec<(u8, u64)> {
+        pool.ready().iter().map(|tx| (tx.data.sender, tx.data.nonce)).collect()
+    }
+
+    #[test]
+    fn transactions_of_an_account_wait_for_the_previous_nonce() {
+        let api = TestApi::default();
+        let mut pool = TransactionPool::default();
+
+        pool.submit(&api, tx(1, 2, 30)).unwrap();
+        pool.submit(&api, tx(1, 1, 20)).unwrap();
+        assert_eq!((pool.status().ready, pool.status().future), (0, 2));
+        assert_eq!(ready(&pool), vec![]);
+
+        pool.submit(&api, tx(1, 0, 10)).unwrap();
+        assert_eq!((pool.status().ready, pool.status().future), (3, 0));
+        // Higher priorities come after the nonces they require.
+        assert_eq!(ready(&pool), vec![(1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn ready_transactions_are_ordered_by_priority() {
+        let api = TestApi::default();
+        let mut pool = TransactionPool::default();
+
+        pool.submit(&api, tx(1, 0, 1)).unwrap();
+        pool.submit(&api, tx(1, 1, 10)).unwrap();
+        pool.submit(&api, tx(2, 0, 5)).unwrap();
+        pool.submit(&api, tx(3, 0, 5)).unwrap();
+        // Transactions of the same priority come in the order they were submitted.
+        assert_eq!(ready(&pool), vec![(2, 0), (3, 0), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn transactions_of_the_same_tags_are_replaced_by_priority() {
+        let mut api = TestApi::default();
+        let mut pool = TransactionPool::default();
+
+        let hash = pool.submit(&api, tx(1, 0, 5)).unwrap();
+        pool.submit(&api, tx(1, 1, 5)).unwrap();
+        assert_eq!(pool.submit(&api, tx(1, 0, 5)), Err(Error::AlreadyImported));
+        assert_eq!(
+            pool.submit(&api, Tx { longevity: 1, ..tx(1, 0, 5) }),
+            Err(Error::TooLowPriority { old: 5, new: 5 })
+        );
+
+        pool.submit(&api, tx(1, 0, 6)).unwrap();
+        assert!(pool.get(&hash).is_none());
+        assert_eq!(ready(&pool), vec![(1, 0), (1, 1)]);
+        assert_eq!(pool.ready()[0].priority, 6);
+
+        api.nonces.insert(1, 1);
+        assert_eq!(
+            pool.submit(&api, tx(1, 0, 7)),
+            Err(Error::Invalid(InvalidTransaction::Stale.into()))
+        );
+    }
+
+    #[test]
+    fn maintain_drops_included_and_outlived_transactions() {
+        let mut api = TestApi::default();
+        let mut pool = TransactionPool::default();
+
+        let included = pool.submit(&api, tx(1, 0, 1)).unwrap();
+        pool.submit(&api, tx(1, 1, 1)).unwrap();
+        let outlived = pool.submit(&api, Tx { longevity: 2, ..tx(2, 0, 1) }).unwrap();
+        pool.submit(&api, Tx { longevity: 3, ..tx(3, 0, 1) }).unwrap();
+
+        // A block includes the first nonce of 1.
+        api.nonces.insert(1, 1);
+        api.block_number = 2;
+        assert_eq!(pool.maintain(&api), vec![included, outlived]);
+        assert_eq!(ready(&pool), vec![(1, 1), (3, 0)]);
+        assert_eq!(pool.ready()[0].requires, Vec::<TransactionTag>::new());
+
+        api.block_number = 3;
+        assert_eq!(pool.maintain(&api).len(), 1);
+        assert_eq!(ready(&pool), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn removed_transactions_take_their_dependents_back_to_the_future() {
+        let api = TestApi::default();
+        let mut pool = TransactionPool::default();
+
+        let first = pool.submit(&api, tx(1, 0, 1)).unwrap();
+        pool.submit(&api, tx(1, 1, 1)).unwrap();
+        pool.remove(&[first]);
+        assert_eq!((pool.status().ready, pool.status().future), (0, 1));
+
+        pool.submit(&api, tx(1, 0, 1)).unwrap();
+        assert_eq!(ready(&pool), vec![(1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn transactions_of_lowest_priority_are_evicted_when_full() {
+        let api = TestApi::default();
+        let options = Options {
+            ready: Limit { count: 3, total_bytes: 1024 },
+            future: Limit { count: 1, total_bytes: 1024 },
+        };
+        let mut pool = TransactionPool::new(options);
+
+        pool.submit(&api, tx(1, 0, 1)).unwrap();
+        pool.submit(&api, tx(1, 1, 9)).unwrap();
+        pool.submit(&api, tx(2, 0, 5)).unwrap();
+        // The lowest priority is evicted, with the transaction requiring it.
+        pool.submit(&api, tx(3, 0, 3)).unwrap();
+        assert_eq!(ready(&pool), vec![(2, 0), (3, 0)]);
+
+        pool.submit(&api, tx(4, 0, 4)).unwrap();
+        assert_eq!(pool.submit(&api, tx(5, 0, 2)), Err(Error::ImmediatelyDropped));
+        assert_eq!(ready(&pool), vec![(2, 0), (4, 0), (3, 0)]);
+
+        pool.submit(&api, tx(6, 1, 1)).unwrap();
+        pool.submit(&api, tx(7, 1, 2)).unwrap();
+        assert_eq!(pool.status().future, 1);
+        assert!(pool.get(&BlakeTwo256::hash_of(&tx(7, 1, 2))).is_some());
+    }
+}
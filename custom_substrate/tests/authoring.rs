@@ -0,0 +1,269 @@
+//! Blocks authored by a node from submitted transactions, with the template runtime.
+
+use custom_substrate::{
+    block_tree,
+    node::{Error, Node, Sealing},
+    transaction_pool,
+};
+use frame_support::{
+    codec::Encode,
+    dispatch::{DispatchError, GetDispatchInfo},
+    generic::{Era, SignedPayload},
+    storage::{Storage, TestExternalities},
+    testing::TestSignature,
+    traits::Currency,
+    transaction_validity::InvalidTransaction,
+};
+use template::{
+    Balances, Executive, Runtime, RuntimeCall, RuntimeEvent, System, UncheckedExtrinsic,
+};
+
+/// The genesis state: account 1 has a balance of 100.
+fn genesis() -> Storage {
+    let mut ext = TestExternalities::default();
+    ext.execute_with(|| drop(Balances::deposit_creating(&1, 100)));
+    ext.into_storage()
+}
+
+/// `call` signed by `who` with `nonce`, for the chain of `node`.
+fn sign(node: &Node<Executive>, who: u32, nonce: u32, call: RuntimeCall) -> UncheckedExtrinsic {
+    let extra = (
+        frame_system::CheckSpecVersion::new(),
+        frame_system::CheckGenesis::new(),
+        frame_system::CheckMortality::from(Era::immortal()),
+        frame_system::CheckNonce::from(nonce),
+        frame_system::CheckWeight::new(),
+    );
+    let genesis_hash = node.genesis_hash();
+    let additional = (0, genesis_hash, genesis_hash, (), ());
+    let payload = SignedPayload::from_raw(call.clone(), extra.clone(), additional);
+    UncheckedExtrinsic::new_signed(call, who, TestSignature(who, payload.encode()), extra)
+}
+
+fn remark(len: usize) -> RuntimeCall {
+    RuntimeCall::System(frame_system::Call::remark { remark: vec![0; len] })
+}
+
+fn transfer(dest: u32, value: u32) -> RuntimeCall {
+    RuntimeCall::Balances(pallet_balances::Call::transfer { dest, value })
+}
+
+#[test]
+fn instant_seal_seals_a_block_per_transaction() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Instant);
+
+    node.submit(sign(&node, 1, 0, remark(1))).unwrap();
+    assert_eq!(node.best_block().header.number, 1);
+    assert_eq!(node.finalized_hash(), node.best_hash());
+
+    // A transaction waiting for another one is not sealed alone.
+    node.submit(sign(&node, 1, 2, remark(2))).unwrap();
+    assert_eq!(node.best_block().header.number, 1);
+    node.submit(sign(&node, 1, 1, remark(3))).unwrap();
+    assert_eq!(node.best_block().header.number, 2);
+    assert_eq!(node.best_block().extrinsics.len(), 2);
+    assert_eq!(node.pool().status(), Default::default());
+    assert_eq!(node.execute_with(|| System::account_nonce(&1)), 3);
+}
+
+#[test]
+fn manual_seal_seals_and_finalizes_on_request() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Manual);
+
+    node.submit(sign(&node, 1, 0, remark(1))).unwrap();
+    node.submit(sign(&node, 2, 0, remark(1))).unwrap();
+    assert_eq!(node.best_block().header.number, 0);
+
+    let first = node.seal_block(false, false).unwrap();
+    assert_eq!(node.best_block().extrinsics.len(), 2);
+    assert_eq!(node.finalized_hash(), node.genesis_hash());
+
+    assert_eq!(node.seal_block(false, false), Err(Error::EmptyTransactionPool));
+    let second = node.seal_block(true, false).unwrap();
+    assert_eq!(node.block(&second).unwrap().header.parent_hash, first);
+    assert!(node.best_block().extrinsics.is_empty());
+
+    node.finalize_block(&first).unwrap();
+    assert_eq!(node.finalized_hash(), first);
+    assert_eq!(
+        node.finalize_block(&node.genesis_hash()),
+        Err(Error::Block(block_tree::Error::AlreadyFinalized))
+    );
+    assert_eq!(node.finalize_block(&[7; 32]), Err(Error::Block(block_tree::Error::UnknownBlock)));
+    let third = node.seal_block(true, true).unwrap();
+    assert_eq!(node.finalized_hash(), third);
+}
+
+#[test]
+fn blocks_are_filled_from_the_pool_up_to_their_length() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Manual);
+
+    // Normal extrinsics may use 3/4 of the 5 MiB of a block: two of these fit, not three.
+    for who in 1..=3 {
+        node.submit(sign(&node, who, 0, remark(1300 * 1024))).unwrap();
+    }
+    node.seal_block(false, true).unwrap();
+    assert_eq!(node.best_block().extrinsics.len(), 2);
+    assert_eq!(node.pool().status().ready, 1);
+
+    node.seal_block(false, true).unwrap();
+    assert_eq!(node.best_block().extrinsics.len(), 1);
+    assert_eq!(node.pool().status().ready, 0);
+}
+
+#[test]
+fn included_transactions_are_not_replayed() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Instant);
+
+    let xt = sign(&node, 1, 0, remark(1));
+    node.submit(xt.clone()).unwrap();
+    assert_eq!(
+        node.submit(xt),
+        Err(Error::Pool(transaction_pool::Error::Invalid(InvalidTransaction::Stale.into())))
+    );
+    // A signature of another message doesn't verify.
+    let mut forged = sign(&node, 1, 1, remark(1));
+    forged.signature.as_mut().unwrap().1 = TestSignature(1, vec![]);
+    assert_eq!(
+        node.submit(forged),
+        Err(Error::Pool(transaction_pool::Error::Invalid(InvalidTransaction::BadProof.into())))
+    );
+}
+
+#[test]
+fn balance_calls_are_applied_in_blocks() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Instant);
+    assert_eq!(node.execute_with(|| Balances::get_balances(1)), 100);
+
+    // Setting balances needs root, which no signed transaction is.
+    let set_balance = RuntimeCall::Balances(pallet_balances::Call::set_balance {
+        who: 1,
+        new_free: 5,
+        new_reserved: 0,
+    });
+    node.submit(sign(&node, 1, 0, set_balance.clone())).unwrap();
+    assert_eq!(node.best_block().header.number, 1);
+    node.execute_with(|| {
+        System::assert_last_event(RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+            dispatch_error: DispatchError::BadOrigin,
+            dispatch_info: set_balance.get_dispatch_info(),
+        }));
+        assert_eq!(Balances::get_balances(1), 100);
+    });
+}
+
+#[test]
+fn transfers_are_applied_in_blocks() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Instant);
+    node.submit(sign(&node, 1, 0, transfer(2, 30))).unwrap();
+    node.execute_with(|| {
+        System::assert_has_event(RuntimeEvent::Balances(pallet_balances::Event::Transfer {
+            from: 1,
+            to: 2,
+            amount: 30,
+        }));
+        assert_eq!((Balances::get_balances(1), Balances::get_balances(2)), (70, 30));
+        assert_eq!(System::providers(&2), 1);
+    });
+
+    // The existential deposit of the template is 10.
+    let keep_alive =
+        RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive { dest: 2, value: 65 });
+    node.submit(sign(&node, 1, 1, keep_alive.clone())).unwrap();
+    node.execute_with(|| {
+        System::assert_last_event(RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+            dispatch_error: pallet_balances::Error::<Runtime>::KeepAlive.into(),
+            dispatch_info: keep_alive.get_dispatch_info(),
+        }));
+        assert_eq!(Balances::get_balances(1), 70);
+    });
+
+    // Sending everything reaps the sender.
+    let transfer_all =
+        RuntimeCall::Balances(pallet_balances::Call::transfer_all { dest: 3, keep_alive: false });
+    node.submit(sign(&node, 2, 0, transfer_all)).unwrap();
+    assert_eq!(node.best_block().header.number, 3);
+    node.execute_with(|| {
+        System::assert_has_event(RuntimeEvent::Balances(pallet_balances::Event::Endowed {
+            account: 3,
+            free_balance: 30,
+        }));
+        assert_eq!((Balances::get_balances(2), Balances::get_balances(3)), (0, 30));
+        assert_eq!(System::providers(&2), 0);
+        assert_eq!(Balances::total_issuance(), 100);
+    });
+}
+
+#[test]
+fn blocks_with_transfers_are_executed_by_other_nodes() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Manual);
+    node.submit(sign(&node, 1, 0, transfer(2, 30))).unwrap();
+    // More than account 1 has left, which fails in the block.
+    node.submit(sign(&node, 1, 1, transfer(3, 71))).unwrap();
+    node.seal_block(false, true).unwrap();
+    assert_eq!(node.best_block().extrinsics.len(), 2);
+
+    let mut other = Node::<Executive>::new(genesis(), Sealing::Manual);
+    assert_eq!(other.import_block(node.best_block().clone()), Ok(node.best_hash()));
+    other.execute_with(|| {
+        assert_eq!((Balances::get_balances(1), Balances::get_balances(2)), (70, 30));
+        assert_eq!(Balances::get_balances(3), 0);
+        assert_eq!(System::account_nonce(&1), 2);
+        assert_eq!(Balances::total_issuance(), 100);
+    });
+}
+
+#[test]
+fn sealed_blocks_are_executed_by_other_nodes() {
+    let mut node = Node::<Executive>::new(genesis(), Sealing::Manual);
+    node.submit(sign(&node, 1, 0, remark(1))).unwrap();
+    node.submit(sign(&node, 1, 1, remark(2))).unwrap();
+    node.seal_block(false, true).unwrap();
+
+    let block = node.best_block().clone();
+    let mut ext = TestExternalities::new(genesis());
+    ext.execute_with(|| Executive::execute_block(block.clone()));
+    assert_eq!(ext.execute_with(|| System::account_nonce(&1)), 2);
+
+    let mut other = Node::<Executive>::new(genesis(), Sealing::Manual);
+    assert_eq!(other.import_block(block.clone()), Ok(node.best_hash()));
+    assert_eq!(other.execute_with(|| System::account_nonce(&1)), 2);
+    assert_eq!(other.import_block(block), Err(Error::Block(block_tree::Error::AlreadyImported)));
+}
+
+#[test]
+fn nodes_reorganize_onto_longer_chains() {
+    let mut alice = Node::<Executive>::new(genesis(), Sealing::Manual);
+    let mut bob = Node::<Executive>::new(genesis(), Sealing::Manual);
+
+    // Alice includes a remark of account 1, which Bob never hears of.
+    alice.submit(sign(&alice, 1, 0, remark(1))).unwrap();
+    let retracted = alice.seal_block(false, false).unwrap();
+    assert_eq!(alice.execute_with(|| System::account_nonce(&1)), 1);
+
+    // Bob seals a longer chain, including a remark of account 2.
+    bob.submit(sign(&bob, 2, 0, remark(1))).unwrap();
+    bob.seal_block(false, false).unwrap();
+    bob.seal_block(true, false).unwrap();
+
+    for number in 1..=2 {
+        let hash = bob.tree().hash(number).unwrap();
+        alice.import_block(bob.block(&hash).unwrap().clone()).unwrap();
+    }
+    assert_eq!(alice.best_hash(), bob.best_hash());
+    assert_eq!(alice.tree().leaves().len(), 2);
+    assert!(alice.block(&retracted).is_some());
+
+    // The state is the one of Bob's chain, and the retracted remark is back in the pool.
+    alice.execute_with(|| {
+        assert_eq!(System::account_nonce(&1), 0);
+        assert_eq!(System::account_nonce(&2), 1);
+    });
+    assert_eq!(alice.pool().status().ready, 1);
+    let included = alice.seal_block(false, true).unwrap();
+    assert_eq!(alice.execute_with(|| System::account_nonce(&1)), 1);
+
+    // Finalizing prunes the fork Alice left.
+    assert_eq!(alice.tree().leaves(), vec![included]);
+    assert!(alice.block(&retracted).is_none());
+}